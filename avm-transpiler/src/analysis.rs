@@ -0,0 +1,492 @@
+//! Static analysis of the size and gas cost of transpiled public functions.
+//!
+//! Gas is estimated from each opcode's base cost in a [`GasTable`]. Costs which depend on
+//! runtime values, such as the per-element cost of copying calldata or emitting logs and the
+//! cost of memory accesses, aren't included.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::ops::Add;
+
+use acvm::acir::circuit::brillig::BrilligFunctionId;
+use acvm::acir::circuit::BrilligOpcodeLocation;
+use noirc_errors::debug_info::ProgramDebugInfo;
+use serde::{Deserialize, Serialize};
+
+use crate::disassembler::{decode_bytecode, DecodeError};
+use crate::instructions::{AvmInstruction, AvmOperand};
+use crate::opcodes::AvmOpcode;
+use crate::utils::format_location;
+
+/// An amount of gas in each of the AVM's gas dimensions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Gas {
+    pub l2_gas: u64,
+    pub da_gas: u64,
+}
+
+impl Gas {
+    pub const fn new(l2_gas: u64, da_gas: u64) -> Self {
+        Gas { l2_gas, da_gas }
+    }
+
+    /// The larger of `self` and `other` in each dimension
+    pub fn max(self, other: Gas) -> Gas {
+        Gas::new(self.l2_gas.max(other.l2_gas), self.da_gas.max(other.da_gas))
+    }
+}
+
+impl Add for Gas {
+    type Output = Gas;
+
+    fn add(self, other: Gas) -> Gas {
+        Gas::new(self.l2_gas + other.l2_gas, self.da_gas + other.da_gas)
+    }
+}
+
+impl fmt::Display for Gas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} L2, {} DA", self.l2_gas, self.da_gas)
+    }
+}
+
+/// The base gas cost of each opcode
+#[derive(Debug, Clone)]
+pub struct GasTable {
+    costs: HashMap<AvmOpcode, Gas>,
+}
+
+impl GasTable {
+    pub fn cost(&self, opcode: AvmOpcode) -> Gas {
+        self.costs.get(&opcode).copied().unwrap_or_default()
+    }
+
+    /// Override the costs of some opcodes with the ones in a JSON object mapping opcode names
+    /// to costs, such as `{ "SLOAD": { "l2_gas": 1500, "da_gas": 0 } }`.
+    pub fn with_overrides(mut self, json: &str) -> Result<Self, String> {
+        let overrides: HashMap<String, Gas> =
+            serde_json::from_str(json).map_err(|err| format!("invalid gas table: {err}"))?;
+        for (name, cost) in overrides {
+            let opcode =
+                AvmOpcode::from_name(&name).ok_or_else(|| format!("unknown opcode `{name}`"))?;
+            self.costs.insert(opcode, cost);
+        }
+        Ok(self)
+    }
+}
+
+/// The base costs the simulator charges, from the `AVM_*_BASE_*_GAS` protocol constants.
+impl Default for GasTable {
+    fn default() -> Self {
+        use AvmOpcode::*;
+        let costs = AvmOpcode::ALL
+            .into_iter()
+            .map(|opcode| {
+                let cost = match opcode {
+                    ADD_8 | ADD_16 | SUB_8 | SUB_16 | FDIV_8 | FDIV_16 | EQ_8 | EQ_16 => {
+                        Gas::new(32, 0)
+                    }
+                    MUL_8 | MUL_16 | AND_8 | AND_16 | OR_8 | OR_16 | XOR_8 | XOR_16 => {
+                        Gas::new(33, 0)
+                    }
+                    DIV_8 | DIV_16 => Gas::new(43, 0),
+                    LT_8 | LT_16 | LTE_8 | LTE_16 => Gas::new(64, 0),
+                    NOT_8 | NOT_16 => Gas::new(27, 0),
+                    SHL_8 | SHL_16 | SHR_8 | SHR_16 => Gas::new(32, 0),
+                    CAST_8 | CAST_16 => Gas::new(30, 0),
+                    GETENVVAR_16 | RETURNDATASIZE => Gas::new(20, 0),
+                    CALLDATACOPY | RETURNDATACOPY => Gas::new(29, 0),
+                    JUMP_32 => Gas::new(12, 0),
+                    JUMPI_32 | INTERNALCALL | INTERNALRETURN => Gas::new(18, 0),
+                    SET_8 | SET_16 | SET_32 | SET_64 | SET_128 | SET_FF => Gas::new(18, 0),
+                    MOV_8 | MOV_16 => Gas::new(23, 0),
+                    SLOAD => Gas::new(1218, 0),
+                    SSTORE => Gas::new(2642, 512),
+                    NOTEHASHEXISTS => Gas::new(1226, 0),
+                    EMITNOTEHASH => Gas::new(2650, 512),
+                    NULLIFIEREXISTS => Gas::new(2426, 0),
+                    EMITNULLIFIER => Gas::new(4250, 512),
+                    L1TOL2MSGEXISTS => Gas::new(1196, 0),
+                    GETCONTRACTINSTANCE => Gas::new(2448, 0),
+                    EMITUNENCRYPTEDLOG => Gas::new(18, 0),
+                    SENDL2TOL1MSG => Gas::new(226, 512),
+                    CALL | STATICCALL => Gas::new(4845, 0),
+                    RETURN | REVERT_8 | REVERT_16 => Gas::new(28, 0),
+                    DEBUGLOG => Gas::new(12, 0),
+                    POSEIDON2 => Gas::new(78, 0),
+                    SHA256COMPRESSION => Gas::new(261, 0),
+                    KECCAKF1600 => Gas::new(300, 0),
+                    ECADD => Gas::new(62, 0),
+                    MSM => Gas::new(1000, 0),
+                    TORADIXBE => Gas::new(46, 0),
+                };
+                (opcode, cost)
+            })
+            .collect();
+        GasTable { costs }
+    }
+}
+
+/// A static upper bound on the gas a function can use
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GasBound {
+    /// The most gas used by any path through the function, counting the body of each loop once
+    pub gas: Gas,
+    /// The pcs of the instructions which start a loop (or a recursive internal call).
+    /// If there are any, `gas` is only a bound on a single iteration of each loop.
+    pub loop_headers: Vec<usize>,
+}
+
+impl GasBound {
+    pub fn is_bounded(&self) -> bool {
+        self.loop_headers.is_empty()
+    }
+}
+
+/// The static gas cost of the instructions transpiled from one source location
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourceCost {
+    /// The location as `path:line:column`
+    pub location: String,
+    pub instructions: usize,
+    pub gas: Gas,
+}
+
+/// The size and cost of a transpiled public function
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FunctionReport {
+    pub name: String,
+    /// The size of the bytecode in bytes
+    pub bytecode_size: usize,
+    pub instruction_count: usize,
+    /// How many instructions there are with each opcode
+    pub instruction_mix: BTreeMap<&'static str, usize>,
+    pub gas_bound: GasBound,
+    /// The source locations with the most expensive instructions, most expensive first.
+    /// Empty if the function has no debug symbols.
+    pub source_costs: Vec<SourceCost>,
+}
+
+/// Analyze the bytecode of a transpiled function.
+/// `debug_symbols` must have been patched with AVM pcs, as `patch_debug_info_pcs` does.
+pub fn analyze_function(
+    name: &str,
+    bytecode: &[u8],
+    debug_symbols: &ProgramDebugInfo,
+    file_map: &serde_json::Value,
+    gas_table: &GasTable,
+) -> Result<FunctionReport, DecodeError> {
+    let instructions = decode_bytecode(bytecode)?;
+    let mut pcs = Vec::with_capacity(instructions.len());
+    let mut pc = 0;
+    for instruction in &instructions {
+        pcs.push(pc);
+        pc += instruction.size();
+    }
+
+    let mut instruction_mix = BTreeMap::new();
+    for instruction in &instructions {
+        *instruction_mix.entry(instruction.opcode.name()).or_default() += 1;
+    }
+
+    Ok(FunctionReport {
+        name: name.to_string(),
+        bytecode_size: bytecode.len(),
+        instruction_count: instructions.len(),
+        instruction_mix,
+        gas_bound: gas_bound(&instructions, &pcs, gas_table),
+        source_costs: source_costs(&instructions, &pcs, debug_symbols, file_map, gas_table),
+    })
+}
+
+/// A straight-line run of instructions, entered only at its first instruction
+struct Block {
+    pc: usize,
+    cost: Gas,
+    exit: BlockExit,
+}
+
+/// How control leaves a block, as indices of the blocks it continues in
+enum BlockExit {
+    /// The function returns, reverts, or returns from an internal call
+    Stop,
+    Goto(Vec<usize>),
+    /// An internal call to `callee`, continuing in `next` once it returns
+    Call {
+        callee: usize,
+        next: Option<usize>,
+    },
+}
+
+/// Find the most expensive path through the control flow graph of the instructions.
+/// Back edges are ignored, so each loop body is counted once and its header is recorded.
+fn gas_bound(instructions: &[AvmInstruction], pcs: &[usize], gas_table: &GasTable) -> GasBound {
+    let blocks = basic_blocks(instructions, pcs, gas_table);
+    if blocks.is_empty() {
+        return GasBound { gas: Gas::default(), loop_headers: Vec::new() };
+    }
+
+    enum Visit {
+        Enter(usize),
+        Exit(usize),
+    }
+    let successors = |block: &Block| match &block.exit {
+        BlockExit::Stop => Vec::new(),
+        BlockExit::Goto(targets) => targets.clone(),
+        BlockExit::Call { callee, next } => std::iter::once(*callee).chain(*next).collect(),
+    };
+
+    // The most expensive path from the start of each block to the end of its function
+    let mut costs: Vec<Option<Gas>> = vec![None; blocks.len()];
+    let mut on_stack = vec![false; blocks.len()];
+    let mut loop_headers = BTreeSet::new();
+    let mut stack = vec![Visit::Enter(0)];
+    while let Some(visit) = stack.pop() {
+        match visit {
+            Visit::Enter(index) => {
+                if on_stack[index] {
+                    loop_headers.insert(blocks[index].pc);
+                    continue;
+                }
+                if costs[index].is_some() {
+                    continue;
+                }
+                on_stack[index] = true;
+                stack.push(Visit::Exit(index));
+                stack.extend(successors(&blocks[index]).into_iter().map(Visit::Enter));
+            }
+            Visit::Exit(index) => {
+                // Successors which are still on the stack are reached through a back edge
+                let cost_of = |successor: usize| costs[successor].unwrap_or_default();
+                let block = &blocks[index];
+                let rest = match &block.exit {
+                    BlockExit::Stop => Gas::default(),
+                    BlockExit::Goto(targets) => {
+                        targets.iter().map(|target| cost_of(*target)).fold(Gas::default(), Gas::max)
+                    }
+                    BlockExit::Call { callee, next } => {
+                        cost_of(*callee) + next.map(cost_of).unwrap_or_default()
+                    }
+                };
+                costs[index] = Some(block.cost + rest);
+                on_stack[index] = false;
+            }
+        }
+    }
+
+    GasBound { gas: costs[0].unwrap_or_default(), loop_headers: loop_headers.into_iter().collect() }
+}
+
+/// Split the instructions into blocks at jump targets and after control flow instructions.
+fn basic_blocks(
+    instructions: &[AvmInstruction],
+    pcs: &[usize],
+    gas_table: &GasTable,
+) -> Vec<Block> {
+    let target = |instruction: &AvmInstruction| match instruction.immediates.first() {
+        Some(AvmOperand::U32 { value }) => Some(*value as usize),
+        _ => None,
+    };
+
+    let mut leaders = BTreeSet::from([0]);
+    for (index, instruction) in instructions.iter().enumerate() {
+        if let AvmOpcode::JUMP_32 | AvmOpcode::JUMPI_32 | AvmOpcode::INTERNALCALL =
+            instruction.opcode
+        {
+            leaders.extend(target(instruction).and_then(|pc| pcs.binary_search(&pc).ok()));
+        }
+        if ends_block(instruction.opcode) {
+            leaders.insert(index + 1);
+        }
+    }
+    leaders.retain(|leader| *leader < instructions.len());
+
+    let leaders: Vec<usize> = leaders.into_iter().collect();
+    let block_at = |pc: usize| {
+        pcs.binary_search(&pc).ok().and_then(|index| leaders.binary_search(&index).ok())
+    };
+    leaders
+        .iter()
+        .enumerate()
+        .map(|(block_index, &start)| {
+            let end = leaders.get(block_index + 1).copied().unwrap_or(instructions.len());
+            let last = &instructions[end - 1];
+            let next = (end < instructions.len()).then_some(block_index + 1);
+            let exit = match last.opcode {
+                AvmOpcode::JUMP_32 => {
+                    BlockExit::Goto(target(last).and_then(block_at).into_iter().collect())
+                }
+                AvmOpcode::JUMPI_32 => BlockExit::Goto(
+                    target(last).and_then(block_at).into_iter().chain(next).collect(),
+                ),
+                AvmOpcode::INTERNALCALL => match target(last).and_then(block_at) {
+                    Some(callee) => BlockExit::Call { callee, next },
+                    None => BlockExit::Stop,
+                },
+                opcode if ends_block(opcode) => BlockExit::Stop,
+                _ => BlockExit::Goto(next.into_iter().collect()),
+            };
+            let cost = instructions[start..end]
+                .iter()
+                .map(|instruction| gas_table.cost(instruction.opcode))
+                .fold(Gas::default(), Add::add);
+            Block { pc: pcs[start], cost, exit }
+        })
+        .collect()
+}
+
+fn ends_block(opcode: AvmOpcode) -> bool {
+    matches!(
+        opcode,
+        AvmOpcode::JUMP_32
+            | AvmOpcode::JUMPI_32
+            | AvmOpcode::INTERNALCALL
+            | AvmOpcode::INTERNALRETURN
+            | AvmOpcode::RETURN
+            | AvmOpcode::REVERT_8
+            | AvmOpcode::REVERT_16
+    )
+}
+
+/// Attribute the cost of each instruction to the source location it was transpiled from.
+/// The patched debug symbols map the pc of the first AVM instruction of each Brillig opcode to
+/// its location, so an instruction belongs to the closest such pc before it.
+fn source_costs(
+    instructions: &[AvmInstruction],
+    pcs: &[usize],
+    debug_symbols: &ProgramDebugInfo,
+    file_map: &serde_json::Value,
+    gas_table: &GasTable,
+) -> Vec<SourceCost> {
+    let Some(brillig_locations) = debug_symbols
+        .debug_infos
+        .first()
+        .and_then(|debug_info| debug_info.brillig_locations.get(&BrilligFunctionId(0)))
+    else {
+        return Vec::new();
+    };
+
+    let mut costs: HashMap<String, SourceCost> = HashMap::new();
+    for (instruction, pc) in instructions.iter().zip(pcs) {
+        let Some(location) = brillig_locations
+            .range(..=BrilligOpcodeLocation(*pc))
+            .next_back()
+            .and_then(|(_, call_stack)| call_stack.last())
+            .and_then(|location| format_location(location, file_map))
+        else {
+            continue;
+        };
+        let entry = costs.entry(location.clone()).or_insert(SourceCost {
+            location,
+            instructions: 0,
+            gas: Gas::default(),
+        });
+        entry.instructions += 1;
+        entry.gas = entry.gas + gas_table.cost(instruction.opcode);
+    }
+
+    let mut costs: Vec<SourceCost> = costs.into_values().collect();
+    costs.sort_by(|a, b| {
+        (b.gas.l2_gas, b.gas.da_gas)
+            .cmp(&(a.gas.l2_gas, a.gas.da_gas))
+            .then(a.location.cmp(&b.location))
+    });
+    costs
+}
+
+#[cfg(test)]
+mod tests {
+    use noirc_errors::debug_info::ProgramDebugInfo;
+
+    use super::{analyze_function, FunctionReport, Gas, GasTable};
+    use crate::assembler::assemble;
+
+    fn analyze(source: &str) -> FunctionReport {
+        let bytecode: Vec<u8> = assemble(source)
+            .unwrap()
+            .iter()
+            .flat_map(|instruction| instruction.to_bytes())
+            .collect();
+        let debug_symbols = ProgramDebugInfo { debug_infos: vec![] };
+        analyze_function(
+            "test",
+            &bytecode,
+            &debug_symbols,
+            &serde_json::Value::Null,
+            &GasTable::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn straight_line_code() {
+        let report = analyze(
+            "
+            SET_8 0, tag=FIELD, #5
+            SSTORE 0, 0
+            RETURN 0, 0
+            ",
+        );
+        assert_eq!(report.instruction_count, 3);
+        assert_eq!(report.instruction_mix["SSTORE"], 1);
+        assert_eq!(report.gas_bound.gas, Gas::new(18 + 2642 + 28, 512));
+        assert!(report.gas_bound.is_bounded());
+    }
+
+    #[test]
+    fn branches_take_the_most_expensive_path() {
+        let report = analyze(
+            "
+            JUMPI_32 0, @store
+            SLOAD 0, 0
+            RETURN 0, 0
+            store:
+            SSTORE 0, 0
+            RETURN 0, 0
+            ",
+        );
+        assert_eq!(report.gas_bound.gas, Gas::new(18 + 2642 + 28, 512));
+    }
+
+    #[test]
+    fn internal_calls_add_the_callee() {
+        let report = analyze(
+            "
+            INTERNALCALL @callee
+            INTERNALCALL @callee
+            RETURN 0, 0
+            callee:
+            SLOAD 0, 0
+            INTERNALRETURN
+            ",
+        );
+        let callee = 1218 + 18;
+        assert_eq!(report.gas_bound.gas, Gas::new(2 * (18 + callee) + 28, 0));
+    }
+
+    #[test]
+    fn loops_are_counted_once_and_reported() {
+        let report = analyze(
+            "
+            SET_8 0, tag=UINT1, #1
+            header:
+            SLOAD 1, 1
+            JUMPI_32 0, @header
+            RETURN 0, 0
+            ",
+        );
+        assert_eq!(report.gas_bound.gas, Gas::new(18 + 1218 + 18 + 28, 0));
+        assert_eq!(report.gas_bound.loop_headers, vec![5]);
+    }
+
+    #[test]
+    fn gas_table_overrides() {
+        let table =
+            GasTable::default().with_overrides(r#"{ "SLOAD": { "l2_gas": 1, "da_gas": 2 } }"#);
+        assert_eq!(table.unwrap().cost(crate::opcodes::AvmOpcode::SLOAD), Gas::new(1, 2));
+
+        let error =
+            GasTable::default().with_overrides(r#"{ "NOPE": { "l2_gas": 1, "da_gas": 2 } }"#);
+        assert_eq!(error.unwrap_err(), "unknown opcode `NOPE`");
+    }
+}
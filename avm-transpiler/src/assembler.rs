@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use acvm::{AcirField, FieldElement};
+
+use crate::instructions::{AvmInstruction, AvmOperand, AvmTypeTag};
+use crate::opcodes::{AvmInstructionLayout, AvmOpcode, AvmOperandType};
+
+/// A problem with a line of AVM assembly
+#[derive(Debug)]
+pub struct AssemblyError {
+    /// 1-based line number
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for AssemblyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// An instruction whose fields haven't been parsed yet, as labels may be defined further on
+struct UnparsedInstruction<'a> {
+    line: usize,
+    opcode: AvmOpcode,
+    fields: Vec<&'a str>,
+}
+
+/// Assemble AVM instructions from the syntax printed by `disassemble`.
+///
+/// Each line holds an instruction, a label definition (`name:`) or both, and `;` starts a comment.
+/// The addressing mode (`ind=`) may be left out, in which case all operands are direct.
+/// Immediates can be written as `@name` to use the pc of a label, for jumps and internal calls.
+pub fn assemble(source: &str) -> Result<Vec<AvmInstruction>, AssemblyError> {
+    let mut labels = HashMap::new();
+    let mut unparsed = Vec::new();
+    let mut pc = 0;
+
+    for (index, line) in source.lines().enumerate() {
+        let error = |message: String| AssemblyError { line: index + 1, message };
+
+        let mut text = line.split(';').next().unwrap().trim();
+        if let Some((label, rest)) = text.split_once(':') {
+            if !is_label(label) {
+                return Err(error(format!("invalid label `{label}`")));
+            }
+            if labels.insert(label, pc).is_some() {
+                return Err(error(format!("label `{label}` is defined more than once")));
+            }
+            text = rest.trim();
+        }
+        if text.is_empty() {
+            continue;
+        }
+
+        let (name, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let opcode =
+            AvmOpcode::from_name(name).ok_or_else(|| error(format!("unknown opcode `{name}`")))?;
+        let fields = if rest.trim().is_empty() {
+            Vec::new()
+        } else {
+            rest.split(',').map(str::trim).collect()
+        };
+        unparsed.push(UnparsedInstruction { line: index + 1, opcode, fields });
+        pc += opcode.layout().size();
+    }
+
+    unparsed
+        .into_iter()
+        .map(|instruction| {
+            parse_instruction(&instruction, &labels)
+                .map_err(|message| AssemblyError { line: instruction.line, message })
+        })
+        .collect()
+}
+
+fn is_label(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse_instruction(
+    instruction: &UnparsedInstruction,
+    labels: &HashMap<&str, usize>,
+) -> Result<AvmInstruction, String> {
+    let layout = instruction.opcode.layout();
+    let expected = || format!("expected `{}`", syntax(instruction.opcode, &layout));
+    let mut fields = instruction.fields.iter().copied().peekable();
+
+    let indirect = match layout.indirect {
+        Some(typ) => {
+            let value =
+                fields.next_if(|field| field.starts_with("ind=")).map_or("0", |field| &field[4..]);
+            Some(parse_value(typ, value)?)
+        }
+        None => None,
+    };
+
+    let mut operands = Vec::new();
+    for typ in layout.operands {
+        match fields.next() {
+            Some(field) if field.starts_with(|c: char| c.is_ascii_digit()) => {
+                operands.push(parse_value(*typ, field)?);
+            }
+            _ => return Err(expected()),
+        }
+    }
+
+    let tag = if layout.tag {
+        let field =
+            fields.next().and_then(|field| field.strip_prefix("tag=")).ok_or_else(expected)?;
+        Some(AvmTypeTag::from_name(field).ok_or_else(|| format!("unknown type tag `{field}`"))?)
+    } else {
+        None
+    };
+
+    let mut immediates = Vec::new();
+    for typ in layout.immediates {
+        let field = fields.next().ok_or_else(expected)?;
+        let immediate = if let Some(value) = field.strip_prefix('#') {
+            parse_value(*typ, value)?
+        } else if let Some(label) = field.strip_prefix('@') {
+            let pc = labels.get(label).ok_or_else(|| format!("undefined label `{label}`"))?;
+            parse_value(*typ, &pc.to_string())?
+        } else {
+            return Err(expected());
+        };
+        immediates.push(immediate);
+    }
+
+    if fields.next().is_some() {
+        return Err(expected());
+    }
+    Ok(AvmInstruction { opcode: instruction.opcode, indirect, tag, operands, immediates })
+}
+
+/// Parses a decimal or `0x` prefixed hexadecimal value which must fit in `typ`
+fn parse_value(typ: AvmOperandType, text: &str) -> Result<AvmOperand, String> {
+    let out_of_range = || format!("`{text}` is not a valid {typ:?} value");
+    if typ == AvmOperandType::FF {
+        let value = FieldElement::try_from_str(text).ok_or_else(out_of_range)?;
+        // Hex values are reduced modulo the field rather than rejected when they're too large
+        if let Some(hex) = text.strip_prefix("0x") {
+            if value.to_hex().trim_start_matches('0') != hex.to_lowercase().trim_start_matches('0')
+            {
+                return Err(out_of_range());
+            }
+        }
+        return Ok(AvmOperand::FF { value });
+    }
+
+    let value = match text.strip_prefix("0x") {
+        Some(hex) => u128::from_str_radix(hex, 16),
+        None => text.parse::<u128>(),
+    }
+    .map_err(|_| out_of_range())?;
+    Ok(match typ {
+        AvmOperandType::UINT8 => {
+            AvmOperand::U8 { value: value.try_into().map_err(|_| out_of_range())? }
+        }
+        AvmOperandType::UINT16 => {
+            AvmOperand::U16 { value: value.try_into().map_err(|_| out_of_range())? }
+        }
+        AvmOperandType::UINT32 => {
+            AvmOperand::U32 { value: value.try_into().map_err(|_| out_of_range())? }
+        }
+        AvmOperandType::UINT64 => {
+            AvmOperand::U64 { value: value.try_into().map_err(|_| out_of_range())? }
+        }
+        AvmOperandType::UINT128 => AvmOperand::U128 { value },
+        AvmOperandType::FF => unreachable!(),
+    })
+}
+
+/// The syntax of an instruction, for error messages
+fn syntax(opcode: AvmOpcode, layout: &AvmInstructionLayout) -> String {
+    let mut fields = Vec::new();
+    if let Some(typ) = layout.indirect {
+        fields.push(format!("[ind={typ:?}]"));
+    }
+    fields.extend(layout.operands.iter().map(|typ| format!("{typ:?}")));
+    if layout.tag {
+        fields.push("tag=TAG".to_string());
+    }
+    fields.extend(layout.immediates.iter().map(|typ| format!("#{typ:?}")));
+    if fields.is_empty() {
+        opcode.name().to_string()
+    } else {
+        format!("{} {}", opcode.name(), fields.join(", "))
+    }
+}
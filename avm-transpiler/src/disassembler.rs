@@ -0,0 +1,194 @@
+use std::collections::BTreeSet;
+use std::fmt::{self, Display, Formatter};
+
+use acvm::{AcirField, FieldElement};
+
+use crate::instructions::{AvmInstruction, AvmOperand, AvmTypeTag};
+use crate::opcodes::{AvmOpcode, AvmOperandType};
+
+/// Reasons why AVM bytecode can't be decoded
+#[derive(Debug)]
+pub enum DecodeError {
+    UnknownOpcode { pc: usize, byte: u8 },
+    Truncated { pc: usize, opcode: AvmOpcode },
+    UnknownTag { pc: usize, byte: u8 },
+    NonCanonicalField { pc: usize },
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnknownOpcode { pc, byte } => {
+                write!(f, "unknown opcode {byte:#04x} at pc {pc}")
+            }
+            DecodeError::Truncated { pc, opcode } => {
+                write!(f, "bytecode ends partway through {} at pc {pc}", opcode.name())
+            }
+            DecodeError::UnknownTag { pc, byte } => {
+                write!(f, "unknown type tag {byte} at pc {pc}")
+            }
+            DecodeError::NonCanonicalField { pc } => {
+                write!(f, "field immediate at pc {pc} is not less than the field modulus")
+            }
+        }
+    }
+}
+
+/// Decode AVM bytecode back into the instructions which `AvmInstruction::to_bytes` encoded it from.
+/// Every instruction must have all of the fields which its opcode's layout calls for.
+pub fn decode_bytecode(bytecode: &[u8]) -> Result<Vec<AvmInstruction>, DecodeError> {
+    let mut instructions = Vec::new();
+    let mut pc = 0;
+    while pc < bytecode.len() {
+        let opcode = AvmOpcode::from_u8(bytecode[pc])
+            .ok_or(DecodeError::UnknownOpcode { pc, byte: bytecode[pc] })?;
+        let layout = opcode.layout();
+        let encoded =
+            bytecode.get(pc..pc + layout.size()).ok_or(DecodeError::Truncated { pc, opcode })?;
+
+        // Skip the opcode itself
+        let mut fields = Fields { pc, encoded, offset: 1 };
+        let indirect = layout.indirect.map(|typ| fields.operand(typ)).transpose()?;
+        let operands =
+            layout.operands.iter().map(|typ| fields.operand(*typ)).collect::<Result<_, _>>()?;
+        let tag = if layout.tag {
+            let byte = fields.take(1)[0];
+            Some(AvmTypeTag::from_u8(byte).ok_or(DecodeError::UnknownTag { pc, byte })?)
+        } else {
+            None
+        };
+        let immediates =
+            layout.immediates.iter().map(|typ| fields.operand(*typ)).collect::<Result<_, _>>()?;
+
+        instructions.push(AvmInstruction { opcode, indirect, tag, operands, immediates });
+        pc += layout.size();
+    }
+    Ok(instructions)
+}
+
+/// Reads the fields of an encoded instruction in turn
+struct Fields<'a> {
+    pc: usize,
+    encoded: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Fields<'a> {
+    fn take(&mut self, size: usize) -> &'a [u8] {
+        let bytes = &self.encoded[self.offset..self.offset + size];
+        self.offset += size;
+        bytes
+    }
+
+    fn operand(&mut self, typ: AvmOperandType) -> Result<AvmOperand, DecodeError> {
+        let bytes = self.take(typ.size());
+        decode_operand(typ, bytes).ok_or(DecodeError::NonCanonicalField { pc: self.pc })
+    }
+}
+
+/// Returns None if a field element isn't in canonical form
+fn decode_operand(typ: AvmOperandType, bytes: &[u8]) -> Option<AvmOperand> {
+    Some(match typ {
+        AvmOperandType::UINT8 => AvmOperand::U8 { value: bytes[0] },
+        AvmOperandType::UINT16 => {
+            AvmOperand::U16 { value: u16::from_be_bytes(bytes.try_into().unwrap()) }
+        }
+        AvmOperandType::UINT32 => {
+            AvmOperand::U32 { value: u32::from_be_bytes(bytes.try_into().unwrap()) }
+        }
+        AvmOperandType::UINT64 => {
+            AvmOperand::U64 { value: u64::from_be_bytes(bytes.try_into().unwrap()) }
+        }
+        AvmOperandType::UINT128 => {
+            AvmOperand::U128 { value: u128::from_be_bytes(bytes.try_into().unwrap()) }
+        }
+        AvmOperandType::FF => {
+            let value = FieldElement::from_be_bytes_reduce(bytes);
+            if value.to_be_bytes() != bytes {
+                return None;
+            }
+            AvmOperand::FF { value }
+        }
+    })
+}
+
+/// Print AVM bytecode as assembly which `assemble` turns back into the same bytecode.
+///
+/// Each instruction is written as its opcode name followed by its fields in the order they're encoded:
+/// `ind=<flags>` for the addressing mode, the memory offset operands, `tag=<TAG>` and `#<value>` immediates.
+/// Locations which are jumped to or called are given labels.
+pub fn disassemble(bytecode: &[u8]) -> Result<String, DecodeError> {
+    let instructions = decode_bytecode(bytecode)?;
+
+    let mut instruction_pcs = BTreeSet::new();
+    let mut pc = 0;
+    for instruction in &instructions {
+        instruction_pcs.insert(pc);
+        pc += instruction.size();
+    }
+    let labels: BTreeSet<usize> = instructions
+        .iter()
+        .filter_map(jump_target)
+        .filter(|target| instruction_pcs.contains(target))
+        .collect();
+
+    let mut assembly = String::new();
+    let mut pc = 0;
+    for instruction in &instructions {
+        if labels.contains(&pc) {
+            assembly.push_str(&format!("L{pc}:\n"));
+        }
+        assembly.push_str(&format!("    {}\n", format_instruction(instruction, &labels)));
+        pc += instruction.size();
+    }
+    Ok(assembly)
+}
+
+/// The pc which a jump or internal call goes to
+fn jump_target(instruction: &AvmInstruction) -> Option<usize> {
+    match (instruction.opcode, instruction.immediates.as_slice()) {
+        (
+            AvmOpcode::JUMP_32 | AvmOpcode::JUMPI_32 | AvmOpcode::INTERNALCALL,
+            [AvmOperand::U32 { value }],
+        ) => Some(*value as usize),
+        _ => None,
+    }
+}
+
+fn format_instruction(instruction: &AvmInstruction, labels: &BTreeSet<usize>) -> String {
+    let mut fields = Vec::new();
+    if let Some(indirect) = &instruction.indirect {
+        fields.push(format!("ind={}", format_value(indirect)));
+    }
+    fields.extend(instruction.operands.iter().map(format_value));
+    if let Some(tag) = instruction.tag {
+        fields.push(format!("tag={tag:?}"));
+    }
+    match jump_target(instruction) {
+        Some(target) if labels.contains(&target) => fields.push(format!("@L{target}")),
+        _ => fields.extend(
+            instruction.immediates.iter().map(|immediate| format!("#{}", format_value(immediate))),
+        ),
+    }
+
+    if fields.is_empty() {
+        instruction.opcode.name().to_string()
+    } else {
+        format!("{} {}", instruction.opcode.name(), fields.join(", "))
+    }
+}
+
+fn format_value(operand: &AvmOperand) -> String {
+    match operand {
+        AvmOperand::U8 { value } => value.to_string(),
+        AvmOperand::U16 { value } => value.to_string(),
+        AvmOperand::U32 { value } => value.to_string(),
+        AvmOperand::U64 { value } => value.to_string(),
+        AvmOperand::U128 { value } => value.to_string(),
+        AvmOperand::FF { value } => match value.try_into_u128() {
+            Some(value) => value.to_string(),
+            None => format!("0x{}", value.to_hex()),
+        },
+        AvmOperand::BRILLIG_LOCATION { brillig_pc } => brillig_pc.to_string(),
+    }
+}
@@ -106,7 +106,7 @@ impl Default for AvmInstruction {
 
 /// AVM instructions may include a type tag
 #[allow(clippy::upper_case_acronyms, dead_code)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum AvmTypeTag {
     FIELD,
     UINT1,
@@ -118,6 +118,30 @@ pub enum AvmTypeTag {
     INVALID,
 }
 
+impl AvmTypeTag {
+    /// Every tag, indexed by the byte which encodes it.
+    const ALL: [AvmTypeTag; 8] = [
+        AvmTypeTag::FIELD,
+        AvmTypeTag::UINT1,
+        AvmTypeTag::UINT8,
+        AvmTypeTag::UINT16,
+        AvmTypeTag::UINT32,
+        AvmTypeTag::UINT64,
+        AvmTypeTag::UINT128,
+        AvmTypeTag::INVALID,
+    ];
+
+    /// Returns the tag encoded by `byte`, if there is one.
+    pub fn from_u8(byte: u8) -> Option<AvmTypeTag> {
+        Self::ALL.get(byte as usize).copied()
+    }
+
+    /// Returns the tag with the given name, as printed by `Debug`.
+    pub fn from_name(name: &str) -> Option<AvmTypeTag> {
+        Self::ALL.into_iter().find(|tag| format!("{tag:?}") == name)
+    }
+}
+
 /// Operands are usually 8, 16 and 32 bits (offsets)
 /// Immediates (as used by the SET instruction) can have different sizes
 #[allow(non_camel_case_types)]
@@ -160,6 +184,19 @@ impl AvmOperand {
             AvmOperand::BRILLIG_LOCATION { brillig_pc } => brillig_pc.to_be_bytes().to_vec(),
         }
     }
+
+    /// The value of the operand, as a field element
+    pub fn to_field(&self) -> FieldElement {
+        match self {
+            AvmOperand::U8 { value } => (*value as u128).into(),
+            AvmOperand::U16 { value } => (*value as u128).into(),
+            AvmOperand::U32 { value } => (*value as u128).into(),
+            AvmOperand::U64 { value } => (*value as u128).into(),
+            AvmOperand::U128 { value } => (*value).into(),
+            AvmOperand::FF { value } => *value,
+            AvmOperand::BRILLIG_LOCATION { brillig_pc } => (*brillig_pc as u128).into(),
+        }
+    }
 }
 
 #[derive(Debug, Default)]
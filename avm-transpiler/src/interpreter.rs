@@ -0,0 +1,862 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use acvm::blackbox_solver::{keccakf1600, sha256_compression, BlackBoxFunctionSolver};
+use acvm::{AcirField, FieldElement};
+
+use crate::disassembler::{decode_bytecode, DecodeError};
+use crate::instructions::{AvmInstruction, AvmTypeTag};
+use crate::opcodes::AvmOpcode;
+
+#[cfg(test)]
+mod tests;
+
+/// Memory offsets must be less than this, as in the simulator
+const MAX_MEMORY_SIZE: u64 = (1 << 32) - 1;
+
+/// A value in AVM memory along with its type tag
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryValue {
+    pub tag: AvmTypeTag,
+    pub value: FieldElement,
+}
+
+impl MemoryValue {
+    fn field(value: FieldElement) -> Self {
+        MemoryValue { tag: AvmTypeTag::FIELD, value }
+    }
+
+    fn integer(tag: AvmTypeTag, value: u128) -> Self {
+        MemoryValue { tag, value: value.into() }
+    }
+
+    fn boolean(value: bool) -> Self {
+        MemoryValue::integer(AvmTypeTag::UINT1, value as u128)
+    }
+}
+
+/// How a call finished
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExecutionResult {
+    Return(Vec<FieldElement>),
+    Revert(Vec<FieldElement>),
+}
+
+/// The public inputs of a call, which `GETENVVAR_16` and `CALLDATACOPY` read from
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionEnvironment {
+    pub address: FieldElement,
+    pub sender: FieldElement,
+    pub function_selector: u32,
+    pub transaction_fee: FieldElement,
+    pub chain_id: FieldElement,
+    pub version: FieldElement,
+    pub block_number: FieldElement,
+    pub timestamp: u64,
+    pub fee_per_l2_gas: FieldElement,
+    pub fee_per_da_gas: FieldElement,
+    pub is_static_call: bool,
+    /// The interpreter doesn't meter gas, so these are reported as the gas left throughout the call
+    pub l2_gas: u32,
+    pub da_gas: u32,
+    pub calldata: Vec<FieldElement>,
+}
+
+/// The parts of a deployed contract which `GETCONTRACTINSTANCE` can read
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ContractInstance {
+    pub deployer: FieldElement,
+    pub class_id: FieldElement,
+    pub initialization_hash: FieldElement,
+}
+
+/// The state outside of a call's memory, which the world state and external call opcodes act on.
+///
+/// `address` is always the address of the contract being executed, except for `nullifier_exists`
+/// and `contract_instance` where it's read from memory.
+pub trait WorldState {
+    fn storage_read(&mut self, address: FieldElement, slot: FieldElement) -> FieldElement;
+    fn storage_write(&mut self, address: FieldElement, slot: FieldElement, value: FieldElement);
+    fn note_hash_exists(
+        &mut self,
+        address: FieldElement,
+        note_hash: FieldElement,
+        leaf_index: FieldElement,
+    ) -> bool;
+    fn emit_note_hash(&mut self, address: FieldElement, note_hash: FieldElement);
+    fn nullifier_exists(&mut self, address: FieldElement, nullifier: FieldElement) -> bool;
+    /// Returns false if the nullifier has already been emitted
+    fn emit_nullifier(&mut self, address: FieldElement, nullifier: FieldElement) -> bool;
+    fn l1_to_l2_message_exists(
+        &mut self,
+        address: FieldElement,
+        message_hash: FieldElement,
+        leaf_index: FieldElement,
+    ) -> bool;
+    fn contract_instance(&mut self, address: FieldElement) -> Option<ContractInstance>;
+    fn emit_unencrypted_log(&mut self, address: FieldElement, log: Vec<FieldElement>);
+    fn send_l2_to_l1_message(
+        &mut self,
+        address: FieldElement,
+        recipient: FieldElement,
+        content: FieldElement,
+    );
+    /// Run a nested call, with at most the given amounts of gas
+    fn call(
+        &mut self,
+        address: FieldElement,
+        calldata: Vec<FieldElement>,
+        l2_gas: u32,
+        da_gas: u32,
+        is_static_call: bool,
+    ) -> ExecutionResult;
+    fn debug_log(&mut self, _message: &str, _fields: &[FieldElement]) {}
+}
+
+/// Reasons why the interpreter halts without returning or reverting
+#[derive(Debug)]
+pub enum InterpreterError {
+    Decode(DecodeError),
+    InvalidPc { pc: usize },
+    TagMismatch { pc: usize, offset: u32, expected: AvmTypeTag, found: AvmTypeTag },
+    InvalidTag { pc: usize, opcode: AvmOpcode, tag: AvmTypeTag },
+    AddressOutOfRange { pc: usize },
+    DivisionByZero { pc: usize },
+    EmptyInternalCallStack { pc: usize },
+    StaticCallAlteration { pc: usize, opcode: AvmOpcode },
+    Failed { pc: usize, opcode: AvmOpcode, message: String },
+}
+
+impl Display for InterpreterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpreterError::Decode(error) => write!(f, "{error}"),
+            InterpreterError::InvalidPc { pc } => {
+                write!(f, "pc {pc} is not the start of an instruction")
+            }
+            InterpreterError::TagMismatch { pc, offset, expected, found } => {
+                write!(f, "expected {expected:?} at offset {offset} but found {found:?} at pc {pc}")
+            }
+            InterpreterError::InvalidTag { pc, opcode, tag } => {
+                write!(f, "{} can't operate on {tag:?} values at pc {pc}", opcode.name())
+            }
+            InterpreterError::AddressOutOfRange { pc } => {
+                write!(f, "memory address out of range at pc {pc}")
+            }
+            InterpreterError::DivisionByZero { pc } => write!(f, "division by zero at pc {pc}"),
+            InterpreterError::EmptyInternalCallStack { pc } => {
+                write!(f, "INTERNALRETURN with an empty call stack at pc {pc}")
+            }
+            InterpreterError::StaticCallAlteration { pc, opcode } => {
+                write!(f, "{} can't alter state in a static call at pc {pc}", opcode.name())
+            }
+            InterpreterError::Failed { pc, opcode, message } => {
+                write!(f, "{} failed at pc {pc}: {message}", opcode.name())
+            }
+        }
+    }
+}
+
+/// Run AVM bytecode with the semantics of the simulator, as a reference to check the transpiler against.
+///
+/// Instructions which fail in the simulator (such as tag mismatches) halt with an error here,
+/// rather than reverting.
+pub fn execute<W: WorldState, B: BlackBoxFunctionSolver<FieldElement>>(
+    bytecode: &[u8],
+    environment: &ExecutionEnvironment,
+    world_state: &mut W,
+    black_box_solver: &B,
+) -> Result<ExecutionResult, InterpreterError> {
+    let instructions = decode_bytecode(bytecode).map_err(InterpreterError::Decode)?;
+    let mut pcs = HashMap::new();
+    let mut pc = 0;
+    for (index, instruction) in instructions.iter().enumerate() {
+        pcs.insert(pc, index);
+        pc += instruction.opcode.layout().size();
+    }
+
+    let mut machine = Machine {
+        environment,
+        world_state,
+        black_box_solver,
+        memory: HashMap::new(),
+        pc: 0,
+        internal_call_stack: Vec::new(),
+        nested_returndata: Vec::new(),
+    };
+    loop {
+        let index = *pcs.get(&machine.pc).ok_or(InterpreterError::InvalidPc { pc: machine.pc })?;
+        if let Some(result) = machine.execute_instruction(&instructions[index])? {
+            return Ok(result);
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    FDiv,
+    Eq,
+    Lt,
+    Lte,
+    And,
+    Or,
+    Xor,
+    Shl,
+    Shr,
+}
+
+impl BinaryOp {
+    fn of(opcode: AvmOpcode) -> BinaryOp {
+        match opcode {
+            AvmOpcode::ADD_8 | AvmOpcode::ADD_16 => BinaryOp::Add,
+            AvmOpcode::SUB_8 | AvmOpcode::SUB_16 => BinaryOp::Sub,
+            AvmOpcode::MUL_8 | AvmOpcode::MUL_16 => BinaryOp::Mul,
+            AvmOpcode::DIV_8 | AvmOpcode::DIV_16 => BinaryOp::Div,
+            AvmOpcode::FDIV_8 | AvmOpcode::FDIV_16 => BinaryOp::FDiv,
+            AvmOpcode::EQ_8 | AvmOpcode::EQ_16 => BinaryOp::Eq,
+            AvmOpcode::LT_8 | AvmOpcode::LT_16 => BinaryOp::Lt,
+            AvmOpcode::LTE_8 | AvmOpcode::LTE_16 => BinaryOp::Lte,
+            AvmOpcode::AND_8 | AvmOpcode::AND_16 => BinaryOp::And,
+            AvmOpcode::OR_8 | AvmOpcode::OR_16 => BinaryOp::Or,
+            AvmOpcode::XOR_8 | AvmOpcode::XOR_16 => BinaryOp::Xor,
+            AvmOpcode::SHL_8 | AvmOpcode::SHL_16 => BinaryOp::Shl,
+            AvmOpcode::SHR_8 | AvmOpcode::SHR_16 => BinaryOp::Shr,
+            _ => unreachable!("{} is not a binary operation", opcode.name()),
+        }
+    }
+}
+
+struct Machine<'a, W, B> {
+    environment: &'a ExecutionEnvironment,
+    world_state: &'a mut W,
+    black_box_solver: &'a B,
+    /// Offsets which haven't been written to read as a field zero
+    memory: HashMap<u32, MemoryValue>,
+    pc: usize,
+    internal_call_stack: Vec<usize>,
+    /// The output of the last nested call
+    nested_returndata: Vec<FieldElement>,
+}
+
+impl<'a, W: WorldState, B: BlackBoxFunctionSolver<FieldElement>> Machine<'a, W, B> {
+    /// Executes an instruction and moves the pc on, returning the result of the call if it has halted
+    fn execute_instruction(
+        &mut self,
+        instruction: &AvmInstruction,
+    ) -> Result<Option<ExecutionResult>, InterpreterError> {
+        let opcode = instruction.opcode;
+        let next_pc = self.pc + opcode.layout().size();
+        let offsets = self.resolve(instruction)?;
+
+        match opcode {
+            AvmOpcode::ADD_8
+            | AvmOpcode::ADD_16
+            | AvmOpcode::SUB_8
+            | AvmOpcode::SUB_16
+            | AvmOpcode::MUL_8
+            | AvmOpcode::MUL_16
+            | AvmOpcode::DIV_8
+            | AvmOpcode::DIV_16
+            | AvmOpcode::FDIV_8
+            | AvmOpcode::FDIV_16
+            | AvmOpcode::EQ_8
+            | AvmOpcode::EQ_16
+            | AvmOpcode::LT_8
+            | AvmOpcode::LT_16
+            | AvmOpcode::LTE_8
+            | AvmOpcode::LTE_16
+            | AvmOpcode::AND_8
+            | AvmOpcode::AND_16
+            | AvmOpcode::OR_8
+            | AvmOpcode::OR_16
+            | AvmOpcode::XOR_8
+            | AvmOpcode::XOR_16
+            | AvmOpcode::SHL_8
+            | AvmOpcode::SHL_16
+            | AvmOpcode::SHR_8
+            | AvmOpcode::SHR_16 => self.binary_op(opcode, &offsets)?,
+            AvmOpcode::NOT_8 | AvmOpcode::NOT_16 => {
+                let value = self.read(offsets[0]);
+                let bits = self.integral_bits(opcode, value.tag)?;
+                self.write(
+                    offsets[1],
+                    MemoryValue::integer(value.tag, !value.value.to_u128() & mask(bits)),
+                );
+            }
+            AvmOpcode::CAST_8 | AvmOpcode::CAST_16 => {
+                let value = self.read(offsets[0]).value;
+                let result = self.truncate(opcode, instruction.tag.unwrap(), value)?;
+                self.write(offsets[1], result);
+            }
+
+            AvmOpcode::GETENVVAR_16 => {
+                let value = self.environment_variable(opcode, immediate(instruction, 0))?;
+                self.write(offsets[0], value);
+            }
+            AvmOpcode::CALLDATACOPY => {
+                let start = self.read_u32(offsets[0])? as usize;
+                let size = self.read_u32(offsets[1])? as usize;
+                let data = copy_range(&self.environment.calldata, start, size);
+                self.write_slice(offsets[2], data)?;
+            }
+            AvmOpcode::RETURNDATASIZE => {
+                let size = self.nested_returndata.len() as u128;
+                self.write(offsets[0], MemoryValue::integer(AvmTypeTag::UINT32, size));
+            }
+            AvmOpcode::RETURNDATACOPY => {
+                let start = self.read_u32(offsets[0])? as usize;
+                let size = self.read_u32(offsets[1])? as usize;
+                let data = copy_range(&self.nested_returndata, start, size);
+                self.write_slice(offsets[2], data)?;
+            }
+
+            AvmOpcode::JUMP_32 => {
+                self.pc = immediate(instruction, 0) as usize;
+                return Ok(None);
+            }
+            AvmOpcode::JUMPI_32 => {
+                if !self.read(offsets[0]).value.is_zero() {
+                    self.pc = immediate(instruction, 0) as usize;
+                    return Ok(None);
+                }
+            }
+            AvmOpcode::INTERNALCALL => {
+                self.internal_call_stack.push(next_pc);
+                self.pc = immediate(instruction, 0) as usize;
+                return Ok(None);
+            }
+            AvmOpcode::INTERNALRETURN => {
+                self.pc = self
+                    .internal_call_stack
+                    .pop()
+                    .ok_or(InterpreterError::EmptyInternalCallStack { pc: self.pc })?;
+                return Ok(None);
+            }
+
+            AvmOpcode::SET_8
+            | AvmOpcode::SET_16
+            | AvmOpcode::SET_32
+            | AvmOpcode::SET_64
+            | AvmOpcode::SET_128
+            | AvmOpcode::SET_FF => {
+                let value = instruction.immediates[0].to_field();
+                let result = self.truncate(opcode, instruction.tag.unwrap(), value)?;
+                self.write(offsets[0], result);
+            }
+            AvmOpcode::MOV_8 | AvmOpcode::MOV_16 => {
+                let value = self.read(offsets[0]);
+                self.write(offsets[1], value);
+            }
+
+            AvmOpcode::SLOAD => {
+                let slot = self.read_tagged(offsets[0], AvmTypeTag::FIELD)?;
+                let value = self.world_state.storage_read(self.environment.address, slot);
+                self.write(offsets[1], MemoryValue::field(value));
+            }
+            AvmOpcode::SSTORE => {
+                self.check_not_static(opcode)?;
+                let value = self.read_tagged(offsets[0], AvmTypeTag::FIELD)?;
+                let slot = self.read_tagged(offsets[1], AvmTypeTag::FIELD)?;
+                self.world_state.storage_write(self.environment.address, slot, value);
+            }
+            AvmOpcode::NOTEHASHEXISTS => {
+                let note_hash = self.read_tagged(offsets[0], AvmTypeTag::FIELD)?;
+                let leaf_index = self.read_tagged(offsets[1], AvmTypeTag::FIELD)?;
+                let exists = self.world_state.note_hash_exists(
+                    self.environment.address,
+                    note_hash,
+                    leaf_index,
+                );
+                self.write(offsets[2], MemoryValue::boolean(exists));
+            }
+            AvmOpcode::EMITNOTEHASH => {
+                self.check_not_static(opcode)?;
+                let note_hash = self.read_tagged(offsets[0], AvmTypeTag::FIELD)?;
+                self.world_state.emit_note_hash(self.environment.address, note_hash);
+            }
+            AvmOpcode::NULLIFIEREXISTS => {
+                let nullifier = self.read_tagged(offsets[0], AvmTypeTag::FIELD)?;
+                let address = self.read_tagged(offsets[1], AvmTypeTag::FIELD)?;
+                let exists = self.world_state.nullifier_exists(address, nullifier);
+                self.write(offsets[2], MemoryValue::boolean(exists));
+            }
+            AvmOpcode::EMITNULLIFIER => {
+                self.check_not_static(opcode)?;
+                let nullifier = self.read_tagged(offsets[0], AvmTypeTag::FIELD)?;
+                if !self.world_state.emit_nullifier(self.environment.address, nullifier) {
+                    return Err(self.failed(opcode, "duplicate nullifier".to_string()));
+                }
+            }
+            AvmOpcode::L1TOL2MSGEXISTS => {
+                let message_hash = self.read_tagged(offsets[0], AvmTypeTag::FIELD)?;
+                let leaf_index = self.read_tagged(offsets[1], AvmTypeTag::FIELD)?;
+                let exists = self.world_state.l1_to_l2_message_exists(
+                    self.environment.address,
+                    message_hash,
+                    leaf_index,
+                );
+                self.write(offsets[2], MemoryValue::boolean(exists));
+            }
+            AvmOpcode::GETCONTRACTINSTANCE => {
+                let member = immediate(instruction, 0);
+                if member > 2 {
+                    return Err(self.failed(opcode, format!("invalid member {member}")));
+                }
+                let address = self.read_tagged(offsets[0], AvmTypeTag::FIELD)?;
+                let instance = self.world_state.contract_instance(address);
+                let value = instance.map_or(FieldElement::zero(), |instance| match member {
+                    0 => instance.deployer,
+                    1 => instance.class_id,
+                    _ => instance.initialization_hash,
+                });
+                self.write(offsets[2], MemoryValue::boolean(instance.is_some()));
+                self.write(offsets[1], MemoryValue::field(value));
+            }
+            AvmOpcode::EMITUNENCRYPTEDLOG => {
+                self.check_not_static(opcode)?;
+                let size = self.read_u32(offsets[1])?;
+                let log = self.read_slice(offsets[0], size, Some(AvmTypeTag::FIELD))?;
+                self.world_state.emit_unencrypted_log(self.environment.address, log);
+            }
+            AvmOpcode::SENDL2TOL1MSG => {
+                self.check_not_static(opcode)?;
+                let recipient = self.read_tagged(offsets[0], AvmTypeTag::FIELD)?;
+                let content = self.read_tagged(offsets[1], AvmTypeTag::FIELD)?;
+                self.world_state.send_l2_to_l1_message(
+                    self.environment.address,
+                    recipient,
+                    content,
+                );
+            }
+
+            AvmOpcode::CALL | AvmOpcode::STATICCALL => {
+                let l2_gas = self.read_tagged(offsets[0], AvmTypeTag::FIELD)?;
+                let da_gas = self.read_tagged(offsets[0] + 1, AvmTypeTag::FIELD)?;
+                let address = self.read_tagged(offsets[1], AvmTypeTag::FIELD)?;
+                let size = self.read_u32(offsets[3])?;
+                let calldata = self.read_slice(offsets[2], size, Some(AvmTypeTag::FIELD))?;
+                // Calls made from a static call are static too
+                let is_static_call =
+                    self.environment.is_static_call || opcode == AvmOpcode::STATICCALL;
+
+                let result = self.world_state.call(
+                    address,
+                    calldata,
+                    l2_gas.to_u128().min(self.environment.l2_gas.into()) as u32,
+                    da_gas.to_u128().min(self.environment.da_gas.into()) as u32,
+                    is_static_call,
+                );
+                let (success, returndata) = match result {
+                    ExecutionResult::Return(data) => (true, data),
+                    ExecutionResult::Revert(data) => (false, data),
+                };
+                self.nested_returndata = returndata;
+                self.write(offsets[4], MemoryValue::boolean(success));
+            }
+            AvmOpcode::RETURN | AvmOpcode::REVERT_8 | AvmOpcode::REVERT_16 => {
+                let size = self.read_u32(offsets[1])?;
+                let output = self.read_slice(offsets[0], size, None)?;
+                return Ok(Some(if opcode == AvmOpcode::RETURN {
+                    ExecutionResult::Return(output)
+                } else {
+                    ExecutionResult::Revert(output)
+                }));
+            }
+
+            AvmOpcode::DEBUGLOG => {
+                let fields_size = self.read_u32(offsets[2])?;
+                let message_size = immediate(instruction, 0) as u32;
+                let message = self.read_slice(offsets[0], message_size, Some(AvmTypeTag::UINT8))?;
+                let fields = self.read_slice(offsets[1], fields_size, Some(AvmTypeTag::FIELD))?;
+                let message: String =
+                    message.iter().map(|byte| byte.to_u128() as u8 as char).collect();
+                self.world_state.debug_log(&message, &fields);
+            }
+
+            AvmOpcode::POSEIDON2 => {
+                let input = self.read_slice(offsets[0], 4, Some(AvmTypeTag::FIELD))?;
+                let output = self
+                    .black_box_solver
+                    .poseidon2_permutation(&input, 4)
+                    .map_err(|err| self.failed(opcode, err.to_string()))?;
+                self.write_slice(offsets[1], output.into_iter().map(MemoryValue::field).collect())?;
+            }
+            AvmOpcode::SHA256COMPRESSION => {
+                let mut state = [0; 8];
+                let mut inputs = [0; 16];
+                let input_values = self.read_slice(offsets[2], 16, Some(AvmTypeTag::UINT32))?;
+                let state_values = self.read_slice(offsets[1], 8, Some(AvmTypeTag::UINT32))?;
+                for (word, value) in inputs.iter_mut().zip(input_values) {
+                    *word = value.to_u128() as u32;
+                }
+                for (word, value) in state.iter_mut().zip(state_values) {
+                    *word = value.to_u128() as u32;
+                }
+                sha256_compression(&mut state, &inputs);
+                let output = state
+                    .iter()
+                    .map(|word| MemoryValue::integer(AvmTypeTag::UINT32, *word as u128))
+                    .collect();
+                self.write_slice(offsets[0], output)?;
+            }
+            AvmOpcode::KECCAKF1600 => {
+                let mut state = [0; 25];
+                let values = self.read_slice(offsets[1], 25, Some(AvmTypeTag::UINT64))?;
+                for (lane, value) in state.iter_mut().zip(values) {
+                    *lane = value.to_u128() as u64;
+                }
+                let state =
+                    keccakf1600(state).map_err(|err| self.failed(opcode, err.to_string()))?;
+                let output = state
+                    .iter()
+                    .map(|lane| MemoryValue::integer(AvmTypeTag::UINT64, *lane as u128))
+                    .collect();
+                self.write_slice(offsets[0], output)?;
+            }
+            AvmOpcode::ECADD => {
+                let field = |i: usize| self.read_tagged(offsets[i], AvmTypeTag::FIELD);
+                let flag = |i: usize| self.read_tagged(offsets[i], AvmTypeTag::UINT1);
+                let (x, y, infinite) = self
+                    .black_box_solver
+                    .ec_add(&field(0)?, &field(1)?, &flag(2)?, &field(3)?, &field(4)?, &flag(5)?)
+                    .map_err(|err| self.failed(opcode, err.to_string()))?;
+                self.write_point(offsets[6], x, y, infinite)?;
+            }
+            AvmOpcode::MSM => {
+                let points_length = self.read_u32(offsets[3])?;
+                if points_length % 3 != 0 {
+                    let message = format!("points length {points_length} is not a multiple of 3");
+                    return Err(self.failed(opcode, message));
+                }
+                let num_points = points_length / 3;
+                let mut points = Vec::new();
+                for i in 0..num_points {
+                    points.extend(self.read_slice(
+                        offsets[0] + 3 * i,
+                        2,
+                        Some(AvmTypeTag::FIELD),
+                    )?);
+                    points.push(self.read_tagged(offsets[0] + 3 * i + 2, AvmTypeTag::UINT1)?);
+                }
+                let scalars =
+                    self.read_slice(offsets[1], num_points * 2, Some(AvmTypeTag::FIELD))?;
+                let scalars_lo: Vec<_> = scalars.iter().step_by(2).copied().collect();
+                let scalars_hi: Vec<_> = scalars.iter().skip(1).step_by(2).copied().collect();
+                let (x, y, infinite) = self
+                    .black_box_solver
+                    .multi_scalar_mul(&points, &scalars_lo, &scalars_hi)
+                    .map_err(|err| self.failed(opcode, err.to_string()))?;
+                self.write_point(offsets[2], x, y, infinite)?;
+            }
+
+            AvmOpcode::TORADIXBE => {
+                let value = self.read_tagged(offsets[0], AvmTypeTag::FIELD)?;
+                let radix = self.read_u32(offsets[2])?;
+                let num_limbs = immediate(instruction, 0) as usize;
+                let tag = if immediate(instruction, 1) != 0 {
+                    AvmTypeTag::UINT1
+                } else {
+                    AvmTypeTag::UINT8
+                };
+                let limbs = self.to_radix(opcode, value, radix, num_limbs, tag)?;
+                self.write_slice(offsets[1], limbs)?;
+            }
+        }
+
+        self.pc = next_pc;
+        Ok(None)
+    }
+
+    /// The memory offsets which an instruction's operands refer to.
+    ///
+    /// Relative operands are offset from the value at address 0, and then indirect operands are
+    /// replaced by the value at their offset.
+    fn resolve(&self, instruction: &AvmInstruction) -> Result<Vec<u32>, InterpreterError> {
+        let mode =
+            instruction.indirect.as_ref().map_or(0, |indirect| indirect.to_field().to_u128());
+        let num_operands = instruction.operands.len();
+        let mut offsets = Vec::with_capacity(num_operands);
+        for (i, operand) in instruction.operands.iter().enumerate() {
+            let mut offset = operand.to_field().to_u128() as u64;
+            if (mode >> (num_operands + i)) & 1 == 1 {
+                offset += self.read_u32(0)? as u64;
+                if offset >= MAX_MEMORY_SIZE {
+                    return Err(InterpreterError::AddressOutOfRange { pc: self.pc });
+                }
+            }
+            let mut offset = offset as u32;
+            if (mode >> i) & 1 == 1 {
+                offset = self.read_u32(offset)?;
+            }
+            offsets.push(offset);
+        }
+        Ok(offsets)
+    }
+
+    fn binary_op(&mut self, opcode: AvmOpcode, offsets: &[u32]) -> Result<(), InterpreterError> {
+        let op = BinaryOp::of(opcode);
+        let lhs = self.read(offsets[0]);
+        let rhs = match op {
+            BinaryOp::Shl | BinaryOp::Shr => {
+                MemoryValue::integer(AvmTypeTag::UINT8, self.read_u8(offsets[1])?.into())
+            }
+            _ => {
+                let rhs = self.read(offsets[1]);
+                if rhs.tag != lhs.tag {
+                    return Err(InterpreterError::TagMismatch {
+                        pc: self.pc,
+                        offset: offsets[1],
+                        expected: lhs.tag,
+                        found: rhs.tag,
+                    });
+                }
+                rhs
+            }
+        };
+
+        let result = if lhs.tag == AvmTypeTag::FIELD {
+            let (a, b) = (lhs.value, rhs.value);
+            match op {
+                BinaryOp::Add => MemoryValue::field(a + b),
+                BinaryOp::Sub => MemoryValue::field(a - b),
+                BinaryOp::Mul => MemoryValue::field(a * b),
+                BinaryOp::FDiv if b.is_zero() => {
+                    return Err(InterpreterError::DivisionByZero { pc: self.pc })
+                }
+                BinaryOp::FDiv => MemoryValue::field(a / b),
+                BinaryOp::Eq => MemoryValue::boolean(a == b),
+                BinaryOp::Lt => MemoryValue::boolean(a < b),
+                BinaryOp::Lte => MemoryValue::boolean(a <= b),
+                _ => {
+                    return Err(InterpreterError::InvalidTag { pc: self.pc, opcode, tag: lhs.tag })
+                }
+            }
+        } else {
+            let bits = self.integral_bits(opcode, lhs.tag)?;
+            let (a, b) = (lhs.value.to_u128(), rhs.value.to_u128());
+            let integer = |value| MemoryValue::integer(lhs.tag, value);
+            match op {
+                BinaryOp::Add => integer(a.wrapping_add(b) & mask(bits)),
+                BinaryOp::Sub => integer(a.wrapping_sub(b) & mask(bits)),
+                BinaryOp::Mul => integer(a.wrapping_mul(b) & mask(bits)),
+                BinaryOp::Div if b == 0 => {
+                    return Err(InterpreterError::DivisionByZero { pc: self.pc })
+                }
+                BinaryOp::Div => integer(a / b),
+                BinaryOp::FDiv => {
+                    return Err(InterpreterError::InvalidTag { pc: self.pc, opcode, tag: lhs.tag })
+                }
+                BinaryOp::Eq => MemoryValue::boolean(a == b),
+                BinaryOp::Lt => MemoryValue::boolean(a < b),
+                BinaryOp::Lte => MemoryValue::boolean(a <= b),
+                BinaryOp::And => integer(a & b),
+                BinaryOp::Or => integer(a | b),
+                BinaryOp::Xor => integer(a ^ b),
+                BinaryOp::Shl if b >= bits as u128 => integer(0),
+                BinaryOp::Shl => integer((a << b) & mask(bits)),
+                BinaryOp::Shr if b >= bits as u128 => integer(0),
+                BinaryOp::Shr => integer(a >> b),
+            }
+        };
+        self.write(offsets[2], result);
+        Ok(())
+    }
+
+    fn environment_variable(
+        &self,
+        opcode: AvmOpcode,
+        variable: u128,
+    ) -> Result<MemoryValue, InterpreterError> {
+        let environment = self.environment;
+        Ok(match variable {
+            0 => MemoryValue::field(environment.address),
+            1 => MemoryValue::field(environment.sender),
+            2 => MemoryValue::integer(AvmTypeTag::UINT32, environment.function_selector.into()),
+            3 => MemoryValue::field(environment.transaction_fee),
+            4 => MemoryValue::field(environment.chain_id),
+            5 => MemoryValue::field(environment.version),
+            6 => MemoryValue::field(environment.block_number),
+            7 => MemoryValue::integer(AvmTypeTag::UINT64, environment.timestamp.into()),
+            8 => MemoryValue::field(environment.fee_per_l2_gas),
+            9 => MemoryValue::field(environment.fee_per_da_gas),
+            10 => MemoryValue::field((environment.is_static_call as u128).into()),
+            11 => MemoryValue::field((environment.l2_gas as u128).into()),
+            12 => MemoryValue::field((environment.da_gas as u128).into()),
+            _ => {
+                return Err(self.failed(opcode, format!("unknown environment variable {variable}")))
+            }
+        })
+    }
+
+    /// The big-endian digits of `value` in `radix`, dropping any which don't fit in `num_limbs`
+    fn to_radix(
+        &self,
+        opcode: AvmOpcode,
+        value: FieldElement,
+        radix: u32,
+        num_limbs: usize,
+        tag: AvmTypeTag,
+    ) -> Result<Vec<MemoryValue>, InterpreterError> {
+        if num_limbs == 0 {
+            return Err(self.failed(opcode, "number of limbs must be at least 1".to_string()));
+        }
+        if radix > 256 {
+            return Err(self.failed(opcode, format!("radix {radix} is greater than 256")));
+        }
+        if radix == 0 {
+            return Err(InterpreterError::DivisionByZero { pc: self.pc });
+        }
+
+        let mut bytes = value.to_be_bytes();
+        let mut limbs = vec![MemoryValue::integer(tag, 0); num_limbs];
+        for limb in limbs.iter_mut().rev() {
+            // Long division of the bytes by the radix, leaving the quotient in their place
+            let mut remainder = 0;
+            for byte in bytes.iter_mut() {
+                let dividend = (remainder << 8) | *byte as u32;
+                *byte = (dividend / radix) as u8;
+                remainder = dividend % radix;
+            }
+            if tag == AvmTypeTag::UINT1 && remainder > 1 {
+                let message = format!("limb {remainder} doesn't fit in {tag:?}");
+                return Err(self.failed(opcode, message));
+            }
+            *limb = MemoryValue::integer(tag, remainder.into());
+        }
+        Ok(limbs)
+    }
+
+    /// Builds a value of type `tag`, keeping only the bits which fit if it's an integer
+    fn truncate(
+        &self,
+        opcode: AvmOpcode,
+        tag: AvmTypeTag,
+        value: FieldElement,
+    ) -> Result<MemoryValue, InterpreterError> {
+        if tag == AvmTypeTag::FIELD {
+            return Ok(MemoryValue::field(value));
+        }
+        let bits = self.integral_bits(opcode, tag)?;
+        Ok(MemoryValue::integer(tag, value.to_u128() & mask(bits)))
+    }
+
+    fn integral_bits(&self, opcode: AvmOpcode, tag: AvmTypeTag) -> Result<u32, InterpreterError> {
+        match tag {
+            AvmTypeTag::UINT1 => Ok(1),
+            AvmTypeTag::UINT8 => Ok(8),
+            AvmTypeTag::UINT16 => Ok(16),
+            AvmTypeTag::UINT32 => Ok(32),
+            AvmTypeTag::UINT64 => Ok(64),
+            AvmTypeTag::UINT128 => Ok(128),
+            AvmTypeTag::FIELD | AvmTypeTag::INVALID => {
+                Err(InterpreterError::InvalidTag { pc: self.pc, opcode, tag })
+            }
+        }
+    }
+
+    fn check_not_static(&self, opcode: AvmOpcode) -> Result<(), InterpreterError> {
+        if self.environment.is_static_call {
+            return Err(InterpreterError::StaticCallAlteration { pc: self.pc, opcode });
+        }
+        Ok(())
+    }
+
+    fn failed(&self, opcode: AvmOpcode, message: String) -> InterpreterError {
+        InterpreterError::Failed { pc: self.pc, opcode, message }
+    }
+
+    fn read(&self, offset: u32) -> MemoryValue {
+        self.memory.get(&offset).copied().unwrap_or(MemoryValue::field(FieldElement::zero()))
+    }
+
+    fn read_tagged(&self, offset: u32, tag: AvmTypeTag) -> Result<FieldElement, InterpreterError> {
+        let value = self.read(offset);
+        if value.tag != tag {
+            return Err(InterpreterError::TagMismatch {
+                pc: self.pc,
+                offset,
+                expected: tag,
+                found: value.tag,
+            });
+        }
+        Ok(value.value)
+    }
+
+    fn read_u8(&self, offset: u32) -> Result<u8, InterpreterError> {
+        Ok(self.read_tagged(offset, AvmTypeTag::UINT8)?.to_u128() as u8)
+    }
+
+    fn read_u32(&self, offset: u32) -> Result<u32, InterpreterError> {
+        Ok(self.read_tagged(offset, AvmTypeTag::UINT32)?.to_u128() as u32)
+    }
+
+    /// Reads `size` values starting at `offset`, checking that they all have the type `tag` if given
+    fn read_slice(
+        &self,
+        offset: u32,
+        size: u32,
+        tag: Option<AvmTypeTag>,
+    ) -> Result<Vec<FieldElement>, InterpreterError> {
+        if offset as u64 + size as u64 > MAX_MEMORY_SIZE {
+            return Err(InterpreterError::AddressOutOfRange { pc: self.pc });
+        }
+        (offset..offset + size)
+            .map(|offset| match tag {
+                Some(tag) => self.read_tagged(offset, tag),
+                None => Ok(self.read(offset).value),
+            })
+            .collect()
+    }
+
+    fn write(&mut self, offset: u32, value: MemoryValue) {
+        self.memory.insert(offset, value);
+    }
+
+    fn write_slice(
+        &mut self,
+        offset: u32,
+        values: Vec<MemoryValue>,
+    ) -> Result<(), InterpreterError> {
+        if offset as u64 + values.len() as u64 > MAX_MEMORY_SIZE {
+            return Err(InterpreterError::AddressOutOfRange { pc: self.pc });
+        }
+        for (value_offset, value) in (offset..).zip(values) {
+            self.write(value_offset, value);
+        }
+        Ok(())
+    }
+
+    /// Writes the coordinates of a curve point followed by whether it's the point at infinity
+    fn write_point(
+        &mut self,
+        offset: u32,
+        x: FieldElement,
+        y: FieldElement,
+        infinite: FieldElement,
+    ) -> Result<(), InterpreterError> {
+        let infinite = MemoryValue::boolean(infinite.is_one());
+        self.write_slice(offset, vec![MemoryValue::field(x), MemoryValue::field(y), infinite])
+    }
+}
+
+fn mask(bits: u32) -> u128 {
+    if bits == 128 {
+        u128::MAX
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+/// Copies up to `size` values from `data` starting at `start`, as fields
+fn copy_range(data: &[FieldElement], start: usize, size: usize) -> Vec<MemoryValue> {
+    data.iter().skip(start).take(size).copied().map(MemoryValue::field).collect()
+}
+
+/// The value of an integer immediate of an instruction
+fn immediate(instruction: &AvmInstruction, index: usize) -> u128 {
+    instruction.immediates[index].to_field().to_u128()
+}
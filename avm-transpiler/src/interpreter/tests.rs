@@ -0,0 +1,729 @@
+//! Differential tests which run Brillig programs in the Brillig VM and their transpiled bytecode in
+//! the interpreter, checking that they return the same values for random inputs.
+
+use std::collections::{HashMap, HashSet};
+
+use acvm::acir::brillig::{
+    BinaryFieldOp, BinaryIntOp, BitSize, HeapArray, HeapVector, IntegerBitSize, MemoryAddress,
+    Opcode as BrilligOpcode,
+};
+use acvm::blackbox_solver::StubbedBlackBoxSolver;
+use acvm::brillig_vm::brillig::BlackBoxOp;
+use acvm::brillig_vm::{FailureReason, VMStatus, VM};
+use acvm::{AcirField, FieldElement};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use super::{execute, ContractInstance, ExecutionEnvironment, ExecutionResult, WorldState};
+use crate::assembler::assemble;
+use crate::transpile::brillig_to_avm;
+
+const INTEGER_BIT_SIZES: [IntegerBitSize; 6] = [
+    IntegerBitSize::U1,
+    IntegerBitSize::U8,
+    IntegerBitSize::U16,
+    IntegerBitSize::U32,
+    IntegerBitSize::U64,
+    IntegerBitSize::U128,
+];
+
+/// Runs a Brillig program, returning None if it fails with a runtime error
+fn run_brillig(
+    program: &[BrilligOpcode<FieldElement>],
+    calldata: &[FieldElement],
+) -> Option<ExecutionResult> {
+    let mut vm = VM::new(calldata.to_vec(), program, vec![], &StubbedBlackBoxSolver, false);
+    let read = |vm: &VM<_, _>, offset: usize, size: usize| {
+        vm.get_memory()[offset..offset + size].iter().map(|value| value.to_field()).collect()
+    };
+    match vm.process_opcodes() {
+        VMStatus::Finished { return_data_offset, return_data_size } => {
+            Some(ExecutionResult::Return(read(&vm, return_data_offset, return_data_size)))
+        }
+        VMStatus::Failure {
+            reason: FailureReason::Trap { revert_data_offset, revert_data_size },
+            ..
+        } => Some(ExecutionResult::Revert(read(&vm, revert_data_offset, revert_data_size))),
+        VMStatus::Failure { reason: FailureReason::RuntimeError { .. }, .. } => None,
+        status => panic!("unexpected Brillig VM status {status:?}"),
+    }
+}
+
+/// Transpiles a Brillig program and runs it in the interpreter, returning None if it fails
+fn run_transpiled(
+    program: &[BrilligOpcode<FieldElement>],
+    calldata: &[FieldElement],
+) -> Option<ExecutionResult> {
+    let (bytecode, _) = brillig_to_avm(program).unwrap();
+    let environment = ExecutionEnvironment { calldata: calldata.to_vec(), ..Default::default() };
+    execute(&bytecode, &environment, &mut TestWorldState::default(), &StubbedBlackBoxSolver).ok()
+}
+
+fn assert_equivalent(program: &[BrilligOpcode<FieldElement>], calldata: &[FieldElement]) {
+    let expected = run_brillig(program, calldata);
+    let actual = run_transpiled(program, calldata);
+    assert_eq!(
+        actual, expected,
+        "transpiled program disagrees with the Brillig VM\ncalldata: {calldata:?}\nprogram: {program:#?}"
+    );
+}
+
+fn address(offset: usize) -> MemoryAddress {
+    MemoryAddress::direct(offset)
+}
+
+fn u32_const(destination: MemoryAddress, value: usize) -> BrilligOpcode<FieldElement> {
+    BrilligOpcode::Const {
+        destination,
+        bit_size: BitSize::Integer(IntegerBitSize::U32),
+        value: FieldElement::from(value),
+    }
+}
+
+/// Copies the calldata to `destination`, using addresses 1 and 2 as scratch space
+fn copy_calldata(destination: usize, size: usize) -> Vec<BrilligOpcode<FieldElement>> {
+    vec![
+        u32_const(address(1), size),
+        u32_const(address(2), 0),
+        BrilligOpcode::CalldataCopy {
+            destination_address: address(destination),
+            size_address: address(1),
+            offset_address: address(2),
+        },
+    ]
+}
+
+/// Returns the values at `offset..offset + size`, using addresses 3 and 4 as scratch space
+fn stop(offset: usize, size: usize) -> Vec<BrilligOpcode<FieldElement>> {
+    vec![
+        u32_const(address(3), offset),
+        u32_const(address(4), size),
+        BrilligOpcode::Stop { return_data: HeapVector { pointer: address(3), size: address(4) } },
+    ]
+}
+
+fn random_field(rng: &mut StdRng) -> FieldElement {
+    match rng.gen_range(0..4) {
+        0 => FieldElement::from(rng.gen_range(0..4_u128)),
+        1 => -FieldElement::from(rng.gen_range(1..4_u128)),
+        2 => FieldElement::from(rng.gen::<u128>()),
+        _ => FieldElement::from_be_bytes_reduce(&rng.gen::<[u8; 32]>()),
+    }
+}
+
+fn random_value(rng: &mut StdRng, bit_size: BitSize) -> FieldElement {
+    match bit_size {
+        BitSize::Field => random_field(rng),
+        BitSize::Integer(bit_size) => {
+            let bits: u32 = bit_size.into();
+            let value = match rng.gen_range(0..3) {
+                0 => rng.gen_range(0..4),
+                1 => u128::MAX - rng.gen_range(0..4),
+                _ => rng.gen(),
+            };
+            FieldElement::from(value & super::mask(bits))
+        }
+    }
+}
+
+/// Builds a straight line Brillig program out of random operations on its inputs,
+/// giving every result a new address and returning all of them.
+struct RandomProgram {
+    rng: StdRng,
+    opcodes: Vec<BrilligOpcode<FieldElement>>,
+    /// The bit size of the value at each address from `base`
+    values: Vec<BitSize>,
+    base: usize,
+}
+
+impl RandomProgram {
+    fn generate(seed: u64) -> (Vec<BrilligOpcode<FieldElement>>, Vec<FieldElement>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        // Some programs straddle address 256 so that both the 8 and 16 bit opcodes are used
+        let base = *[16, 240, 1000].choose(&mut rng).unwrap();
+        let num_inputs = rng.gen_range(1..=6);
+        let calldata = (0..num_inputs).map(|_| random_field(&mut rng)).collect();
+
+        let mut program = RandomProgram {
+            rng,
+            opcodes: copy_calldata(base, num_inputs),
+            values: vec![BitSize::Field; num_inputs],
+            base,
+        };
+        for input in 0..num_inputs {
+            let bit_size = program.random_bit_size();
+            program.opcodes.push(BrilligOpcode::Cast {
+                destination: program.address(input),
+                source: program.address(input),
+                bit_size,
+            });
+            program.values[input] = bit_size;
+        }
+        for _ in 0..program.rng.gen_range(1..=40) {
+            program.random_operation();
+        }
+
+        let mut opcodes = program.opcodes;
+        opcodes.extend(stop(program.base, program.values.len()));
+        (opcodes, calldata)
+    }
+
+    fn address(&self, index: usize) -> MemoryAddress {
+        address(self.base + index)
+    }
+
+    fn random_bit_size(&mut self) -> BitSize {
+        if self.rng.gen_bool(0.25) {
+            BitSize::Field
+        } else {
+            BitSize::Integer(*INTEGER_BIT_SIZES.choose(&mut self.rng).unwrap())
+        }
+    }
+
+    /// An address holding a value of the given bit size, which is made if there isn't one already
+    fn operand(&mut self, bit_size: BitSize) -> MemoryAddress {
+        let candidates: Vec<usize> =
+            (0..self.values.len()).filter(|index| self.values[*index] == bit_size).collect();
+        match candidates.choose(&mut self.rng) {
+            Some(index) if self.rng.gen_bool(0.8) => self.address(*index),
+            _ => {
+                let value = random_value(&mut self.rng, bit_size);
+                self.constant(bit_size, value)
+            }
+        }
+    }
+
+    fn constant(&mut self, bit_size: BitSize, value: FieldElement) -> MemoryAddress {
+        let destination = self.push(bit_size);
+        self.opcodes.push(BrilligOpcode::Const { destination, bit_size, value });
+        destination
+    }
+
+    /// The address for a new value
+    fn push(&mut self, bit_size: BitSize) -> MemoryAddress {
+        self.values.push(bit_size);
+        self.address(self.values.len() - 1)
+    }
+
+    fn random_operation(&mut self) {
+        let bit_size = self.random_bit_size();
+        match (bit_size, self.rng.gen_range(0..4)) {
+            (BitSize::Field, 0 | 1) => {
+                let op = *[
+                    BinaryFieldOp::Add,
+                    BinaryFieldOp::Sub,
+                    BinaryFieldOp::Mul,
+                    BinaryFieldOp::Div,
+                    BinaryFieldOp::Equals,
+                    BinaryFieldOp::LessThan,
+                    BinaryFieldOp::LessThanEquals,
+                ]
+                .choose(&mut self.rng)
+                .unwrap();
+                let lhs = self.operand(bit_size);
+                // The Brillig VM divides fields by zero to get zero but the simulator fails,
+                // so the divisor is always a non-zero constant.
+                // Integer division of fields isn't used as the simulator only divides integers.
+                let rhs = if op == BinaryFieldOp::Div {
+                    let value = random_field(&mut self.rng);
+                    let value = if value.is_zero() { FieldElement::one() } else { value };
+                    self.constant(bit_size, value)
+                } else {
+                    self.operand(bit_size)
+                };
+                let result_bit_size = match op {
+                    BinaryFieldOp::Equals
+                    | BinaryFieldOp::LessThan
+                    | BinaryFieldOp::LessThanEquals => BitSize::Integer(IntegerBitSize::U1),
+                    _ => BitSize::Field,
+                };
+                let destination = self.push(result_bit_size);
+                self.opcodes.push(BrilligOpcode::BinaryFieldOp { destination, op, lhs, rhs });
+            }
+            (BitSize::Integer(integer_bit_size), 0 | 1) => {
+                let op = *[
+                    BinaryIntOp::Add,
+                    BinaryIntOp::Sub,
+                    BinaryIntOp::Mul,
+                    BinaryIntOp::Div,
+                    BinaryIntOp::Equals,
+                    BinaryIntOp::LessThan,
+                    BinaryIntOp::LessThanEquals,
+                    BinaryIntOp::And,
+                    BinaryIntOp::Or,
+                    BinaryIntOp::Xor,
+                    BinaryIntOp::Shl,
+                    BinaryIntOp::Shr,
+                ]
+                .choose(&mut self.rng)
+                .unwrap();
+                let lhs = self.operand(bit_size);
+                let rhs = match op {
+                    // Shifts are by a u8 in both the Brillig VM and the simulator
+                    BinaryIntOp::Shl | BinaryIntOp::Shr => {
+                        let shift = self.rng.gen_range(0..=(u32::from(integer_bit_size) + 1));
+                        self.constant(
+                            BitSize::Integer(IntegerBitSize::U8),
+                            FieldElement::from(shift as u128),
+                        )
+                    }
+                    _ => self.operand(bit_size),
+                };
+                let result_bit_size = match op {
+                    BinaryIntOp::Equals | BinaryIntOp::LessThan | BinaryIntOp::LessThanEquals => {
+                        BitSize::Integer(IntegerBitSize::U1)
+                    }
+                    _ => bit_size,
+                };
+                let destination = self.push(result_bit_size);
+                self.opcodes.push(BrilligOpcode::BinaryIntOp {
+                    destination,
+                    op,
+                    bit_size: integer_bit_size,
+                    lhs,
+                    rhs,
+                });
+            }
+            (BitSize::Integer(integer_bit_size), 2) => {
+                let source = self.operand(bit_size);
+                let destination = self.push(bit_size);
+                self.opcodes.push(BrilligOpcode::Not {
+                    destination,
+                    source,
+                    bit_size: integer_bit_size,
+                });
+            }
+            (_, 2) => {
+                let source = self.operand(bit_size);
+                let destination = self.push(bit_size);
+                self.opcodes.push(BrilligOpcode::Mov { destination, source });
+            }
+            _ => {
+                let source_bit_size = self.random_bit_size();
+                let source = self.operand(source_bit_size);
+                let destination = self.push(bit_size);
+                self.opcodes.push(BrilligOpcode::Cast { destination, source, bit_size });
+            }
+        }
+    }
+}
+
+#[test]
+fn random_straight_line_programs() {
+    for seed in 0..500 {
+        let (program, calldata) = RandomProgram::generate(seed);
+        assert_equivalent(&program, &calldata);
+    }
+}
+
+/// Sums the squares of the numbers below the input, which is cast to a u8
+#[test]
+fn loops() {
+    let n = address(10);
+    let i = address(11);
+    let sum = address(12);
+    let one = address(13);
+    let condition = address(14);
+    let square = address(15);
+    let u8_size = BitSize::Integer(IntegerBitSize::U8);
+    let u64_size = BitSize::Integer(IntegerBitSize::U64);
+
+    let mut program = copy_calldata(10, 1);
+    program.extend([
+        BrilligOpcode::Cast { destination: n, source: n, bit_size: u8_size },
+        BrilligOpcode::Const { destination: i, bit_size: u64_size, value: FieldElement::zero() },
+        BrilligOpcode::Const { destination: sum, bit_size: u64_size, value: FieldElement::zero() },
+        BrilligOpcode::Const { destination: one, bit_size: u64_size, value: FieldElement::one() },
+        BrilligOpcode::Cast { destination: n, source: n, bit_size: u64_size },
+    ]);
+    let loop_start = program.len();
+    program.extend([
+        BrilligOpcode::BinaryIntOp {
+            destination: condition,
+            op: BinaryIntOp::LessThan,
+            bit_size: IntegerBitSize::U64,
+            lhs: i,
+            rhs: n,
+        },
+        BrilligOpcode::JumpIf { condition, location: loop_start + 3 },
+        BrilligOpcode::Jump { location: loop_start + 7 },
+        BrilligOpcode::BinaryIntOp {
+            destination: square,
+            op: BinaryIntOp::Mul,
+            bit_size: IntegerBitSize::U64,
+            lhs: i,
+            rhs: i,
+        },
+        BrilligOpcode::BinaryIntOp {
+            destination: sum,
+            op: BinaryIntOp::Add,
+            bit_size: IntegerBitSize::U64,
+            lhs: sum,
+            rhs: square,
+        },
+        BrilligOpcode::BinaryIntOp {
+            destination: i,
+            op: BinaryIntOp::Add,
+            bit_size: IntegerBitSize::U64,
+            lhs: i,
+            rhs: one,
+        },
+        BrilligOpcode::Jump { location: loop_start },
+    ]);
+    program.extend(stop(12, 1));
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..20 {
+        assert_equivalent(&program, &[FieldElement::from(rng.gen::<u8>() as u128)]);
+    }
+}
+
+/// Calls a function which uses relative addresses to multiply its arguments, and reverts if
+/// the product is less than the first input
+#[test]
+fn internal_calls_with_relative_addresses() {
+    let field = BitSize::Field;
+    let frame = 100;
+    let mut program = copy_calldata(10, 2);
+    program.extend([
+        // Point the stack at the frame of the function, whose arguments are at offsets 1 and 2
+        u32_const(address(0), frame),
+        BrilligOpcode::Mov { destination: address(frame + 1), source: address(10) },
+        BrilligOpcode::Mov { destination: address(frame + 2), source: address(11) },
+        BrilligOpcode::Call { location: 13 },
+        BrilligOpcode::Mov { destination: address(12), source: address(frame + 3) },
+        BrilligOpcode::BinaryFieldOp {
+            destination: address(13),
+            op: BinaryFieldOp::LessThan,
+            lhs: address(12),
+            rhs: address(10),
+        },
+        BrilligOpcode::JumpIf { condition: address(13), location: 17 },
+    ]);
+    program.extend(stop(12, 1));
+    assert_eq!(program.len(), 13);
+    program.extend([
+        BrilligOpcode::BinaryFieldOp {
+            destination: MemoryAddress::relative(3),
+            op: BinaryFieldOp::Mul,
+            lhs: MemoryAddress::relative(1),
+            rhs: MemoryAddress::relative(2),
+        },
+        BrilligOpcode::Const {
+            destination: MemoryAddress::relative(4),
+            bit_size: field,
+            value: FieldElement::from(7_u128),
+        },
+        BrilligOpcode::BinaryFieldOp {
+            destination: MemoryAddress::relative(3),
+            op: BinaryFieldOp::Add,
+            lhs: MemoryAddress::relative(3),
+            rhs: MemoryAddress::relative(4),
+        },
+        BrilligOpcode::Return,
+        // Revert with the product and the first input
+        u32_const(address(3), 12),
+        u32_const(address(4), 2),
+        BrilligOpcode::Trap { revert_data: HeapVector { pointer: address(3), size: address(4) } },
+    ]);
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..50 {
+        let calldata = [random_field(&mut rng), random_field(&mut rng)];
+        assert_equivalent(&program, &calldata);
+    }
+}
+
+/// Moves and casts which the optimizer may remove, including through relative addresses which
+/// are the same as direct ones and across a change of the stack pointer
+#[test]
+fn redundant_moves_and_casts() {
+    let field = BitSize::Field;
+    let u8_size = BitSize::Integer(IntegerBitSize::U8);
+    let constant = |destination, bit_size, value: u128| BrilligOpcode::Const {
+        destination,
+        bit_size,
+        value: FieldElement::from(value),
+    };
+    let mut program = copy_calldata(10, 2);
+    program.extend([
+        constant(address(53), field, 300),
+        u32_const(address(0), 100),
+        // A constant moved elsewhere and then overwritten through another address
+        constant(MemoryAddress::relative(1), field, 5),
+        BrilligOpcode::Mov { destination: address(12), source: MemoryAddress::relative(1) },
+        constant(address(101), field, 6),
+        BrilligOpcode::Mov { destination: address(13), source: MemoryAddress::relative(1) },
+        // A u8 overwritten with a field through another address, so the cast isn't redundant
+        constant(address(102), u8_size, 7),
+        constant(MemoryAddress::relative(2), field, 1000),
+        BrilligOpcode::Cast { destination: address(14), source: address(102), bit_size: u8_size },
+        // A u8 whose relative address changes with the stack pointer
+        constant(MemoryAddress::relative(3), u8_size, 9),
+        u32_const(address(0), 50),
+        BrilligOpcode::Cast {
+            destination: address(15),
+            source: MemoryAddress::relative(3),
+            bit_size: u8_size,
+        },
+        // Casts of values whose tags are known, and of inputs whose tags aren't
+        constant(address(16), u8_size, 255),
+        BrilligOpcode::Cast { destination: address(16), source: address(16), bit_size: u8_size },
+        BrilligOpcode::Cast { destination: address(17), source: address(16), bit_size: u8_size },
+        BrilligOpcode::Cast { destination: address(18), source: address(10), bit_size: u8_size },
+        BrilligOpcode::Mov { destination: address(19), source: address(11) },
+        BrilligOpcode::Mov { destination: address(19), source: address(19) },
+    ]);
+    program.extend(stop(12, 8));
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..20 {
+        let calldata = [random_field(&mut rng), random_field(&mut rng)];
+        assert_equivalent(&program, &calldata);
+    }
+}
+
+/// Reverses an array in memory through pointers
+#[test]
+fn loads_and_stores() {
+    let length = 5;
+    let u32_size = BitSize::Integer(IntegerBitSize::U32);
+    let array = 300;
+    let (front, back, one, condition, front_value, back_value) =
+        (address(10), address(11), address(12), address(13), address(14), address(15));
+
+    let mut program = copy_calldata(array, length);
+    program.extend([
+        u32_const(front, array),
+        u32_const(back, array + length - 1),
+        BrilligOpcode::Const { destination: one, bit_size: u32_size, value: FieldElement::one() },
+    ]);
+    let loop_start = program.len();
+    program.extend([
+        BrilligOpcode::BinaryIntOp {
+            destination: condition,
+            op: BinaryIntOp::LessThan,
+            bit_size: IntegerBitSize::U32,
+            lhs: front,
+            rhs: back,
+        },
+        BrilligOpcode::JumpIf { condition, location: loop_start + 3 },
+        BrilligOpcode::Jump { location: loop_start + 10 },
+        BrilligOpcode::Load { destination: front_value, source_pointer: front },
+        BrilligOpcode::Load { destination: back_value, source_pointer: back },
+        BrilligOpcode::Store { destination_pointer: front, source: back_value },
+        BrilligOpcode::Store { destination_pointer: back, source: front_value },
+        BrilligOpcode::BinaryIntOp {
+            destination: front,
+            op: BinaryIntOp::Add,
+            bit_size: IntegerBitSize::U32,
+            lhs: front,
+            rhs: one,
+        },
+        BrilligOpcode::BinaryIntOp {
+            destination: back,
+            op: BinaryIntOp::Sub,
+            bit_size: IntegerBitSize::U32,
+            lhs: back,
+            rhs: one,
+        },
+        BrilligOpcode::Jump { location: loop_start },
+    ]);
+    program.extend(stop(array, length));
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..20 {
+        let calldata: Vec<_> = (0..length).map(|_| random_field(&mut rng)).collect();
+        assert_equivalent(&program, &calldata);
+    }
+}
+
+#[test]
+fn to_radix() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..50 {
+        // The simulator only allows bits to be output in base 2
+        let output_bits = rng.gen_bool(0.25);
+        let radix = if output_bits { 2 } else { rng.gen_range(2..=256) };
+        let num_limbs = rng.gen_range(1..=64);
+
+        let mut program = copy_calldata(10, 1);
+        program.extend([
+            u32_const(address(11), radix),
+            u32_const(address(12), 20),
+            BrilligOpcode::BlackBox(BlackBoxOp::ToRadix {
+                input: address(10),
+                radix: address(11),
+                output: HeapArray { pointer: address(12), size: num_limbs },
+                output_bits,
+            }),
+        ]);
+        program.extend(stop(20, num_limbs));
+
+        assert_equivalent(&program, &[random_field(&mut rng)]);
+    }
+}
+
+/// World state kept in memory, where calls return their calldata
+#[derive(Default)]
+struct TestWorldState {
+    storage: HashMap<(FieldElement, FieldElement), FieldElement>,
+    nullifiers: HashSet<(FieldElement, FieldElement)>,
+    logs: Vec<Vec<FieldElement>>,
+}
+
+impl WorldState for TestWorldState {
+    fn storage_read(&mut self, address: FieldElement, slot: FieldElement) -> FieldElement {
+        self.storage.get(&(address, slot)).copied().unwrap_or_default()
+    }
+
+    fn storage_write(&mut self, address: FieldElement, slot: FieldElement, value: FieldElement) {
+        self.storage.insert((address, slot), value);
+    }
+
+    fn note_hash_exists(
+        &mut self,
+        _address: FieldElement,
+        _note_hash: FieldElement,
+        _leaf_index: FieldElement,
+    ) -> bool {
+        false
+    }
+
+    fn emit_note_hash(&mut self, _address: FieldElement, _note_hash: FieldElement) {}
+
+    fn nullifier_exists(&mut self, address: FieldElement, nullifier: FieldElement) -> bool {
+        self.nullifiers.contains(&(address, nullifier))
+    }
+
+    fn emit_nullifier(&mut self, address: FieldElement, nullifier: FieldElement) -> bool {
+        self.nullifiers.insert((address, nullifier))
+    }
+
+    fn l1_to_l2_message_exists(
+        &mut self,
+        _address: FieldElement,
+        _message_hash: FieldElement,
+        _leaf_index: FieldElement,
+    ) -> bool {
+        false
+    }
+
+    fn contract_instance(&mut self, _address: FieldElement) -> Option<ContractInstance> {
+        None
+    }
+
+    fn emit_unencrypted_log(&mut self, _address: FieldElement, log: Vec<FieldElement>) {
+        self.logs.push(log);
+    }
+
+    fn send_l2_to_l1_message(
+        &mut self,
+        _address: FieldElement,
+        _recipient: FieldElement,
+        _content: FieldElement,
+    ) {
+    }
+
+    fn call(
+        &mut self,
+        _address: FieldElement,
+        calldata: Vec<FieldElement>,
+        _l2_gas: u32,
+        _da_gas: u32,
+        _is_static_call: bool,
+    ) -> ExecutionResult {
+        ExecutionResult::Return(calldata)
+    }
+}
+
+fn run_assembly(
+    source: &str,
+    environment: &ExecutionEnvironment,
+    world_state: &mut TestWorldState,
+) -> Result<ExecutionResult, String> {
+    let instructions = assemble(source).unwrap();
+    let bytecode: Vec<u8> =
+        instructions.iter().flat_map(|instruction| instruction.to_bytes()).collect();
+    execute(&bytecode, environment, world_state, &StubbedBlackBoxSolver)
+        .map_err(|err| err.to_string())
+}
+
+#[test]
+fn world_state_opcodes() {
+    let source = "
+        SET_8 0, tag=FIELD, #5      ; slot
+        SET_8 1, tag=FIELD, #42     ; value
+        SSTORE 1, 0
+        SLOAD 0, 2
+        EMITNULLIFIER 1
+        GETENVVAR_16 3, #0          ; address
+        NULLIFIEREXISTS 1, 3, 3
+        SET_8 5, tag=UINT32, #3
+        EMITUNENCRYPTEDLOG 0, 5
+        SET_8 6, tag=UINT32, #2
+        RETURN 2, 6
+    ";
+    let environment =
+        ExecutionEnvironment { address: FieldElement::from(7_u128), ..Default::default() };
+    let mut world_state = TestWorldState::default();
+
+    let result = run_assembly(source, &environment, &mut world_state);
+    assert_eq!(result, Ok(ExecutionResult::Return(vec![42_u128.into(), FieldElement::one()])));
+    assert_eq!(world_state.storage[&(7_u128.into(), 5_u128.into())], 42_u128.into());
+    assert_eq!(world_state.logs, vec![vec![5_u128.into(), 42_u128.into(), 42_u128.into()]]);
+
+    // Emitting the nullifier again fails
+    let result = run_assembly(source, &environment, &mut world_state);
+    assert_eq!(result, Err("EMITNULLIFIER failed at pc 22: duplicate nullifier".to_string()));
+
+    let environment = ExecutionEnvironment { is_static_call: true, ..environment };
+    let result = run_assembly(source, &environment, &mut TestWorldState::default());
+    assert_eq!(result, Err("SSTORE can't alter state in a static call at pc 10".to_string()));
+}
+
+#[test]
+fn external_calls() {
+    let source = "
+        SET_8 0, tag=FIELD, #100    ; l2 gas
+        SET_8 1, tag=FIELD, #100    ; da gas
+        SET_8 2, tag=FIELD, #9      ; address
+        SET_8 3, tag=FIELD, #11     ; arguments
+        SET_8 4, tag=FIELD, #12
+        SET_8 5, tag=UINT32, #2
+        CALL 0, 2, 3, 5, 6
+        RETURNDATASIZE 7
+        SET_8 8, tag=UINT32, #1
+        RETURNDATACOPY 8, 8, 9      ; copy from index 1
+        RETURN 9, 8
+    ";
+    let result =
+        run_assembly(source, &ExecutionEnvironment::default(), &mut TestWorldState::default());
+    assert_eq!(result, Ok(ExecutionResult::Return(vec![12_u128.into()])));
+}
+
+#[test]
+fn tag_checks() {
+    let result = run_assembly(
+        "
+        SET_8 0, tag=UINT8, #1
+        SET_8 1, tag=UINT16, #1
+        ADD_8 0, 1, 2
+        ",
+        &ExecutionEnvironment::default(),
+        &mut TestWorldState::default(),
+    );
+    assert_eq!(result, Err("expected UINT8 at offset 1 but found UINT16 at pc 10".to_string()));
+
+    // Indirect operands must point through a UINT32
+    let result = run_assembly(
+        "
+        SET_8 0, tag=FIELD, #5
+        MOV_8 ind=1, 0, 1
+        ",
+        &ExecutionEnvironment::default(),
+        &mut TestWorldState::default(),
+    );
+    assert_eq!(result, Err("expected UINT32 at offset 0 but found FIELD at pc 5".to_string()));
+}
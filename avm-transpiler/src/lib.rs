@@ -0,0 +1,27 @@
+#![warn(clippy::semicolon_if_nothing_returned)]
+// The dependencies of the binary are only unused by the library when the `cli` feature is off.
+#![cfg_attr(not(any(test, feature = "cli")), warn(unused_crate_dependencies, unused_extern_crates))]
+
+//! Transpiles the public functions of Aztec contracts from Brillig bytecode to AVM bytecode.
+//!
+//! The `avm-transpiler` binary is a thin command line interface over this library,
+//! which can also be used directly by tools that already hold a contract artifact in memory.
+
+pub mod analysis;
+pub mod assembler;
+mod bit_traits;
+pub mod disassembler;
+pub mod instructions;
+pub mod interpreter;
+pub mod opcodes;
+mod optimizer;
+mod transpile;
+mod transpile_contract;
+mod utils;
+
+pub use transpile::{brillig_to_avm, patch_debug_info_pcs, TranspileError, TranspileErrorKind};
+pub use transpile_contract::{
+    transpile_artifact, AcirContractFunctionArtifact, ArtifactError, AvmContractFunctionArtifact,
+    AvmOrAcirContractFunctionArtifact, CompiledAcirContractArtifact, TranspiledContractArtifact,
+};
+pub use utils::extract_brillig_from_acir_program;
@@ -1,60 +1,388 @@
 #![warn(clippy::semicolon_if_nothing_returned)]
-#![cfg_attr(not(test), warn(unused_crate_dependencies, unused_extern_crates))]
 
-use log::warn;
-use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use base64::Engine;
+use clap::{Args, Parser, Subcommand};
+use log::warn;
+
+use avm_transpiler::analysis::{analyze_function, FunctionReport, GasTable};
+use avm_transpiler::assembler::assemble;
+use avm_transpiler::disassembler::disassemble;
+use avm_transpiler::{
+    transpile_artifact, AvmOrAcirContractFunctionArtifact, TranspiledContractArtifact,
+};
+
+/// Transpiles the public functions of Aztec contract artifacts to AVM bytecode.
+///
+/// `avm-transpiler <INPUT> <OUTPUT>` transpiles a single artifact, as the `transpile`
+/// subcommand does for any number of them.
+#[derive(Parser, Debug)]
+#[command(name = "avm-transpiler", version, args_conflicts_with_subcommands = true)]
+#[command(subcommand_negates_reqs = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
 
-mod bit_traits;
-mod instructions;
-mod opcodes;
-mod transpile;
-mod transpile_contract;
-mod utils;
+    /// The contract artifact to transpile
+    #[arg(required = true)]
+    input: Option<PathBuf>,
+
+    /// Where to write the transpiled artifact
+    #[arg(required = true)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    Transpile(TranspileCommand),
+    Disassemble(DisassembleCommand),
+    Assemble(AssembleCommand),
+    Analyze(AnalyzeCommand),
+}
+
+/// Transpile contract artifacts, in place unless another destination is given
+#[derive(Args, Debug)]
+struct TranspileCommand {
+    /// The contract artifacts to transpile
+    #[arg(required = true)]
+    artifacts: Vec<PathBuf>,
+
+    /// Write the transpiled artifacts to this directory instead of overwriting them
+    #[arg(long, conflicts_with_all = ["stdout", "check"])]
+    output_dir: Option<PathBuf>,
+
+    /// Print the transpiled artifacts to stdout, one per line, instead of writing them
+    #[arg(long, conflicts_with = "check")]
+    stdout: bool,
+
+    /// Only check that the artifacts can be transpiled, without writing anything
+    #[arg(long)]
+    check: bool,
+}
+
+/// Print the AVM assembly of a function in a transpiled contract artifact
+#[derive(Args, Debug)]
+struct DisassembleCommand {
+    /// The transpiled contract artifact
+    artifact: PathBuf,
+
+    /// The name of the public function to disassemble
+    function: String,
+}
 
-use transpile_contract::{CompiledAcirContractArtifact, TranspiledContractArtifact};
+/// Assemble a file of AVM assembly into bytecode
+#[derive(Args, Debug)]
+struct AssembleCommand {
+    /// The file of AVM assembly, in the syntax printed by `disassemble`
+    source: PathBuf,
 
-fn main() {
+    /// Where to write the bytecode
+    output: PathBuf,
+}
+
+/// Report the bytecode size, instruction mix and static gas bound of transpiled public functions
+#[derive(Args, Debug)]
+struct AnalyzeCommand {
+    /// The transpiled contract artifact
+    artifact: PathBuf,
+
+    /// Only analyze the public function with this name
+    #[arg(long)]
+    function: Option<String>,
+
+    /// A JSON file of per-opcode gas costs overriding the default ones,
+    /// such as `{ "SLOAD": { "l2_gas": 1500, "da_gas": 0 } }`
+    #[arg(long)]
+    gas_table: Option<PathBuf>,
+
+    /// How many of the most expensive source locations to list for each function
+    #[arg(long, default_value_t = 10)]
+    hotspots: usize,
+
+    /// Print the reports as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Fail if the L2 gas bound of any function is above this
+    #[arg(long)]
+    max_l2_gas: Option<u64>,
+
+    /// Fail if the DA gas bound of any function is above this
+    #[arg(long)]
+    max_da_gas: Option<u64>,
+
+    /// Fail if the bytecode of any function is larger than this many bytes
+    #[arg(long)]
+    max_bytecode_size: Option<usize>,
+}
+
+/// Where a transpiled artifact goes.
+enum Destination<'a> {
+    File(&'a Path),
+    Stdout,
+    /// Nowhere, as we're only checking that the artifact transpiles.
+    Discard,
+}
+
+fn main() -> ExitCode {
     env_logger::init();
 
-    let args: Vec<String> = env::args().collect();
-    let in_contract_artifact_path = &args[1];
-    let out_transpiled_artifact_path = &args[2];
-    let json_parse_error = format!(
-        "Unable to parse json for: {in_contract_artifact_path}
-    This is probably a stale json file with a different wire format.
-    You might need to recompile the contract or delete the json file"
-    );
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Some(Command::Transpile(command)) => transpile_all(&command),
+        Some(Command::Disassemble(command)) => {
+            disassemble_function(&command.artifact, &command.function)
+        }
+        Some(Command::Assemble(command)) => assemble_program(&command.source, &command.output),
+        Some(Command::Analyze(command)) => analyze_artifact(&command),
+        None => {
+            let input = cli.input.expect("clap requires an input");
+            let output = cli.output.expect("clap requires an output");
+            transpile(&input, Destination::File(&output))
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Transpile every artifact given to the `transpile` subcommand, reporting all failures
+/// rather than stopping at the first one.
+fn transpile_all(command: &TranspileCommand) -> Result<(), String> {
+    if let Some(output_dir) = &command.output_dir {
+        fs::create_dir_all(output_dir)
+            .map_err(|err| format!("Unable to create {}: {err}", output_dir.display()))?;
+    }
 
-    // Parse original (pre-transpile) contract.
-    let contract_json = fs::read_to_string(Path::new(in_contract_artifact_path))
-        .unwrap_or_else(|_| panic!("Unable to read file: {in_contract_artifact_path}"));
-    let raw_json_obj: serde_json::Value =
-        serde_json::from_str(&contract_json).expect(&json_parse_error);
+    let mut failures = 0;
+    for artifact in &command.artifacts {
+        let output_path = match &command.output_dir {
+            Some(output_dir) => {
+                let Some(file_name) = artifact.file_name() else {
+                    eprintln!("error: {} does not name a file", artifact.display());
+                    failures += 1;
+                    continue;
+                };
+                Some(output_dir.join(file_name))
+            }
+            None => None,
+        };
+        let destination = if command.stdout {
+            Destination::Stdout
+        } else if command.check {
+            Destination::Discard
+        } else {
+            Destination::File(output_path.as_deref().unwrap_or(artifact))
+        };
 
-    // Skip if contract has "transpiled: true" flag!
-    if let Some(serde_json::Value::Bool(true)) = raw_json_obj.get("transpiled") {
-        warn!("Contract already transpiled. Skipping.");
-        return;
+        if let Err(message) = transpile(artifact, destination) {
+            eprintln!("error: {message}");
+            failures += 1;
+        }
     }
 
-    // Backup the output file if it already exists.
-    if Path::new(out_transpiled_artifact_path).exists() {
-        std::fs::copy(
-            Path::new(out_transpiled_artifact_path),
-            Path::new(&(out_transpiled_artifact_path.clone() + ".bak")),
+    match failures {
+        0 => Ok(()),
+        _ => Err(format!(
+            "{failures} of {} contract artifacts failed to transpile",
+            command.artifacts.len()
+        )),
+    }
+}
+
+fn transpile(in_contract_artifact_path: &Path, destination: Destination) -> Result<(), String> {
+    let display_path = in_contract_artifact_path.display();
+    let contract_json = fs::read_to_string(in_contract_artifact_path)
+        .map_err(|err| format!("Unable to read {display_path}: {err}"))?;
+
+    let transpiled_json = match transpile_artifact(&contract_json) {
+        Ok(Some(transpiled_json)) => transpiled_json,
+        Ok(None) => {
+            warn!("Contract {display_path} already transpiled. Skipping.");
+            match destination {
+                Destination::File(path) if path == in_contract_artifact_path => return Ok(()),
+                _ => contract_json,
+            }
+        }
+        Err(err) => return Err(format!("{display_path}: {err}")),
+    };
+
+    match destination {
+        Destination::File(out_transpiled_artifact_path) => {
+            // Backup the output file if it already exists.
+            if out_transpiled_artifact_path.exists() {
+                let mut backup_path = out_transpiled_artifact_path.as_os_str().to_owned();
+                backup_path.push(".bak");
+                fs::copy(out_transpiled_artifact_path, backup_path).map_err(|err| {
+                    format!("Unable to backup {}: {err}", out_transpiled_artifact_path.display())
+                })?;
+            }
+            fs::write(out_transpiled_artifact_path, transpiled_json).map_err(|err| {
+                format!("Unable to write {}: {err}", out_transpiled_artifact_path.display())
+            })
+        }
+        Destination::Stdout => {
+            println!("{}", transpiled_json.trim_end());
+            Ok(())
+        }
+        Destination::Discard => Ok(()),
+    }
+}
+
+/// Print the AVM assembly of a function in a transpiled contract artifact
+fn disassemble_function(
+    transpiled_artifact_path: &Path,
+    function_name: &str,
+) -> Result<(), String> {
+    let display_path = transpiled_artifact_path.display();
+    let contract_json = fs::read_to_string(transpiled_artifact_path)
+        .map_err(|err| format!("Unable to read {display_path}: {err}"))?;
+    let contract: TranspiledContractArtifact = serde_json::from_str(&contract_json)
+        .map_err(|err| format!("Unable to parse json for {display_path}: {err}"))?;
+
+    let function = contract
+        .functions
+        .iter()
+        .find_map(|function| match function {
+            AvmOrAcirContractFunctionArtifact::Avm(function) if function.name == function_name => {
+                Some(function)
+            }
+            _ => None,
+        })
+        .ok_or_else(|| format!("No AVM function named {function_name} in {}", contract.name))?;
+    let bytecode = base64::prelude::BASE64_STANDARD
+        .decode(&function.bytecode)
+        .map_err(|err| format!("Unable to decode the bytecode of {function_name}: {err}"))?;
+
+    let assembly = disassemble(&bytecode)
+        .map_err(|err| format!("Unable to disassemble {function_name}: {err}"))?;
+    print!("{assembly}");
+    Ok(())
+}
+
+/// Assemble a file of AVM assembly into bytecode
+fn assemble_program(in_assembly_path: &Path, out_bytecode_path: &Path) -> Result<(), String> {
+    let display_path = in_assembly_path.display();
+    let source = fs::read_to_string(in_assembly_path)
+        .map_err(|err| format!("Unable to read {display_path}: {err}"))?;
+    let instructions =
+        assemble(&source).map_err(|err| format!("Unable to assemble {display_path}: {err}"))?;
+
+    let bytecode: Vec<u8> =
+        instructions.iter().flat_map(|instruction| instruction.to_bytes()).collect();
+    fs::write(out_bytecode_path, bytecode)
+        .map_err(|err| format!("Unable to write {}: {err}", out_bytecode_path.display()))
+}
+
+/// Analyze the public functions of a transpiled contract artifact, failing if any of them is
+/// over the command's limits
+fn analyze_artifact(command: &AnalyzeCommand) -> Result<(), String> {
+    let display_path = command.artifact.display();
+    let contract_json = fs::read_to_string(&command.artifact)
+        .map_err(|err| format!("Unable to read {display_path}: {err}"))?;
+    let contract: TranspiledContractArtifact = serde_json::from_str(&contract_json)
+        .map_err(|err| format!("Unable to parse json for {display_path}: {err}"))?;
+
+    let mut gas_table = GasTable::default();
+    if let Some(path) = &command.gas_table {
+        let json = fs::read_to_string(path)
+            .map_err(|err| format!("Unable to read {}: {err}", path.display()))?;
+        gas_table = gas_table.with_overrides(&json)?;
+    }
+
+    let mut reports = Vec::new();
+    for function in &contract.functions {
+        let AvmOrAcirContractFunctionArtifact::Avm(function) = function else { continue };
+        if command.function.as_ref().is_some_and(|name| *name != function.name) {
+            continue;
+        }
+        let name = format!("{}::{}", contract.name, function.name);
+        let bytecode = base64::prelude::BASE64_STANDARD
+            .decode(&function.bytecode)
+            .map_err(|err| format!("Unable to decode the bytecode of {name}: {err}"))?;
+        let mut report = analyze_function(
+            &name,
+            &bytecode,
+            &function.debug_symbols,
+            &contract.file_map,
+            &gas_table,
         )
-        .unwrap_or_else(|_| panic!("Unable to backup file: {out_transpiled_artifact_path}"));
+        .map_err(|err| format!("Unable to decode the bytecode of {name}: {err}"))?;
+        report.source_costs.truncate(command.hotspots);
+        reports.push(report);
+    }
+    if let Some(function_name) = &command.function {
+        if reports.is_empty() {
+            return Err(format!("No AVM function named {function_name} in {}", contract.name));
+        }
+    }
+
+    if command.json {
+        let json = serde_json::to_string_pretty(&reports).expect("Unable to serialize json");
+        println!("{json}");
+    } else {
+        for report in &reports {
+            print_report(report);
+        }
+    }
+
+    let mut violations = Vec::new();
+    for report in &reports {
+        let gas = report.gas_bound.gas;
+        if command.max_l2_gas.is_some_and(|max| gas.l2_gas > max) {
+            violations.push(format!("{} may use {} L2 gas", report.name, gas.l2_gas));
+        }
+        if command.max_da_gas.is_some_and(|max| gas.da_gas > max) {
+            violations.push(format!("{} may use {} DA gas", report.name, gas.da_gas));
+        }
+        if command.max_bytecode_size.is_some_and(|max| report.bytecode_size > max) {
+            violations.push(format!("{} is {} bytes", report.name, report.bytecode_size));
+        }
+    }
+    match violations.len() {
+        0 => Ok(()),
+        _ => Err(format!("functions over the limits: {}", violations.join(", "))),
+    }
+}
+
+fn print_report(report: &FunctionReport) {
+    println!("{}", report.name);
+    println!(
+        "  bytecode: {} bytes, {} instructions",
+        report.bytecode_size, report.instruction_count
+    );
+    if report.gas_bound.is_bounded() {
+        println!("  gas bound: {}", report.gas_bound.gas);
+    } else {
+        let headers: Vec<String> =
+            report.gas_bound.loop_headers.iter().map(|pc| pc.to_string()).collect();
+        println!(
+            "  gas bound: {} with one iteration of the loops at pc {}",
+            report.gas_bound.gas,
+            headers.join(", ")
+        );
     }
 
-    // Parse json into contract object
-    let contract: CompiledAcirContractArtifact =
-        serde_json::from_str(&contract_json).expect(&json_parse_error);
+    let mut mix: Vec<(&str, usize)> =
+        report.instruction_mix.iter().map(|(name, count)| (*name, *count)).collect();
+    mix.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    println!("  instruction mix:");
+    for (name, count) in mix {
+        println!("    {name:<20} {count}");
+    }
 
-    // Transpile contract to AVM bytecode
-    let transpiled_contract = TranspiledContractArtifact::from(contract);
-    let transpiled_json =
-        serde_json::to_string(&transpiled_contract).expect("Unable to serialize json");
-    fs::write(out_transpiled_artifact_path, transpiled_json).expect("Unable to write file");
+    if !report.source_costs.is_empty() {
+        println!("  most expensive source locations:");
+        for cost in &report.source_costs {
+            println!("    {:<40} {} ({} instructions)", cost.location, cost.gas, cost.instructions);
+        }
+    }
 }
@@ -83,6 +83,88 @@ pub enum AvmOpcode {
 }
 
 impl AvmOpcode {
+    /// Every opcode, indexed by the byte which encodes it.
+    pub const ALL: [AvmOpcode; 68] = [
+        AvmOpcode::ADD_8,
+        AvmOpcode::ADD_16,
+        AvmOpcode::SUB_8,
+        AvmOpcode::SUB_16,
+        AvmOpcode::MUL_8,
+        AvmOpcode::MUL_16,
+        AvmOpcode::DIV_8,
+        AvmOpcode::DIV_16,
+        AvmOpcode::FDIV_8,
+        AvmOpcode::FDIV_16,
+        AvmOpcode::EQ_8,
+        AvmOpcode::EQ_16,
+        AvmOpcode::LT_8,
+        AvmOpcode::LT_16,
+        AvmOpcode::LTE_8,
+        AvmOpcode::LTE_16,
+        AvmOpcode::AND_8,
+        AvmOpcode::AND_16,
+        AvmOpcode::OR_8,
+        AvmOpcode::OR_16,
+        AvmOpcode::XOR_8,
+        AvmOpcode::XOR_16,
+        AvmOpcode::NOT_8,
+        AvmOpcode::NOT_16,
+        AvmOpcode::SHL_8,
+        AvmOpcode::SHL_16,
+        AvmOpcode::SHR_8,
+        AvmOpcode::SHR_16,
+        AvmOpcode::CAST_8,
+        AvmOpcode::CAST_16,
+        AvmOpcode::GETENVVAR_16,
+        AvmOpcode::CALLDATACOPY,
+        AvmOpcode::RETURNDATASIZE,
+        AvmOpcode::RETURNDATACOPY,
+        AvmOpcode::JUMP_32,
+        AvmOpcode::JUMPI_32,
+        AvmOpcode::INTERNALCALL,
+        AvmOpcode::INTERNALRETURN,
+        AvmOpcode::SET_8,
+        AvmOpcode::SET_16,
+        AvmOpcode::SET_32,
+        AvmOpcode::SET_64,
+        AvmOpcode::SET_128,
+        AvmOpcode::SET_FF,
+        AvmOpcode::MOV_8,
+        AvmOpcode::MOV_16,
+        AvmOpcode::SLOAD,
+        AvmOpcode::SSTORE,
+        AvmOpcode::NOTEHASHEXISTS,
+        AvmOpcode::EMITNOTEHASH,
+        AvmOpcode::NULLIFIEREXISTS,
+        AvmOpcode::EMITNULLIFIER,
+        AvmOpcode::L1TOL2MSGEXISTS,
+        AvmOpcode::GETCONTRACTINSTANCE,
+        AvmOpcode::EMITUNENCRYPTEDLOG,
+        AvmOpcode::SENDL2TOL1MSG,
+        AvmOpcode::CALL,
+        AvmOpcode::STATICCALL,
+        AvmOpcode::RETURN,
+        AvmOpcode::REVERT_8,
+        AvmOpcode::REVERT_16,
+        AvmOpcode::DEBUGLOG,
+        AvmOpcode::POSEIDON2,
+        AvmOpcode::SHA256COMPRESSION,
+        AvmOpcode::KECCAKF1600,
+        AvmOpcode::ECADD,
+        AvmOpcode::MSM,
+        AvmOpcode::TORADIXBE,
+    ];
+
+    /// Returns the opcode encoded by `byte`, if there is one.
+    pub fn from_u8(byte: u8) -> Option<AvmOpcode> {
+        Self::ALL.get(byte as usize).copied()
+    }
+
+    /// Returns the opcode with the given name, as printed by [`AvmOpcode::name`].
+    pub fn from_name(name: &str) -> Option<AvmOpcode> {
+        Self::ALL.into_iter().find(|opcode| opcode.name() == name)
+    }
+
     pub fn name(&self) -> &'static str {
         match self {
             // Compute
@@ -179,3 +261,195 @@ impl AvmOpcode {
         }
     }
 }
+
+/// The type of a single field within an encoded instruction
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
+#[derive(PartialEq, Copy, Clone, Debug, Eq)]
+pub enum AvmOperandType {
+    UINT8,
+    UINT16,
+    UINT32,
+    UINT64,
+    UINT128,
+    FF,
+}
+
+impl AvmOperandType {
+    /// Number of bytes taken up by a field of this type
+    pub fn size(&self) -> usize {
+        match self {
+            AvmOperandType::UINT8 => 1,
+            AvmOperandType::UINT16 => 2,
+            AvmOperandType::UINT32 => 4,
+            AvmOperandType::UINT64 => 8,
+            AvmOperandType::UINT128 => 16,
+            AvmOperandType::FF => 32,
+        }
+    }
+}
+
+/// The fields which follow an opcode in the bytecode.
+/// They are serialized in the order INDIRECT, OPERANDS, TAG, IMMEDIATES, as in `AvmInstruction::to_bytes`.
+/// Keep updated with the wire formats in TS and cpp!
+pub struct AvmInstructionLayout {
+    pub indirect: Option<AvmOperandType>,
+    pub operands: &'static [AvmOperandType],
+    /// Whether there is a one byte type tag
+    pub tag: bool,
+    pub immediates: &'static [AvmOperandType],
+}
+
+impl AvmInstructionLayout {
+    /// Number of bytes taken up by an instruction with this layout, including its opcode
+    pub fn size(&self) -> usize {
+        1 + self.indirect.map_or(0, |indirect| indirect.size())
+            + self.operands.iter().map(AvmOperandType::size).sum::<usize>()
+            + usize::from(self.tag)
+            + self.immediates.iter().map(AvmOperandType::size).sum::<usize>()
+    }
+}
+
+impl AvmOpcode {
+    pub fn layout(&self) -> AvmInstructionLayout {
+        use AvmOperandType::*;
+
+        const fn with_indirect(operands: &'static [AvmOperandType]) -> AvmInstructionLayout {
+            AvmInstructionLayout { indirect: Some(UINT8), operands, tag: false, immediates: &[] }
+        }
+
+        match self {
+            // Compute
+            AvmOpcode::ADD_8
+            | AvmOpcode::SUB_8
+            | AvmOpcode::MUL_8
+            | AvmOpcode::DIV_8
+            | AvmOpcode::FDIV_8
+            | AvmOpcode::EQ_8
+            | AvmOpcode::LT_8
+            | AvmOpcode::LTE_8
+            | AvmOpcode::AND_8
+            | AvmOpcode::OR_8
+            | AvmOpcode::XOR_8
+            | AvmOpcode::SHL_8
+            | AvmOpcode::SHR_8 => with_indirect(&[UINT8, UINT8, UINT8]),
+            AvmOpcode::ADD_16
+            | AvmOpcode::SUB_16
+            | AvmOpcode::MUL_16
+            | AvmOpcode::DIV_16
+            | AvmOpcode::FDIV_16
+            | AvmOpcode::EQ_16
+            | AvmOpcode::LT_16
+            | AvmOpcode::LTE_16
+            | AvmOpcode::AND_16
+            | AvmOpcode::OR_16
+            | AvmOpcode::XOR_16
+            | AvmOpcode::SHL_16
+            | AvmOpcode::SHR_16 => with_indirect(&[UINT16, UINT16, UINT16]),
+            AvmOpcode::NOT_8 => with_indirect(&[UINT8, UINT8]),
+            AvmOpcode::NOT_16 => with_indirect(&[UINT16, UINT16]),
+            AvmOpcode::CAST_8 => {
+                AvmInstructionLayout { tag: true, ..with_indirect(&[UINT8, UINT8]) }
+            }
+            AvmOpcode::CAST_16 => {
+                AvmInstructionLayout { tag: true, ..with_indirect(&[UINT16, UINT16]) }
+            }
+
+            // Execution Environment
+            AvmOpcode::GETENVVAR_16 => {
+                AvmInstructionLayout { immediates: &[UINT8], ..with_indirect(&[UINT16]) }
+            }
+            AvmOpcode::CALLDATACOPY | AvmOpcode::RETURNDATACOPY => {
+                with_indirect(&[UINT16, UINT16, UINT16])
+            }
+            AvmOpcode::RETURNDATASIZE => with_indirect(&[UINT16]),
+
+            // Machine State - Internal Control Flow
+            AvmOpcode::JUMP_32 | AvmOpcode::INTERNALCALL => AvmInstructionLayout {
+                indirect: None,
+                operands: &[],
+                tag: false,
+                immediates: &[UINT32],
+            },
+            AvmOpcode::JUMPI_32 => {
+                AvmInstructionLayout { immediates: &[UINT32], ..with_indirect(&[UINT16]) }
+            }
+            AvmOpcode::INTERNALRETURN => {
+                AvmInstructionLayout { indirect: None, operands: &[], tag: false, immediates: &[] }
+            }
+
+            // Machine State - Memory
+            AvmOpcode::SET_8 => {
+                AvmInstructionLayout { tag: true, immediates: &[UINT8], ..with_indirect(&[UINT8]) }
+            }
+            AvmOpcode::SET_16 => AvmInstructionLayout {
+                tag: true,
+                immediates: &[UINT16],
+                ..with_indirect(&[UINT16])
+            },
+            AvmOpcode::SET_32 => AvmInstructionLayout {
+                tag: true,
+                immediates: &[UINT32],
+                ..with_indirect(&[UINT16])
+            },
+            AvmOpcode::SET_64 => AvmInstructionLayout {
+                tag: true,
+                immediates: &[UINT64],
+                ..with_indirect(&[UINT16])
+            },
+            AvmOpcode::SET_128 => AvmInstructionLayout {
+                tag: true,
+                immediates: &[UINT128],
+                ..with_indirect(&[UINT16])
+            },
+            AvmOpcode::SET_FF => {
+                AvmInstructionLayout { tag: true, immediates: &[FF], ..with_indirect(&[UINT16]) }
+            }
+            AvmOpcode::MOV_8 => with_indirect(&[UINT8, UINT8]),
+            AvmOpcode::MOV_16 => with_indirect(&[UINT16, UINT16]),
+
+            // World State
+            AvmOpcode::SLOAD | AvmOpcode::SSTORE => with_indirect(&[UINT16, UINT16]),
+            AvmOpcode::NOTEHASHEXISTS | AvmOpcode::NULLIFIEREXISTS | AvmOpcode::L1TOL2MSGEXISTS => {
+                with_indirect(&[UINT16, UINT16, UINT16])
+            }
+            AvmOpcode::EMITNOTEHASH | AvmOpcode::EMITNULLIFIER => with_indirect(&[UINT16]),
+
+            // Accrued Substate
+            AvmOpcode::EMITUNENCRYPTEDLOG | AvmOpcode::SENDL2TOL1MSG => {
+                with_indirect(&[UINT16, UINT16])
+            }
+            AvmOpcode::GETCONTRACTINSTANCE => AvmInstructionLayout {
+                immediates: &[UINT8],
+                ..with_indirect(&[UINT16, UINT16, UINT16])
+            },
+
+            // Control Flow - Contract Calls
+            AvmOpcode::CALL | AvmOpcode::STATICCALL => AvmInstructionLayout {
+                indirect: Some(UINT16),
+                ..with_indirect(&[UINT16, UINT16, UINT16, UINT16, UINT16])
+            },
+            AvmOpcode::RETURN | AvmOpcode::REVERT_16 => with_indirect(&[UINT16, UINT16]),
+            AvmOpcode::REVERT_8 => with_indirect(&[UINT8, UINT8]),
+
+            // Misc
+            AvmOpcode::DEBUGLOG => AvmInstructionLayout {
+                immediates: &[UINT16],
+                ..with_indirect(&[UINT16, UINT16, UINT16])
+            },
+
+            // Gadgets
+            AvmOpcode::POSEIDON2 | AvmOpcode::KECCAKF1600 => with_indirect(&[UINT16, UINT16]),
+            AvmOpcode::SHA256COMPRESSION => with_indirect(&[UINT16, UINT16, UINT16]),
+            AvmOpcode::ECADD => AvmInstructionLayout {
+                indirect: Some(UINT16),
+                ..with_indirect(&[UINT16, UINT16, UINT16, UINT16, UINT16, UINT16, UINT16])
+            },
+            AvmOpcode::MSM => with_indirect(&[UINT16, UINT16, UINT16, UINT16]),
+            // Conversions
+            AvmOpcode::TORADIXBE => AvmInstructionLayout {
+                immediates: &[UINT16, UINT8],
+                ..with_indirect(&[UINT16, UINT16, UINT16])
+            },
+        }
+    }
+}
@@ -0,0 +1,504 @@
+//! Peephole optimizations of the AVM instructions generated by `brillig_to_avm`.
+//!
+//! Brillig opcodes are transpiled one at a time, so the AVM program has moves and casts which
+//! don't change anything, and constants which are set only to be moved somewhere else.
+//! The optimizer runs before jumps are resolved, while their targets are still Brillig pcs,
+//! and keeps track of where the instructions of each Brillig opcode start so that the jump
+//! targets and the pc map used for debug info can be computed from the optimized program.
+//!
+//! Only `SET`, `MOV` and `CAST` are looked into. Any other instruction is assumed to read and
+//! write all of memory, and nothing is assumed about memory at the start of a basic block.
+
+use std::collections::HashMap;
+
+use acvm::acir::brillig::MemoryAddress;
+use acvm::{AcirField, FieldElement};
+
+use crate::bit_traits::{bits_needed_for, BitsQueryable};
+use crate::instructions::{AddressingModeBuilder, AvmInstruction, AvmOperand, AvmTypeTag};
+use crate::opcodes::AvmOpcode;
+use crate::utils::make_operand;
+
+/// Optimizes a transpiled program.
+///
+/// `brillig_pc_starts[i]` is the index of the first AVM instruction of the Brillig opcode at pc
+/// `i`, with a final entry for the end of the program. It is updated to index into the optimized
+/// program, where Brillig opcodes whose instructions were all removed start at the next instruction.
+pub(crate) fn optimize(
+    instructions: Vec<AvmInstruction>,
+    brillig_pc_starts: &mut [usize],
+) -> Vec<AvmInstruction> {
+    let instructions = remove_redundant_instructions(instructions, brillig_pc_starts);
+    let instructions = fold_constant_moves(instructions, brillig_pc_starts);
+    instructions.into_iter().map(narrow).collect()
+}
+
+/// A memory operand of an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Address {
+    offset: u32,
+    indirect: bool,
+    relative: bool,
+}
+
+impl Address {
+    /// The `index`th memory operand of `instruction`
+    fn of(instruction: &AvmInstruction, index: usize) -> Address {
+        let operand_count = instruction.operands.len();
+        let mode = instruction.indirect.as_ref().map_or(0, |mode| mode.to_field().to_u128());
+        Address {
+            offset: instruction.operands[index].to_field().to_u128() as u32,
+            indirect: mode & (1 << index) != 0,
+            relative: mode & (1 << (operand_count + index)) != 0,
+        }
+    }
+
+    /// Whether a write to this address could move the stack pointer at address 0,
+    /// changing what relative addresses refer to
+    fn may_be_stack_pointer(&self) -> bool {
+        self.indirect || self.offset == 0
+    }
+}
+
+/// What is known about the value at a memory address
+#[derive(Debug, Clone, PartialEq)]
+enum Known {
+    Tag(AvmTypeTag),
+    Constant(AvmTypeTag, FieldElement),
+}
+
+impl Known {
+    fn tag(&self) -> AvmTypeTag {
+        match self {
+            Known::Tag(tag) | Known::Constant(tag, _) => *tag,
+        }
+    }
+}
+
+/// What is known about the values in memory at some point of a basic block.
+/// Only direct addresses are tracked.
+#[derive(Default)]
+struct KnownMemory {
+    values: HashMap<Address, Known>,
+}
+
+impl KnownMemory {
+    fn get(&self, address: Address) -> Option<&Known> {
+        if address.indirect {
+            None
+        } else {
+            self.values.get(&address)
+        }
+    }
+
+    /// Records a write to `address`, forgetting whatever it may have overwritten
+    fn write(&mut self, address: Address, value: Option<Known>) {
+        // An indirect write could be to anywhere, and a write to relative offset 0
+        // is to the stack pointer if the stack pointer is 0.
+        if address.indirect || (address.relative && address.offset == 0) {
+            self.values.clear();
+            return;
+        }
+        // A relative address may be the same as any direct one, and writing to direct
+        // address 0 moves all of the relative addresses.
+        self.values.retain(|known, _| known.relative == address.relative);
+        match value {
+            Some(value) => self.values.insert(address, value),
+            None => self.values.remove(&address),
+        };
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+/// Removes moves to the same address or of a constant which is already there,
+/// and casts of values which already have the tag being cast to.
+/// Casts to a different address are replaced with moves, which are smaller.
+fn remove_redundant_instructions(
+    instructions: Vec<AvmInstruction>,
+    brillig_pc_starts: &mut [usize],
+) -> Vec<AvmInstruction> {
+    let leaders = block_leaders(&instructions, brillig_pc_starts);
+    let mut memory = KnownMemory::default();
+    let mut optimized = Vec::with_capacity(instructions.len());
+    for (index, instruction) in instructions.into_iter().enumerate() {
+        if leaders[index] {
+            memory.clear();
+        }
+        match instruction.opcode {
+            AvmOpcode::MOV_8 | AvmOpcode::MOV_16 => {
+                let (source, destination) =
+                    (Address::of(&instruction, 0), Address::of(&instruction, 1));
+                let value = memory.get(source).cloned();
+                let already_there = matches!(value, Some(Known::Constant(..)))
+                    && memory.get(destination) == value.as_ref();
+                if source == destination || already_there {
+                    optimized.push(None);
+                    continue;
+                }
+                memory.write(destination, value);
+            }
+            AvmOpcode::CAST_8 | AvmOpcode::CAST_16 => {
+                let tag = instruction.tag.expect("CAST has a tag");
+                let (source, destination) =
+                    (Address::of(&instruction, 0), Address::of(&instruction, 1));
+                let value = memory.get(source).cloned();
+                if value.as_ref().is_some_and(|value| value.tag() == tag) {
+                    if source == destination {
+                        optimized.push(None);
+                        continue;
+                    }
+                    let opcode = if instruction.opcode == AvmOpcode::CAST_8 {
+                        AvmOpcode::MOV_8
+                    } else {
+                        AvmOpcode::MOV_16
+                    };
+                    memory.write(destination, value);
+                    optimized.push(Some(AvmInstruction { opcode, tag: None, ..instruction }));
+                    continue;
+                }
+                memory.write(destination, Some(Known::Tag(tag)));
+            }
+            opcode if is_set(opcode) => {
+                let tag = instruction.tag.expect("SET has a tag");
+                let value = instruction.immediates[0].to_field();
+                let known = fits_tag(&value, tag).then_some(Known::Constant(tag, value));
+                memory.write(Address::of(&instruction, 0), known);
+            }
+            _ => memory.clear(),
+        }
+        optimized.push(Some(instruction));
+    }
+    compact(optimized, brillig_pc_starts)
+}
+
+/// Replaces a constant which is set only to be moved elsewhere with one set at the destination
+/// of the move: `SET a, v; MOV a, b` becomes `SET b, v` if `a` is overwritten before being read.
+fn fold_constant_moves(
+    instructions: Vec<AvmInstruction>,
+    brillig_pc_starts: &mut [usize],
+) -> Vec<AvmInstruction> {
+    let leaders = block_leaders(&instructions, brillig_pc_starts);
+    let mut folded: Vec<Option<AvmInstruction>> = instructions.into_iter().map(Some).collect();
+    let mut index = 0;
+    while index + 1 < folded.len() {
+        let foldable = match (&folded[index], &folded[index + 1]) {
+            (Some(set), Some(mov))
+                if is_set(set.opcode)
+                    && matches!(mov.opcode, AvmOpcode::MOV_8 | AvmOpcode::MOV_16)
+                    && !leaders[index + 1] =>
+            {
+                let constant = Address::of(set, 0);
+                let (source, destination) = (Address::of(mov, 0), Address::of(mov, 1));
+                source == constant
+                    && !constant.may_be_stack_pointer()
+                    && !destination.may_be_stack_pointer()
+                    && is_overwritten(&folded, &leaders, index + 2, constant)
+            }
+            _ => false,
+        };
+        if !foldable {
+            index += 1;
+            continue;
+        }
+
+        let set = folded[index].take().unwrap();
+        let mov = folded[index + 1].take().unwrap();
+        let destination = Address::of(&mov, 1);
+        let memory_address = if destination.relative {
+            MemoryAddress::relative(destination.offset as usize)
+        } else {
+            MemoryAddress::direct(destination.offset as usize)
+        };
+        folded[index + 1] = Some(AvmInstruction {
+            opcode: AvmOpcode::SET_FF,
+            indirect: Some(
+                AddressingModeBuilder::default().direct_operand(&memory_address).build(),
+            ),
+            tag: set.tag,
+            operands: vec![AvmOperand::U16 { value: destination.offset as u16 }],
+            immediates: vec![AvmOperand::FF { value: set.immediates[0].to_field() }],
+        });
+        index += 2;
+    }
+    compact(folded, brillig_pc_starts)
+}
+
+/// Whether the value at `address` is overwritten before it is read, by the instructions
+/// following `from` in its basic block. Reads and writes which may or may not be to `address`
+/// count as reads.
+fn is_overwritten(
+    instructions: &[Option<AvmInstruction>],
+    leaders: &[bool],
+    from: usize,
+    address: Address,
+) -> bool {
+    for index in from..instructions.len() {
+        if leaders[index] {
+            return false;
+        }
+        let Some(instruction) = &instructions[index] else { continue };
+        let destination = match instruction.opcode {
+            AvmOpcode::MOV_8 | AvmOpcode::MOV_16 | AvmOpcode::CAST_8 | AvmOpcode::CAST_16 => {
+                let source = Address::of(instruction, 0);
+                if source == address || source.indirect || source.relative != address.relative {
+                    return false;
+                }
+                Address::of(instruction, 1)
+            }
+            opcode if is_set(opcode) => Address::of(instruction, 0),
+            _ => return false,
+        };
+        if destination == address {
+            return true;
+        }
+        if destination.may_be_stack_pointer() {
+            return false;
+        }
+    }
+    false
+}
+
+/// Re-encodes an instruction with the narrowest variant of its opcode which fits its operands
+fn narrow(instruction: AvmInstruction) -> AvmInstruction {
+    if is_set(instruction.opcode) {
+        let offset = instruction.operands[0].to_field();
+        let value = instruction.immediates[0].to_field();
+        // Only SET_8 has an 8 bit offset
+        let value_bits = bits_needed_for(&value);
+        let offset_bits = if value_bits >= 16 { 16 } else { bits_needed_for(&offset) };
+        let bits = value_bits.max(offset_bits);
+        let opcode = match bits {
+            8 => AvmOpcode::SET_8,
+            16 => AvmOpcode::SET_16,
+            32 => AvmOpcode::SET_32,
+            64 => AvmOpcode::SET_64,
+            128 => AvmOpcode::SET_128,
+            _ => AvmOpcode::SET_FF,
+        };
+        return AvmInstruction {
+            opcode,
+            operands: vec![make_operand(offset_bits, &offset)],
+            immediates: vec![make_operand(bits, &value)],
+            ..instruction
+        };
+    }
+
+    let narrow_opcode = match instruction.opcode {
+        AvmOpcode::ADD_16 => AvmOpcode::ADD_8,
+        AvmOpcode::SUB_16 => AvmOpcode::SUB_8,
+        AvmOpcode::MUL_16 => AvmOpcode::MUL_8,
+        AvmOpcode::DIV_16 => AvmOpcode::DIV_8,
+        AvmOpcode::FDIV_16 => AvmOpcode::FDIV_8,
+        AvmOpcode::EQ_16 => AvmOpcode::EQ_8,
+        AvmOpcode::LT_16 => AvmOpcode::LT_8,
+        AvmOpcode::LTE_16 => AvmOpcode::LTE_8,
+        AvmOpcode::AND_16 => AvmOpcode::AND_8,
+        AvmOpcode::OR_16 => AvmOpcode::OR_8,
+        AvmOpcode::XOR_16 => AvmOpcode::XOR_8,
+        AvmOpcode::NOT_16 => AvmOpcode::NOT_8,
+        AvmOpcode::SHL_16 => AvmOpcode::SHL_8,
+        AvmOpcode::SHR_16 => AvmOpcode::SHR_8,
+        AvmOpcode::CAST_16 => AvmOpcode::CAST_8,
+        AvmOpcode::MOV_16 => AvmOpcode::MOV_8,
+        AvmOpcode::REVERT_16 => AvmOpcode::REVERT_8,
+        _ => return instruction,
+    };
+    let operands: Vec<FieldElement> =
+        instruction.operands.iter().map(AvmOperand::to_field).collect();
+    if operands.iter().any(|operand| bits_needed_for(operand) > 8) {
+        return instruction;
+    }
+    AvmInstruction {
+        opcode: narrow_opcode,
+        operands: operands.iter().map(|operand| make_operand(8, operand)).collect(),
+        ..instruction
+    }
+}
+
+/// Marks the instructions which start a basic block: the targets of jumps and calls,
+/// and the instructions after them. The extra final entry is for the end of the program.
+fn block_leaders(instructions: &[AvmInstruction], brillig_pc_starts: &[usize]) -> Vec<bool> {
+    let mut leaders = vec![false; instructions.len() + 1];
+    leaders[0] = true;
+    for (index, instruction) in instructions.iter().enumerate() {
+        for immediate in &instruction.immediates {
+            if let AvmOperand::BRILLIG_LOCATION { brillig_pc } = immediate {
+                leaders[brillig_pc_starts[*brillig_pc as usize]] = true;
+            }
+        }
+        if matches!(
+            instruction.opcode,
+            AvmOpcode::JUMP_32
+                | AvmOpcode::JUMPI_32
+                | AvmOpcode::INTERNALCALL
+                | AvmOpcode::INTERNALRETURN
+                | AvmOpcode::RETURN
+                | AvmOpcode::REVERT_8
+                | AvmOpcode::REVERT_16
+        ) {
+            leaders[index + 1] = true;
+        }
+    }
+    leaders
+}
+
+/// Drops removed instructions, updating where each Brillig opcode starts
+fn compact(
+    instructions: Vec<Option<AvmInstruction>>,
+    brillig_pc_starts: &mut [usize],
+) -> Vec<AvmInstruction> {
+    let mut new_indices = Vec::with_capacity(instructions.len() + 1);
+    let mut kept = Vec::with_capacity(instructions.len());
+    for instruction in instructions {
+        new_indices.push(kept.len());
+        kept.extend(instruction);
+    }
+    new_indices.push(kept.len());
+    for start in brillig_pc_starts.iter_mut() {
+        *start = new_indices[*start];
+    }
+    kept
+}
+
+fn is_set(opcode: AvmOpcode) -> bool {
+    matches!(
+        opcode,
+        AvmOpcode::SET_8
+            | AvmOpcode::SET_16
+            | AvmOpcode::SET_32
+            | AvmOpcode::SET_64
+            | AvmOpcode::SET_128
+            | AvmOpcode::SET_FF
+    )
+}
+
+/// Whether `value` is in the range of values with type `tag`
+fn fits_tag(value: &FieldElement, tag: AvmTypeTag) -> bool {
+    let bits = BitsQueryable::num_bits(value);
+    match tag {
+        AvmTypeTag::FIELD => true,
+        AvmTypeTag::UINT1 => bits <= 1,
+        AvmTypeTag::UINT8 => bits <= 8,
+        AvmTypeTag::UINT16 => bits <= 16,
+        AvmTypeTag::UINT32 => bits <= 32,
+        AvmTypeTag::UINT64 => bits <= 64,
+        AvmTypeTag::UINT128 => bits <= 128,
+        AvmTypeTag::INVALID => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::brillig::Opcode as BrilligOpcode;
+    use acvm::acir::brillig::{BitSize, HeapVector, IntegerBitSize, MemoryAddress};
+    use acvm::FieldElement;
+
+    use crate::disassembler::disassemble;
+    use crate::transpile::brillig_to_avm;
+
+    fn address(offset: usize) -> MemoryAddress {
+        MemoryAddress::direct(offset)
+    }
+
+    fn constant(destination: usize, bit_size: BitSize, value: u128) -> BrilligOpcode<FieldElement> {
+        BrilligOpcode::Const {
+            destination: address(destination),
+            bit_size,
+            value: FieldElement::from(value),
+        }
+    }
+
+    fn mov(destination: usize, source: usize) -> BrilligOpcode<FieldElement> {
+        BrilligOpcode::Mov { destination: address(destination), source: address(source) }
+    }
+
+    fn stop() -> BrilligOpcode<FieldElement> {
+        BrilligOpcode::Stop { return_data: HeapVector { pointer: address(1), size: address(2) } }
+    }
+
+    /// Transpiles a program, returning its disassembly without the trailing magic number
+    fn transpile(program: &[BrilligOpcode<FieldElement>]) -> (String, Vec<usize>) {
+        let (bytecode, pc_map) = brillig_to_avm(program).unwrap();
+        let assembly = disassemble(&bytecode).unwrap();
+        let lines: Vec<&str> = assembly.lines().collect();
+        (lines[..lines.len() - 1].join("\n"), pc_map)
+    }
+
+    #[test]
+    fn removes_redundant_moves_and_casts() {
+        let u8_size = BitSize::Integer(IntegerBitSize::U8);
+        let (assembly, pc_map) = transpile(&[
+            constant(5, u8_size, 3),
+            mov(5, 5),
+            BrilligOpcode::Cast { destination: address(5), source: address(5), bit_size: u8_size },
+            BrilligOpcode::Cast { destination: address(6), source: address(5), bit_size: u8_size },
+            mov(6, 5),
+            stop(),
+        ]);
+        assert_eq!(
+            assembly,
+            [
+                "    SET_8 ind=0, 5, tag=UINT8, #3",
+                "    MOV_8 ind=0, 5, 6",
+                "    RETURN ind=1, 1, 2",
+            ]
+            .join("\n")
+        );
+        assert_eq!(pc_map, [0, 5, 5, 5, 9, 9, 15]);
+    }
+
+    #[test]
+    fn folds_constants_which_are_only_moved() {
+        let (assembly, _) = transpile(&[
+            constant(7, BitSize::Field, 300),
+            mov(8, 7),
+            constant(7, BitSize::Field, 1),
+            // Still read by the second move, so it is kept
+            constant(9, BitSize::Field, 2),
+            mov(10, 9),
+            mov(11, 9),
+            stop(),
+        ]);
+        assert_eq!(
+            assembly,
+            [
+                "    SET_16 ind=0, 8, tag=FIELD, #300",
+                "    SET_8 ind=0, 7, tag=FIELD, #1",
+                "    SET_8 ind=0, 9, tag=FIELD, #2",
+                "    MOV_8 ind=0, 9, 10",
+                "    MOV_8 ind=0, 9, 11",
+                "    RETURN ind=1, 1, 2",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn forgets_memory_at_jump_targets() {
+        let u8_size = BitSize::Integer(IntegerBitSize::U8);
+        let (assembly, pc_map) = transpile(&[
+            constant(5, u8_size, 3),
+            BrilligOpcode::Jump { location: 3 },
+            mov(5, 5),
+            BrilligOpcode::Cast { destination: address(6), source: address(5), bit_size: u8_size },
+            stop(),
+        ]);
+        // The removed move's Brillig pc maps to the instruction after it,
+        // which is also where the jump goes.
+        assert_eq!(pc_map, [0, 5, 10, 10, 15, 21]);
+        assert_eq!(
+            assembly,
+            [
+                "    SET_8 ind=0, 5, tag=UINT8, #3",
+                "    JUMP_32 @L10",
+                "L10:",
+                "    CAST_8 ind=0, 5, 6, tag=UINT8",
+                "    RETURN ind=1, 1, 2",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -2,12 +2,14 @@ use acvm::FieldElement;
 use base64::Engine;
 use log::info;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
-use acvm::acir::circuit::Program;
+use acvm::acir::circuit::brillig::BrilligFunctionId;
+use acvm::acir::circuit::{BrilligOpcodeLocation, Program};
 use noirc_errors::debug_info::ProgramDebugInfo;
 
-use crate::transpile::{brillig_to_avm, patch_debug_info_pcs};
-use crate::utils::extract_brillig_from_acir_program;
+use crate::transpile::{brillig_to_avm, patch_debug_info_pcs, TranspileError};
+use crate::utils::{extract_brillig_from_acir_program, format_location};
 
 /// Representation of a contract with some transpiled functions
 #[derive(Debug, Serialize, Deserialize)]
@@ -80,10 +82,57 @@ pub enum AvmOrAcirContractFunctionArtifact {
     Avm(AvmContractFunctionArtifact),
 }
 
+/// An error raised while transpiling a contract artifact given as JSON.
+#[derive(Debug)]
+pub enum ArtifactError {
+    /// The artifact isn't a contract artifact in the format this transpiler understands.
+    Json(serde_json::Error),
+    Transpile(TranspileError),
+}
+
+impl fmt::Display for ArtifactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArtifactError::Json(error) => write!(
+                f,
+                "unable to parse the contract artifact: {error}. \
+                This is probably a stale artifact with a different wire format, \
+                you might need to recompile the contract"
+            ),
+            ArtifactError::Transpile(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for ArtifactError {}
+
+/// Transpile the public functions of a contract artifact given as JSON,
+/// returning the transpiled artifact as JSON.
+/// Returns `None` if the artifact has already been transpiled.
+pub fn transpile_artifact(contract_json: &str) -> Result<Option<String>, ArtifactError> {
+    let raw_json_obj: serde_json::Value =
+        serde_json::from_str(contract_json).map_err(ArtifactError::Json)?;
+
+    // Skip if contract has "transpiled: true" flag!
+    if let Some(serde_json::Value::Bool(true)) = raw_json_obj.get("transpiled") {
+        return Ok(None);
+    }
+
+    let contract: CompiledAcirContractArtifact =
+        serde_json::from_value(raw_json_obj).map_err(ArtifactError::Json)?;
+    let transpiled_contract =
+        TranspiledContractArtifact::try_from(contract).map_err(ArtifactError::Transpile)?;
+    let transpiled_json =
+        serde_json::to_string(&transpiled_contract).expect("Unable to serialize json");
+    Ok(Some(transpiled_json))
+}
+
 /// Transpilation is performed when a TranspiledContract
 /// is constructed from a CompiledAcirContract
-impl From<CompiledAcirContractArtifact> for TranspiledContractArtifact {
-    fn from(contract: CompiledAcirContractArtifact) -> Self {
+impl TryFrom<CompiledAcirContractArtifact> for TranspiledContractArtifact {
+    type Error = TranspileError;
+
+    fn try_from(contract: CompiledAcirContractArtifact) -> Result<Self, TranspileError> {
         let mut functions: Vec<AvmOrAcirContractFunctionArtifact> = Vec::new();
 
         for function in contract.functions {
@@ -92,11 +141,20 @@ impl From<CompiledAcirContractArtifact> for TranspiledContractArtifact {
                 info!("Transpiling AVM function {} on contract {}", function.name, contract.name);
                 // Extract Brillig Opcodes from acir
                 let acir_program = function.bytecode;
-                let brillig_bytecode = extract_brillig_from_acir_program(&acir_program);
+                let with_context = |error: TranspileError| TranspileError {
+                    function: Some(format!("{}::{}", contract.name, function.name)),
+                    location: error.brillig_pc.and_then(|brillig_pc| {
+                        source_location(&function.debug_symbols, &contract.file_map, brillig_pc)
+                    }),
+                    ..error
+                };
+                let brillig_bytecode = extract_brillig_from_acir_program(&acir_program)
+                    .map_err(|kind| with_context(kind.into()))?;
                 info!("Extracted Brillig program has {} instructions", brillig_bytecode.len());
 
                 // Transpile to AVM
-                let (avm_bytecode, brillig_pcs_to_avm_pcs) = brillig_to_avm(brillig_bytecode);
+                let (avm_bytecode, brillig_pcs_to_avm_pcs) =
+                    brillig_to_avm(brillig_bytecode).map_err(with_context)?;
 
                 log::info!(
                     "{}::{}: bytecode is {} bytes",
@@ -128,13 +186,30 @@ impl From<CompiledAcirContractArtifact> for TranspiledContractArtifact {
                 functions.push(AvmOrAcirContractFunctionArtifact::Acir(function));
             }
         }
-        TranspiledContractArtifact {
+        Ok(TranspiledContractArtifact {
             transpiled: true,
             noir_version: contract.noir_version,
             name: contract.name,
             functions, // some acir, some transpiled avm functions
             outputs: contract.outputs,
             file_map: contract.file_map,
-        }
+        })
     }
 }
+
+/// Looks up the source location of a Brillig instruction in a function's debug symbols.
+/// The innermost location of the call stack is used, as that's the code which was inlined.
+fn source_location(
+    debug_symbols: &ProgramDebugInfo,
+    file_map: &serde_json::Value,
+    brillig_pc: usize,
+) -> Option<String> {
+    let location = debug_symbols
+        .debug_infos
+        .first()?
+        .brillig_locations
+        .get(&BrilligFunctionId(0))?
+        .get(&BrilligOpcodeLocation(brillig_pc))?
+        .last()?;
+    format_location(location, file_map)
+}
@@ -1,12 +1,14 @@
 use acvm::acir::circuit::brillig::BrilligFunctionId;
 use acvm::{AcirField, FieldElement};
 use log::{debug, info, trace};
+use noirc_errors::Location;
 
 use acvm::acir::brillig::Opcode as BrilligOpcode;
 use acvm::acir::circuit::{Opcode, Program};
 
 use crate::instructions::{AvmInstruction, AvmOperand};
 use crate::opcodes::AvmOpcode;
+use crate::transpile::TranspileErrorKind;
 
 /// Extract the Brillig program from its `Program` wrapper.
 /// Noir entry point unconstrained functions are compiled to their own list contained
@@ -16,25 +18,38 @@ use crate::opcodes::AvmOpcode;
 /// 0th unconstrained function in the full `Program` structure.
 pub fn extract_brillig_from_acir_program(
     program: &Program<FieldElement>,
-) -> &[BrilligOpcode<FieldElement>] {
-    assert_eq!(
-        program.functions.len(),
-        1,
-        "An AVM program should have only a single ACIR function with a 'BrilligCall'"
-    );
+) -> Result<&[BrilligOpcode<FieldElement>], TranspileErrorKind> {
+    let invalid = |message: &str| Err(TranspileErrorKind::InvalidProgram(message.to_string()));
+    if program.functions.len() != 1 {
+        return invalid(
+            "An AVM program should have only a single ACIR function with a 'BrilligCall'",
+        );
+    }
     let main_function = &program.functions[0];
     let opcodes = &main_function.opcodes;
-    assert_eq!(opcodes.len(), 1, "An AVM program should only have a single `BrilligCall`");
+    if opcodes.len() != 1 {
+        return invalid("An AVM program should only have a single `BrilligCall`");
+    }
     match opcodes[0] {
-        Opcode::BrilligCall { id, .. } => assert_eq!(id, BrilligFunctionId(0), "The ID of the `BrilligCall` must be 0 as we have a single `Brillig` function"),
-        _ => panic!("Tried to extract a Brillig program from its ACIR wrapper opcode, but the opcode doesn't contain Brillig!"),
+        Opcode::BrilligCall { id, .. } => {
+            if id != BrilligFunctionId(0) {
+                return invalid(
+                    "The ID of the `BrilligCall` must be 0 as we have a single `Brillig` function",
+                );
+            }
+        }
+        _ => {
+            return invalid(
+                "Tried to extract a Brillig program from its ACIR wrapper opcode, but the opcode doesn't contain Brillig!",
+            )
+        }
+    }
+    if program.unconstrained_functions.len() != 1 {
+        return invalid(
+            "An AVM program should be contained entirely in only a single `Brillig` function",
+        );
     }
-    assert_eq!(
-        program.unconstrained_functions.len(),
-        1,
-        "An AVM program should be contained entirely in only a single `Brillig` function"
-    );
-    &program.unconstrained_functions[0].bytecode
+    Ok(&program.unconstrained_functions[0].bytecode)
 }
 
 /// Print inputs, outputs, and instructions in a Brillig program
@@ -76,3 +91,16 @@ pub fn make_operand<T: Into<FieldElement> + Clone>(bits: usize, value: &T) -> Av
         _ => panic!("Invalid operand size for bits: {}", bits),
     }
 }
+
+/// Format a source location as `path:line:column`, using the sources in a contract's file map.
+pub(crate) fn format_location(location: &Location, file_map: &serde_json::Value) -> Option<String> {
+    let file = file_map.get(location.file.as_usize().to_string())?;
+    let path = file.get("path")?.as_str()?;
+    let source = file.get("source")?.as_str()?;
+
+    let start = (location.span.start() as usize).min(source.len());
+    let preceding = source.get(..start)?;
+    let line = preceding.matches('\n').count() + 1;
+    let column = preceding.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    Some(format!("{path}:{line}:{column}"))
+}
@@ -0,0 +1,387 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use itertools::Itertools;
+use powdr_ast::analyzed::Analyzed;
+use powdr_ast::parsed::SelectedExpressions;
+use powdr_number::{DegreeType, FieldElement};
+use serde_json::{json, Value as Json};
+
+use crate::expression_evaluation::{get_references, Target};
+use crate::lookup_builder::get_lookups;
+use crate::permutation_builder::get_permutations;
+use crate::relation_builder::get_relations;
+use crate::utils::{sanitize_name, sort_cols};
+
+/// Pil Analysis
+///
+/// Statistics of a pil, for its authors to keep track of the cost of its relations
+pub struct PilAnalysis {
+    pub relations: Vec<RelationStats>,
+    /// The number of fixed columns
+    pub fixed_columns: usize,
+    /// The number of witness columns, not counting the inverses and counts of lookups and permutations
+    pub witness_columns: usize,
+    /// Columns which are not referenced by any identity, lookup or permutation
+    pub unused_columns: Vec<String>,
+    /// Columns which are referenced at the next row
+    pub shifted_columns: Vec<String>,
+    pub lookups: Vec<InteractionStats>,
+    pub permutations: Vec<InteractionStats>,
+}
+
+pub struct RelationStats {
+    pub name: String,
+    /// The highest degree of the relation's subrelations
+    pub degree: DegreeType,
+    pub subrelations: Vec<SubrelationStats>,
+    /// Whether the relation has a `skippable_if` condition
+    pub skippable: bool,
+}
+
+pub struct SubrelationStats {
+    /// The label of the subrelation, or its index within the relation if it doesn't have one
+    pub label: String,
+    pub degree: DegreeType,
+}
+
+/// A lookup or permutation, with the columns of each side
+pub struct InteractionStats {
+    pub name: String,
+    pub lhs_selector: Option<String>,
+    pub lhs_cols: Vec<String>,
+    pub rhs_selector: Option<String>,
+    pub rhs_cols: Vec<String>,
+}
+
+/// Analyze Pil
+///
+/// Collects the degrees of the relations of a pil, the columns which are unused or shifted, and its lookups
+/// and permutations
+pub fn analyze_pil<F: FieldElement>(analyzed: &Analyzed<F>) -> PilAnalysis {
+    let relations = get_relations(analyzed, Target::Cpp)
+        .into_iter()
+        .map(|relation| {
+            let subrelations = relation
+                .identities
+                .iter()
+                .enumerate()
+                .map(|(index, identity)| SubrelationStats {
+                    label: identity.label.clone().unwrap_or_else(|| index.to_string()),
+                    degree: identity.degree,
+                })
+                .collect_vec();
+            RelationStats {
+                name: relation.name,
+                degree: subrelations.iter().map(|s| s.degree).max().unwrap_or(0),
+                subrelations,
+                skippable: relation.skippable_if.is_some(),
+            }
+        })
+        .collect_vec();
+
+    let fixed = analyzed
+        .constant_polys_in_source_order()
+        .iter()
+        .map(|(sym, _)| sanitize_name(&sym.absolute_name))
+        .collect_vec();
+    let witness = analyzed
+        .committed_polys_in_source_order()
+        .iter()
+        .map(|(sym, _)| sanitize_name(&sym.absolute_name))
+        .collect_vec();
+
+    let identities = analyzed.identities_with_inlined_intermediate_polynomials();
+    let references = identities
+        .iter()
+        .flat_map(|identity| {
+            side_expressions(&identity.left).chain(side_expressions(&identity.right))
+        })
+        .flat_map(get_references)
+        .collect_vec();
+    let used: HashSet<String> = references
+        .iter()
+        .map(|polyref| sanitize_name(&polyref.name))
+        .collect();
+    let unused_columns = fixed
+        .iter()
+        .chain(&witness)
+        .filter(|col| !used.contains(*col))
+        .cloned()
+        .collect_vec();
+    let shifted_columns = sort_cols(
+        &references
+            .iter()
+            .filter(|polyref| polyref.next)
+            .map(|polyref| sanitize_name(&polyref.name))
+            .unique()
+            .collect_vec(),
+    );
+
+    let lookups = get_lookups(analyzed)
+        .into_iter()
+        .map(|lookup| InteractionStats {
+            name: lookup.name,
+            lhs_selector: lookup.left.selector,
+            lhs_cols: lookup.left.cols,
+            rhs_selector: lookup.right.selector,
+            rhs_cols: lookup.right.cols,
+        })
+        .collect_vec();
+    let permutations = get_permutations(analyzed)
+        .into_iter()
+        .map(|permutation| InteractionStats {
+            name: permutation.name,
+            lhs_selector: permutation.left.selector,
+            lhs_cols: permutation.left.cols,
+            rhs_selector: permutation.right.selector,
+            rhs_cols: permutation.right.cols,
+        })
+        .collect_vec();
+
+    PilAnalysis {
+        relations,
+        fixed_columns: fixed.len(),
+        witness_columns: witness.len(),
+        unused_columns,
+        shifted_columns,
+        lookups,
+        permutations,
+    }
+}
+
+fn side_expressions<T>(side: &SelectedExpressions<T>) -> impl Iterator<Item = &T> {
+    side.selector.iter().chain(&side.expressions)
+}
+
+impl PilAnalysis {
+    /// The subrelations of the highest degree, as (relation, subrelation), highest first
+    pub fn hotspots(&self, count: usize) -> Vec<(&RelationStats, &SubrelationStats)> {
+        self.relations
+            .iter()
+            .flat_map(|relation| {
+                relation
+                    .subrelations
+                    .iter()
+                    .map(move |subrelation| (relation, subrelation))
+            })
+            .sorted_by(|a, b| b.1.degree.cmp(&a.1.degree))
+            .take(count)
+            .collect_vec()
+    }
+
+    pub fn to_json(&self, hotspots: usize) -> Json {
+        let interaction_json = |interaction: &InteractionStats| {
+            json!({
+                "name": interaction.name,
+                "lhs_selector": interaction.lhs_selector,
+                "lhs_cols": interaction.lhs_cols,
+                "rhs_selector": interaction.rhs_selector,
+                "rhs_cols": interaction.rhs_cols,
+            })
+        };
+
+        json!({
+            "relations": self.relations.iter().map(|relation| {
+                json!({
+                    "name": relation.name,
+                    "degree": relation.degree,
+                    "skippable": relation.skippable,
+                    "subrelations": relation.subrelations.iter().map(|subrelation| {
+                        json!({
+                            "label": subrelation.label,
+                            "degree": subrelation.degree,
+                        })
+                    }).collect_vec(),
+                })
+            }).collect_vec(),
+            "hotspots": self.hotspots(hotspots).into_iter().map(|(relation, subrelation)| {
+                json!({
+                    "relation": relation.name,
+                    "subrelation": subrelation.label,
+                    "degree": subrelation.degree,
+                })
+            }).collect_vec(),
+            "fixed_columns": self.fixed_columns,
+            "witness_columns": self.witness_columns,
+            "unused_columns": self.unused_columns,
+            "shifted_columns": self.shifted_columns,
+            "lookups": self.lookups.iter().map(interaction_json).collect_vec(),
+            "permutations": self.permutations.iter().map(interaction_json).collect_vec(),
+        })
+    }
+
+    /// The human readable report of the analysis
+    pub fn to_report(&self, hotspots: usize) -> String {
+        let mut report = String::new();
+
+        let num_subrelations: usize = self.relations.iter().map(|r| r.subrelations.len()).sum();
+        writeln!(
+            report,
+            "Relations: {} with {} subrelations",
+            self.relations.len(),
+            num_subrelations
+        )
+        .unwrap();
+        for relation in &self.relations {
+            writeln!(
+                report,
+                "  {}: degree {}, {} subrelations{}",
+                relation.name,
+                relation.degree,
+                relation.subrelations.len(),
+                if relation.skippable {
+                    ", skippable"
+                } else {
+                    ""
+                }
+            )
+            .unwrap();
+        }
+
+        writeln!(report, "\nHighest degree subrelations:").unwrap();
+        for (relation, subrelation) in self.hotspots(hotspots) {
+            writeln!(
+                report,
+                "  {}.{}: degree {}",
+                relation.name, subrelation.label, subrelation.degree
+            )
+            .unwrap();
+        }
+
+        writeln!(
+            report,
+            "\nColumns: {} fixed, {} witness",
+            self.fixed_columns, self.witness_columns
+        )
+        .unwrap();
+        write_list(&mut report, "Shifted columns", &self.shifted_columns);
+        write_list(&mut report, "Unused columns", &self.unused_columns);
+
+        for (title, interactions) in [
+            ("Lookups", &self.lookups),
+            ("Permutations", &self.permutations),
+        ] {
+            writeln!(report, "\n{}: {}", title, interactions.len()).unwrap();
+            for interaction in interactions {
+                writeln!(
+                    report,
+                    "  {}: {} -> {}",
+                    interaction.name,
+                    format_side(&interaction.lhs_selector, &interaction.lhs_cols),
+                    format_side(&interaction.rhs_selector, &interaction.rhs_cols)
+                )
+                .unwrap();
+            }
+        }
+
+        report
+    }
+}
+
+fn write_list(report: &mut String, title: &str, items: &[String]) {
+    writeln!(report, "\n{}: {}", title, items.len()).unwrap();
+    for item in items {
+        writeln!(report, "  {}", item).unwrap();
+    }
+}
+
+fn format_side(selector: &Option<String>, cols: &[String]) -> String {
+    let cols = format!("{{{}}}", cols.join(", "));
+    match selector {
+        Some(selector) => format!("{} {}", selector, cols),
+        None => cols,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use powdr_number::Bn254Field;
+    use powdr_pil_analyzer::analyze_string;
+
+    const PIL: &str = "
+namespace main(4);
+    pol constant first = [1] + [0]*;
+    pol commit sel, a, b, c, unused, sel_t, t;
+    pol sum = a + b;
+
+    #[skippable_if]
+    sel = 0;
+    #[ADD]
+    sel * (sum - c) = 0;
+    #[INCREMENT]
+    sel * (a' - a - 1) = 0;
+    #[CUBE]
+    sel * (a * a * b - c) = 0;
+    sel * (1 - sel) = 0;
+
+    #[LOOKUP_T]
+    sel { c } in sel_t { t };
+    #[PERM_AB]
+    sel { a, b } is sel_t { t, c };
+";
+
+    fn analysis() -> PilAnalysis {
+        analyze_pil(&analyze_string::<Bn254Field>(PIL))
+    }
+
+    fn strings(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect_vec()
+    }
+
+    #[test]
+    fn collects_the_degrees_of_the_subrelations() {
+        let analysis = analysis();
+
+        assert_eq!(analysis.relations.len(), 1);
+        let relation = &analysis.relations[0];
+        assert!(relation.skippable);
+        assert_eq!(relation.degree, 4);
+        let degrees = relation
+            .subrelations
+            .iter()
+            .map(|subrelation| (subrelation.label.as_str(), subrelation.degree))
+            .collect_vec();
+        assert_eq!(
+            degrees,
+            [("ADD", 2), ("INCREMENT", 2), ("CUBE", 4), ("3", 2)]
+        );
+
+        let hotspots = analysis.hotspots(1);
+        assert_eq!(hotspots.len(), 1);
+        assert_eq!(hotspots[0].1.label, "CUBE");
+    }
+
+    #[test]
+    fn collects_the_unused_and_shifted_columns() {
+        let analysis = analysis();
+
+        assert_eq!(analysis.fixed_columns, 1);
+        assert_eq!(analysis.witness_columns, 7);
+        assert_eq!(
+            analysis.unused_columns,
+            strings(&["main_first", "main_unused"])
+        );
+        assert_eq!(analysis.shifted_columns, strings(&["main_a"]));
+    }
+
+    #[test]
+    fn collects_the_lookups_and_permutations() {
+        let analysis = analysis();
+
+        assert_eq!(analysis.lookups.len(), 1);
+        let lookup = &analysis.lookups[0];
+        assert_eq!(lookup.name, "lookup_t");
+        assert_eq!(lookup.lhs_selector.as_deref(), Some("main_sel"));
+        assert_eq!(lookup.lhs_cols, strings(&["main_c"]));
+        assert_eq!(lookup.rhs_selector.as_deref(), Some("main_sel_t"));
+        assert_eq!(lookup.rhs_cols, strings(&["main_t"]));
+
+        assert_eq!(analysis.permutations.len(), 1);
+        let permutation = &analysis.permutations[0];
+        assert_eq!(permutation.name, "perm_ab");
+        assert_eq!(permutation.lhs_cols, strings(&["main_a", "main_b"]));
+        assert_eq!(permutation.rhs_cols, strings(&["main_t", "main_c"]));
+    }
+}
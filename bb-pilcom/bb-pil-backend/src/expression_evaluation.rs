@@ -1,16 +1,24 @@
-use crate::utils::{format_field, sanitize_name};
+use crate::utils::{format_field, format_rust_field, sanitize_name};
 use itertools::Itertools;
 
 use std::collections::{HashMap, HashSet};
 
 use powdr_ast::analyzed::{
-    AlgebraicBinaryOperation, AlgebraicBinaryOperator, AlgebraicExpression,
+    AlgebraicBinaryOperation, AlgebraicBinaryOperator, AlgebraicExpression, AlgebraicReference,
     AlgebraicUnaryOperation, AlgebraicUnaryOperator, Analyzed, Symbol,
 };
 use powdr_number::FieldElement;
 
+/// The language that expressions are generated in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Cpp,
+    Rust,
+}
+
 pub fn get_alias_expressions_in_order<F: FieldElement>(
     alias_polys_in_order: &Vec<(&Symbol, &AlgebraicExpression<F>)>,
+    target: Target,
 ) -> Vec<(String, u64, String)> {
     let aliases_hash = alias_polys_in_order
         .iter()
@@ -21,7 +29,7 @@ pub fn get_alias_expressions_in_order<F: FieldElement>(
         .iter()
         .map(|(sym, _)| {
             let (degree, expr) =
-                get_alias_expression_and_degree(sym.absolute_name.as_str(), &aliases_hash);
+                get_alias_expression_and_degree(sym.absolute_name.as_str(), &aliases_hash, target);
             (sanitize_name(&sym.absolute_name), degree, expr)
         })
         .collect_vec()
@@ -47,9 +55,14 @@ pub fn get_alias_polys_in_order<F: FieldElement>(
 pub fn get_alias_expression_and_degree<F: FieldElement>(
     alias_name: &str,
     aliases: &HashMap<&String, &AlgebraicExpression<F>>,
+    target: Target,
 ) -> (u64, String) {
-    let (degree, expression, _) =
-        recurse_expression(aliases.get(&alias_name.to_owned()).unwrap(), aliases, false);
+    let (degree, expression, _) = recurse_expression(
+        aliases.get(&alias_name.to_owned()).unwrap(),
+        aliases,
+        false,
+        target,
+    );
     (degree, expression)
 }
 
@@ -60,14 +73,21 @@ pub fn recurse_expression<F: FieldElement>(
     top_expr: &AlgebraicExpression<F>,
     aliases: &HashMap<&String, &AlgebraicExpression<F>>,
     inline_aliases: bool,
+    target: Target,
 ) -> (u64, String, HashSet<String>) {
     match top_expr {
-        AlgebraicExpression::Number(n) => (0, format_field(n), HashSet::new()),
+        AlgebraicExpression::Number(n) => {
+            let number = match target {
+                Target::Cpp => format_field(n),
+                Target::Rust => format_rust_field(n),
+            };
+            (0, number, HashSet::new())
+        }
         AlgebraicExpression::Reference(polyref) => {
             if aliases.contains_key(&polyref.name) {
                 let alias_expr = aliases.get(&polyref.name).unwrap();
                 let (d, expr, mut rec_aliases) =
-                    recurse_expression(alias_expr, aliases, inline_aliases);
+                    recurse_expression(alias_expr, aliases, inline_aliases, target);
                 let sanitized_name = sanitize_name(&polyref.name);
                 rec_aliases.insert(sanitized_name.clone());
                 if inline_aliases {
@@ -88,8 +108,8 @@ pub fn recurse_expression<F: FieldElement>(
             op,
             right: rhe,
         }) => {
-            let (ld, lhs, lhs_aliases) = recurse_expression(lhe, aliases, inline_aliases);
-            let (rd, rhs, rhs_aliases) = recurse_expression(rhe, aliases, inline_aliases);
+            let (ld, lhs, lhs_aliases) = recurse_expression(lhe, aliases, inline_aliases, target);
+            let (rd, rhs, rhs_aliases) = recurse_expression(rhe, aliases, inline_aliases, target);
             let joined_aliases = lhs_aliases.union(&rhs_aliases).cloned().collect();
 
             match op {
@@ -122,7 +142,8 @@ pub fn recurse_expression<F: FieldElement>(
             expr: rec_expr,
         }) => match operator {
             AlgebraicUnaryOperator::Minus => {
-                let (d, e, rec_aliases) = recurse_expression(rec_expr, aliases, inline_aliases);
+                let (d, e, rec_aliases) =
+                    recurse_expression(rec_expr, aliases, inline_aliases, target);
                 (d, format!("-{}", e), rec_aliases)
             }
         },
@@ -132,3 +153,71 @@ pub fn recurse_expression<F: FieldElement>(
         AlgebraicExpression::Challenge(_) => unimplemented!("{:?}", top_expr),
     }
 }
+
+/// Evaluates an expression whose aliases have been inlined.
+/// `value` returns the value of a column reference, which is the value at the next row if the
+/// reference is shifted.
+/// Returns an error for the expressions our pils don't use: powers, public references and challenges.
+pub fn evaluate_expression<F: FieldElement>(
+    expr: &AlgebraicExpression<F>,
+    value: &impl Fn(&AlgebraicReference) -> F,
+) -> Result<F, String> {
+    match expr {
+        AlgebraicExpression::Number(n) => Ok(*n),
+        AlgebraicExpression::Reference(polyref) => Ok(value(polyref)),
+        AlgebraicExpression::BinaryOperation(AlgebraicBinaryOperation {
+            left: lhe,
+            op,
+            right: rhe,
+        }) => {
+            let lhs = evaluate_expression(lhe, value)?;
+            let rhs = evaluate_expression(rhe, value)?;
+            match op {
+                AlgebraicBinaryOperator::Add => Ok(lhs + rhs),
+                AlgebraicBinaryOperator::Sub => Ok(lhs - rhs),
+                AlgebraicBinaryOperator::Mul => Ok(lhs * rhs),
+                _ => Err(format!("cannot evaluate operator {:?}", op)),
+            }
+        }
+        AlgebraicExpression::UnaryOperation(AlgebraicUnaryOperation {
+            op: operator,
+            expr: rec_expr,
+        }) => match operator {
+            AlgebraicUnaryOperator::Minus => Ok(-evaluate_expression(rec_expr, value)?),
+        },
+        AlgebraicExpression::PublicReference(_) | AlgebraicExpression::Challenge(_) => {
+            Err(format!("cannot evaluate {:?}", expr))
+        }
+    }
+}
+
+/// Collects the column references of an expression whose aliases have been inlined,
+/// without duplicates and in the order they appear
+pub fn get_references<F: FieldElement>(expr: &AlgebraicExpression<F>) -> Vec<&AlgebraicReference> {
+    let mut references = Vec::new();
+    collect_references(expr, &mut references);
+    references
+}
+
+fn collect_references<'a, F: FieldElement>(
+    expr: &'a AlgebraicExpression<F>,
+    references: &mut Vec<&'a AlgebraicReference>,
+) {
+    match expr {
+        AlgebraicExpression::Reference(polyref) if !references.contains(&polyref) => {
+            references.push(polyref);
+        }
+        AlgebraicExpression::BinaryOperation(AlgebraicBinaryOperation {
+            left: lhe,
+            right: rhe,
+            ..
+        }) => {
+            collect_references(lhe, references);
+            collect_references(rhe, references);
+        }
+        AlgebraicExpression::UnaryOperation(AlgebraicUnaryOperation { expr, .. }) => {
+            collect_references(expr, references);
+        }
+        _ => {}
+    }
+}
@@ -1,11 +1,25 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
 use std::fs::File;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Whether generated files are written to disk, or only checked against the files on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteMode {
+    Write,
+    Check,
+}
 
 pub struct BBFiles {
     pub vm_name: String,
     pub base_dir: String,
     pub relations: String,
+    pub mode: WriteMode,
+    /// The paths of the files generated so far, relative to the base directory
+    generated: RefCell<BTreeSet<PathBuf>>,
+    /// In check mode, the paths of the generated files which differ from the files on disk
+    out_of_date: RefCell<BTreeSet<PathBuf>>,
 }
 
 impl BBFiles {
@@ -24,23 +38,169 @@ impl BBFiles {
             vm_name: vm_name.to_owned(),
             base_dir,
             relations,
+            mode: WriteMode::Write,
+            generated: RefCell::new(BTreeSet::new()),
+            out_of_date: RefCell::new(BTreeSet::new()),
         }
     }
 
-    pub fn remove_generated_dir(&self) {
-        let path = Path::new(&self.base_dir);
-        if path.exists() {
-            std::fs::remove_dir_all(path).unwrap();
-        }
+    pub fn with_mode(mut self, mode: WriteMode) -> Self {
+        self.mode = mode;
+        self
     }
 
+    /// Write File
+    ///
+    /// Files whose contents are unchanged are not rewritten, so that what depends on them isn't rebuilt.
+    /// In check mode, nothing is written, and the files which would be are recorded as out of date.
     pub fn write_file(&self, folder: Option<&str>, filename: &str, contents: &str) {
-        // attempt to create dir
-        let base_path = Path::new(&self.base_dir).join(folder.unwrap_or(""));
-        let _ = std::fs::create_dir_all(&base_path);
+        let relative_path = Path::new(folder.unwrap_or("")).join(filename);
+        let path = Path::new(&self.base_dir).join(&relative_path);
+        self.generated.borrow_mut().insert(relative_path.clone());
+
+        if std::fs::read(&path).is_ok_and(|existing| existing == contents.as_bytes()) {
+            return;
+        }
+
+        match self.mode {
+            WriteMode::Write => {
+                // attempt to create dir
+                let _ = std::fs::create_dir_all(path.parent().unwrap());
+
+                let mut file = File::create(path).unwrap();
+                file.write_all(contents.as_bytes()).unwrap();
+            }
+            WriteMode::Check => {
+                self.out_of_date.borrow_mut().insert(relative_path);
+            }
+        }
+    }
+
+    /// Stale Files
+    ///
+    /// The files of the base directory which have not been generated, relative to the base directory
+    pub fn stale_files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        collect_files(Path::new(&self.base_dir), Path::new(""), &mut files);
+
+        let generated = self.generated.borrow();
+        files.retain(|file| !generated.contains(file));
+        files.sort();
+        files
+    }
+
+    /// Removes files relative to the base directory, and the directories they leave empty
+    pub fn remove_files(&self, files: &[PathBuf]) {
+        let base_dir = Path::new(&self.base_dir);
+        for file in files {
+            std::fs::remove_file(base_dir.join(file)).unwrap();
+
+            // `remove_dir` fails on the first directory which isn't empty
+            for dir in file.ancestors().skip(1) {
+                if dir.as_os_str().is_empty() || std::fs::remove_dir(base_dir.join(dir)).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// In check mode, the generated files which are missing or differ from the files on disk, relative to
+    /// the base directory
+    pub fn out_of_date_files(&self) -> Vec<PathBuf> {
+        self.out_of_date.borrow().iter().cloned().collect()
+    }
+}
+
+/// Collects the paths of the files of a directory recursively, relative to `base_dir`
+fn collect_files(base_dir: &Path, relative_dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(base_dir.join(relative_dir)) else {
+        return;
+    };
+    for entry in entries {
+        let entry = entry.unwrap();
+        let relative_path = relative_dir.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            collect_files(base_dir, &relative_path, files);
+        } else {
+            files.push(relative_path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bb_pil_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn only_writes_changed_files_and_removes_stale_ones() {
+        let dir = temp_dir("write");
+        let files = BBFiles::new("test", Some(dir.to_str().unwrap()), None);
+        files.write_file(None, "same.hpp", "same");
+        files.write_file(Some("relations"), "changed.hpp", "old");
+        files.write_file(Some("relations"), "stale.hpp", "stale");
+        files.write_file(Some("stale"), "stale.hpp", "stale");
+        let modified = std::fs::metadata(dir.join("same.hpp"))
+            .unwrap()
+            .modified()
+            .unwrap();
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let files = BBFiles::new("test", Some(dir.to_str().unwrap()), None);
+        files.write_file(None, "same.hpp", "same");
+        files.write_file(Some("relations"), "changed.hpp", "new");
+        let stale = files.stale_files();
+        assert_eq!(
+            stale,
+            vec![
+                PathBuf::from("relations/stale.hpp"),
+                PathBuf::from("stale/stale.hpp")
+            ]
+        );
+        files.remove_files(&stale);
+
+        let same = std::fs::metadata(dir.join("same.hpp")).unwrap();
+        assert_eq!(same.modified().unwrap(), modified);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("relations/changed.hpp")).unwrap(),
+            "new"
+        );
+        assert!(!dir.join("relations/stale.hpp").exists());
+        assert!(!dir.join("stale").exists());
+        assert!(files.out_of_date_files().is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn check_mode_reports_out_of_date_files_without_writing() {
+        let dir = temp_dir("check");
+        let files = BBFiles::new("test", Some(dir.to_str().unwrap()), None);
+        files.write_file(None, "same.hpp", "same");
+        files.write_file(None, "changed.hpp", "old");
+        files.write_file(None, "stale.hpp", "stale");
+
+        let files =
+            BBFiles::new("test", Some(dir.to_str().unwrap()), None).with_mode(WriteMode::Check);
+        files.write_file(None, "same.hpp", "same");
+        files.write_file(None, "changed.hpp", "new");
+        files.write_file(None, "missing.hpp", "missing");
+        assert_eq!(
+            files.out_of_date_files(),
+            vec![PathBuf::from("changed.hpp"), PathBuf::from("missing.hpp")]
+        );
+        assert_eq!(files.stale_files(), vec![PathBuf::from("stale.hpp")]);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("changed.hpp")).unwrap(),
+            "old"
+        );
+        assert!(!dir.join("missing.hpp").exists());
 
-        let joined = base_path.join(filename);
-        let mut file = File::create(joined).unwrap();
-        file.write_all(contents.as_bytes()).unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
     }
 }
@@ -1,3 +1,4 @@
+pub mod analysis;
 mod circuit_builder;
 mod composer_builder;
 mod expression_evaluation;
@@ -7,6 +8,9 @@ pub mod lookup_builder;
 pub mod permutation_builder;
 mod prover_builder;
 mod relation_builder;
+mod rust_builder;
+pub mod trace;
+pub mod trace_checker;
 mod utils;
 mod verifier_builder;
 pub mod vm_builder;
@@ -36,41 +36,18 @@ pub struct Lookup {
 /// One side of a two sided lookup relationship
 pub struct LookupSide {
     /// -> Option<String> - the selector for the lookup ( on / off toggle )
-    selector: Option<String>,
+    pub selector: Option<String>,
     /// The columns involved in this side of the lookup
-    cols: Vec<String>,
+    pub cols: Vec<String>,
 }
 
 pub trait LookupBuilder {
-    /// Takes in an AST and works out what lookup relations are needed
-    /// Note: returns the name of the inverse columns, such that they can be added to the prover in subsequent steps
-    fn create_lookup_files<F: FieldElement>(&self, analyzed: &Analyzed<F>) -> Vec<Lookup>;
+    /// Creates the settings file of each lookup relation, collected from the AST by `get_lookups`
+    fn create_lookup_files(&self, lookups: &[Lookup]);
 }
 
 impl LookupBuilder for BBFiles {
-    fn create_lookup_files<F: FieldElement>(&self, analyzed: &Analyzed<F>) -> Vec<Lookup> {
-        let lookups = analyzed
-            .identities
-            .iter()
-            .filter(|identity| matches!(identity.kind, IdentityKind::Plookup))
-            .map(|lookup| {
-                let name = lookup
-                    .attribute
-                    .clone()
-                    .expect(
-                        "Inverse column name must be provided within lookup attribute - #[<here>]",
-                    )
-                    .to_lowercase();
-                Lookup {
-                    name: name.clone(),
-                    inverse: format!("{}_inv", &name),
-                    counts_poly: format!("{}_counts", &name),
-                    left: get_lookup_side(&lookup.left),
-                    right: get_lookup_side(&lookup.right),
-                }
-            })
-            .collect_vec();
-
+    fn create_lookup_files(&self, lookups: &[Lookup]) {
         let mut handlebars = Handlebars::new();
 
         handlebars
@@ -87,11 +64,32 @@ impl LookupBuilder for BBFiles {
             let file_name = format!("{}.hpp", lookup.name);
             self.write_file(Some(&self.relations), &file_name, &lookup_settings);
         }
-
-        lookups
     }
 }
 
+/// Collects the lookups of the pil, in source order
+pub fn get_lookups<F: FieldElement>(analyzed: &Analyzed<F>) -> Vec<Lookup> {
+    analyzed
+        .identities
+        .iter()
+        .filter(|identity| matches!(identity.kind, IdentityKind::Plookup))
+        .map(|lookup| {
+            let name = lookup
+                .attribute
+                .clone()
+                .expect("Inverse column name must be provided within lookup attribute - #[<here>]")
+                .to_lowercase();
+            Lookup {
+                name: name.clone(),
+                inverse: format!("{}_inv", &name),
+                counts_poly: format!("{}_counts", &name),
+                left: get_lookup_side(&lookup.left),
+                right: get_lookup_side(&lookup.right),
+            }
+        })
+        .collect_vec()
+}
+
 /// The attributes of a lookup contain the name of the inverse, we collect all of these to create the inverse column
 pub fn get_inverses_from_lookups(lookups: &[Lookup]) -> Vec<String> {
     lookups
@@ -32,42 +32,18 @@ pub struct Permutation {
 /// One side of a two sided permutation relationship
 pub struct PermutationSide {
     /// -> Option<String> - the selector for the permutation ( on / off toggle )
-    selector: Option<String>,
+    pub selector: Option<String>,
     /// The columns involved in this side of the permutation
-    cols: Vec<String>,
+    pub cols: Vec<String>,
 }
 
 pub trait PermutationBuilder {
-    /// Takes in an AST and works out what permutation relations are needed
-    /// Note: returns the name of the inverse columns, such that they can be added to he prover in subsequent steps
-    fn create_permutation_files<F: FieldElement>(&self, analyzed: &Analyzed<F>)
-        -> Vec<Permutation>;
+    /// Creates the settings file of each permutation relation, collected from the AST by `get_permutations`
+    fn create_permutation_files(&self, permutations: &[Permutation]);
 }
 
 impl PermutationBuilder for BBFiles {
-    fn create_permutation_files<F: FieldElement>(
-        &self,
-        analyzed: &Analyzed<F>,
-    ) -> Vec<Permutation> {
-        let permutations = analyzed
-            .identities
-            .iter()
-            .filter(|identity| matches!(identity.kind, IdentityKind::Permutation))
-            .map(|perm| {
-                let name = perm
-                    .attribute
-                    .clone()
-                    .expect("Permutation name must be provided using attribute syntax")
-                    .to_lowercase();
-                Permutation {
-                    name: name.clone(),
-                    inverse: format!("{}_inv", &name),
-                    left: get_perm_side(&perm.left),
-                    right: get_perm_side(&perm.right),
-                }
-            })
-            .collect_vec();
-
+    fn create_permutation_files(&self, permutations: &[Permutation]) {
         let mut handlebars = Handlebars::new();
 
         handlebars
@@ -84,11 +60,31 @@ impl PermutationBuilder for BBFiles {
             let file_name = format!("{}.hpp", permutation.name);
             self.write_file(Some(&self.relations), &file_name, &perm_settings);
         }
-
-        permutations
     }
 }
 
+/// Collects the permutations of the pil, in source order
+pub fn get_permutations<F: FieldElement>(analyzed: &Analyzed<F>) -> Vec<Permutation> {
+    analyzed
+        .identities
+        .iter()
+        .filter(|identity| matches!(identity.kind, IdentityKind::Permutation))
+        .map(|perm| {
+            let name = perm
+                .attribute
+                .clone()
+                .expect("Permutation name must be provided using attribute syntax")
+                .to_lowercase();
+            Permutation {
+                name: name.clone(),
+                inverse: format!("{}_inv", &name),
+                left: get_perm_side(&perm.left),
+                right: get_perm_side(&perm.right),
+            }
+        })
+        .collect_vec()
+}
+
 /// The attributes of a permutation contain the name of the inverse, we collect all of these to create the inverse column
 pub fn get_inverses_from_permutations(permutations: &[Permutation]) -> Vec<String> {
     permutations
@@ -17,6 +17,7 @@ use serde_json::json;
 use crate::expression_evaluation::get_alias_expressions_in_order;
 use crate::expression_evaluation::get_alias_polys_in_order;
 use crate::expression_evaluation::recurse_expression;
+use crate::expression_evaluation::Target;
 use crate::file_writer::BBFiles;
 use crate::utils::snake_case;
 
@@ -32,17 +33,11 @@ pub struct BBIdentity {
 pub trait RelationBuilder {
     /// Create Relations
     ///
-    /// Takes in the ast ( for relations ), groups each of them by file, and then
+    /// Takes in the relations, grouped by file by `get_relations`, and then
     /// calls 'create relation' for each
     ///
-    /// Relation output is passed back to the caller as the prover requires both:
-    /// - The shifted polys
-    /// - The names of the relations files created
-    fn create_relations<F: FieldElement>(
-        &self,
-        root_name: &str,
-        analyzed: &Analyzed<F>,
-    ) -> Vec<String>;
+    /// The names of the relations files created are passed back to the caller
+    fn create_relations(&self, root_name: &str, relations: &[RelationIdentities]) -> Vec<String>;
 
     /// Create Relation
     ///
@@ -64,55 +59,22 @@ pub trait RelationBuilder {
 }
 
 impl RelationBuilder for BBFiles {
-    fn create_relations<F: FieldElement>(
-        &self,
-        file_name: &str,
-        analyzed: &Analyzed<F>,
-    ) -> Vec<String> {
-        // These identities' terminal objects are either fields, columns, or alias expressions.
-        let mut analyzed_identities = analyzed.identities.clone();
-        analyzed_identities.sort_by(|a, b| a.id.cmp(&b.id));
-
-        let alias_polys_in_order = get_alias_polys_in_order(analyzed);
-        let alias_expressions_in_order = get_alias_expressions_in_order(&alias_polys_in_order);
-        let indexed_aliases = alias_polys_in_order
-            .into_iter()
-            .map(|(sym, expr)| (&sym.absolute_name, expr))
-            .collect::<HashMap<_, _>>();
-
-        // Group relations per file
-        let grouped_relations: HashMap<String, Vec<Identity<AlgebraicExpression<F>>>> =
-            group_relations_per_file(&analyzed_identities);
-        let mut relations = grouped_relations.keys().cloned().collect_vec();
-        relations.sort();
-
+    fn create_relations(&self, file_name: &str, relations: &[RelationIdentities]) -> Vec<String> {
         // ----------------------- Create the relation files -----------------------
-        for (relation_name, analyzed_idents) in grouped_relations.iter() {
-            let IdentitiesOutput {
-                identities,
-                skippable_if,
-                // These are the aliases used in the identities in this file.
-                collected_aliases,
-            } = create_identities(analyzed_idents, &indexed_aliases);
-
-            let used_alias_defs_in_order = alias_expressions_in_order
-                .iter()
-                .filter(|(name, _, _)| collected_aliases.contains(name))
-                .cloned()
-                .collect_vec();
-
+        for relation in relations.iter() {
             self.create_relation(
                 file_name,
-                relation_name,
-                &identities,
-                &skippable_if,
-                &used_alias_defs_in_order,
+                &relation.name,
+                &relation.identities,
+                &relation.skippable_if,
+                &relation.alias_defs,
             );
         }
 
-        relations.sort();
-
         relations
+            .iter()
+            .map(|relation| relation.name.clone())
+            .collect()
     }
 
     fn create_relation(
@@ -174,6 +136,66 @@ impl RelationBuilder for BBFiles {
     }
 }
 
+/// The identities of the relations defined in one .pil file
+pub struct RelationIdentities {
+    /// The name of the relation, from the name of its file
+    pub name: String,
+    pub identities: Vec<BBIdentity>,
+    pub skippable_if: Option<BBIdentity>,
+    /// The aliases used by the identities as (name, degree, expression), in the order they must be defined
+    pub alias_defs: Vec<(String, u64, String)>,
+}
+
+/// Get Relations
+///
+/// Collects the identities of each relation, with their expressions generated in the target language.
+/// Relations are sorted by name.
+pub fn get_relations<F: FieldElement>(
+    analyzed: &Analyzed<F>,
+    target: Target,
+) -> Vec<RelationIdentities> {
+    // These identities' terminal objects are either fields, columns, or alias expressions.
+    let mut analyzed_identities = analyzed.identities.clone();
+    analyzed_identities.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let alias_polys_in_order = get_alias_polys_in_order(analyzed);
+    let alias_expressions_in_order = get_alias_expressions_in_order(&alias_polys_in_order, target);
+    let indexed_aliases = alias_polys_in_order
+        .into_iter()
+        .map(|(sym, expr)| (&sym.absolute_name, expr))
+        .collect::<HashMap<_, _>>();
+
+    // Group relations per file
+    let grouped_relations: HashMap<String, Vec<Identity<AlgebraicExpression<F>>>> =
+        group_relations_per_file(&analyzed_identities);
+
+    grouped_relations
+        .into_iter()
+        .map(|(name, analyzed_idents)| {
+            let IdentitiesOutput {
+                identities,
+                skippable_if,
+                // These are the aliases used in the identities in this file.
+                collected_aliases,
+            } = create_identities(&analyzed_idents, &indexed_aliases, target);
+
+            let alias_defs = alias_expressions_in_order
+                .iter()
+                .filter(|(alias, _, _)| collected_aliases.contains(alias))
+                .cloned()
+                .collect_vec();
+
+            RelationIdentities {
+                name,
+                identities,
+                skippable_if,
+                alias_defs,
+            }
+        })
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+        .collect_vec()
+}
+
 /// Group relations per file
 ///
 /// The compiler returns all relations in one large vector, however we want to distinguish
@@ -194,7 +216,7 @@ impl RelationBuilder for BBFiles {
 /// }
 ///
 /// This allows us to generate a relation.hpp file containing ONLY the relations for that .pil file
-fn group_relations_per_file<F: FieldElement>(
+pub(crate) fn group_relations_per_file<F: FieldElement>(
     identities: &[Identity<AlgebraicExpression<F>>],
 ) -> HashMap<String, Vec<Identity<AlgebraicExpression<F>>>> {
     identities.iter().cloned().into_group_map_by(|identity| {
@@ -214,10 +236,11 @@ fn create_identity<F: FieldElement>(
     collected_aliases: &mut HashSet<String>,
     label: &Option<String>,
     indexed_aliases: &HashMap<&String, &AlgebraicExpression<F>>,
+    target: Target,
 ) -> Option<BBIdentity> {
     // We want to read the types of operators and then create the appropiate code
     if let Some(expr) = &expression.selector {
-        let (degree, id, col_aliases) = recurse_expression(expr, indexed_aliases, false);
+        let (degree, id, col_aliases) = recurse_expression(expr, indexed_aliases, false, target);
         collected_aliases.extend(col_aliases);
         log::trace!("expression {:?}, {:?}", degree, id);
         Some(BBIdentity {
@@ -239,6 +262,7 @@ pub struct IdentitiesOutput {
 pub(crate) fn create_identities<F: FieldElement>(
     identities: &[Identity<AlgebraicExpression<F>>],
     indexed_aliases: &HashMap<&String, &AlgebraicExpression<F>>,
+    target: Target,
 ) -> IdentitiesOutput {
     // We only want the expressions for now
     // When we have a poly type, we only need the left side of it
@@ -257,6 +281,7 @@ pub(crate) fn create_identities<F: FieldElement>(
             &mut collected_aliases,
             &expression.attribute,
             indexed_aliases,
+            target,
         )
         .unwrap();
 
@@ -0,0 +1,210 @@
+use crate::file_writer::BBFiles;
+use crate::lookup_builder::Lookup;
+use crate::permutation_builder::Permutation;
+use crate::relation_builder::RelationIdentities;
+use crate::utils::snake_case;
+use handlebars::{no_escape, Handlebars};
+use itertools::Itertools;
+use serde_json::json;
+
+/// The folder of the generated relation, lookup and permutation modules
+const RELATIONS_FOLDER: &str = "relations";
+
+pub trait RustBuilder {
+    /// Create Rust Columns
+    ///
+    /// Creates `columns.rs`, with the `Row` struct of the trace and the `Entities` that relations are
+    /// evaluated over, which are a row's columns along with the shifted columns of the next row
+    fn create_rust_columns(&self, all_cols: &[String], to_be_shifted: &[String]);
+
+    /// Create Rust Relation
+    ///
+    /// Creates the module of one relation, with a function evaluating each of its subrelations
+    fn create_rust_relation(&self, relation: &RelationIdentities);
+
+    /// Create Rust Lookup
+    ///
+    /// Creates the module of one lookup, with its settings and the tuples read from each row
+    fn create_rust_lookup(&self, lookup: &Lookup);
+
+    /// Create Rust Permutation
+    ///
+    /// Creates the module of one permutation, with its settings and the tuples read from each row
+    fn create_rust_permutation(&self, permutation: &Permutation);
+
+    /// Create Rust Mod
+    ///
+    /// Creates `mod.rs`, with the `Field` trait that the generated code is generic over and a
+    /// circuit checker running every relation, lookup and permutation over a trace,
+    /// and the `mod.rs` of the relations folder
+    fn create_rust_mod(
+        &self,
+        name: &str,
+        relations: &[String],
+        lookups: &[String],
+        permutations: &[String],
+    );
+}
+
+impl RustBuilder for BBFiles {
+    fn create_rust_columns(&self, all_cols: &[String], to_be_shifted: &[String]) {
+        let data = &json!({
+            "all_cols": all_cols,
+            "to_be_shifted": to_be_shifted,
+        });
+
+        let columns_rs = render(
+            "columns.rs",
+            std::str::from_utf8(include_bytes!("../templates/columns.rs.hbs")).unwrap(),
+            data,
+        );
+
+        self.write_file(None, "columns.rs", &columns_rs);
+    }
+
+    fn create_rust_relation(&self, relation: &RelationIdentities) {
+        let degrees: Vec<_> = relation.identities.iter().map(|id| id.degree + 1).collect();
+        let labels = relation
+            .identities
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, id)| id.label.clone().map(|label| (idx, label)))
+            .collect_vec();
+
+        let data = &json!({
+            "name": relation.name,
+            "identities": relation.identities.iter().map(|id| id.identity.clone()).collect_vec(),
+            "alias_defs": relation.alias_defs.iter().map(|(name, _, expr)| {
+                json!({
+                    "name": name,
+                    "expr": expr,
+                })
+            }).collect_vec(),
+            "skippable_if": relation.skippable_if.as_ref().map(|id| id.identity.clone()),
+            "degrees": degrees,
+            "labels": labels,
+        });
+
+        let relation_rs = render(
+            "relation.rs",
+            std::str::from_utf8(include_bytes!("../templates/relation.rs.hbs")).unwrap(),
+            data,
+        );
+
+        self.write_file(
+            Some(RELATIONS_FOLDER),
+            &format!("{}.rs", snake_case(&relation.name)),
+            &relation_rs,
+        );
+    }
+
+    fn create_rust_lookup(&self, lookup: &Lookup) {
+        let data = &json!({
+            "name": lookup.name,
+            "inverse": lookup.inverse,
+            "counts_poly": lookup.counts_poly,
+            "lhs_selector": lookup
+                .left
+                .selector
+                .clone()
+                .expect("Left hand side selector for lookup required"),
+            "rhs_selector": lookup
+                .right
+                .selector
+                .clone()
+                .expect("Right hand side selector for lookup required"),
+            "lhs_cols": lookup.left.cols,
+            "rhs_cols": lookup.right.cols,
+        });
+        assert!(
+            lookup.left.cols.len() == lookup.right.cols.len(),
+            "Lookup columns lhs must be the same length as rhs"
+        );
+
+        let lookup_rs = render(
+            "lookup.rs",
+            std::str::from_utf8(include_bytes!("../templates/lookup.rs.hbs")).unwrap(),
+            data,
+        );
+
+        self.write_file(
+            Some(RELATIONS_FOLDER),
+            &format!("{}.rs", lookup.name),
+            &lookup_rs,
+        );
+    }
+
+    fn create_rust_permutation(&self, permutation: &Permutation) {
+        let lhs_selector = permutation
+            .left
+            .selector
+            .clone()
+            .expect("At least one selector must be provided");
+        // As in the C++ settings, the lhs selector is used if there is no rhs selector
+        let rhs_selector = permutation
+            .right
+            .selector
+            .clone()
+            .unwrap_or(lhs_selector.clone());
+
+        let data = &json!({
+            "name": permutation.name,
+            "inverse": permutation.inverse,
+            "lhs_selector": lhs_selector,
+            "rhs_selector": rhs_selector,
+            "lhs_cols": permutation.left.cols,
+            "rhs_cols": permutation.right.cols,
+        });
+
+        let permutation_rs = render(
+            "permutation.rs",
+            std::str::from_utf8(include_bytes!("../templates/permutation.rs.hbs")).unwrap(),
+            data,
+        );
+
+        self.write_file(
+            Some(RELATIONS_FOLDER),
+            &format!("{}.rs", permutation.name),
+            &permutation_rs,
+        );
+    }
+
+    fn create_rust_mod(
+        &self,
+        name: &str,
+        relations: &[String],
+        lookups: &[String],
+        permutations: &[String],
+    ) {
+        let data = &json!({
+            "name": name,
+            "relations": relations,
+            "lookups": lookups,
+            "permutations": permutations,
+        });
+
+        let mod_rs = render(
+            "mod.rs",
+            std::str::from_utf8(include_bytes!("../templates/mod.rs.hbs")).unwrap(),
+            data,
+        );
+        self.write_file(None, "mod.rs", &mod_rs);
+
+        let relations_mod_rs = render(
+            "relations_mod.rs",
+            std::str::from_utf8(include_bytes!("../templates/relations_mod.rs.hbs")).unwrap(),
+            data,
+        );
+        self.write_file(Some(RELATIONS_FOLDER), "mod.rs", &relations_mod_rs);
+    }
+}
+
+/// Renders a template of Rust code, which mustn't be escaped as html
+fn render(name: &str, template: &str, data: &serde_json::Value) -> String {
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(no_escape);
+
+    handlebars.register_template_string(name, template).unwrap();
+
+    handlebars.render(name, data).unwrap()
+}
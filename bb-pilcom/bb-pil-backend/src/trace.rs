@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, Read};
+
+use powdr_number::FieldElement;
+
+use crate::utils::sanitize_name;
+
+/// The size in bytes of a value in a binary trace
+const BINARY_VALUE_SIZE: usize = 32;
+
+/// Trace
+///
+/// The values of the columns of a witness, keyed by their sanitized names.
+/// Rows past the end of a column are zero, as barretenberg leaves out the trailing rows which are all
+/// zero when it dumps a trace.
+pub struct Trace<F> {
+    columns: HashMap<String, Vec<F>>,
+    num_rows: usize,
+}
+
+impl<F: FieldElement> Trace<F> {
+    pub fn new(columns: HashMap<String, Vec<F>>) -> Self {
+        let columns: HashMap<_, _> = columns
+            .into_iter()
+            .map(|(name, values)| (sanitize_name(&name), values))
+            .collect();
+        let num_rows = columns
+            .values()
+            .map(|values| values.len())
+            .max()
+            .unwrap_or(0);
+        Self { columns, num_rows }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn has_column(&self, column: &str) -> bool {
+        self.columns.contains_key(column)
+    }
+
+    /// The value of a column at a row, which is zero if either is not in the trace
+    pub fn get(&self, column: &str, row: usize) -> F {
+        self.columns
+            .get(column)
+            .and_then(|values| values.get(row))
+            .copied()
+            .unwrap_or_else(F::zero)
+    }
+
+    /// From Csv
+    ///
+    /// Reads a trace in the csv format of `bb --avm-dump-trace`: a header of column names, then a line of values
+    /// per row. Values are decimal, or hexadecimal if prefixed with `0x`, and may be negated.
+    /// If the first column is `ROW_NUMBER`, it is the index of the row of each line, otherwise lines are
+    /// consecutive rows.
+    pub fn from_csv(reader: impl BufRead) -> io::Result<Self> {
+        let mut lines = reader
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()));
+
+        let header = match lines.next() {
+            Some((_, line)) => split_csv_line(&line?),
+            None => return Ok(Self::new(HashMap::new())),
+        };
+        let has_row_numbers = header.first().is_some_and(|name| name == "ROW_NUMBER");
+        let names = header
+            .iter()
+            .skip(has_row_numbers as usize)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut columns: Vec<Vec<F>> = vec![Vec::new(); names.len()];
+        for (row, (index, line)) in lines.enumerate() {
+            let line_number = index + 1;
+            let fields = split_csv_line(&line?);
+            if fields.len() != header.len() {
+                return Err(invalid_data(format!(
+                    "line {line_number} has {} values but the header has {} columns",
+                    fields.len(),
+                    header.len()
+                )));
+            }
+
+            let row = if has_row_numbers {
+                fields[0].parse::<usize>().map_err(|err| {
+                    invalid_data(format!("line {line_number}: invalid row number: {err}"))
+                })?
+            } else {
+                row
+            };
+            for (column, field) in columns
+                .iter_mut()
+                .zip(fields.iter().skip(has_row_numbers as usize))
+            {
+                let value = parse_value(field)
+                    .map_err(|err| invalid_data(format!("line {line_number}: {err}")))?;
+                if column.len() <= row {
+                    column.resize(row + 1, F::zero());
+                }
+                column[row] = value;
+            }
+        }
+
+        Ok(Self::new(names.into_iter().zip(columns).collect()))
+    }
+
+    /// From Binary
+    ///
+    /// Reads a trace dumped column by column. Each column is the length of its name as a u32, its name,
+    /// its number of rows as a u64, and then the value of each row as a 32 byte integer.
+    /// All integers are little-endian.
+    pub fn from_binary(mut reader: impl Read) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let mut columns = HashMap::new();
+        let mut rest = bytes.as_slice();
+        while !rest.is_empty() {
+            let name_len = u32::from_le_bytes(take(&mut rest, 4)?.try_into().unwrap()) as usize;
+            let name = String::from_utf8(take(&mut rest, name_len)?.to_vec())
+                .map_err(|_| invalid_data("column name is not valid utf-8".to_string()))?;
+            let num_rows = u64::from_le_bytes(take(&mut rest, 8)?.try_into().unwrap());
+            let num_rows = usize::try_from(num_rows).map_err(|_| {
+                invalid_data(format!("column {name} has too many rows: {num_rows}"))
+            })?;
+            let len = num_rows.checked_mul(BINARY_VALUE_SIZE).ok_or_else(|| {
+                invalid_data(format!("column {name} has too many rows: {num_rows}"))
+            })?;
+            let values = take(&mut rest, len)?
+                .chunks(BINARY_VALUE_SIZE)
+                .map(F::from_bytes_le)
+                .collect();
+            columns.insert(name, values);
+        }
+
+        Ok(Self::new(columns))
+    }
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = line
+        .split(',')
+        .map(|field| field.trim().to_string())
+        .collect::<Vec<_>>();
+    // barretenberg ends each line with a comma
+    if fields.last().is_some_and(|field| field.is_empty()) {
+        fields.pop();
+    }
+    fields
+}
+
+fn parse_value<F: FieldElement>(value: &str) -> Result<F, String> {
+    let (negated, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value),
+    };
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => F::from_str_radix(hex, 16),
+        None => F::from_str_radix(value, 10),
+    }
+    .map_err(|err| format!("invalid value {value}: {err}"))?;
+    Ok(if negated { -parsed } else { parsed })
+}
+
+/// Takes the next `len` bytes of a binary trace
+fn take<'a>(rest: &mut &'a [u8], len: usize) -> io::Result<&'a [u8]> {
+    if rest.len() < len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "binary trace ends in the middle of a column",
+        ));
+    }
+    let (taken, remaining) = rest.split_at(len);
+    *rest = remaining;
+    Ok(taken)
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use powdr_number::Bn254Field;
+
+    fn binary_column(name: &str, num_rows: u64, values: &[u64]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend((name.len() as u32).to_le_bytes());
+        bytes.extend(name.as_bytes());
+        bytes.extend(num_rows.to_le_bytes());
+        for value in values {
+            let mut value_bytes = Bn254Field::from(*value).to_bytes_le();
+            value_bytes.resize(BINARY_VALUE_SIZE, 0);
+            bytes.extend(value_bytes);
+        }
+        bytes
+    }
+
+    #[test]
+    fn reads_a_binary_trace() {
+        let mut bytes = binary_column("main.a", 2, &[3, 4]);
+        bytes.extend(binary_column("main.b", 1, &[5]));
+
+        let trace = Trace::<Bn254Field>::from_binary(bytes.as_slice()).unwrap();
+        assert_eq!(trace.num_rows(), 2);
+        assert_eq!(trace.get("main_a", 1), Bn254Field::from(4));
+        assert_eq!(trace.get("main_b", 0), Bn254Field::from(5));
+        assert_eq!(trace.get("main_b", 1), Bn254Field::from(0));
+    }
+
+    #[test]
+    fn rejects_a_truncated_binary_trace() {
+        let bytes = binary_column("main.a", 2, &[3]);
+
+        let err = Trace::<Bn254Field>::from_binary(bytes.as_slice())
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn rejects_a_binary_trace_with_too_many_rows() {
+        let bytes = binary_column("main.a", u64::MAX, &[]);
+
+        let err = Trace::<Bn254Field>::from_binary(bytes.as_slice())
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn reads_a_csv_trace_with_row_numbers() {
+        let csv = "ROW_NUMBER,main.a,main.b,\n0,1,0x10,\n2,-1,0,\n";
+
+        let trace = Trace::<Bn254Field>::from_csv(csv.as_bytes()).unwrap();
+        assert_eq!(trace.num_rows(), 3);
+        assert_eq!(trace.get("main_a", 1), Bn254Field::from(0));
+        assert_eq!(trace.get("main_a", 2), -Bn254Field::from(1));
+        assert_eq!(trace.get("main_b", 0), Bn254Field::from(16));
+    }
+}
@@ -0,0 +1,480 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+
+use itertools::Itertools;
+use powdr_ast::analyzed::{
+    AlgebraicExpression, AlgebraicReference, Analyzed, Identity, IdentityKind,
+};
+use powdr_ast::parsed::SelectedExpressions;
+use powdr_number::FieldElement;
+
+use crate::expression_evaluation::{evaluate_expression, get_references};
+use crate::relation_builder::group_relations_per_file;
+use crate::trace::Trace;
+use crate::utils::sanitize_name;
+
+/// The constraint that a trace fails at its first failing row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckFailure<F> {
+    Relation {
+        relation: String,
+        /// The label of the subrelation, or its index within the relation if it doesn't have one
+        subrelation: String,
+        row: usize,
+        /// The values of the columns the subrelation refers to at the row
+        values: Vec<(String, F)>,
+    },
+    Lookup {
+        lookup: String,
+        row: usize,
+        /// The values of the columns of the side of the lookup which is unbalanced at the row
+        values: Vec<(String, F)>,
+    },
+    Permutation {
+        permutation: String,
+        row: usize,
+        /// The values of the columns of the side of the permutation which is unbalanced at the row
+        values: Vec<(String, F)>,
+    },
+}
+
+impl<F> CheckFailure<F> {
+    pub fn row(&self) -> usize {
+        match self {
+            CheckFailure::Relation { row, .. }
+            | CheckFailure::Lookup { row, .. }
+            | CheckFailure::Permutation { row, .. } => *row,
+        }
+    }
+
+    pub fn values(&self) -> &[(String, F)] {
+        match self {
+            CheckFailure::Relation { values, .. }
+            | CheckFailure::Lookup { values, .. }
+            | CheckFailure::Permutation { values, .. } => values,
+        }
+    }
+}
+
+impl<F: Display> Display for CheckFailure<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckFailure::Relation {
+                relation,
+                subrelation,
+                row,
+                ..
+            } => write!(
+                f,
+                "Relation {relation}, subrelation {subrelation} failed at row {row}"
+            )?,
+            CheckFailure::Lookup { lookup, row, .. } => {
+                write!(f, "Lookup {lookup} failed at row {row}")?
+            }
+            CheckFailure::Permutation {
+                permutation, row, ..
+            } => write!(f, "Permutation {permutation} failed at row {row}")?,
+        }
+        for (column, value) in self.values() {
+            write!(f, "\n  {column} = {value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The reason a trace can't be checked against a pil
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckError<F> {
+    /// The committed columns which the identities of the pil use but the trace doesn't have
+    MissingColumns(Vec<String>),
+    /// An expression which can't be evaluated
+    Unsupported(String),
+    Failure(CheckFailure<F>),
+}
+
+impl<F: Display> Display for CheckError<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckError::MissingColumns(columns) => {
+                write!(f, "The trace is missing columns: {}", columns.join(", "))
+            }
+            CheckError::Unsupported(message) => write!(f, "Unsupported pil: {message}"),
+            CheckError::Failure(failure) => write!(f, "{failure}"),
+        }
+    }
+}
+
+/// Check Trace
+///
+/// Evaluates every polynomial identity, lookup and permutation of the pil over the trace, and returns the
+/// failure at the lowest row, if any.
+/// The trace must have every committed column that the identities use, including the counts of lookups.
+/// As in the circuit checker of barretenberg:
+/// - the shifted columns of the last row are zero,
+/// - relations are evaluated at every row, even if they are skippable,
+/// - a lookup holds if, for each tuple, the rows reading it sum to the counts of the rows of the table with
+///   the tuple, and a permutation holds if the selectors of both sides sum to the same for each tuple.
+pub fn check_trace<F: FieldElement>(
+    analyzed: &Analyzed<F>,
+    trace: &Trace<F>,
+) -> Result<(), CheckError<F>> {
+    let mut identities = analyzed.identities_with_inlined_intermediate_polynomials();
+    identities.sort_by_key(|identity| identity.id);
+
+    check_columns(analyzed, &identities, trace)?;
+
+    let mut failures =
+        Vec::from_iter(check_relations(&identities, trace).map_err(CheckError::Unsupported)?);
+    for identity in &identities {
+        let name = interaction_name(identity);
+        let failure = match identity.kind {
+            IdentityKind::Plookup => {
+                let counts = format!("{}_counts", name);
+                check_interaction(&identity.left, &identity.right, Some(&counts), trace)
+                    .map_err(CheckError::Unsupported)?
+                    .map(|(row, values)| CheckFailure::Lookup {
+                        lookup: name,
+                        row,
+                        values,
+                    })
+            }
+            IdentityKind::Permutation => {
+                check_interaction(&identity.left, &identity.right, None, trace)
+                    .map_err(CheckError::Unsupported)?
+                    .map(|(row, values)| CheckFailure::Permutation {
+                        permutation: name,
+                        row,
+                        values,
+                    })
+            }
+            _ => None,
+        };
+        failures.extend(failure);
+    }
+
+    // Relations come first among the failures at the same row
+    match failures.into_iter().min_by_key(|failure| failure.row()) {
+        Some(failure) => Err(CheckError::Failure(failure)),
+        None => Ok(()),
+    }
+}
+
+/// The name of a lookup or permutation, which is its lowercased label or its id if it doesn't have one
+fn interaction_name<F>(identity: &Identity<AlgebraicExpression<F>>) -> String {
+    identity
+        .attribute
+        .clone()
+        .map(|name| name.to_lowercase())
+        .unwrap_or_else(|| format!("#{}", identity.id))
+}
+
+/// Returns an error with the committed columns which the identities use but the trace doesn't have, as a
+/// missing column would otherwise be read as zero
+fn check_columns<F: FieldElement>(
+    analyzed: &Analyzed<F>,
+    identities: &[Identity<AlgebraicExpression<F>>],
+    trace: &Trace<F>,
+) -> Result<(), CheckError<F>> {
+    let committed: HashSet<String> = analyzed
+        .committed_polys_in_source_order()
+        .iter()
+        .map(|(sym, _)| sanitize_name(&sym.absolute_name))
+        .collect();
+
+    let mut used: Vec<String> = Vec::new();
+    for identity in identities {
+        let sides = [&identity.left, &identity.right];
+        let expressions = sides
+            .into_iter()
+            .flat_map(|side| side.selector.iter().chain(&side.expressions));
+        let references = expressions
+            .flat_map(get_references)
+            .map(|polyref| sanitize_name(&polyref.name));
+        let counts = (identity.kind == IdentityKind::Plookup)
+            .then(|| format!("{}_counts", interaction_name(identity)));
+        for column in references.chain(counts) {
+            if !used.contains(&column) {
+                used.push(column);
+            }
+        }
+    }
+
+    let missing = used
+        .into_iter()
+        .filter(|column| committed.contains(column) && !trace.has_column(column))
+        .collect_vec();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(CheckError::MissingColumns(missing))
+    }
+}
+
+/// A polynomial identity, with the names of its relation and subrelation as in the generated relations
+struct Subrelation<'a, F> {
+    relation: &'a str,
+    subrelation: String,
+    expression: &'a AlgebraicExpression<F>,
+}
+
+/// Returns the failure of a polynomial identity at the lowest row
+fn check_relations<F: FieldElement>(
+    identities: &[Identity<AlgebraicExpression<F>>],
+    trace: &Trace<F>,
+) -> Result<Option<CheckFailure<F>>, String> {
+    let grouped_relations = group_relations_per_file(identities);
+    let subrelations = grouped_relations
+        .iter()
+        .sorted_by(|a, b| a.0.cmp(b.0))
+        .flat_map(|(relation, identities)| {
+            identities
+                .iter()
+                .filter(|identity| identity.kind == IdentityKind::Polynomial)
+                .filter(|identity| identity.attribute.as_deref() != Some("skippable_if"))
+                .enumerate()
+                .map(move |(index, identity)| Subrelation {
+                    relation,
+                    subrelation: identity
+                        .attribute
+                        .clone()
+                        .unwrap_or_else(|| index.to_string()),
+                    expression: identity.left.selector.as_ref().unwrap(),
+                })
+        })
+        .collect_vec();
+
+    for row in 0..trace.num_rows() {
+        for subrelation in &subrelations {
+            let value = evaluate_expression(subrelation.expression, &|polyref| {
+                value_at(trace, polyref, row)
+            })?;
+            if !value.is_zero() {
+                return Ok(Some(CheckFailure::Relation {
+                    relation: subrelation.relation.to_string(),
+                    subrelation: subrelation.subrelation.clone(),
+                    row,
+                    values: values_at(trace, [subrelation.expression], row),
+                }));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// The lowest row of an interaction with a tuple whose multiplicities differ, and the values of the side
+/// of the interaction at the row
+type Imbalance<F> = (usize, Vec<(String, F)>);
+
+/// Check Interaction
+///
+/// Checks a lookup, if it has a counts column, or a permutation, by summing the multiplicity of each tuple
+/// over the rows of each side. The lhs multiplicity of a row is its selector. The rhs multiplicity is the
+/// counts of the row for a lookup, and its selector for a permutation, and only applies where the rhs
+/// selector is on.
+fn check_interaction<F: FieldElement>(
+    lhs: &SelectedExpressions<AlgebraicExpression<F>>,
+    rhs: &SelectedExpressions<AlgebraicExpression<F>>,
+    counts: Option<&str>,
+    trace: &Trace<F>,
+) -> Result<Option<Imbalance<F>>, String> {
+    let selector = |side: &SelectedExpressions<AlgebraicExpression<F>>, row: usize| {
+        side.selector
+            .as_ref()
+            .map(|selector| evaluate_expression(selector, &|polyref| value_at(trace, polyref, row)))
+            .unwrap_or_else(|| Ok(F::one()))
+    };
+    let tuple = |side: &SelectedExpressions<AlgebraicExpression<F>>, row: usize| {
+        side.expressions
+            .iter()
+            .map(|expr| evaluate_expression(expr, &|polyref| value_at(trace, polyref, row)))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let lhs_multiplicity = |row: usize| selector(lhs, row);
+    let rhs_multiplicity = |row: usize| -> Result<F, String> {
+        let rhs_selector = selector(rhs, row)?;
+        Ok(match counts {
+            Some(counts) if !rhs_selector.is_zero() => trace.get(counts, row),
+            _ => rhs_selector,
+        })
+    };
+
+    let mut balance: HashMap<Vec<F>, F> = HashMap::new();
+    for row in 0..trace.num_rows() {
+        let multiplicity = lhs_multiplicity(row)?;
+        if !multiplicity.is_zero() {
+            let entry = balance.entry(tuple(lhs, row)?).or_insert_with(F::zero);
+            *entry = *entry + multiplicity;
+        }
+        let multiplicity = rhs_multiplicity(row)?;
+        if !multiplicity.is_zero() {
+            let entry = balance.entry(tuple(rhs, row)?).or_insert_with(F::zero);
+            *entry = *entry - multiplicity;
+        }
+    }
+    if balance.values().all(|multiplicity| multiplicity.is_zero()) {
+        return Ok(None);
+    }
+
+    let is_unbalanced = |side: &SelectedExpressions<AlgebraicExpression<F>>, row: usize| {
+        Ok::<_, String>(!balance[&tuple(side, row)?].is_zero())
+    };
+    for row in 0..trace.num_rows() {
+        let side = if !lhs_multiplicity(row)?.is_zero() && is_unbalanced(lhs, row)? {
+            lhs
+        } else if !rhs_multiplicity(row)?.is_zero() && is_unbalanced(rhs, row)? {
+            rhs
+        } else {
+            continue;
+        };
+        let mut values = values_at(trace, side.selector.iter().chain(&side.expressions), row);
+        if let (true, Some(counts)) = (std::ptr::eq(side, rhs), counts) {
+            values.push((counts.to_string(), trace.get(counts, row)));
+        }
+        return Ok(Some((row, values)));
+    }
+    Ok(None)
+}
+
+/// The value of a column reference at a row
+fn value_at<F: FieldElement>(trace: &Trace<F>, polyref: &AlgebraicReference, row: usize) -> F {
+    trace.get(&sanitize_name(&polyref.name), row + polyref.next as usize)
+}
+
+/// The values at a row of the columns which expressions refer to, shifted columns being suffixed with
+/// `_shift`
+fn values_at<'a, F: FieldElement + 'a>(
+    trace: &Trace<F>,
+    expressions: impl IntoIterator<Item = &'a AlgebraicExpression<F>>,
+    row: usize,
+) -> Vec<(String, F)> {
+    let mut references: Vec<&AlgebraicReference> = Vec::new();
+    for polyref in expressions.into_iter().flat_map(get_references) {
+        if !references.contains(&polyref) {
+            references.push(polyref);
+        }
+    }
+    references
+        .into_iter()
+        .map(|polyref| {
+            let mut name = sanitize_name(&polyref.name);
+            if polyref.next {
+                name = format!("{}_shift", name);
+            }
+            (name, value_at(trace, polyref, row))
+        })
+        .collect_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use powdr_number::Bn254Field;
+    use powdr_pil_analyzer::analyze_string;
+
+    const PIL: &str = "
+namespace main(4);
+    pol commit sel, a, b, c;
+    pol sum = a + b;
+
+    #[ADD]
+    sel * (sum - c) = 0;
+    #[INCREMENT]
+    sel * (a' - a - 1) = 0;
+";
+
+    fn column(values: &[u64]) -> Vec<Bn254Field> {
+        values
+            .iter()
+            .map(|value| Bn254Field::from(*value))
+            .collect()
+    }
+
+    /// A trace of the pil where every relation holds, with `sel` off at the last row
+    fn columns() -> HashMap<String, Vec<Bn254Field>> {
+        HashMap::from([
+            ("main.sel".to_string(), column(&[1, 1, 1, 0])),
+            ("main.a".to_string(), column(&[0, 1, 2, 3])),
+            ("main.b".to_string(), column(&[1, 2, 3, 0])),
+            ("main.c".to_string(), column(&[1, 3, 5, 0])),
+        ])
+    }
+
+    fn check(columns: HashMap<String, Vec<Bn254Field>>) -> Result<(), CheckError<Bn254Field>> {
+        let analyzed = analyze_string::<Bn254Field>(PIL);
+        check_trace(&analyzed, &Trace::new(columns))
+    }
+
+    fn values(values: &[(&str, u64)]) -> Vec<(String, Bn254Field)> {
+        values
+            .iter()
+            .map(|(name, value)| (name.to_string(), Bn254Field::from(*value)))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_a_satisfied_trace() {
+        assert_eq!(check(columns()), Ok(()));
+    }
+
+    #[test]
+    fn reports_the_first_row_violating_a_relation() {
+        let mut columns = columns();
+        columns.get_mut("main.c").unwrap()[1] = Bn254Field::from(4);
+
+        let Err(CheckError::Failure(CheckFailure::Relation {
+            subrelation,
+            row,
+            values: failing_values,
+            ..
+        })) = check(columns)
+        else {
+            panic!("expected a relation failure");
+        };
+        assert_eq!(subrelation, "ADD");
+        assert_eq!(row, 1);
+        assert_eq!(
+            failing_values,
+            values(&[("main_sel", 1), ("main_a", 1), ("main_b", 2), ("main_c", 4)])
+        );
+    }
+
+    #[test]
+    fn reads_shifted_columns_at_the_next_row() {
+        let mut columns = columns();
+        columns.get_mut("main.a").unwrap()[3] = Bn254Field::from(4);
+
+        let Err(CheckError::Failure(CheckFailure::Relation {
+            subrelation,
+            row,
+            values: failing_values,
+            ..
+        })) = check(columns)
+        else {
+            panic!("expected a relation failure");
+        };
+        assert_eq!(subrelation, "INCREMENT");
+        assert_eq!(row, 2);
+        assert_eq!(
+            failing_values,
+            values(&[("main_sel", 1), ("main_a_shift", 4), ("main_a", 2)])
+        );
+    }
+
+    #[test]
+    fn rejects_a_trace_missing_a_used_column() {
+        let mut columns = columns();
+        columns.remove("main.b");
+
+        assert_eq!(
+            check(columns),
+            Err(CheckError::MissingColumns(vec!["main_b".to_string()]))
+        );
+    }
+
+    #[test]
+    fn rejects_expressions_it_cannot_evaluate() {
+        let expression = AlgebraicExpression::PublicReference("main.out".to_string());
+
+        assert!(evaluate_expression(&expression, &|_| Bn254Field::from(0)).is_err());
+    }
+}
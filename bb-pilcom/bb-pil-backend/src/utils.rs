@@ -80,3 +80,22 @@ pub fn format_field<F: FieldElement>(n: &F) -> String {
         )
     }
 }
+
+/// Format Rust Field
+///
+/// Formats a constant as an expression constructing it in generated Rust code,
+/// whose field type is called `FF`
+pub fn format_rust_field<F: FieldElement>(n: &F) -> String {
+    let number: BigInt = BigInt::from_bytes_le(num_bigint::Sign::Plus, &n.to_bytes_le());
+    if number.bits() < 64 {
+        format!("FF::from_u64({})", number)
+    } else {
+        let mut bytes = n.to_bytes_le();
+        bytes.resize(32, 0);
+        let limbs = bytes
+            .chunks(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()).to_string())
+            .collect::<Vec<_>>();
+        format!("FF::from_limbs([{}])", limbs.join(", "))
+    }
+}
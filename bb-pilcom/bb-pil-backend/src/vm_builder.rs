@@ -1,16 +1,25 @@
 use crate::circuit_builder::CircuitBuilder;
 use crate::composer_builder::ComposerBuilder;
+use crate::expression_evaluation::Target;
 use crate::file_writer::BBFiles;
+pub use crate::file_writer::WriteMode;
 use crate::flavor_builder::FlavorBuilder;
 use crate::lookup_builder::{
-    get_counts_from_lookups, get_inverses_from_lookups, Lookup, LookupBuilder,
+    get_counts_from_lookups, get_inverses_from_lookups, get_lookups, Lookup, LookupBuilder,
+};
+use crate::permutation_builder::{
+    get_inverses_from_permutations, get_permutations, Permutation, PermutationBuilder,
 };
-use crate::permutation_builder::{get_inverses_from_permutations, Permutation, PermutationBuilder};
 use crate::prover_builder::ProverBuilder;
-use crate::relation_builder::{get_shifted_polys, RelationBuilder};
+use crate::relation_builder::{
+    get_relations, get_shifted_polys, RelationBuilder, RelationIdentities,
+};
+use crate::rust_builder::RustBuilder;
 use crate::utils::{flatten, sanitize_name, snake_case, sort_cols};
 use crate::verifier_builder::VerifierBuilder;
 
+use std::path::Path;
+
 use dialoguer::Confirm;
 use itertools::Itertools;
 use powdr_ast::analyzed::Analyzed;
@@ -44,29 +53,49 @@ struct ColumnGroups {
 
 /// Analyzed to cpp
 ///
-/// Converts an analyzed pil AST into a set of cpp files that can be used to generate a proof
-pub fn analyzed_to_cpp<F: FieldElement>(analyzed: &Analyzed<F>, vm_name: &str, delete_dir: bool) {
-    let mut bb_files = BBFiles::default(&snake_case(&vm_name));
-
-    // Remove the generated directory if it exists.
-    // Pass `-y` as parameter if you want to skip the confirmation prompt.
-    let confirmation = delete_dir
-        || Confirm::new()
-            .with_prompt(format!("Going to remove: {}. OK?", bb_files.base_dir))
-            .default(true)
-            .interact()
-            .unwrap();
-    if confirmation {
-        println!("Removing generated directory: {}", bb_files.base_dir);
-        bb_files.remove_generated_dir();
-    }
+/// Converts an analyzed pil AST into a set of cpp files that can be used to generate a proof.
+/// Only the files whose contents changed are written, and the files of the generated directory which are
+/// no longer generated are removed. In check mode, nothing is written.
+/// Returns whether the generated directory was up to date.
+pub fn analyzed_to_cpp<F: FieldElement>(
+    analyzed: &Analyzed<F>,
+    vm_name: &str,
+    mode: WriteMode,
+    delete_stale: bool,
+) -> bool {
+    let mut bb_files = BBFiles::default(&snake_case(&vm_name)).with_mode(mode);
+
+    let relations = get_relations(analyzed, Target::Cpp);
+    let permutations = get_permutations(analyzed);
+    let lookups = get_lookups(analyzed);
+    let columns = get_all_col_names(analyzed, &permutations, &lookups);
+
+    generate_cpp(
+        &mut bb_files,
+        vm_name,
+        &relations,
+        &permutations,
+        &lookups,
+        &columns,
+    );
+
+    finish_generation(&bb_files, delete_stale)
+}
 
+fn generate_cpp(
+    bb_files: &mut BBFiles,
+    vm_name: &str,
+    relations: &[RelationIdentities],
+    permutations: &[Permutation],
+    lookups: &[Lookup],
+    columns: &ColumnGroups,
+) {
     // ----------------------- Handle Standard Relation Identities -----------------------
-    let relations = bb_files.create_relations(vm_name, analyzed);
+    let relations = bb_files.create_relations(vm_name, relations);
 
     // ----------------------- Handle Lookup / Permutation Relation Identities -----------------------
-    let permutations = bb_files.create_permutation_files(analyzed);
-    let lookups = bb_files.create_lookup_files(analyzed);
+    bb_files.create_permutation_files(permutations);
+    bb_files.create_lookup_files(lookups);
     let lookup_and_permutations_names = sort_cols(&flatten(&[
         permutations.iter().map(|p| p.name.clone()).collect_vec(),
         lookups.iter().map(|l| l.name.clone()).collect_vec(),
@@ -85,42 +114,42 @@ pub fn analyzed_to_cpp<F: FieldElement>(analyzed: &Analyzed<F>, vm_name: &str, d
         all_cols_with_shifts,
         inverses,
         public_inputs,
-    } = get_all_col_names(analyzed, &permutations, &lookups);
+    } = columns;
 
     // ----------------------- Create the full row files -----------------------
-    bb_files.create_full_row_hpp(vm_name, &all_cols);
-    bb_files.create_full_row_cpp(vm_name, &all_cols);
+    bb_files.create_full_row_hpp(vm_name, all_cols);
+    bb_files.create_full_row_cpp(vm_name, all_cols);
 
     // ----------------------- Create the circuit builder files -----------------------
     bb_files.create_circuit_builder_hpp(vm_name);
-    bb_files.create_circuit_builder_cpp(vm_name, &all_cols_without_inverses);
+    bb_files.create_circuit_builder_cpp(vm_name, all_cols_without_inverses);
 
     // ----------------------- Create the flavor files -----------------------
     bb_files.create_flavor_hpp(
         vm_name,
         &relations,
         &lookup_and_permutations_names,
-        &inverses,
-        &fixed,
-        &witness,
-        &witnesses_without_inverses,
-        &all_cols,
-        &to_be_shifted,
-        &shifted,
-        &all_cols_with_shifts,
+        inverses,
+        fixed,
+        witness,
+        witnesses_without_inverses,
+        all_cols,
+        to_be_shifted,
+        shifted,
+        all_cols_with_shifts,
     );
 
     bb_files.create_flavor_cpp(
         vm_name,
         &relations,
-        &inverses,
-        &fixed,
-        &witness,
-        &witnesses_without_inverses,
-        &all_cols,
-        &to_be_shifted,
-        &shifted,
-        &all_cols_with_shifts,
+        inverses,
+        fixed,
+        witness,
+        witnesses_without_inverses,
+        all_cols,
+        to_be_shifted,
+        shifted,
+        all_cols_with_shifts,
     );
 
     bb_files.create_flavor_settings_hpp(vm_name);
@@ -130,15 +159,133 @@ pub fn analyzed_to_cpp<F: FieldElement>(analyzed: &Analyzed<F>, vm_name: &str, d
     bb_files.create_composer_hpp(vm_name);
 
     // ----------------------- Create the Verifier files -----------------------
-    bb_files.create_verifier_cpp(vm_name, &public_inputs);
+    bb_files.create_verifier_cpp(vm_name, public_inputs);
     bb_files.create_verifier_hpp(vm_name);
-    bb_files.create_recursive_verifier_cpp(vm_name, &public_inputs);
+    bb_files.create_recursive_verifier_cpp(vm_name, public_inputs);
 
     // ----------------------- Create the Prover files -----------------------
     bb_files.create_prover_cpp(vm_name);
     bb_files.create_prover_hpp(vm_name);
+}
+
+/// Analyzed to rust
+///
+/// Converts an analyzed pil AST into a Rust module named after the vm, in `output_dir`, with the
+/// columns of the trace and the relations, lookups and permutations over them, that can be used to
+/// check a witness.
+/// Files are written and checked as by `analyzed_to_cpp`.
+pub fn analyzed_to_rust<F: FieldElement>(
+    analyzed: &Analyzed<F>,
+    vm_name: &str,
+    output_dir: &str,
+    mode: WriteMode,
+    delete_stale: bool,
+) -> bool {
+    let vm_name_snake = snake_case(vm_name);
+    let base_dir = Path::new(output_dir).join(&vm_name_snake);
+    let bb_files =
+        BBFiles::new(&vm_name_snake, Some(&base_dir.to_string_lossy()), None).with_mode(mode);
+
+    let relations = get_relations(analyzed, Target::Rust);
+    let permutations = get_permutations(analyzed);
+    let lookups = get_lookups(analyzed);
+    let columns = get_all_col_names(analyzed, &permutations, &lookups);
+
+    generate_rust(
+        &bb_files,
+        vm_name,
+        &relations,
+        &permutations,
+        &lookups,
+        &columns,
+    );
 
-    println!("Done with generation.");
+    finish_generation(&bb_files, delete_stale)
+}
+
+fn generate_rust(
+    bb_files: &BBFiles,
+    vm_name: &str,
+    relations: &[RelationIdentities],
+    permutations: &[Permutation],
+    lookups: &[Lookup],
+    columns: &ColumnGroups,
+) {
+    // ----------------------- Handle Standard Relation Identities -----------------------
+    for relation in relations {
+        bb_files.create_rust_relation(relation);
+    }
+
+    // ----------------------- Handle Lookup / Permutation Relation Identities -----------------------
+    for permutation in permutations {
+        bb_files.create_rust_permutation(permutation);
+    }
+    for lookup in lookups {
+        bb_files.create_rust_lookup(lookup);
+    }
+
+    // ----------------------- Create the columns file -----------------------
+    bb_files.create_rust_columns(&columns.all_cols, &columns.to_be_shifted);
+
+    // ----------------------- Create the module files -----------------------
+    bb_files.create_rust_mod(
+        vm_name,
+        &relations.iter().map(|r| snake_case(&r.name)).collect_vec(),
+        &lookups.iter().map(|l| l.name.clone()).collect_vec(),
+        &permutations.iter().map(|p| p.name.clone()).collect_vec(),
+    );
+}
+
+/// Finish Generation
+///
+/// Removes the stale files of the generated directory, which were not generated this time, after a
+/// confirmation prompt unless `delete_stale` is set. Pass `-y` as parameter to skip the prompt.
+/// In check mode, reports the files which are out of date or stale instead.
+/// Returns whether the generated directory was up to date.
+fn finish_generation(bb_files: &BBFiles, delete_stale: bool) -> bool {
+    let stale_files = bb_files.stale_files();
+    match bb_files.mode {
+        WriteMode::Write => {
+            if !stale_files.is_empty() {
+                let confirmation = delete_stale
+                    || Confirm::new()
+                        .with_prompt(format!(
+                            "Going to remove {} stale files from: {}. OK?",
+                            stale_files.len(),
+                            bb_files.base_dir
+                        ))
+                        .default(true)
+                        .interact()
+                        .unwrap();
+                if confirmation {
+                    println!("Removing stale files from: {}", bb_files.base_dir);
+                    bb_files.remove_files(&stale_files);
+                }
+            }
+            println!("Done with generation.");
+            true
+        }
+        WriteMode::Check => {
+            let out_of_date_files = bb_files.out_of_date_files();
+            for file in &out_of_date_files {
+                println!(
+                    "Out of date: {}",
+                    Path::new(&bb_files.base_dir).join(file).display()
+                );
+            }
+            for file in &stale_files {
+                println!(
+                    "Stale: {}",
+                    Path::new(&bb_files.base_dir).join(file).display()
+                );
+            }
+            let up_to_date = out_of_date_files.is_empty() && stale_files.is_empty();
+            if up_to_date {
+                println!("Generated files are up to date: {}", bb_files.base_dir);
+            }
+            up_to_date
+        }
+    }
 }
 
 fn get_all_col_names<F: FieldElement>(
@@ -180,6 +327,27 @@ fn get_all_col_names<F: FieldElement>(
         .map(|n| sanitize_name(&n))
         .collect_vec(),
     );
+
+    get_column_groups(
+        constant,
+        committed,
+        public,
+        to_be_shifted,
+        permutations,
+        lookups,
+    )
+}
+
+/// Groups the columns of a pil, sorted except for the public inputs, along with the inverse and counts
+/// columns of its permutations and lookups
+fn get_column_groups(
+    constant: Vec<String>,
+    committed: Vec<String>,
+    public: Vec<String>,
+    to_be_shifted: Vec<String>,
+    permutations: &[Permutation],
+    lookups: &[Lookup],
+) -> ColumnGroups {
     let shifted = to_be_shifted
         .iter()
         .map(|n| format!("{}_shift", n))
@@ -228,3 +396,167 @@ fn get_all_col_names<F: FieldElement>(
         public_inputs: public.iter().cloned().enumerate().collect_vec(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lookup_builder::LookupSide;
+    use crate::permutation_builder::PermutationSide;
+    use crate::relation_builder::BBIdentity;
+
+    /// Set to regenerate the golden files, after changing what the generators produce
+    const UPDATE_GOLDEN: &str = "BB_PIL_UPDATE_GOLDEN";
+
+    fn strings(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect_vec()
+    }
+
+    fn identity(degree: u64, identity: String, label: Option<&str>) -> BBIdentity {
+        BBIdentity {
+            degree,
+            identity,
+            label: label.map(|label| label.to_string()),
+        }
+    }
+
+    /// The relations of an example vm, with the constants formatted by `field` for the target
+    fn example_relations(field: fn(u64) -> String) -> Vec<RelationIdentities> {
+        vec![
+            RelationIdentities {
+                name: "main".to_string(),
+                identities: vec![
+                    identity(
+                        2,
+                        format!("(new_term.main_sel * (new_term.main_sel - {}))", field(1)),
+                        None,
+                    ),
+                    identity(
+                        3,
+                        "(new_term.main_sel * (new_term.main_a_shift - main_sum))".to_string(),
+                        Some("NEXT_A"),
+                    ),
+                ],
+                skippable_if: Some(identity(1, "new_term.main_sel".to_string(), None)),
+                alias_defs: vec![(
+                    "main_sum".to_string(),
+                    1,
+                    "(new_term.main_a + new_term.main_b)".to_string(),
+                )],
+            },
+            RelationIdentities {
+                name: "range".to_string(),
+                identities: vec![identity(
+                    2,
+                    format!(
+                        "(new_term.range_sel * (new_term.range_val - {}))",
+                        field(255)
+                    ),
+                    Some("RANGE_MAX"),
+                )],
+                skippable_if: None,
+                alias_defs: vec![],
+            },
+        ]
+    }
+
+    fn example_lookups() -> Vec<Lookup> {
+        vec![Lookup {
+            name: "lookup_range".to_string(),
+            inverse: "lookup_range_inv".to_string(),
+            counts_poly: "lookup_range_counts".to_string(),
+            left: LookupSide {
+                selector: Some("main_sel_range".to_string()),
+                cols: strings(&["main_a"]),
+            },
+            right: LookupSide {
+                selector: Some("range_sel".to_string()),
+                cols: strings(&["range_val"]),
+            },
+        }]
+    }
+
+    fn example_permutations() -> Vec<Permutation> {
+        vec![Permutation {
+            name: "perm_main_range".to_string(),
+            inverse: "perm_main_range_inv".to_string(),
+            left: PermutationSide {
+                selector: Some("main_sel".to_string()),
+                cols: strings(&["main_b"]),
+            },
+            right: PermutationSide {
+                selector: Some("range_sel".to_string()),
+                cols: strings(&["range_val"]),
+            },
+        }]
+    }
+
+    fn example_columns(permutations: &[Permutation], lookups: &[Lookup]) -> ColumnGroups {
+        get_column_groups(
+            strings(&["main_clk", "main_sel_first"]),
+            strings(&[
+                "main_a",
+                "main_b",
+                "main_sel",
+                "main_sel_range",
+                "range_sel",
+                "range_val",
+            ]),
+            strings(&["main_input"]),
+            strings(&["main_a"]),
+            permutations,
+            lookups,
+        )
+    }
+
+    /// The files of the golden directory of a target, which are only checked unless `UPDATE_GOLDEN` is set
+    fn golden_files(target: &str) -> BBFiles {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/golden")
+            .join(target);
+        let mode = match std::env::var_os(UPDATE_GOLDEN) {
+            Some(_) => WriteMode::Write,
+            None => WriteMode::Check,
+        };
+        BBFiles::new("example", Some(&dir.to_string_lossy()), None).with_mode(mode)
+    }
+
+    #[test]
+    fn cpp_generation_matches_golden_files() {
+        let mut bb_files = golden_files("cpp");
+        let permutations = example_permutations();
+        let lookups = example_lookups();
+        generate_cpp(
+            &mut bb_files,
+            "Example",
+            &example_relations(|n| format!("FF({})", n)),
+            &permutations,
+            &lookups,
+            &example_columns(&permutations, &lookups),
+        );
+        assert!(
+            finish_generation(&bb_files, true),
+            "the golden files are out of date, set {} to update them",
+            UPDATE_GOLDEN
+        );
+    }
+
+    #[test]
+    fn rust_generation_matches_golden_files() {
+        let bb_files = golden_files("rust");
+        let permutations = example_permutations();
+        let lookups = example_lookups();
+        generate_rust(
+            &bb_files,
+            "Example",
+            &example_relations(|n| format!("FF::from_u64({})", n)),
+            &permutations,
+            &lookups,
+            &example_columns(&permutations, &lookups),
+        );
+        assert!(
+            finish_generation(&bb_files, true),
+            "the golden files are out of date, set {} to update them",
+            UPDATE_GOLDEN
+        );
+    }
+}
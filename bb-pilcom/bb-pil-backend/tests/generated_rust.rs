@@ -0,0 +1,138 @@
+//! Compiles the golden Rust generated for the example VM of the generator's tests,
+//! and checks traces with it.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+// The golden files are kept as generated, rather than as rustfmt would lay them out.
+#[rustfmt::skip]
+#[path = "golden/rust/mod.rs"]
+mod example;
+
+use example::columns::Row;
+use example::{check_circuit, CheckFailure, Field};
+
+/// The field of integers modulo the Mersenne prime 2^31 - 1, which is enough to run the checker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Mersenne31(u64);
+
+const MODULUS: u64 = (1 << 31) - 1;
+
+impl Field for Mersenne31 {
+    fn from_u64(value: u64) -> Self {
+        Mersenne31(value % MODULUS)
+    }
+
+    fn from_limbs(limbs: [u64; 4]) -> Self {
+        let limb_base = Self::from_u64(1 << 32) * Self::from_u64(1 << 32);
+        limbs.iter().rev().fold(Self::zero(), |value, limb| {
+            value * limb_base + Self::from_u64(*limb)
+        })
+    }
+}
+
+impl Add for Mersenne31 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::from_u64(self.0 + other.0)
+    }
+}
+
+impl Sub for Mersenne31 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Mul for Mersenne31 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::from_u64(self.0 * other.0)
+    }
+}
+
+impl Neg for Mersenne31 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from_u64(MODULUS - self.0)
+    }
+}
+
+fn field(value: u64) -> Mersenne31 {
+    Mersenne31::from_u64(value)
+}
+
+/// A trace where the main VM adds 255 from the range table to its accumulator,
+/// and then looks up the sum in the range table.
+fn trace() -> Vec<Row<Mersenne31>> {
+    let first = Row {
+        main_sel: field(1),
+        main_b: field(255),
+        range_sel: field(1),
+        range_val: field(255),
+        lookup_range_counts: field(1),
+        ..Row::default()
+    };
+    let second = Row {
+        main_a: field(255),
+        main_sel_range: field(1),
+        ..Row::default()
+    };
+    vec![first, second]
+}
+
+#[test]
+fn accepts_a_valid_trace() {
+    assert_eq!(check_circuit(&trace()), Ok(()));
+}
+
+#[test]
+fn reports_the_first_failing_relation() {
+    let mut rows = trace();
+    rows[1].main_a = field(254);
+    assert_eq!(
+        check_circuit(&rows),
+        Err(CheckFailure::Relation {
+            relation: "main",
+            subrelation: "NEXT_A".to_string(),
+            row: 0,
+        })
+    );
+}
+
+#[test]
+fn lookups_are_checked_against_the_counts_of_the_table() {
+    let mut rows = trace();
+    rows[0].lookup_range_counts = field(2);
+    assert_eq!(
+        check_circuit(&rows),
+        Err(CheckFailure::Lookup {
+            lookup: "lookup_range",
+            row: 0,
+        })
+    );
+}
+
+#[test]
+fn permutations_are_checked_with_the_multiplicities_of_their_selectors() {
+    let mut rows = trace();
+    rows[0].range_sel = field(2);
+    assert_eq!(
+        check_circuit(&rows),
+        Err(CheckFailure::Permutation {
+            permutation: "perm_main_range",
+            row: 0,
+        })
+    );
+}
+
+#[test]
+fn builds_field_elements_from_limbs() {
+    // 2^64 is 4 modulo 2^31 - 1, as 2^62 is 1.
+    assert_eq!(Mersenne31::from_limbs([0, 1, 0, 0]), field(4));
+    assert_eq!(Mersenne31::from_limbs([MODULUS + 1, 0, 0, 0]), field(1));
+}
@@ -0,0 +1,206 @@
+// AUTOGENERATED FILE
+#include "barretenberg/vm/example/generated/circuit_builder.hpp"
+
+#include <mutex>
+#include <set>
+#include <unordered_map>
+
+#include "barretenberg/common/constexpr_utils.hpp"
+#include "barretenberg/common/thread.hpp"
+#include "barretenberg/common/throw_or_abort.hpp"
+#include "barretenberg/ecc/curves/bn254/fr.hpp"
+#include "barretenberg/relations/generic_permutation/generic_permutation_relation.hpp"
+#include "barretenberg/relations/generic_lookup/generic_lookup_relation.hpp"
+#include "barretenberg/honk/proof_system/logderivative_library.hpp"
+#include "barretenberg/numeric/bitop/get_msb.hpp"
+#include "barretenberg/vm/stats.hpp"
+
+namespace bb {
+
+ExampleCircuitBuilder::ProverPolynomials ExampleCircuitBuilder::compute_polynomials() const {
+    const size_t num_rows = get_estimated_num_finalized_gates();
+    const size_t circuit_subgroup_size = get_circuit_subgroup_size();
+    ASSERT(num_rows <= circuit_subgroup_size);
+    ProverPolynomials polys;
+
+    // We create a mapping between the polynomial index and the corresponding column index when row
+    // is expressed as a vector, i.e., column of the trace matrix.
+    std::unordered_map<std::string, size_t> names_to_col_idx;
+    const auto names = Row::names();
+    for (size_t i = 0; i < names.size(); i++) {
+        names_to_col_idx[names[i]] = i;
+    }
+
+    const auto labels = polys.get_unshifted_labels();
+    const size_t num_unshifted = labels.size();
+
+    // Mapping
+    std::vector<size_t> polys_to_cols_unshifted_idx(num_unshifted);
+    for (size_t i = 0; i < num_unshifted; i++) {
+        polys_to_cols_unshifted_idx[i] = names_to_col_idx.at(labels[i]);
+    }
+
+    // Allocate mem for each column
+    AVM_TRACK_TIME("circuit_builder/init_polys_to_be_shifted", ({
+                       for (auto& poly : polys.get_to_be_shifted()) {
+                           poly = Polynomial{ /*memory size*/ num_rows - 1,
+                                              /*largest possible index*/ circuit_subgroup_size,
+                                              /*make shiftable with offset*/ 1 };
+                       }
+                   }));
+
+    // catch-all with fully formed polynomials
+    AVM_TRACK_TIME(
+        "circuit_builder/init_polys_unshifted", ({
+            auto unshifted = polys.get_unshifted();
+
+            // An array which stores for each column of the trace the smallest size of the
+            // truncated column containing all non-zero elements.
+            // It is used to allocate the polynomials without memory overhead for the tail of zeros.
+            std::array<size_t, Row::SIZE> col_nonzero_size{};
+
+            // Computation of size of columns.
+            // Non-parallel version takes 0.5 second for a trace size of 200k rows.
+            // A parallel version might be considered in the future.
+            for (size_t i = 0; i < num_rows; i++) {
+                const auto row = rows[i].as_vector();
+                for (size_t col = 0; col < Row::SIZE; col++) {
+                    if (!row[col].is_zero()) {
+                        col_nonzero_size[col] = i + 1;
+                    }
+                }
+            }
+
+            // Set of the labels for derived/inverse polynomials.
+            const auto derived_labels = polys.get_derived_labels();
+            std::set<std::string> derived_labels_set(derived_labels.begin(), derived_labels.end());
+
+            bb::parallel_for(num_unshifted, [&](size_t i) {
+                auto& poly = unshifted[i];
+                const auto col_idx = polys_to_cols_unshifted_idx[i];
+                const bool is_derived = derived_labels_set.contains(labels[i]);
+
+                if (poly.is_empty()) {
+                    // Not set above. Non-derived unshifted polynomials are initialized below. The non-derived ones
+                    // do need to be initialized with zeros and are fully allocated (size == num_rows).
+                    poly = is_derived ? Polynomial::create_non_parallel_zero_init(num_rows, circuit_subgroup_size)
+                                      : Polynomial{ col_nonzero_size[col_idx], circuit_subgroup_size, Polynomial::DontZeroMemory::FLAG };
+                }
+            });
+        }));
+
+    AVM_TRACK_TIME(
+        "circuit_builder/set_polys_unshifted", ({
+            bb::parallel_for(rows.size(), [&](size_t i) {
+        polys.main_clk.set_if_valid_index(i, rows[i].main_clk);
+        polys.main_sel_first.set_if_valid_index(i, rows[i].main_sel_first);
+        polys.main_input.set_if_valid_index(i, rows[i].main_input);
+        polys.main_a.set_if_valid_index(i, rows[i].main_a);
+        polys.main_b.set_if_valid_index(i, rows[i].main_b);
+        polys.main_sel.set_if_valid_index(i, rows[i].main_sel);
+        polys.main_sel_range.set_if_valid_index(i, rows[i].main_sel_range);
+        polys.range_sel.set_if_valid_index(i, rows[i].range_sel);
+        polys.range_val.set_if_valid_index(i, rows[i].range_val);
+        polys.lookup_range_counts.set_if_valid_index(i, rows[i].lookup_range_counts);
+        });
+    }));
+
+    AVM_TRACK_TIME(
+        "circuit_builder/set_polys_shifted", ({
+        for (auto [shifted, to_be_shifted] : zip_view(polys.get_shifted(), polys.get_to_be_shifted())) {
+            shifted = to_be_shifted.shifted();
+        }
+    }));
+
+    return polys;
+}
+
+bool ExampleCircuitBuilder::check_circuit() const {
+    const FF gamma = FF::random_element();
+    const FF beta = FF::random_element();
+    bb::RelationParameters<typename Flavor::FF> params{
+        .eta = 0,
+        .beta = beta,
+        .gamma = gamma,
+        .public_input_delta = 0,
+        .lookup_grand_product_delta = 0,
+        .beta_sqr = 0,
+        .beta_cube = 0,
+        .eccvm_set_permutation_delta = 0,
+    };
+
+    auto polys = compute_polynomials();
+    // We'll only check up to the generated trace which might be << than the circuit subgroup size.
+    const size_t num_rows = get_estimated_num_finalized_gates();
+
+    // Checks that we will run.
+    using SignalErrorFn = const std::function<void(const std::string&)>&;
+    std::vector<std::function<void(SignalErrorFn)>> checks;
+
+    // Add relation checks.
+    bb::constexpr_for<0, std::tuple_size_v<ExampleFlavor::MainRelations>, 1>([&]<size_t i>() {
+        using Relation = std::tuple_element_t<i, ExampleFlavor::MainRelations>;
+        checks.push_back([&](SignalErrorFn signal_error) {
+            typename Relation::SumcheckArrayOfValuesOverSubrelations result;
+            for (auto& r : result) {
+                r = 0;
+            }
+
+            std::array<bool, result.size()> subrelation_failed = { false };
+            for (size_t r = 0; r < num_rows; ++r) {
+                Relation::accumulate(result, polys.get_row(r), {}, 1);
+                for (size_t j = 0; j < result.size(); ++j) {
+                    if (!subrelation_failed[j] && result[j] != 0) {
+                        signal_error(format("Relation ",
+                                            Relation::NAME,
+                                            ", subrelation ",
+                                            Relation::get_subrelation_label(j),
+                                            " failed at row ",
+                                            r));
+                        // We will not check this subrelation for any other rows.
+                        // Since the accumulation will keep being != 0.
+                        subrelation_failed[j] = true;
+                    }
+                }
+            }
+        });
+    });
+
+    // Add calculation of logderivatives and lookup/permutation checks.
+    bb::constexpr_for<0, std::tuple_size_v<ExampleFlavor::LookupRelations>, 1>([&]<size_t i>() {
+        using Relation = std::tuple_element_t<i, ExampleFlavor::LookupRelations>;
+        checks.push_back([&, num_rows](SignalErrorFn signal_error) {
+            // Check the logderivative relation
+            bb::compute_logderivative_inverse<Flavor, Relation>(polys, params, num_rows);
+
+            typename Relation::SumcheckArrayOfValuesOverSubrelations lookup_result;
+
+            for (auto& r : lookup_result) {
+                r = 0;
+            }
+            for (size_t r = 0; r < num_rows; ++r) {
+                Relation::accumulate(lookup_result, polys.get_row(r), params, 1);
+            }
+            for (auto r : lookup_result) {
+                if (r != 0) {
+                    signal_error(format("Lookup ", Relation::NAME, " failed."));
+                }
+            }
+        });
+    });
+
+    std::string errors;
+    std::mutex m;
+    auto signal_error = [&](const std::string& error) {
+        std::lock_guard<std::mutex> lock(m);
+        errors += error + "\n";
+    };
+    bb::parallel_for(checks.size(), [&](size_t i) { checks[i](signal_error); });
+    if (!errors.empty()) {
+        throw_or_abort(errors);
+    }
+
+    return errors.empty();
+}
+
+}  // namespace bb
\ No newline at end of file
@@ -0,0 +1,47 @@
+// AUTOGENERATED FILE
+#pragma once
+
+#include <vector>
+
+#include "barretenberg/vm/example/generated/full_row.hpp"
+#include "barretenberg/vm/example/generated/flavor.hpp"
+
+namespace bb {
+
+class ExampleCircuitBuilder {
+  public:
+    // Do not use this constant directly, use get_circuit_subgroup_size() instead.
+    constexpr static size_t CIRCUIT_SUBGROUP_SIZE = 1 << 21;
+
+    using Flavor = bb::ExampleFlavor;
+    using FF = Flavor::FF;
+    using Row = ExampleFullRow<FF>;
+    using Polynomial = Flavor::Polynomial;
+    using ProverPolynomials = Flavor::ProverPolynomials;
+
+    void set_trace(std::vector<Row>&& trace)
+    {
+        rows = std::move(trace);
+        num_rows = rows.size();
+    }
+    void clear_trace()
+    {
+        rows.clear();
+        rows.shrink_to_fit();
+        num_rows = 0;
+    }
+
+    ProverPolynomials compute_polynomials() const;
+
+    bool check_circuit() const;
+
+    size_t get_estimated_num_finalized_gates() const { return num_rows; }
+
+    size_t get_circuit_subgroup_size() const { return CIRCUIT_SUBGROUP_SIZE; }
+
+  private:
+    size_t num_rows = 0;
+    std::vector<Row> rows;
+};
+
+}  // namespace bb
\ No newline at end of file
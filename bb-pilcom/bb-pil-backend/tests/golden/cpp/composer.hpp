@@ -0,0 +1,66 @@
+// AUTOGENERATED FILE
+#pragma once
+
+#include "barretenberg/srs/global_crs.hpp"
+#include "barretenberg/vm/example/generated/circuit_builder.hpp"
+#include "barretenberg/vm/example/generated/prover.hpp"
+#include "barretenberg/vm/example/generated/verifier.hpp"
+
+namespace bb {
+
+class ExampleComposer {
+  public:
+    using Flavor = ExampleFlavor;
+    using CircuitConstructor = ExampleCircuitBuilder;
+    using ProvingKey = Flavor::ProvingKey;
+    using VerificationKey = Flavor::VerificationKey;
+    using PCS = Flavor::PCS;
+    using CommitmentKey = Flavor::CommitmentKey;
+    using VerifierCommitmentKey = Flavor::VerifierCommitmentKey;
+
+    // TODO: which of these will we really need
+    static constexpr std::string_view NAME_STRING = "Example";
+    static constexpr size_t NUM_RESERVED_GATES = 0;
+    static constexpr size_t NUM_WIRES = Flavor::NUM_WIRES;
+
+    std::shared_ptr<ProvingKey> proving_key;
+    std::shared_ptr<VerificationKey> verification_key;
+
+    // The crs_factory holds the path to the srs and exposes methods to extract the srs elements
+    std::shared_ptr<bb::srs::factories::CrsFactory<Flavor::Curve>> crs_factory_;
+
+    // The commitment key is passed to the prover but also used herein to compute the verfication key commitments
+    std::shared_ptr<CommitmentKey> commitment_key;
+
+    bool computed_witness = false;
+
+    ExampleComposer() { crs_factory_ = bb::srs::get_bn254_crs_factory(); }
+
+    ExampleComposer(std::shared_ptr<ProvingKey> p_key, std::shared_ptr<VerificationKey> v_key)
+        : proving_key(std::move(p_key))
+        , verification_key(std::move(v_key))
+    {}
+
+    ExampleComposer(ExampleComposer&& other) noexcept = default;
+    ExampleComposer(ExampleComposer const& other) noexcept = default;
+    ExampleComposer& operator=(ExampleComposer&& other) noexcept = default;
+    ExampleComposer& operator=(ExampleComposer const& other) noexcept = default;
+    ~ExampleComposer() = default;
+
+    std::shared_ptr<ProvingKey> compute_proving_key(CircuitConstructor& circuit_constructor);
+    std::shared_ptr<VerificationKey> compute_verification_key(CircuitConstructor& circuit_constructor);
+
+    void compute_witness(CircuitConstructor& circuit_constructor);
+
+    ExampleProver create_prover(CircuitConstructor& circuit_constructor);
+    ExampleVerifier create_verifier(CircuitConstructor& circuit_constructor);
+
+    void add_table_column_selector_poly_to_proving_key(bb::polynomial& small, const std::string& tag);
+
+    void compute_commitment_key(size_t circuit_size)
+    {
+        proving_key->commitment_key = std::make_shared<CommitmentKey>(circuit_size);
+    };
+};
+
+} // namespace bb
\ No newline at end of file
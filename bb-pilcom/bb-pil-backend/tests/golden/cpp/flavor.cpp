@@ -0,0 +1,155 @@
+// AUTOGENERATED FILE
+#include "barretenberg/vm/example/generated/flavor.hpp"
+
+namespace bb {
+
+ExampleFlavor::AllConstRefValues::AllConstRefValues(const RefArray<ExampleFlavor::AllConstRefValues::BaseDataType, ExampleFlavor::NUM_ALL_ENTITIES>& il) :
+    main_clk(il[0]),main_sel_first(il[1]),main_input(il[2]),main_a(il[3]),main_b(il[4]),main_sel(il[5]),main_sel_range(il[6]),range_sel(il[7]),range_val(il[8]),lookup_range_counts(il[9]),perm_main_range_inv(il[10]),lookup_range_inv(il[11]),main_a_shift(il[12])
+  {}
+
+ExampleFlavor::ProverPolynomials::ProverPolynomials(ProvingKey& proving_key)
+{
+    for (auto [prover_poly, key_poly] : zip_view(this->get_unshifted(), proving_key.get_all())) {
+        ASSERT(flavor_get_label(*this, prover_poly) == flavor_get_label(proving_key, key_poly));
+        prover_poly = key_poly.share();
+    }
+    for (auto [prover_poly, key_poly] : zip_view(this->get_shifted(), proving_key.get_to_be_shifted())) {
+        ASSERT(flavor_get_label(*this, prover_poly) == (flavor_get_label(proving_key, key_poly) + "_shift"));
+        prover_poly = key_poly.shifted();
+    }
+}
+
+ExampleFlavor::AllConstRefValues ExampleFlavor::ProverPolynomials::get_row(size_t row_idx) const
+{
+    return RefArray{
+      main_clk[row_idx],
+      main_sel_first[row_idx],
+      main_input[row_idx],
+      main_a[row_idx],
+      main_b[row_idx],
+      main_sel[row_idx],
+      main_sel_range[row_idx],
+      range_sel[row_idx],
+      range_val[row_idx],
+      lookup_range_counts[row_idx],
+      perm_main_range_inv[row_idx],
+      lookup_range_inv[row_idx],
+      main_a_shift[row_idx]
+    };
+}
+
+ExampleFlavor::CommitmentLabels::CommitmentLabels()
+{
+    Base::main_clk = "MAIN_CLK";
+    Base::main_sel_first = "MAIN_SEL_FIRST";
+    Base::main_input = "MAIN_INPUT";
+    Base::main_a = "MAIN_A";
+    Base::main_b = "MAIN_B";
+    Base::main_sel = "MAIN_SEL";
+    Base::main_sel_range = "MAIN_SEL_RANGE";
+    Base::range_sel = "RANGE_SEL";
+    Base::range_val = "RANGE_VAL";
+    Base::perm_main_range_inv = "PERM_MAIN_RANGE_INV";
+    Base::lookup_range_inv = "LOOKUP_RANGE_INV";
+    Base::lookup_range_counts = "LOOKUP_RANGE_COUNTS";
+};
+
+void ExampleFlavor::Transcript::deserialize_full_transcript() {
+    size_t num_frs_read = 0;
+    circuit_size = deserialize_from_buffer<uint32_t>(proof_data, num_frs_read);
+
+    for (auto& commitment : commitments) {
+        commitment = deserialize_from_buffer<Commitment>(proof_data, num_frs_read);
+    }
+
+    for (size_t i = 0; i < CONST_PROOF_SIZE_LOG_N; ++i) {
+        sumcheck_univariates.emplace_back(
+            deserialize_from_buffer<bb::Univariate<FF, BATCHED_RELATION_PARTIAL_LENGTH>>(
+                Transcript::proof_data, num_frs_read));
+    }
+
+    sumcheck_evaluations =
+        deserialize_from_buffer<std::array<FF, NUM_ALL_ENTITIES>>(Transcript::proof_data, num_frs_read);
+
+    for (size_t i = 0; i < CONST_PROOF_SIZE_LOG_N - 1; ++i) {
+        gemini_fold_comms.push_back(deserialize_from_buffer<Commitment>(proof_data, num_frs_read));
+    }
+
+    for (size_t i = 0; i < CONST_PROOF_SIZE_LOG_N; ++i) {
+        gemini_fold_evals.push_back(deserialize_from_buffer<FF>(proof_data, num_frs_read));
+    }
+
+    shplonk_q_comm = deserialize_from_buffer<Commitment>(proof_data, num_frs_read);
+
+    kzg_w_comm = deserialize_from_buffer<Commitment>(proof_data, num_frs_read);
+}
+
+void ExampleFlavor::Transcript::serialize_full_transcript() {
+    size_t old_proof_length = proof_data.size();
+    Transcript::proof_data.clear();
+
+    serialize_to_buffer(circuit_size, Transcript::proof_data);
+
+    for (const auto& commitment : commitments) {
+        serialize_to_buffer(commitment, Transcript::proof_data);
+    }
+
+    for (size_t i = 0; i < CONST_PROOF_SIZE_LOG_N; ++i) {
+        serialize_to_buffer(sumcheck_univariates[i], Transcript::proof_data);
+    }
+
+    serialize_to_buffer(sumcheck_evaluations, Transcript::proof_data);
+
+    for (size_t i = 0; i < CONST_PROOF_SIZE_LOG_N - 1; ++i) {
+        serialize_to_buffer(gemini_fold_comms[i], proof_data);
+    }
+
+    for (size_t i = 0; i < CONST_PROOF_SIZE_LOG_N; ++i) {
+        serialize_to_buffer(gemini_fold_evals[i], proof_data);
+    }
+
+    serialize_to_buffer(shplonk_q_comm, proof_data);
+    serialize_to_buffer(kzg_w_comm, proof_data);
+
+    // sanity check to make sure we generate the same length of proof as before.
+    ASSERT(proof_data.size() == old_proof_length);
+}
+
+ExampleFlavor::PartiallyEvaluatedMultivariates::PartiallyEvaluatedMultivariates(const size_t circuit_size)
+{
+    // Storage is only needed after the first partial evaluation, hence polynomials of size (n / 2)
+    for (auto& poly : get_all()) {
+        poly = Polynomial(circuit_size / 2);
+    }
+}
+
+AvmFlavor::ProvingKey::ProvingKey(const size_t circuit_size, const size_t num_public_inputs)
+    : circuit_size(circuit_size)
+    , evaluation_domain(bb::EvaluationDomain<FF>(circuit_size, circuit_size))
+    , commitment_key(std::make_shared<CommitmentKey>(circuit_size + 1))
+{
+    // TODO: These come from PrecomputedEntitiesBase, ideal we'd just call that class's constructor.
+    this->log_circuit_size = numeric::get_msb(circuit_size);
+    this->num_public_inputs = num_public_inputs;
+
+    // The proving key's polynomials are not allocated here because they are later overwritten
+    // AvmComposer::compute_witness(). We should probably refactor this flow.
+};
+
+/**
+ * @brief Serialize verification key to field elements
+ *
+ * @return std::vector<FF>
+ */
+std::vector<AvmFlavor::VerificationKey::FF> AvmFlavor::VerificationKey::to_field_elements() const
+{
+    std::vector<FF> elements = { FF(circuit_size), FF(num_public_inputs) };
+
+    for (auto const& comm : get_all()) {
+        std::vector<FF> comm_as_fields = field_conversion::convert_to_bn254_frs(comm);
+        elements.insert(elements.end(), comm_as_fields.begin(), comm_as_fields.end());
+    }
+    return elements;
+}
+
+} // namespace bb
\ No newline at end of file
@@ -0,0 +1,375 @@
+// AUTOGENERATED FILE
+#pragma once
+
+#include "barretenberg/commitment_schemes/kzg/kzg.hpp"
+#include "barretenberg/ecc/curves/bn254/g1.hpp"
+#include "barretenberg/flavor/relation_definitions.hpp"
+#include "barretenberg/polynomials/barycentric.hpp"
+#include "barretenberg/polynomials/univariate.hpp"
+
+#include "barretenberg/flavor/flavor_macros.hpp"
+#include "barretenberg/transcript/transcript.hpp"
+#include "barretenberg/polynomials/evaluation_domain.hpp"
+#include "barretenberg/flavor/flavor.hpp"
+
+#include "barretenberg/vm/example/generated/flavor_settings.hpp"
+#include "barretenberg/vm/aztec_constants.hpp"
+
+// Relations
+#include "barretenberg/vm/example/generated/relations/main.hpp"
+#include "barretenberg/vm/example/generated/relations/range.hpp"
+
+// Lookup and permutation relations
+#include "barretenberg/vm/example/generated/relations/lookup_range.hpp"
+#include "barretenberg/vm/example/generated/relations/perm_main_range.hpp"
+
+// Metaprogramming to concatenate tuple types.
+template <typename... input_t> using tuple_cat_t = decltype(std::tuple_cat(std::declval<input_t>()...));
+
+// The entities that will be used in the flavor.
+// clang-format off
+#define PRECOMPUTED_ENTITIES main_clk, main_sel_first
+#define WIRE_ENTITIES main_input, main_a, main_b, main_sel, main_sel_range, range_sel, range_val, lookup_range_counts
+#define DERIVED_WITNESS_ENTITIES perm_main_range_inv, lookup_range_inv
+#define SHIFTED_ENTITIES main_a_shift
+#define TO_BE_SHIFTED(e) e.main_a
+#define ALL_ENTITIES PRECOMPUTED_ENTITIES, WIRE_ENTITIES, DERIVED_WITNESS_ENTITIES, SHIFTED_ENTITIES
+// clang-format on
+
+namespace bb {
+
+class ExampleFlavor {
+  public:
+    using Curve = ExampleFlavorSettings::Curve;
+    using G1 = ExampleFlavorSettings::G1;
+    using PCS = ExampleFlavorSettings::PCS;
+
+    using FF = ExampleFlavorSettings::FF;
+    using Polynomial = ExampleFlavorSettings::Polynomial;
+    using PolynomialHandle = ExampleFlavorSettings::PolynomialHandle;
+    using GroupElement = ExampleFlavorSettings::GroupElement;
+    using Commitment = ExampleFlavorSettings::Commitment;
+    using CommitmentHandle = ExampleFlavorSettings::CommitmentHandle;
+    using CommitmentKey = ExampleFlavorSettings::CommitmentKey;
+    using VerifierCommitmentKey = ExampleFlavorSettings::VerifierCommitmentKey;
+    using RelationSeparator = ExampleFlavorSettings::RelationSeparator;
+
+    // This flavor would not be used with ZK Sumcheck
+    static constexpr bool HasZK = false;
+
+    static constexpr size_t NUM_PRECOMPUTED_ENTITIES = 2;
+    static constexpr size_t NUM_WITNESS_ENTITIES = 10;
+    static constexpr size_t NUM_SHIFTED_ENTITIES = 1;
+    static constexpr size_t NUM_WIRES = NUM_WITNESS_ENTITIES + NUM_PRECOMPUTED_ENTITIES;
+    // We have two copies of the witness entities, so we subtract the number of fixed ones (they have no shift), one for the unshifted and one for the shifted
+    static constexpr size_t NUM_ALL_ENTITIES = 13;
+    // The total number of witnesses including shifts and derived entities.
+    static constexpr size_t NUM_ALL_WITNESS_ENTITIES = NUM_WITNESS_ENTITIES + NUM_SHIFTED_ENTITIES;
+
+    // Need to be templated for recursive verifier
+    template <typename FF_>
+    using MainRelations_ = std::tuple<
+      // Relations
+      Example_vm::main<FF_>,Example_vm::range<FF_>
+    >;
+
+    using MainRelations = MainRelations_<FF>;
+
+    // Need to be templated for recursive verifier
+    template <typename FF_>
+    using LookupRelations_ = std::tuple<
+      // Lookups
+      lookup_range_relation<FF_>,perm_main_range_relation<FF_>
+    >;
+
+    using LookupRelations = LookupRelations_<FF>;
+
+    // Need to be templated for recursive verifier
+    template <typename FF_> using Relations_ = tuple_cat_t<MainRelations_<FF_>, LookupRelations_<FF_>>;
+    using Relations = Relations_<FF>;
+
+    static constexpr size_t MAX_PARTIAL_RELATION_LENGTH = compute_max_partial_relation_length<Relations>();
+
+    // BATCHED_RELATION_PARTIAL_LENGTH = algebraic degree of sumcheck relation *after* multiplying by the `pow_zeta`
+    // random polynomial e.g. For \sum(x) [A(x) * B(x) + C(x)] * PowZeta(X), relation length = 2 and random relation
+    // length = 3
+    static constexpr size_t BATCHED_RELATION_PARTIAL_LENGTH = MAX_PARTIAL_RELATION_LENGTH + 1;
+    static constexpr size_t NUM_RELATIONS = std::tuple_size_v<Relations>;
+
+    using SumcheckTupleOfTuplesOfUnivariates = decltype(create_sumcheck_tuple_of_tuples_of_univariates<Relations>());
+    using TupleOfArraysOfValues = decltype(create_tuple_of_arrays_of_values<Relations>());
+
+    static constexpr bool has_zero_row = true;
+
+    static constexpr size_t NUM_FRS_COM = field_conversion::calc_num_bn254_frs<Commitment>();
+    static constexpr size_t NUM_FRS_FR = field_conversion::calc_num_bn254_frs<FF>();
+
+    // After any circuit changes, hover `COMPUTED_AVM_PROOF_LENGTH_IN_FIELDS` in your IDE
+    // to see its value and then update `AVM_PROOF_LENGTH_IN_FIELDS` in constants.nr.
+    static constexpr size_t COMPUTED_AVM_PROOF_LENGTH_IN_FIELDS =
+        (NUM_WITNESS_ENTITIES + 1) * NUM_FRS_COM + (NUM_ALL_ENTITIES + 1) * NUM_FRS_FR +
+        CONST_PROOF_SIZE_LOG_N * (NUM_FRS_COM + NUM_FRS_FR * (BATCHED_RELATION_PARTIAL_LENGTH + 1));
+
+    static_assert(AVM_PROOF_LENGTH_IN_FIELDS == COMPUTED_AVM_PROOF_LENGTH_IN_FIELDS,
+                  "\nUnexpected AVM proof length. This might be due to some changes in the\n"
+                  "AVM circuit layout. In this case, modify AVM_PROOF_LENGTH_IN_FIELDS \n"
+                  "in constants.nr accordingly.");
+
+    // VK is composed of
+    // - circuit size encoded as a fr field element
+    // - num of inputs encoded as a fr field element
+    // - NUM_PRECOMPUTED_ENTITIES commitments
+    static_assert(AVM_VERIFICATION_KEY_LENGTH_IN_FIELDS == 2 * NUM_FRS_FR + NUM_PRECOMPUTED_ENTITIES * NUM_FRS_COM,
+                  "\nUnexpected AVM VK length. This might be due to some changes in the\n"
+                  "AVM circuit. In this case, modify AVM_VERIFICATION_LENGTH_IN_FIELDS \n"
+                  "in constants.nr accordingly.");
+
+    template <typename DataType_> class PrecomputedEntities : public PrecomputedEntitiesBase {
+      public:
+        using DataType = DataType_;
+
+        DEFINE_FLAVOR_MEMBERS(DataType, PRECOMPUTED_ENTITIES)
+
+        RefVector<DataType> get_selectors() { return get_all(); }
+        RefVector<DataType> get_sigma_polynomials() { return {}; }
+        RefVector<DataType> get_id_polynomials() { return {}; }
+        RefVector<DataType> get_table_polynomials() { return {}; }
+    };
+
+  private:
+    template <typename DataType>
+    class WireEntities {
+      public:
+        DEFINE_FLAVOR_MEMBERS(DataType, WIRE_ENTITIES)
+    };
+
+    template <typename DataType>
+    class DerivedWitnessEntities {
+      public:
+        DEFINE_FLAVOR_MEMBERS(DataType, DERIVED_WITNESS_ENTITIES)
+    };
+
+    template <typename DataType>
+    class ShiftedEntities {
+      public:
+        DEFINE_FLAVOR_MEMBERS(DataType, SHIFTED_ENTITIES)
+    };
+
+    template <typename DataType, typename PrecomputedAndWitnessEntitiesSuperset>
+    static auto get_to_be_shifted([[maybe_unused]] PrecomputedAndWitnessEntitiesSuperset& entities) {
+        return RefArray<DataType, NUM_SHIFTED_ENTITIES>{ TO_BE_SHIFTED(entities) };
+    }
+
+  public:
+    template <typename DataType>
+      class WitnessEntities: public WireEntities<DataType>, public DerivedWitnessEntities<DataType> {
+          public:
+              DEFINE_COMPOUND_GET_ALL(WireEntities<DataType>, DerivedWitnessEntities<DataType>)
+              auto get_wires() { return WireEntities<DataType>::get_all(); }
+              auto get_derived() { return DerivedWitnessEntities<DataType>::get_all(); }
+              auto get_derived_labels() { return DerivedWitnessEntities<DataType>::get_labels(); }
+    };
+
+    template <typename DataType>
+    class AllEntities : public PrecomputedEntities<DataType>
+                  , public WitnessEntities<DataType>
+                  , public ShiftedEntities<DataType> {
+      public:
+        DEFINE_COMPOUND_GET_ALL(PrecomputedEntities<DataType>, WitnessEntities<DataType>, ShiftedEntities<DataType>)
+
+        auto get_unshifted()
+        {
+            return concatenate(PrecomputedEntities<DataType>::get_all(), WitnessEntities<DataType>::get_all());
+        }
+
+        auto get_unshifted_labels()
+        {
+            return concatenate(PrecomputedEntities<DataType>::get_labels(), WitnessEntities<DataType>::get_labels());
+        }
+
+        auto get_to_be_shifted() { return ExampleFlavor::get_to_be_shifted<DataType>(*this); }
+        auto get_shifted() { return ShiftedEntities<DataType>::get_all(); }
+        auto get_precomputed() { return PrecomputedEntities<DataType>::get_all(); }
+    };
+
+    class ProvingKey : public PrecomputedEntities<Polynomial>, public WitnessEntities<Polynomial> {
+      public:
+        using FF = typename Polynomial::FF;
+
+        ProvingKey() = default;
+        ProvingKey(const size_t circuit_size, const size_t num_public_inputs);
+
+        size_t circuit_size;
+        bb::EvaluationDomain<FF> evaluation_domain;
+        std::shared_ptr<CommitmentKey> commitment_key;
+
+        // Offset off the public inputs from the start of the execution trace
+        size_t pub_inputs_offset = 0;
+
+        // The number of public inputs has to be the same for all instances because they are
+        // folded element by element.
+        std::vector<FF> public_inputs;
+
+        std::vector<std::string> get_labels() const
+        {
+            return concatenate(PrecomputedEntities<Polynomial>::get_labels(),
+                               WitnessEntities<Polynomial>::get_labels());
+        }
+        auto get_witness_polynomials() { return WitnessEntities<Polynomial>::get_all(); }
+        auto get_precomputed_polynomials() { return PrecomputedEntities<Polynomial>::get_all(); }
+        auto get_selectors() { return PrecomputedEntities<Polynomial>::get_all(); }
+        auto get_to_be_shifted() { return AvmFlavor::get_to_be_shifted<Polynomial>(*this); }
+        // This order matters! must match get_unshifted in entity classes
+        auto get_all() { return concatenate(get_precomputed_polynomials(), get_witness_polynomials()); }
+    };
+
+    class VerificationKey : public VerificationKey_<PrecomputedEntities<Commitment>, VerifierCommitmentKey> {
+      public:
+       using FF = VerificationKey_::FF;
+
+       VerificationKey() = default;
+
+        VerificationKey(const std::shared_ptr<ProvingKey>& proving_key)
+            : VerificationKey_(proving_key->circuit_size, static_cast<size_t>(proving_key->num_public_inputs))
+        {
+            for (auto [polynomial, commitment] :
+                zip_view(proving_key->get_precomputed_polynomials(), this->get_all())) {
+                    commitment = proving_key->commitment_key->commit(polynomial);
+            }
+            pcs_verification_key = std::make_shared<VerifierCommitmentKey>();
+        }
+
+        VerificationKey(const size_t circuit_size,
+                        const size_t num_public_inputs,
+                        std::array<Commitment, NUM_PRECOMPUTED_ENTITIES> const& precomputed_cmts)
+            : VerificationKey_(circuit_size, num_public_inputs)
+        {
+            for (auto [vk_cmt, cmt] : zip_view(this->get_all(), precomputed_cmts)) {
+                vk_cmt = cmt;
+            }
+            pcs_verification_key = std::make_shared<VerifierCommitmentKey>();
+        }
+
+        std::vector<FF> to_field_elements() const;
+    };
+
+    class AllValues : public AllEntities<FF> {
+      public:
+        using Base = AllEntities<FF>;
+        using Base::Base;
+    };
+
+    class AllConstRefValues {
+      public:
+        using BaseDataType = const FF;
+        using DataType = BaseDataType&;
+
+        DEFINE_FLAVOR_MEMBERS(DataType, ALL_ENTITIES)
+
+        AllConstRefValues(const RefArray<BaseDataType, NUM_ALL_ENTITIES>& il);
+    };
+
+    /**
+    * @brief A container for the prover polynomials handles.
+    */
+    class ProverPolynomials : public AllEntities<Polynomial> {
+      public:
+        // Define all operations as default, except copy construction/assignment
+        ProverPolynomials() = default;
+        ProverPolynomials& operator=(const ProverPolynomials&) = delete;
+        ProverPolynomials(const ProverPolynomials& o) = delete;
+        ProverPolynomials(ProverPolynomials&& o) noexcept = default;
+        ProverPolynomials& operator=(ProverPolynomials&& o) noexcept = default;
+        ~ProverPolynomials() = default;
+
+        ProverPolynomials(ProvingKey& proving_key);
+
+        [[nodiscard]] size_t get_polynomial_size() const { return main_input.size(); }
+        /**
+        * @brief Returns the evaluations of all prover polynomials at one point on the boolean hypercube, which
+        * represents one row in the execution trace.
+        */
+        [[nodiscard]] AllConstRefValues get_row(size_t row_idx) const;
+    };
+
+    class PartiallyEvaluatedMultivariates : public AllEntities<Polynomial> {
+      public:
+        PartiallyEvaluatedMultivariates() = default;
+        PartiallyEvaluatedMultivariates(const size_t circuit_size);
+    };
+
+    /**
+    * @brief A container for univariates used during Protogalaxy folding and sumcheck.
+    * @details During folding and sumcheck, the prover evaluates the relations on these univariates.
+    */
+    template <size_t LENGTH>
+    using ProverUnivariates = AllEntities<bb::Univariate<FF, LENGTH>>;
+
+      /**
+      * @brief A container for univariates used during Protogalaxy folding and sumcheck with some of the computation
+      * optimistically ignored
+      * @details During folding and sumcheck, the prover evaluates the relations on these univariates.
+      */
+      template <size_t LENGTH, size_t SKIP_COUNT>
+      using ProverUnivariatesWithOptimisticSkipping = AllEntities<bb::Univariate<FF, LENGTH, 0, SKIP_COUNT>>;
+
+    /**
+    * @brief A container for univariates produced during the hot loop in sumcheck.
+    */
+    using ExtendedEdges = ProverUnivariates<MAX_PARTIAL_RELATION_LENGTH>;
+
+    /**
+    * @brief A container for the witness commitments.
+    *
+    */
+    using WitnessCommitments = WitnessEntities<Commitment>;
+
+    class CommitmentLabels: public AllEntities<std::string> {
+      private:
+        using Base = AllEntities<std::string>;
+
+      public:
+        CommitmentLabels();
+    };
+
+    // Templated for use in recursive verifier
+    template <typename Commitment_, typename VerificationKey>
+    class VerifierCommitments_ : public AllEntities<Commitment_> {
+    private:
+      using Base = AllEntities<Commitment_>;
+
+    public:
+      VerifierCommitments_(const std::shared_ptr<VerificationKey>& verification_key) {
+            this->main_clk = verification_key->main_clk;
+            this->main_sel_first = verification_key->main_sel_first;
+        }
+    };
+
+    // Native version of the verifier commitments
+    using VerifierCommitments = VerifierCommitments_<Commitment, VerificationKey>;
+
+    class Transcript : public NativeTranscript {
+    public:
+      uint32_t circuit_size;
+
+      std::array<Commitment, NUM_WITNESS_ENTITIES> commitments;
+
+      std::vector<bb::Univariate<FF, BATCHED_RELATION_PARTIAL_LENGTH>> sumcheck_univariates;
+      std::array<FF, NUM_ALL_ENTITIES> sumcheck_evaluations;
+      std::vector<Commitment> gemini_fold_comms;
+      std::vector<FF> gemini_fold_evals;
+      Commitment shplonk_q_comm;
+      Commitment kzg_w_comm;
+
+      Transcript() = default;
+
+      Transcript(const std::vector<FF>& proof)
+          : NativeTranscript(proof)
+      {}
+
+      void deserialize_full_transcript();
+      void serialize_full_transcript();
+    };
+};
+
+} // namespace bb
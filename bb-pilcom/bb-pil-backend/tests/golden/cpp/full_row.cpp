@@ -0,0 +1,68 @@
+// AUTOGENERATED FILE
+#include "barretenberg/vm/example/generated/full_row.hpp"
+#include "barretenberg/vm/example/generated/flavor_settings.hpp"
+
+namespace bb {
+namespace {
+
+template <typename FF> std::string field_to_string(const FF& ff)
+{
+    std::ostringstream os;
+    os << ff;
+    std::string raw = os.str();
+    auto first_not_zero = raw.find_first_not_of('0', 2);
+    std::string result = "0x" + (first_not_zero != std::string::npos ? raw.substr(first_not_zero) : "0");
+    return result;
+}
+
+} // namespace
+
+template <typename FF> std::vector<std::string> ExampleFullRow<FF>::names()
+{
+    return {
+        "main_clk"
+        ,"main_sel_first"
+        ,"main_input"
+        ,"main_a"
+        ,"main_b"
+        ,"main_sel"
+        ,"main_sel_range"
+        ,"range_sel"
+        ,"range_val"
+        ,"perm_main_range_inv"
+        ,"lookup_range_inv"
+        ,"lookup_range_counts"
+    };
+}
+
+template <typename FF> RefVector<const FF> ExampleFullRow<FF>::as_vector() const {
+    return RefVector<const FF>{
+        main_clk,
+        main_sel_first,
+        main_input,
+        main_a,
+        main_b,
+        main_sel,
+        main_sel_range,
+        range_sel,
+        range_val,
+        perm_main_range_inv,
+        lookup_range_inv,
+        lookup_range_counts,
+    };
+}
+
+template <typename FF> std::ostream& operator<<(std::ostream& os, ExampleFullRow<FF> const& row)
+{
+    for (const auto& ff : row.as_vector()) {
+        os << field_to_string(ff) << ", ";
+    }
+    return os;
+}
+
+// Explicit template instantiation.
+template std::ostream& operator<<(std::ostream& os, ExampleFullRow<ExampleFlavorSettings::FF> const& row);
+template std::vector<std::string> ExampleFullRow<ExampleFlavorSettings::FF>::names();
+template RefVector<const ExampleFlavorSettings::FF> ExampleFullRow<ExampleFlavorSettings::FF>::as_vector() const;
+
+} // namespace bb
@@ -0,0 +1,35 @@
+// AUTOGENERATED FILE
+#pragma once
+
+#include <string>
+#include <sstream>
+#include <vector>
+
+#include "barretenberg/common/ref_vector.hpp"
+
+namespace bb {
+
+template <typename FF>
+struct ExampleFullRow {
+    FF main_clk{};
+    FF main_sel_first{};
+    FF main_input{};
+    FF main_a{};
+    FF main_b{};
+    FF main_sel{};
+    FF main_sel_range{};
+    FF range_sel{};
+    FF range_val{};
+    FF perm_main_range_inv{};
+    FF lookup_range_inv{};
+    FF lookup_range_counts{};
+    
+    RefVector<const FF> as_vector() const;
+
+    static std::vector<std::string> names();
+    static constexpr size_t SIZE = 12;
+};
+
+template <typename FF> std::ostream& operator<<(std::ostream& os, ExampleFullRow<FF> const& row);
+
+} // namespace bb
\ No newline at end of file
@@ -0,0 +1,67 @@
+// AUTOGENERATED FILE
+#pragma once
+
+#include "barretenberg/relations/generic_lookup/generic_lookup_relation.hpp"
+
+#include <cstddef>
+#include <tuple>
+
+namespace bb {
+
+class lookup_range_lookup_settings {
+  public:
+    static constexpr size_t READ_TERMS = 1;
+    static constexpr size_t WRITE_TERMS = 1;
+    static constexpr size_t READ_TERM_TYPES[READ_TERMS] = {0};
+    static constexpr size_t WRITE_TERM_TYPES[WRITE_TERMS] = {0};
+    static constexpr size_t LOOKUP_TUPLE_SIZE = 1;
+    static constexpr size_t INVERSE_EXISTS_POLYNOMIAL_DEGREE = 4;
+    static constexpr size_t READ_TERM_DEGREE = 0;
+    static constexpr size_t WRITE_TERM_DEGREE = 0;
+
+    template <typename AllEntities> static inline auto inverse_polynomial_is_computed_at_row(const AllEntities& in)
+    {
+        return (in.main_sel_range == 1 || in.range_sel == 1);
+    }
+
+    template <typename Accumulator, typename AllEntities>
+    static inline auto compute_inverse_exists(const AllEntities& in)
+    {
+        using View = typename Accumulator::View;
+        const auto is_operation = View(in.main_sel_range);
+        const auto is_table_entry = View(in.range_sel);
+        return (is_operation + is_table_entry - is_operation * is_table_entry);
+    }
+
+    template <typename AllEntities> static inline auto get_const_entities(const AllEntities& in)
+    {
+        return std::forward_as_tuple(
+        in.lookup_range_inv,
+        in.lookup_range_counts,
+        in.main_sel_range,
+        in.range_sel,
+        in.main_a,
+        in.range_val
+        );
+    }
+
+    template <typename AllEntities> static inline auto get_nonconst_entities(AllEntities& in)
+    {
+        return std::forward_as_tuple(
+        in.lookup_range_inv,
+        in.lookup_range_counts,
+        in.main_sel_range,
+        in.range_sel,
+        in.main_a,
+        in.range_val
+        );
+    }
+};
+
+template <typename FF_> class lookup_range_relation : public GenericLookupRelation<lookup_range_lookup_settings, FF_> {
+    public:
+        static constexpr const char* NAME = "LOOKUP_RANGE";
+};
+template <typename FF_> using lookup_range = GenericLookup<lookup_range_lookup_settings, FF_>;
+
+} // namespace bb
\ No newline at end of file
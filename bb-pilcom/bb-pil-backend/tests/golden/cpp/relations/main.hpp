@@ -0,0 +1,61 @@
+// AUTOGENERATED FILE
+#pragma once
+
+#include "barretenberg/relations/relation_parameters.hpp"
+#include "barretenberg/relations/relation_types.hpp"
+
+namespace bb::Example_vm {
+
+
+template <typename FF_> class mainImpl {
+    public:
+        using FF = FF_;
+        
+        static constexpr std::array<size_t, 2> SUBRELATION_PARTIAL_LENGTHS = {
+            3,4
+        };
+
+        template <typename AllEntities> inline static bool skip(const AllEntities& in)
+        {
+            const auto& new_term = in;
+            return (new_term.main_sel).is_zero();
+        }
+
+        template <typename ContainerOverSubrelations, typename AllEntities>
+        void static accumulate(
+            ContainerOverSubrelations& evals,
+            const AllEntities& new_term,
+            [[maybe_unused]] const RelationParameters<FF>&,
+            [[maybe_unused]] const FF& scaling_factor
+        ){
+            const auto main_sum = (new_term.main_a + new_term.main_b);
+
+            {
+                using Accumulator = typename std::tuple_element_t<0, ContainerOverSubrelations>;
+                auto tmp = (new_term.main_sel * (new_term.main_sel - FF(1)));
+                tmp *= scaling_factor;
+                std::get<0>(evals) += typename Accumulator::View(tmp);
+            }
+            {
+                using Accumulator = typename std::tuple_element_t<1, ContainerOverSubrelations>;
+                auto tmp = (new_term.main_sel * (new_term.main_a_shift - main_sum));
+                tmp *= scaling_factor;
+                std::get<1>(evals) += typename Accumulator::View(tmp);
+            }
+        }
+};
+
+template <typename FF> class main : public Relation<mainImpl<FF>> {
+    public:
+        static constexpr const char* NAME = "main";
+
+        static std::string get_subrelation_label(size_t index) {
+            switch (index) {
+                case 1:
+                    return "NEXT_A";
+            }
+            return std::to_string(index);
+        }
+};
+
+} // namespace bb::Example_vm
\ No newline at end of file
@@ -0,0 +1,52 @@
+// AUTOGENERATED FILE
+#pragma once
+
+#include "barretenberg/relations/generic_permutation/generic_permutation_relation.hpp"
+
+#include <cstddef>
+#include <tuple>
+
+namespace bb {
+
+class perm_main_range_permutation_settings {
+  public:
+    // This constant defines how many columns are bundled together to form each set.
+    constexpr static size_t COLUMNS_PER_SET = 1;
+
+    template <typename AllEntities> static inline auto inverse_polynomial_is_computed_at_row(const AllEntities& in)
+    {
+        return (in.main_sel == 1 || in.range_sel == 1);
+    }
+
+    template <typename AllEntities> static inline auto get_const_entities(const AllEntities& in)
+    {
+        return std::forward_as_tuple(
+        in.perm_main_range_inv,
+        in.main_sel,
+        in.main_sel,
+        in.range_sel,
+        in.main_b,
+        in.range_val
+        );
+    }
+
+    template <typename AllEntities> static inline auto get_nonconst_entities(AllEntities& in)
+    {
+        return std::forward_as_tuple(
+        in.perm_main_range_inv,
+        in.main_sel,
+        in.main_sel,
+        in.range_sel,
+        in.main_b,
+        in.range_val
+        );
+    }
+};
+
+template <typename FF_> class perm_main_range_relation : public GenericPermutationRelation<perm_main_range_permutation_settings, FF_> {
+    public:
+        static constexpr const char* NAME = "PERM_MAIN_RANGE";
+};
+template <typename FF_> using perm_main_range = GenericPermutation<perm_main_range_permutation_settings, FF_>;
+
+} // namespace bb
\ No newline at end of file
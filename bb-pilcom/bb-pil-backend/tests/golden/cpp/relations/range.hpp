@@ -0,0 +1,49 @@
+// AUTOGENERATED FILE
+#pragma once
+
+#include "barretenberg/relations/relation_parameters.hpp"
+#include "barretenberg/relations/relation_types.hpp"
+
+namespace bb::Example_vm {
+
+
+template <typename FF_> class rangeImpl {
+    public:
+        using FF = FF_;
+        
+        static constexpr std::array<size_t, 1> SUBRELATION_PARTIAL_LENGTHS = {
+            3
+        };
+
+
+        template <typename ContainerOverSubrelations, typename AllEntities>
+        void static accumulate(
+            ContainerOverSubrelations& evals,
+            const AllEntities& new_term,
+            [[maybe_unused]] const RelationParameters<FF>&,
+            [[maybe_unused]] const FF& scaling_factor
+        ){
+
+            {
+                using Accumulator = typename std::tuple_element_t<0, ContainerOverSubrelations>;
+                auto tmp = (new_term.range_sel * (new_term.range_val - FF(255)));
+                tmp *= scaling_factor;
+                std::get<0>(evals) += typename Accumulator::View(tmp);
+            }
+        }
+};
+
+template <typename FF> class range : public Relation<rangeImpl<FF>> {
+    public:
+        static constexpr const char* NAME = "range";
+
+        static std::string get_subrelation_label(size_t index) {
+            switch (index) {
+                case 0:
+                    return "RANGE_MAX";
+            }
+            return std::to_string(index);
+        }
+};
+
+} // namespace bb::Example_vm
\ No newline at end of file
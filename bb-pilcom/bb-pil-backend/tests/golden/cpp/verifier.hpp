@@ -0,0 +1,36 @@
+// AUTOGENERATED FILE
+#pragma once
+
+#include "barretenberg/plonk/proof_system/types/proof.hpp"
+#include "barretenberg/sumcheck/sumcheck.hpp"
+#include "barretenberg/vm/example/generated/flavor.hpp"
+
+namespace bb {
+
+class ExampleVerifier {
+    using Flavor = ExampleFlavor;
+    using FF = Flavor::FF;
+    using Commitment = Flavor::Commitment;
+    using VerificationKey = Flavor::VerificationKey;
+    using VerifierCommitmentKey = Flavor::VerifierCommitmentKey;
+    using Transcript = Flavor::Transcript;
+
+  public:
+    explicit ExampleVerifier(std::shared_ptr<VerificationKey> verifier_key);
+    ExampleVerifier(ExampleVerifier&& other) noexcept;
+    ExampleVerifier(const ExampleVerifier& other) = delete;
+
+    ExampleVerifier& operator=(const ExampleVerifier& other) = delete;
+    ExampleVerifier& operator=(ExampleVerifier&& other) noexcept;
+
+    bool verify_proof(const HonkProof& proof, const std::vector<std::vector<FF>>& public_inputs);
+
+    std::shared_ptr<VerificationKey> key;
+    std::map<std::string, Commitment> commitments;
+    std::shared_ptr<Transcript> transcript;
+
+  private:
+    FF evaluate_public_input_column(const std::vector<FF>& points, std::vector<FF> challenges);
+};
+
+} // namespace bb
\ No newline at end of file
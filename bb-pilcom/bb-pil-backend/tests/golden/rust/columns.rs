@@ -0,0 +1,115 @@
+// AUTOGENERATED FILE
+#![allow(dead_code, non_snake_case)]
+
+use super::Field;
+
+/// The names of the columns of the trace, in the order of the fields of `Row`
+pub const COLUMN_NAMES: [&str; 12] = [
+    "main_clk",
+    "main_sel_first",
+    "main_input",
+    "main_a",
+    "main_b",
+    "main_sel",
+    "main_sel_range",
+    "range_sel",
+    "range_val",
+    "perm_main_range_inv",
+    "lookup_range_inv",
+    "lookup_range_counts",
+];
+
+/// A row of the trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Row<FF> {
+    pub main_clk: FF,
+    pub main_sel_first: FF,
+    pub main_input: FF,
+    pub main_a: FF,
+    pub main_b: FF,
+    pub main_sel: FF,
+    pub main_sel_range: FF,
+    pub range_sel: FF,
+    pub range_val: FF,
+    pub perm_main_range_inv: FF,
+    pub lookup_range_inv: FF,
+    pub lookup_range_counts: FF,
+}
+
+impl<FF: Field> Default for Row<FF> {
+    fn default() -> Self {
+        Self {
+            main_clk: FF::zero(),
+            main_sel_first: FF::zero(),
+            main_input: FF::zero(),
+            main_a: FF::zero(),
+            main_b: FF::zero(),
+            main_sel: FF::zero(),
+            main_sel_range: FF::zero(),
+            range_sel: FF::zero(),
+            range_val: FF::zero(),
+            perm_main_range_inv: FF::zero(),
+            lookup_range_inv: FF::zero(),
+            lookup_range_counts: FF::zero(),
+        }
+    }
+}
+
+impl<FF: Field> Row<FF> {
+    /// The values of the row's columns, in the order of `COLUMN_NAMES`
+    pub fn as_vec(&self) -> Vec<FF> {
+        vec![
+            self.main_clk,
+            self.main_sel_first,
+            self.main_input,
+            self.main_a,
+            self.main_b,
+            self.main_sel,
+            self.main_sel_range,
+            self.range_sel,
+            self.range_val,
+            self.perm_main_range_inv,
+            self.lookup_range_inv,
+            self.lookup_range_counts,
+        ]
+    }
+}
+
+/// The values that relations are evaluated over at a row: its columns, and the columns which are
+/// shifted at the next row
+#[derive(Debug, Clone, Copy)]
+pub struct Entities<FF> {
+    pub main_clk: FF,
+    pub main_sel_first: FF,
+    pub main_input: FF,
+    pub main_a: FF,
+    pub main_b: FF,
+    pub main_sel: FF,
+    pub main_sel_range: FF,
+    pub range_sel: FF,
+    pub range_val: FF,
+    pub perm_main_range_inv: FF,
+    pub lookup_range_inv: FF,
+    pub lookup_range_counts: FF,
+    pub main_a_shift: FF,
+}
+
+impl<FF: Field> Entities<FF> {
+    pub fn new(row: &Row<FF>, next: &Row<FF>) -> Self {
+        Self {
+            main_clk: row.main_clk,
+            main_sel_first: row.main_sel_first,
+            main_input: row.main_input,
+            main_a: row.main_a,
+            main_b: row.main_b,
+            main_sel: row.main_sel,
+            main_sel_range: row.main_sel_range,
+            range_sel: row.range_sel,
+            range_val: row.range_val,
+            perm_main_range_inv: row.perm_main_range_inv,
+            lookup_range_inv: row.lookup_range_inv,
+            lookup_range_counts: row.lookup_range_counts,
+            main_a_shift: next.main_a,
+        }
+    }
+}
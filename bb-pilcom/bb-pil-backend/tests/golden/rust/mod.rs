@@ -0,0 +1,204 @@
+// AUTOGENERATED FILE
+//! The columns, relations, lookups and permutations of the Example VM,
+//! and a checker of traces against them.
+
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, Mul, Neg, Sub};
+
+pub mod columns;
+pub mod relations;
+
+use columns::{Entities, Row};
+
+/// The field that the VM's trace is over.
+/// Implement it for the field type of the code using the VM.
+pub trait Field:
+    Copy
+    + Eq
+    + Hash
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    fn from_u64(value: u64) -> Self;
+
+    /// The field element with the given 256 bit value, as little-endian 64 bit limbs
+    fn from_limbs(limbs: [u64; 4]) -> Self;
+
+    fn zero() -> Self {
+        Self::from_u64(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+}
+
+/// The first constraint that a trace was found not to satisfy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckFailure {
+    Relation {
+        relation: &'static str,
+        subrelation: String,
+        row: usize,
+    },
+    Lookup {
+        lookup: &'static str,
+        row: usize,
+    },
+    Permutation {
+        permutation: &'static str,
+        row: usize,
+    },
+}
+
+impl Display for CheckFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckFailure::Relation {
+                relation,
+                subrelation,
+                row,
+            } => write!(
+                f,
+                "Relation {relation}, subrelation {subrelation} failed at row {row}"
+            ),
+            CheckFailure::Lookup { lookup, row } => {
+                write!(f, "Lookup {lookup} failed at row {row}")
+            }
+            CheckFailure::Permutation { permutation, row } => {
+                write!(f, "Permutation {permutation} failed at row {row}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CheckFailure {}
+
+/// Check Circuit
+///
+/// Checks that a trace satisfies every relation, lookup and permutation of the VM.
+/// As in the prover, the shifted columns of the last row are zero.
+pub fn check_circuit<FF: Field>(rows: &[Row<FF>]) -> Result<(), CheckFailure> {
+    let zero_row = Row::default();
+    for (index, row) in rows.iter().enumerate() {
+        let next = rows.get(index + 1).unwrap_or(&zero_row);
+        let entities = Entities::new(row, next);
+        if !relations::main::skip(&entities) {
+            let values = relations::main::evaluate(&entities);
+            if let Some(subrelation) = values.iter().position(|value| !value.is_zero()) {
+                return Err(CheckFailure::Relation {
+                    relation: relations::main::NAME,
+                    subrelation: relations::main::subrelation_label(subrelation),
+                    row: index,
+                });
+            }
+        }
+        if !relations::range::skip(&entities) {
+            let values = relations::range::evaluate(&entities);
+            if let Some(subrelation) = values.iter().position(|value| !value.is_zero()) {
+                return Err(CheckFailure::Relation {
+                    relation: relations::range::NAME,
+                    subrelation: relations::range::subrelation_label(subrelation),
+                    row: index,
+                });
+            }
+        }
+    }
+
+    check_lookup(
+        rows,
+        relations::lookup_range::NAME,
+        relations::lookup_range::lhs_selector,
+        relations::lookup_range::rhs_selector,
+        relations::lookup_range::counts,
+        relations::lookup_range::lhs_tuple,
+        relations::lookup_range::rhs_tuple,
+    )?;
+
+    check_permutation(
+        rows,
+        relations::perm_main_range::NAME,
+        relations::perm_main_range::lhs_selector,
+        relations::perm_main_range::rhs_selector,
+        relations::perm_main_range::lhs_tuple,
+        relations::perm_main_range::rhs_tuple,
+    )?;
+
+    Ok(())
+}
+
+/// Checks that, for each tuple, the lhs selectors of the rows reading it sum to the counts of the rows
+/// of the table with it, where the rhs selector is non-zero
+#[allow(dead_code)]
+fn check_lookup<FF: Field, const N: usize>(
+    rows: &[Row<FF>],
+    lookup: &'static str,
+    lhs_selector: fn(&Row<FF>) -> FF,
+    rhs_selector: fn(&Row<FF>) -> FF,
+    counts: fn(&Row<FF>) -> FF,
+    lhs_tuple: fn(&Row<FF>) -> [FF; N],
+    rhs_tuple: fn(&Row<FF>) -> [FF; N],
+) -> Result<(), CheckFailure> {
+    let rhs_multiplicity = |row: &Row<FF>| {
+        if rhs_selector(row).is_zero() {
+            FF::zero()
+        } else {
+            counts(row)
+        }
+    };
+    match unbalanced_row(rows, &lhs_selector, &rhs_multiplicity, lhs_tuple, rhs_tuple) {
+        Some(row) => Err(CheckFailure::Lookup { lookup, row }),
+        None => Ok(()),
+    }
+}
+
+/// Checks that, for each tuple, the lhs selectors of the rows with it sum to the rhs selectors of the
+/// rows with it
+#[allow(dead_code)]
+fn check_permutation<FF: Field, const N: usize>(
+    rows: &[Row<FF>],
+    permutation: &'static str,
+    lhs_selector: fn(&Row<FF>) -> FF,
+    rhs_selector: fn(&Row<FF>) -> FF,
+    lhs_tuple: fn(&Row<FF>) -> [FF; N],
+    rhs_tuple: fn(&Row<FF>) -> [FF; N],
+) -> Result<(), CheckFailure> {
+    match unbalanced_row(rows, &lhs_selector, &rhs_selector, lhs_tuple, rhs_tuple) {
+        Some(row) => Err(CheckFailure::Permutation { permutation, row }),
+        None => Ok(()),
+    }
+}
+
+/// The lowest row of a lookup or permutation with a tuple whose multiplicities over the rows of the lhs
+/// don't sum to the same as over the rows of the rhs
+#[allow(dead_code)]
+fn unbalanced_row<FF: Field, const N: usize>(
+    rows: &[Row<FF>],
+    lhs_multiplicity: &dyn Fn(&Row<FF>) -> FF,
+    rhs_multiplicity: &dyn Fn(&Row<FF>) -> FF,
+    lhs_tuple: fn(&Row<FF>) -> [FF; N],
+    rhs_tuple: fn(&Row<FF>) -> [FF; N],
+) -> Option<usize> {
+    let mut balance: HashMap<[FF; N], FF> = HashMap::new();
+    for row in rows {
+        let multiplicity = lhs_multiplicity(row);
+        if !multiplicity.is_zero() {
+            let entry = balance.entry(lhs_tuple(row)).or_insert_with(FF::zero);
+            *entry = *entry + multiplicity;
+        }
+        let multiplicity = rhs_multiplicity(row);
+        if !multiplicity.is_zero() {
+            let entry = balance.entry(rhs_tuple(row)).or_insert_with(FF::zero);
+            *entry = *entry - multiplicity;
+        }
+    }
+    rows.iter().position(|row| {
+        (!lhs_multiplicity(row).is_zero() && !balance[&lhs_tuple(row)].is_zero())
+            || (!rhs_multiplicity(row).is_zero() && !balance[&rhs_tuple(row)].is_zero())
+    })
+}
@@ -0,0 +1,41 @@
+// AUTOGENERATED FILE
+#![allow(dead_code)]
+
+use super::super::columns::Row;
+use super::super::Field;
+
+pub const NAME: &str = "lookup_range";
+/// The column of inverses computed by the prover for the lookup
+pub const INVERSE_COLUMN: &str = "lookup_range_inv";
+/// The column counting the number of times each row of the table is read
+pub const COUNTS_COLUMN: &str = "lookup_range_counts";
+pub const LOOKUP_TUPLE_SIZE: usize = 1;
+
+/// Whether a row reads its tuple from the table
+pub fn lhs_selector<FF: Field>(row: &Row<FF>) -> FF {
+    row.main_sel_range
+}
+
+/// Whether a row's tuple is in the table
+pub fn rhs_selector<FF: Field>(row: &Row<FF>) -> FF {
+    row.range_sel
+}
+
+/// The number of times a row of the table is read
+pub fn counts<FF: Field>(row: &Row<FF>) -> FF {
+    row.lookup_range_counts
+}
+
+/// The tuple read by a row
+pub fn lhs_tuple<FF: Field>(row: &Row<FF>) -> [FF; LOOKUP_TUPLE_SIZE] {
+    [
+        row.main_a,
+    ]
+}
+
+/// The tuple a row adds to the table
+pub fn rhs_tuple<FF: Field>(row: &Row<FF>) -> [FF; LOOKUP_TUPLE_SIZE] {
+    [
+        row.range_val,
+    ]
+}
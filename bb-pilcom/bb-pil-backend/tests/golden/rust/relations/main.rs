@@ -0,0 +1,34 @@
+// AUTOGENERATED FILE
+#![allow(dead_code, non_snake_case, unused_parens)]
+
+use super::super::columns::Entities;
+use super::super::Field;
+
+pub const NAME: &str = "main";
+
+pub const SUBRELATION_PARTIAL_LENGTHS: [usize; 2] = [
+    3, 4
+];
+
+/// The label of a subrelation, or its index if it doesn't have one
+pub fn subrelation_label(index: usize) -> String {
+    match index {
+        1 => "NEXT_A".to_string(),
+        _ => index.to_string(),
+    }
+}
+
+/// Whether every subrelation is known to be satisfied at a row without evaluating it
+pub fn skip<FF: Field>(new_term: &Entities<FF>) -> bool {
+    (new_term.main_sel).is_zero()
+}
+
+/// Evaluates each subrelation at a row, all of which are zero in a valid trace
+pub fn evaluate<FF: Field>(new_term: &Entities<FF>) -> [FF; 2] {
+    let main_sum = (new_term.main_a + new_term.main_b);
+
+    [
+        (new_term.main_sel * (new_term.main_sel - FF::from_u64(1))),
+        (new_term.main_sel * (new_term.main_a_shift - main_sum)),
+    ]
+}
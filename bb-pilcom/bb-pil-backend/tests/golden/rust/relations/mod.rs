@@ -0,0 +1,6 @@
+// AUTOGENERATED FILE
+
+pub mod main;
+pub mod range;
+pub mod lookup_range;
+pub mod perm_main_range;
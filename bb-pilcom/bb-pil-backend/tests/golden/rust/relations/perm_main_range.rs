@@ -0,0 +1,32 @@
+// AUTOGENERATED FILE
+#![allow(dead_code)]
+
+use super::super::columns::Row;
+use super::super::Field;
+
+pub const NAME: &str = "perm_main_range";
+/// The column of inverses computed by the prover for the permutation
+pub const INVERSE_COLUMN: &str = "perm_main_range_inv";
+pub const COLUMNS_PER_SET: usize = 1;
+
+/// Whether a row's lhs tuple is in the permutation
+pub fn lhs_selector<FF: Field>(row: &Row<FF>) -> FF {
+    row.main_sel
+}
+
+/// Whether a row's rhs tuple is in the permutation
+pub fn rhs_selector<FF: Field>(row: &Row<FF>) -> FF {
+    row.range_sel
+}
+
+pub fn lhs_tuple<FF: Field>(row: &Row<FF>) -> [FF; COLUMNS_PER_SET] {
+    [
+        row.main_b,
+    ]
+}
+
+pub fn rhs_tuple<FF: Field>(row: &Row<FF>) -> [FF; COLUMNS_PER_SET] {
+    [
+        row.range_val,
+    ]
+}
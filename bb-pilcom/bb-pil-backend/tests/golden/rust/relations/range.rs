@@ -0,0 +1,33 @@
+// AUTOGENERATED FILE
+#![allow(dead_code, non_snake_case, unused_parens)]
+
+use super::super::columns::Entities;
+use super::super::Field;
+
+pub const NAME: &str = "range";
+
+pub const SUBRELATION_PARTIAL_LENGTHS: [usize; 1] = [
+    3
+];
+
+/// The label of a subrelation, or its index if it doesn't have one
+pub fn subrelation_label(index: usize) -> String {
+    match index {
+        0 => "RANGE_MAX".to_string(),
+        _ => index.to_string(),
+    }
+}
+
+/// Whether every subrelation is known to be satisfied at a row without evaluating it
+pub fn skip<FF: Field>(new_term: &Entities<FF>) -> bool {
+    let _ = new_term;
+    false
+}
+
+/// Evaluates each subrelation at a row, all of which are zero in a valid trace
+pub fn evaluate<FF: Field>(new_term: &Entities<FF>) -> [FF; 1] {
+
+    [
+        (new_term.range_sel * (new_term.range_val - FF::from_u64(255))),
+    ]
+}
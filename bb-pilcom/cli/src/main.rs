@@ -1,16 +1,42 @@
-use std::{io, path::Path};
+use std::{fs::File, io, io::BufReader, path::Path, process::ExitCode};
 
-use bb_pil_backend::vm_builder::analyzed_to_cpp;
-use clap::Parser;
+use bb_pil_backend::analysis::analyze_pil;
+use bb_pil_backend::trace::Trace;
+use bb_pil_backend::trace_checker::check_trace;
+use bb_pil_backend::vm_builder::{analyzed_to_cpp, analyzed_to_rust, WriteMode};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use powdr_ast::analyzed::Analyzed;
 use powdr_number::Bn254Field;
 use powdr_pil_analyzer::analyze_file;
 
+/// The language of the generated code
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Backend {
+    /// The barretenberg flavor, relations, prover and verifier
+    Cpp,
+    /// The columns, relations, lookups and permutations, with a witness checker
+    Rust,
+}
+
+/// The format of a trace file
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum TraceFormat {
+    /// A header of column names and a line of values per row, as dumped by `bb --avm-dump-trace`
+    Csv,
+    /// Columns dumped one after the other, as their name and their 32 byte little-endian values
+    Binary,
+}
+
 #[derive(Parser)]
 #[command(name = "bb-pil-cli", author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Input file
-    file: String,
+    #[arg(required = true)]
+    file: Option<String>,
 
     /// Output directory for the PIL file, json file and fixed and witness column data.
     #[arg(short, long)]
@@ -21,20 +47,135 @@ struct Cli {
     #[arg(long)]
     name: Option<String>,
 
-    /// Delete the output directory if it already exists
+    /// Remove the stale generated files, which are no longer generated, without confirmation
     #[arg(short, long)]
     #[arg(default_value_t = false)]
     yes: bool,
+
+    /// Check that the generated files are up to date, without writing them.
+    /// Fails if a file would be written or removed.
+    #[arg(long)]
+    #[arg(default_value_t = false)]
+    check: bool,
+
+    /// The language of the generated code.
+    /// Rust code is generated in a module named after the VM in the output directory.
+    #[arg(short, long)]
+    #[arg(value_enum, default_value_t = Backend::Cpp)]
+    backend: Backend,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check a witness trace against the relations, lookups and permutations of the PIL, and report the
+    /// first failing row
+    Check(CheckArgs),
+    /// Report the degrees of the relations of the PIL, its unused and shifted columns, and its lookups and
+    /// permutations
+    Analyze(AnalyzeArgs),
 }
 
-fn main() -> Result<(), io::Error> {
+#[derive(Args)]
+struct CheckArgs {
+    /// Input file
+    file: String,
+
+    /// Trace file
+    #[arg(short, long)]
+    trace: String,
+
+    /// Format of the trace file. Defaults to csv for files ending in .csv, and binary otherwise.
+    #[arg(long, value_enum)]
+    format: Option<TraceFormat>,
+}
+
+#[derive(Args)]
+struct AnalyzeArgs {
+    /// Input file
+    file: String,
+
+    /// Number of highest degree subrelations to report
+    #[arg(long)]
+    #[arg(default_value_t = 10)]
+    hotspots: usize,
+
+    /// Output the report as JSON
+    #[arg(long)]
+    #[arg(default_value_t = false)]
+    json: bool,
+}
+
+fn main() -> Result<ExitCode, io::Error> {
     let args = Cli::parse();
 
-    let file_name = args.file;
+    match args.command {
+        Some(Command::Check(check_args)) => return check(check_args),
+        Some(Command::Analyze(analyze_args)) => {
+            analyze(analyze_args);
+            return Ok(ExitCode::SUCCESS);
+        }
+        None => {}
+    }
+
+    let file_name = args.file.unwrap();
     let name = args.name.unwrap();
     let analyzed: Analyzed<Bn254Field> = analyze_file(Path::new(&file_name));
 
-    analyzed_to_cpp(&analyzed, &name, args.yes);
+    let mode = if args.check {
+        WriteMode::Check
+    } else {
+        WriteMode::Write
+    };
+    let up_to_date = match args.backend {
+        Backend::Cpp => analyzed_to_cpp(&analyzed, &name, mode, args.yes),
+        Backend::Rust => analyzed_to_rust(&analyzed, &name, &args.output_directory, mode, args.yes),
+    };
+
+    Ok(if up_to_date {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn check(args: CheckArgs) -> Result<ExitCode, io::Error> {
+    let analyzed: Analyzed<Bn254Field> = analyze_file(Path::new(&args.file));
+
+    let format = args.format.unwrap_or_else(|| {
+        if args.trace.ends_with(".csv") {
+            TraceFormat::Csv
+        } else {
+            TraceFormat::Binary
+        }
+    });
+    let file = File::open(&args.trace)?;
+    let trace = match format {
+        TraceFormat::Csv => Trace::from_csv(BufReader::new(file))?,
+        TraceFormat::Binary => Trace::from_binary(BufReader::new(file))?,
+    };
+
+    match check_trace(&analyzed, &trace) {
+        Ok(()) => {
+            println!("Trace of {} rows is valid.", trace.num_rows());
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(failure) => {
+            println!("{failure}");
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+fn analyze(args: AnalyzeArgs) {
+    let analyzed: Analyzed<Bn254Field> = analyze_file(Path::new(&args.file));
+    let analysis = analyze_pil(&analyzed);
 
-    Ok(())
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&analysis.to_json(args.hotspots)).unwrap()
+        );
+    } else {
+        print!("{}", analysis.to_report(args.hotspots));
+    }
 }
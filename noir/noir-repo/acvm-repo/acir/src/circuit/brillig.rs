@@ -1,5 +1,6 @@
 use super::opcodes::BlockId;
 use crate::native_types::{Expression, Witness};
+use acir_field::AcirField;
 use brillig::Opcode as BrilligOpcode;
 use serde::{Deserialize, Serialize};
 
@@ -12,6 +13,19 @@ pub enum BrilligInputs<F> {
     MemoryArray(BlockId),
 }
 
+impl<F: AcirField> std::fmt::Display for BrilligInputs<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BrilligInputs::Single(expr) => write!(f, "Single({expr})"),
+            BrilligInputs::Array(exprs) => {
+                let exprs: Vec<_> = exprs.iter().map(ToString::to_string).collect();
+                write!(f, "Array([{}])", exprs.join(", "))
+            }
+            BrilligInputs::MemoryArray(block_id) => write!(f, "MemoryArray({})", block_id.0),
+        }
+    }
+}
+
 /// Outputs for the Brillig VM. Once the VM has completed
 /// execution, this will be the object that is returned.
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
@@ -20,6 +34,21 @@ pub enum BrilligOutputs {
     Array(Vec<Witness>),
 }
 
+impl std::fmt::Display for BrilligOutputs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BrilligOutputs::Simple(witness) => write!(f, "Simple(_{})", witness.witness_index()),
+            BrilligOutputs::Array(witnesses) => {
+                let witnesses: Vec<_> = witnesses
+                    .iter()
+                    .map(|witness| format!("_{}", witness.witness_index()))
+                    .collect();
+                write!(f, "Array([{}])", witnesses.join(", "))
+            }
+        }
+    }
+}
+
 /// This is purely a wrapper struct around a list of Brillig opcode's which represents
 /// a full Brillig function to be executed by the Brillig VM.
 /// This is stored separately on a program and accessed through a [BrilligPointer].
@@ -75,6 +75,15 @@ pub enum ExpressionOrMemory<F> {
     Memory(BlockId),
 }
 
+impl<F: AcirField> std::fmt::Display for ExpressionOrMemory<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExpressionOrMemory::Expression(expr) => write!(f, "Expression({expr})"),
+            ExpressionOrMemory::Memory(block_id) => write!(f, "Memory({})", block_id.0),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AssertionPayload<F> {
     pub error_selector: u64,
@@ -290,9 +299,25 @@ impl<F: for<'a> Deserialize<'a>> Program<F> {
     }
 }
 
-impl<F: AcirField> std::fmt::Display for Circuit<F> {
+/// Adapts a function writing to a formatter into a value which implements [std::fmt::Display].
+struct DisplayWith<W>(W);
+
+impl<W: Fn(&mut std::fmt::Formatter<'_>) -> std::fmt::Result> std::fmt::Display for DisplayWith<W> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self.0)(f)
+    }
+}
+
+impl<F: AcirField> Circuit<F> {
+    /// Returns the textual form of the circuit which, unlike its [Display][std::fmt::Display]
+    /// output, lists every witness so that it can be parsed back into the same circuit.
+    pub fn to_text(&self) -> String {
+        DisplayWith(|f: &mut std::fmt::Formatter<'_>| self.write_text(f, false)).to_string()
+    }
+
+    fn write_text(&self, f: &mut std::fmt::Formatter<'_>, abbreviate: bool) -> std::fmt::Result {
         writeln!(f, "current witness index : {}", self.current_witness_index)?;
+        writeln!(f, "expression width : {:?}", self.expression_width)?;
 
         let write_witness_indices =
             |f: &mut std::fmt::Formatter<'_>, indices: &[u32]| -> Result<(), std::fmt::Error> {
@@ -323,32 +348,65 @@ impl<F: AcirField> std::fmt::Display for Circuit<F> {
         write_witness_indices(f, &self.return_values.indices())?;
 
         for opcode in &self.opcodes {
-            writeln!(f, "{opcode}")?;
+            opcode.write_text(f, abbreviate)?;
+            writeln!(f)?;
+        }
+
+        for (location, assertion_payload) in &self.assert_messages {
+            let payload: Vec<_> =
+                assertion_payload.payload.iter().map(ToString::to_string).collect();
+            writeln!(
+                f,
+                "ASSERTION PAYLOAD {location}: selector: {}, payload: [{}]",
+                assertion_payload.error_selector,
+                payload.join(", ")
+            )?;
         }
         Ok(())
     }
 }
 
+impl<F: AcirField> std::fmt::Display for Circuit<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_text(f, true)
+    }
+}
+
 impl<F: AcirField> std::fmt::Debug for Circuit<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Display::fmt(self, f)
     }
 }
 
-impl<F: AcirField> std::fmt::Display for Program<F> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<F: AcirField> Program<F> {
+    /// Returns the textual form of the program which, unlike its [Display][std::fmt::Display]
+    /// output, lists every witness so that it can be parsed back into the same program.
+    pub fn to_text(&self) -> String {
+        DisplayWith(|f: &mut std::fmt::Formatter<'_>| self.write_text(f, false)).to_string()
+    }
+
+    fn write_text(&self, f: &mut std::fmt::Formatter<'_>, abbreviate: bool) -> std::fmt::Result {
         for (func_index, function) in self.functions.iter().enumerate() {
             writeln!(f, "func {}", func_index)?;
-            writeln!(f, "{}", function)?;
+            function.write_text(f, abbreviate)?;
+            writeln!(f)?;
         }
         for (func_index, function) in self.unconstrained_functions.iter().enumerate() {
             writeln!(f, "unconstrained func {}", func_index)?;
-            writeln!(f, "{:?}", function.bytecode)?;
+            for opcode in &function.bytecode {
+                writeln!(f, "{opcode:?}")?;
+            }
         }
         Ok(())
     }
 }
 
+impl<F: AcirField> std::fmt::Display for Program<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_text(f, true)
+    }
+}
+
 impl<F: AcirField> std::fmt::Debug for Program<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Display::fmt(self, f)
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 mod black_box_function_call;
 mod memory_operation;
 
+use black_box_function_call::get_list_string;
 pub use black_box_function_call::{
     BlackBoxFuncCall, ConstantOrWitnessEnum, FunctionInput, InvalidInputBitSize,
 };
@@ -128,8 +129,14 @@ pub enum Opcode<F> {
     },
 }
 
-impl<F: AcirField> std::fmt::Display for Opcode<F> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<F: AcirField> Opcode<F> {
+    /// Writes the opcode in the textual format, abbreviating long lists of witnesses if `abbreviate`
+    /// is set, in which case it can no longer be parsed back.
+    pub(crate) fn write_text(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        abbreviate: bool,
+    ) -> std::fmt::Result {
         match self {
             Opcode::AssertZero(expr) => {
                 write!(f, "EXPR [ ")?;
@@ -144,7 +151,7 @@ impl<F: AcirField> std::fmt::Display for Opcode<F> {
                 write!(f, " ]")
             }
 
-            Opcode::BlackBoxFuncCall(g) => write!(f, "{g}"),
+            Opcode::BlackBoxFuncCall(g) => g.write_text(f, abbreviate),
             Opcode::MemoryOp { block_id, op, predicate } => {
                 write!(f, "MEM ")?;
                 if let Some(pred) = predicate {
@@ -158,7 +165,11 @@ impl<F: AcirField> std::fmt::Display for Opcode<F> {
                 } else if is_write {
                     write!(f, "(id: {}, write {} at: {}) ", block_id.0, op.value, op.index)
                 } else {
-                    write!(f, "(id: {}, op {} at: {}) ", block_id.0, op.operation, op.index)
+                    write!(
+                        f,
+                        "(id: {}, op {} at: {}, value: {}) ",
+                        block_id.0, op.operation, op.index, op.value
+                    )
                 }
             }
             Opcode::MemoryInit { block_id, init, block_type: databus } => {
@@ -167,7 +178,14 @@ impl<F: AcirField> std::fmt::Display for Opcode<F> {
                     BlockType::CallData(id) => write!(f, "INIT CALLDATA {} ", id)?,
                     BlockType::ReturnData => write!(f, "INIT RETURNDATA ")?,
                 }
-                write!(f, "(id: {}, len: {}) ", block_id.0, init.len())
+                let witnesses = init.iter().map(|witness| format!("_{}", witness.witness_index()));
+                write!(
+                    f,
+                    "(id: {}, len: {}, witnesses: {}) ",
+                    block_id.0,
+                    init.len(),
+                    get_list_string(witnesses.collect(), abbreviate)
+                )
             }
             // We keep the display for a BrilligCall and circuit Call separate as they
             // are distinct in their functionality and we should maintain this separation for debugging.
@@ -176,21 +194,37 @@ impl<F: AcirField> std::fmt::Display for Opcode<F> {
                 if let Some(pred) = predicate {
                     writeln!(f, "PREDICATE = {pred}")?;
                 }
-                write!(f, "inputs: {:?}, ", inputs)?;
-                write!(f, "outputs: {:?}", outputs)
+                let inputs: Vec<_> = inputs.iter().map(ToString::to_string).collect();
+                let outputs: Vec<_> = outputs.iter().map(ToString::to_string).collect();
+                write!(f, "inputs: [{}], ", inputs.join(", "))?;
+                write!(f, "outputs: [{}]", outputs.join(", "))
             }
             Opcode::Call { id, inputs, outputs, predicate } => {
                 write!(f, "CALL func {}: ", id)?;
                 if let Some(pred) = predicate {
                     writeln!(f, "PREDICATE = {pred}")?;
                 }
-                write!(f, "inputs: {:?}, ", inputs)?;
-                write!(f, "outputs: {:?}", outputs)
+                write!(f, "inputs: ")?;
+                write_witnesses(f, inputs)?;
+                write!(f, ", outputs: ")?;
+                write_witnesses(f, outputs)
             }
         }
     }
 }
 
+impl<F: AcirField> std::fmt::Display for Opcode<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_text(f, true)
+    }
+}
+
+fn write_witnesses(f: &mut std::fmt::Formatter<'_>, witnesses: &[Witness]) -> std::fmt::Result {
+    let witnesses: Vec<_> =
+        witnesses.iter().map(|witness| format!("_{}", witness.witness_index())).collect();
+    write!(f, "[{}]", witnesses.join(", "))
+}
+
 impl<F: AcirField> std::fmt::Debug for Opcode<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Display::fmt(self, f)
@@ -73,8 +73,12 @@ impl<F: AcirField> FunctionInput<F> {
 impl<F: std::fmt::Display> std::fmt::Display for FunctionInput<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.input {
-            ConstantOrWitnessEnum::Constant(constant) => write!(f, "{constant}"),
-            ConstantOrWitnessEnum::Witness(witness) => write!(f, "{}", witness.0),
+            ConstantOrWitnessEnum::Constant(constant) => {
+                write!(f, "({constant}, {})", self.num_bits)
+            }
+            ConstantOrWitnessEnum::Witness(witness) => {
+                write!(f, "(_{}, {})", witness.witness_index(), self.num_bits)
+            }
         }
     }
 }
@@ -405,78 +409,173 @@ impl<F: Copy> BlackBoxFuncCall<F> {
 
 const ABBREVIATION_LIMIT: usize = 5;
 
-fn get_inputs_string<F: std::fmt::Display>(inputs: &[FunctionInput<F>]) -> String {
+/// Writes a list of items, abbreviating it when requested.
+pub(super) fn get_list_string(items: Vec<String>, abbreviate: bool) -> String {
     // Once a vectors length gets above this limit,
     // instead of listing all of their elements, we use ellipses
     // to abbreviate them
-    let should_abbreviate_inputs = inputs.len() <= ABBREVIATION_LIMIT;
-
-    if should_abbreviate_inputs {
-        let mut result = String::new();
-        for (index, inp) in inputs.iter().enumerate() {
-            result += &format!("({})", inp);
-            // Add a comma, unless it is the last entry
-            if index != inputs.len() - 1 {
-                result += ", ";
-            }
-        }
-        result
+    if abbreviate && items.len() > ABBREVIATION_LIMIT {
+        format!("[{}...{}]", items[0], items[items.len() - 1])
     } else {
-        let first = inputs.first().unwrap();
-        let last = inputs.last().unwrap();
+        format!("[{}]", items.join(", "))
+    }
+}
 
-        let mut result = String::new();
-        result += &format!("({})...({})", first, last,);
+fn get_inputs_string<F: std::fmt::Display>(
+    inputs: &[FunctionInput<F>],
+    abbreviate: bool,
+) -> String {
+    get_list_string(inputs.iter().map(ToString::to_string).collect(), abbreviate)
+}
 
-        result
-    }
+fn get_outputs_string(outputs: &[Witness], abbreviate: bool) -> String {
+    let outputs = outputs.iter().map(|output| format!("_{}", output.witness_index())).collect();
+    get_list_string(outputs, abbreviate)
 }
 
-fn get_outputs_string(outputs: &[Witness]) -> String {
-    let should_abbreviate_outputs = outputs.len() <= ABBREVIATION_LIMIT;
+fn get_output_tuple_string(outputs: &(Witness, Witness, Witness)) -> String {
+    format!(
+        "(_{}, _{}, _{})",
+        outputs.0.witness_index(),
+        outputs.1.witness_index(),
+        outputs.2.witness_index()
+    )
+}
 
-    if should_abbreviate_outputs {
-        let mut result = String::new();
-        for (index, output) in outputs.iter().enumerate() {
-            result += &format!("_{}", output.witness_index());
-            // Add a comma, unless it is the last entry
-            if index != outputs.len() - 1 {
-                result += ", ";
+impl<F: std::fmt::Display + Copy> BlackBoxFuncCall<F> {
+    /// Writes the call as its name followed by each of its fields, in declaration order, so that
+    /// the textual form can be parsed back into the same call unless `abbreviate` is set.
+    pub(crate) fn write_text(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        abbreviate: bool,
+    ) -> std::fmt::Result {
+        let uppercase_name = self.name().to_uppercase();
+        write!(f, "BLACKBOX::{uppercase_name} ")?;
+
+        match self {
+            BlackBoxFuncCall::AES128Encrypt { inputs, iv, key, outputs } => write!(
+                f,
+                "inputs: {}, iv: {}, key: {}, outputs: {}",
+                get_inputs_string(inputs, abbreviate),
+                get_inputs_string(iv.as_slice(), abbreviate),
+                get_inputs_string(key.as_slice(), abbreviate),
+                get_outputs_string(outputs, abbreviate)
+            ),
+            BlackBoxFuncCall::AND { lhs, rhs, output }
+            | BlackBoxFuncCall::XOR { lhs, rhs, output } => {
+                write!(f, "lhs: {lhs}, rhs: {rhs}, output: _{}", output.witness_index())
             }
+            BlackBoxFuncCall::RANGE { input } => write!(f, "input: {input}"),
+            BlackBoxFuncCall::Blake2s { inputs, outputs }
+            | BlackBoxFuncCall::Blake3 { inputs, outputs } => write!(
+                f,
+                "inputs: {}, outputs: {}",
+                get_inputs_string(inputs, abbreviate),
+                get_outputs_string(outputs.as_slice(), abbreviate)
+            ),
+            BlackBoxFuncCall::SchnorrVerify {
+                public_key_x,
+                public_key_y,
+                signature,
+                message,
+                output,
+            } => write!(
+                f,
+                "public_key_x: {public_key_x}, public_key_y: {public_key_y}, signature: {}, message: {}, output: _{}",
+                get_inputs_string(signature.as_slice(), abbreviate),
+                get_inputs_string(message, abbreviate),
+                output.witness_index()
+            ),
+            BlackBoxFuncCall::EcdsaSecp256k1 {
+                public_key_x,
+                public_key_y,
+                signature,
+                hashed_message,
+                output,
+            }
+            | BlackBoxFuncCall::EcdsaSecp256r1 {
+                public_key_x,
+                public_key_y,
+                signature,
+                hashed_message,
+                output,
+            } => write!(
+                f,
+                "public_key_x: {}, public_key_y: {}, signature: {}, hashed_message: {}, output: _{}",
+                get_inputs_string(public_key_x.as_slice(), abbreviate),
+                get_inputs_string(public_key_y.as_slice(), abbreviate),
+                get_inputs_string(signature.as_slice(), abbreviate),
+                get_inputs_string(hashed_message.as_slice(), abbreviate),
+                output.witness_index()
+            ),
+            BlackBoxFuncCall::MultiScalarMul { points, scalars, outputs } => write!(
+                f,
+                "points: {}, scalars: {}, outputs: {}",
+                get_inputs_string(points, abbreviate),
+                get_inputs_string(scalars, abbreviate),
+                get_output_tuple_string(outputs)
+            ),
+            BlackBoxFuncCall::EmbeddedCurveAdd { input1, input2, outputs } => write!(
+                f,
+                "input1: {}, input2: {}, outputs: {}",
+                get_inputs_string(input1.as_slice(), abbreviate),
+                get_inputs_string(input2.as_slice(), abbreviate),
+                get_output_tuple_string(outputs)
+            ),
+            BlackBoxFuncCall::Keccakf1600 { inputs, outputs } => write!(
+                f,
+                "inputs: {}, outputs: {}",
+                get_inputs_string(inputs.as_slice(), abbreviate),
+                get_outputs_string(outputs.as_slice(), abbreviate)
+            ),
+            BlackBoxFuncCall::RecursiveAggregation {
+                verification_key,
+                proof,
+                public_inputs,
+                key_hash,
+                proof_type,
+            } => write!(
+                f,
+                "verification_key: {}, proof: {}, public_inputs: {}, key_hash: {key_hash}, proof_type: {proof_type}",
+                get_inputs_string(verification_key, abbreviate),
+                get_inputs_string(proof, abbreviate),
+                get_inputs_string(public_inputs, abbreviate)
+            ),
+            BlackBoxFuncCall::BigIntAdd { lhs, rhs, output }
+            | BlackBoxFuncCall::BigIntSub { lhs, rhs, output }
+            | BlackBoxFuncCall::BigIntMul { lhs, rhs, output }
+            | BlackBoxFuncCall::BigIntDiv { lhs, rhs, output } => {
+                write!(f, "lhs: {lhs}, rhs: {rhs}, output: {output}")
+            }
+            BlackBoxFuncCall::BigIntFromLeBytes { inputs, modulus, output } => write!(
+                f,
+                "inputs: {}, modulus: {modulus:?}, output: {output}",
+                get_inputs_string(inputs, abbreviate)
+            ),
+            BlackBoxFuncCall::BigIntToLeBytes { input, outputs } => {
+                write!(f, "input: {input}, outputs: {}", get_outputs_string(outputs, abbreviate))
+            }
+            BlackBoxFuncCall::Poseidon2Permutation { inputs, outputs, len } => write!(
+                f,
+                "inputs: {}, outputs: {}, len: {len}",
+                get_inputs_string(inputs, abbreviate),
+                get_outputs_string(outputs, abbreviate)
+            ),
+            BlackBoxFuncCall::Sha256Compression { inputs, hash_values, outputs } => write!(
+                f,
+                "inputs: {}, hash_values: {}, outputs: {}",
+                get_inputs_string(inputs.as_slice(), abbreviate),
+                get_inputs_string(hash_values.as_slice(), abbreviate),
+                get_outputs_string(outputs.as_slice(), abbreviate)
+            ),
         }
-        result
-    } else {
-        let first = outputs.first().unwrap();
-        let last = outputs.last().unwrap();
-
-        let mut result = String::new();
-        result += &format!("(_{},...,_{})", first.witness_index(), last.witness_index());
-        result
     }
 }
 
 impl<F: std::fmt::Display + Copy> std::fmt::Display for BlackBoxFuncCall<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let uppercase_name = self.name().to_uppercase();
-        write!(f, "BLACKBOX::{uppercase_name} ")?;
-        // INPUTS
-        write!(f, "[")?;
-
-        let inputs_str = get_inputs_string(&self.get_inputs_vec());
-
-        write!(f, "{inputs_str}")?;
-        write!(f, "] ")?;
-
-        // OUTPUTS
-        write!(f, "[ ")?;
-
-        let outputs_str = get_outputs_string(&self.get_outputs_vec());
-
-        write!(f, "{outputs_str}")?;
-
-        write!(f, "]")?;
-
-        write!(f, "")
+        self.write_text(f, true)
     }
 }
 
@@ -42,6 +42,9 @@ impl<F> WitnessMap<F> {
     pub fn insert(&mut self, key: Witness, value: F) -> Option<F> {
         self.0.insert(key, value)
     }
+    pub fn remove(&mut self, key: &Witness) -> Option<F> {
+        self.0.remove(key)
+    }
 }
 
 impl<F> Index<&Witness> for WitnessMap<F> {
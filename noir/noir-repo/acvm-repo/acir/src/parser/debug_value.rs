@@ -0,0 +1,293 @@
+//! Brillig bytecode is written in its derived `Debug` representation, e.g.
+//! `Const { destination: Direct(0), bit_size: Integer(U32), value: 1 }`.
+//!
+//! Rather than parsing every Brillig opcode by hand, the text is parsed into a generic [DebugValue]
+//! which is then fed through the opcodes' `Deserialize` implementations.
+
+use std::marker::PhantomData;
+
+use acir_field::AcirField;
+use serde::de::{
+    self, value::StringDeserializer, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess,
+    SeqAccess, VariantAccess, Visitor,
+};
+
+use super::parse_field;
+
+/// A value written using Rust's derived `Debug` syntax.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum DebugValue {
+    /// A unit struct or variant, or a boolean.
+    Ident(String),
+    /// An integer literal which may be negated, as field elements are displayed with their sign.
+    Int {
+        negative: bool,
+        digits: String,
+    },
+    Str(String),
+    List(Vec<DebugValue>),
+    Tuple(Vec<DebugValue>),
+    TupleStruct(String, Vec<DebugValue>),
+    Struct(String, Vec<(String, DebugValue)>),
+}
+
+#[derive(Debug)]
+pub(super) struct DebugValueError(pub(super) String);
+
+impl std::fmt::Display for DebugValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for DebugValueError {}
+
+impl de::Error for DebugValueError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        DebugValueError(msg.to_string())
+    }
+}
+
+/// Deserializes a [DebugValue] into any type deriving `Deserialize`.
+///
+/// Field elements are displayed in decimal whereas `F`'s `Deserialize` implementation expects the
+/// hexadecimal string written by its `Serialize` implementation, so integer literals requested as
+/// strings are converted into `F` and re-encoded as hex.
+pub(super) struct DebugValueDeserializer<F> {
+    value: DebugValue,
+    _field: PhantomData<F>,
+}
+
+impl<F> DebugValueDeserializer<F> {
+    pub(super) fn new(value: DebugValue) -> Self {
+        DebugValueDeserializer { value, _field: PhantomData }
+    }
+
+    fn unexpected(&self, expected: &str) -> DebugValueError {
+        DebugValueError(format!("expected {expected}, found {:?}", self.value))
+    }
+}
+
+impl<'de, F: AcirField> de::Deserializer<'de> for DebugValueDeserializer<F> {
+    type Error = DebugValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            DebugValue::Ident(ident) if ident == "true" => visitor.visit_bool(true),
+            DebugValue::Ident(ident) if ident == "false" => visitor.visit_bool(false),
+            DebugValue::Ident(ident) => visitor.visit_string(ident),
+            DebugValue::Int { negative: false, ref digits } => {
+                let int = digits.parse().map_err(|_| self.unexpected("an unsigned integer"))?;
+                visitor.visit_u64(int)
+            }
+            DebugValue::Str(string) => visitor.visit_string(string),
+            DebugValue::List(values) | DebugValue::Tuple(values) => {
+                visitor.visit_seq(SeqDeserializer::<F>::new(values))
+            }
+            _ => Err(self.unexpected("a value")),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            DebugValue::Int { negative, digits } => {
+                let field: F = parse_field(negative, &digits)
+                    .ok_or_else(|| DebugValueError(format!("invalid field element `{digits}`")))?;
+                visitor.visit_string(field.to_hex())
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            DebugValue::Ident(ident) if ident == "None" => visitor.visit_none(),
+            DebugValue::TupleStruct(name, mut values) if name == "Some" && values.len() == 1 => {
+                visitor.visit_some(DebugValueDeserializer::<F>::new(values.remove(0)))
+            }
+            _ => Err(self.unexpected("an option")),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value {
+            DebugValue::TupleStruct(_, mut values) if values.len() == 1 => {
+                visitor.visit_newtype_struct(DebugValueDeserializer::<F>::new(values.remove(0)))
+            }
+            _ => visitor.visit_newtype_struct(self),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value {
+            DebugValue::Struct(_, fields) => visitor.visit_map(MapDeserializer::<F>::new(fields)),
+            DebugValue::TupleStruct(_, values) => {
+                visitor.visit_seq(SeqDeserializer::<F>::new(values))
+            }
+            _ => Err(self.unexpected("a struct")),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value {
+            DebugValue::Ident(_) | DebugValue::TupleStruct(..) | DebugValue::Struct(..) => {
+                visitor.visit_enum(self)
+            }
+            _ => Err(self.unexpected("an enum variant")),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char bytes byte_buf
+        unit unit_struct seq tuple tuple_struct map identifier ignored_any
+    }
+}
+
+impl<'de, F: AcirField> EnumAccess<'de> for DebugValueDeserializer<F> {
+    type Error = DebugValueError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let name = match &self.value {
+            DebugValue::Ident(name)
+            | DebugValue::TupleStruct(name, _)
+            | DebugValue::Struct(name, _) => name.clone(),
+            _ => return Err(self.unexpected("an enum variant")),
+        };
+        let deserializer: StringDeserializer<DebugValueError> = name.into_deserializer();
+        Ok((seed.deserialize(deserializer)?, self))
+    }
+}
+
+impl<'de, F: AcirField> VariantAccess<'de> for DebugValueDeserializer<F> {
+    type Error = DebugValueError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.value {
+            DebugValue::Ident(_) => Ok(()),
+            _ => Err(self.unexpected("a unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        match self.value {
+            DebugValue::TupleStruct(_, mut values) if values.len() == 1 => {
+                seed.deserialize(DebugValueDeserializer::<F>::new(values.remove(0)))
+            }
+            _ => Err(self.unexpected("a newtype variant")),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value {
+            DebugValue::TupleStruct(_, values) => {
+                visitor.visit_seq(SeqDeserializer::<F>::new(values))
+            }
+            _ => Err(self.unexpected("a tuple variant")),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value {
+            DebugValue::Struct(_, fields) => visitor.visit_map(MapDeserializer::<F>::new(fields)),
+            _ => Err(self.unexpected("a struct variant")),
+        }
+    }
+}
+
+struct SeqDeserializer<F> {
+    values: std::vec::IntoIter<DebugValue>,
+    _field: PhantomData<F>,
+}
+
+impl<F> SeqDeserializer<F> {
+    fn new(values: Vec<DebugValue>) -> Self {
+        SeqDeserializer { values: values.into_iter(), _field: PhantomData }
+    }
+}
+
+impl<'de, F: AcirField> SeqAccess<'de> for SeqDeserializer<F> {
+    type Error = DebugValueError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        self.values
+            .next()
+            .map(|value| seed.deserialize(DebugValueDeserializer::<F>::new(value)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+struct MapDeserializer<F> {
+    fields: std::vec::IntoIter<(String, DebugValue)>,
+    value: Option<DebugValue>,
+    _field: PhantomData<F>,
+}
+
+impl<F> MapDeserializer<F> {
+    fn new(fields: Vec<(String, DebugValue)>) -> Self {
+        MapDeserializer { fields: fields.into_iter(), value: None, _field: PhantomData }
+    }
+}
+
+impl<'de, F: AcirField> MapAccess<'de> for MapDeserializer<F> {
+    type Error = DebugValueError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((key, value)) = self.fields.next() else {
+            return Ok(None);
+        };
+        self.value = Some(value);
+        let deserializer: StringDeserializer<DebugValueError> = key.into_deserializer();
+        seed.deserialize(deserializer).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let value = self.value.take().expect("value is always taken after its key");
+        seed.deserialize(DebugValueDeserializer::<F>::new(value))
+    }
+}
@@ -0,0 +1,168 @@
+use std::{iter::Peekable, str::Chars};
+
+use super::ParserError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Token {
+    Ident(String),
+    /// An unsigned integer literal, either decimal or `0x` prefixed hexadecimal.
+    Int(String),
+    Str(String),
+    Punct(char),
+    Eof,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "`{ident}`"),
+            Token::Int(int) => write!(f, "`{int}`"),
+            Token::Str(string) => write!(f, "{string:?}"),
+            Token::Punct(punct) => write!(f, "`{punct}`"),
+            Token::Eof => write!(f, "end of input"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Position {
+    pub(super) line: usize,
+    pub(super) column: usize,
+}
+
+const PUNCTUATION: &[char] = &['[', ']', '(', ')', '{', '}', ',', ':', '=', '%', '.', '-'];
+
+/// Splits textual ACIR into tokens. Whitespace (including newlines) is insignificant
+/// and `//` starts a comment which runs until the end of the line.
+pub(super) fn tokenize(source: &str) -> Result<Vec<(Token, Position)>, ParserError> {
+    let mut lexer =
+        Lexer { chars: source.chars().peekable(), position: Position { line: 1, column: 1 } };
+    let mut tokens = Vec::new();
+    loop {
+        let (token, position) = lexer.next_token()?;
+        let is_eof = token == Token::Eof;
+        tokens.push((token, position));
+        if is_eof {
+            return Ok(tokens);
+        }
+    }
+}
+
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+    position: Position,
+}
+
+impl<'a> Lexer<'a> {
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let char = self.chars.next();
+        if char == Some('\n') {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        }
+        char
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let mut string = String::new();
+        while let Some(char) = self.peek().filter(|char| predicate(*char)) {
+            string.push(char);
+            self.advance();
+        }
+        string
+    }
+
+    fn next_token(&mut self) -> Result<(Token, Position), ParserError> {
+        loop {
+            let start = self.position;
+            let Some(char) = self.peek() else {
+                return Ok((Token::Eof, start));
+            };
+
+            let token = if char.is_whitespace() {
+                self.advance();
+                continue;
+            } else if char == '/' {
+                self.advance();
+                if self.peek() != Some('/') {
+                    return Err(ParserError::new(start, "unexpected character `/`"));
+                }
+                self.take_while(|char| char != '\n');
+                continue;
+            } else if char.is_ascii_alphabetic() || char == '_' {
+                Token::Ident(self.take_while(|char| char.is_alphanumeric() || char == '_'))
+            } else if char.is_ascii_digit() {
+                let int = self.take_while(|char| char.is_ascii_alphanumeric());
+                let is_valid = match int.strip_prefix("0x") {
+                    Some(hex) => {
+                        !hex.is_empty() && hex.chars().all(|char| char.is_ascii_hexdigit())
+                    }
+                    None => int.chars().all(|char| char.is_ascii_digit()),
+                };
+                if !is_valid {
+                    return Err(ParserError::new(
+                        start,
+                        format!("invalid integer literal `{int}`"),
+                    ));
+                }
+                Token::Int(int)
+            } else if char == '"' {
+                self.advance();
+                Token::Str(self.string_literal(start)?)
+            } else if PUNCTUATION.contains(&char) {
+                self.advance();
+                Token::Punct(char)
+            } else {
+                return Err(ParserError::new(start, format!("unexpected character `{char}`")));
+            };
+            return Ok((token, start));
+        }
+    }
+
+    /// Reads the remainder of a string literal, undoing the escaping applied by `{:?}`.
+    fn string_literal(&mut self, start: Position) -> Result<String, ParserError> {
+        let unterminated = || ParserError::new(start, "unterminated string literal");
+        let mut string = String::new();
+        loop {
+            match self.advance().ok_or_else(unterminated)? {
+                '"' => return Ok(string),
+                '\\' => {
+                    let escape = self.position;
+                    let escaped = match self.advance().ok_or_else(unterminated)? {
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        '0' => '\0',
+                        char @ ('\\' | '"' | '\'') => char,
+                        'u' if self.advance() == Some('{') => {
+                            let hex = self.take_while(|char| char != '}');
+                            self.advance().ok_or_else(unterminated)?;
+                            u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32).ok_or_else(
+                                || {
+                                    ParserError::new(
+                                        escape,
+                                        format!("invalid unicode escape `{hex}`"),
+                                    )
+                                },
+                            )?
+                        }
+                        char => {
+                            return Err(ParserError::new(
+                                escape,
+                                format!("unknown escape sequence `\\{char}`"),
+                            ))
+                        }
+                    };
+                    string.push(escaped);
+                }
+                char => string.push(char),
+            }
+        }
+    }
+}
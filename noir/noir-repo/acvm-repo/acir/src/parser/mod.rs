@@ -0,0 +1,1184 @@
+//! Parser for the textual representation of ACIR produced by [Program::to_text] and
+//! [Circuit::to_text], so that programs can be written and checked in as readable text.
+//!
+//! See the "Textual format" section of the crate README for a description of the syntax.
+
+use std::{collections::BTreeSet, str::FromStr};
+
+use acir_field::AcirField;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    circuit::{
+        brillig::{BrilligBytecode, BrilligFunctionId, BrilligInputs, BrilligOutputs},
+        opcodes::{AcirFunctionId, BlackBoxFuncCall, BlockId, BlockType, FunctionInput, MemOp},
+        AssertionPayload, Circuit, ExpressionOrMemory, ExpressionWidth, Opcode, OpcodeLocation,
+        Program, PublicInputs,
+    },
+    native_types::{Expression, Witness},
+    BlackBoxFunc,
+};
+
+mod debug_value;
+mod lexer;
+
+use debug_value::{DebugValue, DebugValueDeserializer};
+use lexer::{tokenize, Position, Token};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{message} at line {line}, column {column}")]
+pub struct ParserError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl ParserError {
+    fn new(position: Position, message: impl Into<String>) -> Self {
+        ParserError { message: message.into(), line: position.line, column: position.column }
+    }
+}
+
+impl<F: AcirField> FromStr for Circuit<F> {
+    type Err = ParserError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(source)?;
+        let circuit = parser.parse_circuit()?;
+        parser.expect_eof()?;
+        Ok(circuit)
+    }
+}
+
+impl<F: AcirField + for<'a> Deserialize<'a>> FromStr for Program<F> {
+    type Err = ParserError;
+
+    /// Parses a program, or a single circuit which is then treated as the program's only function.
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(source)?;
+        let program = parser.parse_program()?;
+        parser.expect_eof()?;
+        Ok(program)
+    }
+}
+
+/// Parses a possibly negated decimal or hexadecimal integer literal into a field element.
+fn parse_field<F: AcirField>(negative: bool, digits: &str) -> Option<F> {
+    let value = match digits.strip_prefix("0x") {
+        Some(hex) => F::from_hex(hex)?,
+        None => {
+            let ten = F::from(10_u128);
+            digits.chars().try_fold(F::zero(), |value, digit| {
+                Some(value * ten + F::from(digit.to_digit(10)? as u128))
+            })?
+        }
+    };
+    Some(if negative { -value } else { value })
+}
+
+struct Parser {
+    tokens: Vec<(Token, Position)>,
+    index: usize,
+    /// The highest witness index seen in the circuit currently being parsed.
+    max_witness_index: u32,
+}
+
+impl Parser {
+    fn new(source: &str) -> Result<Self, ParserError> {
+        Ok(Parser { tokens: tokenize(source)?, index: 0, max_witness_index: 0 })
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index].0
+    }
+
+    fn position(&self) -> Position {
+        self.tokens[self.index].1
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.index].0.clone();
+        if token != Token::Eof {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn expected<T>(&self, expected: &str) -> Result<T, ParserError> {
+        Err(ParserError::new(
+            self.position(),
+            format!("expected {expected}, found {}", self.peek()),
+        ))
+    }
+
+    fn expect_eof(&self) -> Result<(), ParserError> {
+        match self.peek() {
+            Token::Eof => Ok(()),
+            _ => self.expected("end of input"),
+        }
+    }
+
+    fn is_ident(&self, ident: &str) -> bool {
+        matches!(self.peek(), Token::Ident(token) if token == ident)
+    }
+
+    fn eat_ident(&mut self, ident: &str) -> bool {
+        let is_ident = self.is_ident(ident);
+        if is_ident {
+            self.next();
+        }
+        is_ident
+    }
+
+    fn expect_ident(&mut self, ident: &str) -> Result<(), ParserError> {
+        if self.eat_ident(ident) {
+            Ok(())
+        } else {
+            self.expected(&format!("`{ident}`"))
+        }
+    }
+
+    fn expect_keywords(&mut self, keywords: &[&str]) -> Result<(), ParserError> {
+        keywords.iter().try_for_each(|keyword| self.expect_ident(keyword))
+    }
+
+    fn ident(&mut self) -> Result<String, ParserError> {
+        match self.peek() {
+            Token::Ident(_) => match self.next() {
+                Token::Ident(ident) => Ok(ident),
+                _ => unreachable!(),
+            },
+            _ => self.expected("an identifier"),
+        }
+    }
+
+    fn eat_punct(&mut self, punct: char) -> bool {
+        let is_punct = self.peek() == &Token::Punct(punct);
+        if is_punct {
+            self.next();
+        }
+        is_punct
+    }
+
+    fn expect_punct(&mut self, punct: char) -> Result<(), ParserError> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            self.expected(&format!("`{punct}`"))
+        }
+    }
+
+    /// Parses `name:`, optionally preceded by the comma separating it from the previous field.
+    fn field_name(&mut self, name: &str) -> Result<(), ParserError> {
+        self.eat_punct(',');
+        self.expect_ident(name)?;
+        self.expect_punct(':')
+    }
+
+    fn list<T>(
+        &mut self,
+        open: char,
+        close: char,
+        mut item: impl FnMut(&mut Self) -> Result<T, ParserError>,
+    ) -> Result<Vec<T>, ParserError> {
+        self.expect_punct(open)?;
+        let mut items = Vec::new();
+        while !self.eat_punct(close) {
+            items.push(item(self)?);
+            if !self.eat_punct(',') {
+                self.expect_punct(close)?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn int<T: FromStr>(&mut self) -> Result<T, ParserError> {
+        let position = self.position();
+        match self.peek() {
+            Token::Int(int) if !int.starts_with("0x") => {
+                let int = int.parse().map_err(|_| {
+                    ParserError::new(position, format!("integer `{int}` is out of range"))
+                })?;
+                self.next();
+                Ok(int)
+            }
+            _ => self.expected("an integer"),
+        }
+    }
+
+    fn field<F: AcirField>(&mut self) -> Result<F, ParserError> {
+        let position = self.position();
+        let negative = self.eat_punct('-');
+        match self.next() {
+            Token::Int(digits) => parse_field(negative, &digits).ok_or_else(|| {
+                ParserError::new(position, format!("invalid field element `{digits}`"))
+            }),
+            _ => Err(ParserError::new(position, "expected a field element")),
+        }
+    }
+
+    /// Parses a witness written as `_1`. The `x1` form used when displaying expressions is also accepted.
+    fn witness(&mut self) -> Result<Witness, ParserError> {
+        let index = match self.peek() {
+            Token::Ident(ident) => ident
+                .strip_prefix('_')
+                .or_else(|| ident.strip_prefix('x'))
+                .and_then(|index| index.parse::<u32>().ok()),
+            _ => None,
+        };
+        match index {
+            Some(index) => {
+                self.next();
+                self.max_witness_index = self.max_witness_index.max(index);
+                Ok(Witness(index))
+            }
+            None => self.expected("a witness"),
+        }
+    }
+
+    fn witnesses(&mut self) -> Result<Vec<Witness>, ParserError> {
+        self.list('[', ']', Self::witness)
+    }
+
+    fn witness_indices(&mut self) -> Result<BTreeSet<Witness>, ParserError> {
+        let indices = self.list('[', ']', Self::int::<u32>)?;
+        Ok(indices
+            .into_iter()
+            .map(|index| {
+                self.max_witness_index = self.max_witness_index.max(index);
+                Witness(index)
+            })
+            .collect())
+    }
+
+    fn parse_program<F: AcirField + for<'a> Deserialize<'a>>(
+        &mut self,
+    ) -> Result<Program<F>, ParserError> {
+        let mut functions = Vec::new();
+        if self.is_ident("func") {
+            while self.eat_ident("func") {
+                self.function_index(functions.len())?;
+                functions.push(self.parse_circuit()?);
+            }
+        } else {
+            functions.push(self.parse_circuit()?);
+        }
+
+        let mut unconstrained_functions = Vec::new();
+        while self.eat_ident("unconstrained") {
+            self.expect_ident("func")?;
+            self.function_index(unconstrained_functions.len())?;
+            let mut bytecode = Vec::new();
+            while !matches!(self.peek(), Token::Eof) && !self.is_ident("unconstrained") {
+                bytecode.push(self.deserialize_debug_value::<F, _>()?);
+            }
+            unconstrained_functions.push(BrilligBytecode { bytecode });
+        }
+
+        Ok(Program { functions, unconstrained_functions })
+    }
+
+    /// Functions are listed in order, so their index must match their position.
+    fn function_index(&mut self, expected: usize) -> Result<(), ParserError> {
+        let position = self.position();
+        let index: usize = self.int()?;
+        if index != expected {
+            return Err(ParserError::new(
+                position,
+                format!("expected function {expected} but found function {index}"),
+            ));
+        }
+        Ok(())
+    }
+
+    fn parse_circuit<F: AcirField>(&mut self) -> Result<Circuit<F>, ParserError> {
+        self.max_witness_index = 0;
+
+        let current_witness_index = if self.eat_ident("current") {
+            self.expect_keywords(&["witness", "index"])?;
+            self.expect_punct(':')?;
+            Some(self.int()?)
+        } else {
+            None
+        };
+
+        let expression_width = if self.eat_ident("expression") {
+            self.expect_ident("width")?;
+            self.expect_punct(':')?;
+            self.deserialize_debug_value::<F, ExpressionWidth>()?
+        } else {
+            ExpressionWidth::default()
+        };
+
+        let parameters = |parser: &mut Self, keywords: &[&str]| {
+            if parser.is_ident(keywords[0]) {
+                parser.expect_keywords(keywords)?;
+                parser.expect_punct(':')?;
+                parser.witness_indices()
+            } else {
+                Ok(BTreeSet::new())
+            }
+        };
+        let private_parameters = parameters(self, &["private", "parameters", "indices"])?;
+        let public_parameters = parameters(self, &["public", "parameters", "indices"])?;
+        let return_values = parameters(self, &["return", "value", "indices"])?;
+
+        let mut opcodes = Vec::new();
+        while !matches!(self.peek(), Token::Eof)
+            && !["func", "unconstrained", "ASSERTION"].iter().any(|ident| self.is_ident(ident))
+        {
+            opcodes.push(self.parse_opcode()?);
+        }
+
+        let mut assert_messages = Vec::new();
+        while self.eat_ident("ASSERTION") {
+            assert_messages.push(self.parse_assertion_payload()?);
+        }
+
+        Ok(Circuit {
+            current_witness_index: current_witness_index.unwrap_or(self.max_witness_index),
+            opcodes,
+            expression_width,
+            private_parameters,
+            public_parameters: PublicInputs(public_parameters),
+            return_values: PublicInputs(return_values),
+            assert_messages,
+        })
+    }
+
+    fn parse_assertion_payload<F: AcirField>(
+        &mut self,
+    ) -> Result<(OpcodeLocation, AssertionPayload<F>), ParserError> {
+        self.expect_ident("PAYLOAD")?;
+        let acir_index = self.int()?;
+        let location = if self.eat_punct('.') {
+            OpcodeLocation::Brillig { acir_index, brillig_index: self.int()? }
+        } else {
+            OpcodeLocation::Acir(acir_index)
+        };
+        self.expect_punct(':')?;
+        self.field_name("selector")?;
+        let error_selector = self.int()?;
+        self.field_name("payload")?;
+        let payload = self.list('[', ']', |parser| {
+            let variant = parser.ident()?;
+            parser.expect_punct('(')?;
+            let item = match variant.as_str() {
+                "Expression" => ExpressionOrMemory::Expression(parser.operand()?),
+                "Memory" => ExpressionOrMemory::Memory(BlockId(parser.int()?)),
+                _ => {
+                    return Err(ParserError::new(
+                        parser.position(),
+                        "expected `Expression` or `Memory`",
+                    ))
+                }
+            };
+            parser.expect_punct(')')?;
+            Ok(item)
+        })?;
+        Ok((location, AssertionPayload { error_selector, payload }))
+    }
+
+    fn parse_opcode<F: AcirField>(&mut self) -> Result<Opcode<F>, ParserError> {
+        let position = self.position();
+        match self.ident()?.as_str() {
+            "EXPR" => Ok(Opcode::AssertZero(self.expression()?)),
+            "BLACKBOX" => Ok(Opcode::BlackBoxFuncCall(self.black_box_call()?)),
+            "MEM" => self.memory_op(),
+            "INIT" => self.memory_init(),
+            "BRILLIG" => self.brillig_call(),
+            "CALL" => self.call(),
+            opcode => Err(ParserError::new(position, format!("unknown opcode `{opcode}`"))),
+        }
+    }
+
+    /// Parses the `[ (q_m, _a, _b) ... (q_l, _c) ... q_c ]` body of an `EXPR`.
+    fn expression<F: AcirField>(&mut self) -> Result<Expression<F>, ParserError> {
+        self.expect_punct('[')?;
+        let mut expression = Expression::default();
+        while self.eat_punct('(') {
+            let coefficient = self.field()?;
+            self.expect_punct(',')?;
+            let lhs = self.witness()?;
+            if self.eat_punct(',') {
+                let rhs = self.witness()?;
+                expression.push_multiplication_term(coefficient, lhs, rhs);
+            } else {
+                expression.push_addition_term(coefficient, lhs);
+            }
+            self.expect_punct(')')?;
+        }
+        expression.q_c = self.field()?;
+        self.expect_punct(']')?;
+        Ok(expression)
+    }
+
+    /// Parses an expression used as an operand, written either as a witness or as `%EXPR [ ... ]%`.
+    fn operand<F: AcirField>(&mut self) -> Result<Expression<F>, ParserError> {
+        if self.eat_punct('%') {
+            self.expect_ident("EXPR")?;
+            let expression = self.expression()?;
+            self.expect_punct('%')?;
+            Ok(expression)
+        } else {
+            Ok(self.witness()?.into())
+        }
+    }
+
+    fn predicate<F: AcirField>(&mut self) -> Result<Option<Expression<F>>, ParserError> {
+        if self.eat_ident("PREDICATE") {
+            self.expect_punct('=')?;
+            Ok(Some(self.operand()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn memory_op<F: AcirField>(&mut self) -> Result<Opcode<F>, ParserError> {
+        let predicate = self.predicate()?;
+        self.expect_punct('(')?;
+        self.field_name("id")?;
+        let block_id = BlockId(self.int()?);
+        self.expect_punct(',')?;
+        let op = if self.eat_ident("read") {
+            self.field_name("at")?;
+            let index = self.operand()?;
+            self.field_name("value")?;
+            MemOp { operation: Expression::zero(), index, value: self.operand()? }
+        } else if self.eat_ident("write") {
+            let value = self.operand()?;
+            self.field_name("at")?;
+            MemOp { operation: Expression::one(), index: self.operand()?, value }
+        } else if self.eat_ident("op") {
+            let operation = self.operand()?;
+            self.field_name("at")?;
+            let index = self.operand()?;
+            self.field_name("value")?;
+            MemOp { operation, index, value: self.operand()? }
+        } else {
+            return self.expected("`read`, `write` or `op`");
+        };
+        self.expect_punct(')')?;
+        Ok(Opcode::MemoryOp { block_id, op, predicate })
+    }
+
+    fn memory_init<F: AcirField>(&mut self) -> Result<Opcode<F>, ParserError> {
+        let block_type = if self.eat_ident("CALLDATA") {
+            BlockType::CallData(self.int()?)
+        } else if self.eat_ident("RETURNDATA") {
+            BlockType::ReturnData
+        } else {
+            BlockType::Memory
+        };
+        self.expect_punct('(')?;
+        self.field_name("id")?;
+        let block_id = BlockId(self.int()?);
+        self.field_name("len")?;
+        let len_position = self.position();
+        let len: usize = self.int()?;
+        self.field_name("witnesses")?;
+        let init = self.witnesses()?;
+        if init.len() != len {
+            return Err(ParserError::new(
+                len_position,
+                format!("memory block has length {len} but {} witnesses", init.len()),
+            ));
+        }
+        self.expect_punct(')')?;
+        Ok(Opcode::MemoryInit { block_id, init, block_type })
+    }
+
+    fn brillig_call<F: AcirField>(&mut self) -> Result<Opcode<F>, ParserError> {
+        self.expect_keywords(&["CALL", "func"])?;
+        let id = BrilligFunctionId(self.int()?);
+        self.expect_punct(':')?;
+        let predicate = self.predicate()?;
+        self.field_name("inputs")?;
+        let inputs = self.list('[', ']', |parser| {
+            let position = parser.position();
+            let input = match parser.ident()?.as_str() {
+                "Single" => {
+                    parser.expect_punct('(')?;
+                    BrilligInputs::Single(parser.operand()?)
+                }
+                "Array" => {
+                    parser.expect_punct('(')?;
+                    BrilligInputs::Array(parser.list('[', ']', Self::operand)?)
+                }
+                "MemoryArray" => {
+                    parser.expect_punct('(')?;
+                    BrilligInputs::MemoryArray(BlockId(parser.int()?))
+                }
+                _ => return Err(ParserError::new(position, "expected a Brillig input")),
+            };
+            parser.expect_punct(')')?;
+            Ok(input)
+        })?;
+        self.field_name("outputs")?;
+        let outputs = self.list('[', ']', |parser| {
+            let position = parser.position();
+            let output = match parser.ident()?.as_str() {
+                "Simple" => {
+                    parser.expect_punct('(')?;
+                    BrilligOutputs::Simple(parser.witness()?)
+                }
+                "Array" => {
+                    parser.expect_punct('(')?;
+                    BrilligOutputs::Array(parser.witnesses()?)
+                }
+                _ => return Err(ParserError::new(position, "expected a Brillig output")),
+            };
+            parser.expect_punct(')')?;
+            Ok(output)
+        })?;
+        Ok(Opcode::BrilligCall { id, inputs, outputs, predicate })
+    }
+
+    fn call<F: AcirField>(&mut self) -> Result<Opcode<F>, ParserError> {
+        self.expect_ident("func")?;
+        let id = AcirFunctionId(self.int()?);
+        self.expect_punct(':')?;
+        let predicate = self.predicate()?;
+        self.field_name("inputs")?;
+        let inputs = self.witnesses()?;
+        self.field_name("outputs")?;
+        let outputs = self.witnesses()?;
+        Ok(Opcode::Call { id, inputs, outputs, predicate })
+    }
+
+    /// Parses a black box function input written as `(_1, 8)` for a witness or `(5, 8)` for a constant.
+    fn function_input<F: AcirField>(&mut self) -> Result<FunctionInput<F>, ParserError> {
+        self.expect_punct('(')?;
+        let position = self.position();
+        let input = if matches!(self.peek(), Token::Ident(_)) {
+            let witness = self.witness()?;
+            self.expect_punct(',')?;
+            FunctionInput::witness(witness, self.int()?)
+        } else {
+            let constant = self.field()?;
+            self.expect_punct(',')?;
+            FunctionInput::constant(constant, self.int()?)
+                .map_err(|error| ParserError::new(position, error.to_string()))?
+        };
+        self.expect_punct(')')?;
+        Ok(input)
+    }
+
+    fn function_inputs<F: AcirField>(&mut self) -> Result<Vec<FunctionInput<F>>, ParserError> {
+        self.list('[', ']', Self::function_input)
+    }
+
+    fn function_input_array<F: AcirField, const N: usize>(
+        &mut self,
+    ) -> Result<Box<[FunctionInput<F>; N]>, ParserError> {
+        let position = self.position();
+        let inputs = self.function_inputs()?;
+        fixed_length(position, inputs)
+    }
+
+    fn witness_array<const N: usize>(&mut self) -> Result<Box<[Witness; N]>, ParserError> {
+        let position = self.position();
+        let witnesses = self.witnesses()?;
+        fixed_length(position, witnesses)
+    }
+
+    fn witness_triple(&mut self) -> Result<(Witness, Witness, Witness), ParserError> {
+        self.expect_punct('(')?;
+        let first = self.witness()?;
+        self.expect_punct(',')?;
+        let second = self.witness()?;
+        self.expect_punct(',')?;
+        let third = self.witness()?;
+        self.expect_punct(')')?;
+        Ok((first, second, third))
+    }
+
+    /// Parses the `::NAME field: value, ...` remainder of a `BLACKBOX` opcode.
+    fn black_box_call<F: AcirField>(&mut self) -> Result<BlackBoxFuncCall<F>, ParserError> {
+        self.expect_punct(':')?;
+        self.expect_punct(':')?;
+        let position = self.position();
+        let name = self.ident()?;
+        let Some(func) = BlackBoxFunc::lookup(&name.to_lowercase()) else {
+            return Err(ParserError::new(position, format!("unknown black box function `{name}`")));
+        };
+
+        let call = match func {
+            BlackBoxFunc::AES128Encrypt => {
+                self.field_name("inputs")?;
+                let inputs = self.function_inputs()?;
+                self.field_name("iv")?;
+                let iv = self.function_input_array()?;
+                self.field_name("key")?;
+                let key = self.function_input_array()?;
+                self.field_name("outputs")?;
+                BlackBoxFuncCall::AES128Encrypt { inputs, iv, key, outputs: self.witnesses()? }
+            }
+            BlackBoxFunc::AND | BlackBoxFunc::XOR => {
+                self.field_name("lhs")?;
+                let lhs = self.function_input()?;
+                self.field_name("rhs")?;
+                let rhs = self.function_input()?;
+                self.field_name("output")?;
+                let output = self.witness()?;
+                if func == BlackBoxFunc::AND {
+                    BlackBoxFuncCall::AND { lhs, rhs, output }
+                } else {
+                    BlackBoxFuncCall::XOR { lhs, rhs, output }
+                }
+            }
+            BlackBoxFunc::RANGE => {
+                self.field_name("input")?;
+                BlackBoxFuncCall::RANGE { input: self.function_input()? }
+            }
+            BlackBoxFunc::Blake2s | BlackBoxFunc::Blake3 => {
+                self.field_name("inputs")?;
+                let inputs = self.function_inputs()?;
+                self.field_name("outputs")?;
+                let outputs = self.witness_array()?;
+                if func == BlackBoxFunc::Blake2s {
+                    BlackBoxFuncCall::Blake2s { inputs, outputs }
+                } else {
+                    BlackBoxFuncCall::Blake3 { inputs, outputs }
+                }
+            }
+            BlackBoxFunc::SchnorrVerify => {
+                self.field_name("public_key_x")?;
+                let public_key_x = self.function_input()?;
+                self.field_name("public_key_y")?;
+                let public_key_y = self.function_input()?;
+                self.field_name("signature")?;
+                let signature = self.function_input_array()?;
+                self.field_name("message")?;
+                let message = self.function_inputs()?;
+                self.field_name("output")?;
+                let output = self.witness()?;
+                BlackBoxFuncCall::SchnorrVerify {
+                    public_key_x,
+                    public_key_y,
+                    signature,
+                    message,
+                    output,
+                }
+            }
+            BlackBoxFunc::EcdsaSecp256k1 | BlackBoxFunc::EcdsaSecp256r1 => {
+                self.field_name("public_key_x")?;
+                let public_key_x = self.function_input_array()?;
+                self.field_name("public_key_y")?;
+                let public_key_y = self.function_input_array()?;
+                self.field_name("signature")?;
+                let signature = self.function_input_array()?;
+                self.field_name("hashed_message")?;
+                let hashed_message = self.function_input_array()?;
+                self.field_name("output")?;
+                let output = self.witness()?;
+                if func == BlackBoxFunc::EcdsaSecp256k1 {
+                    BlackBoxFuncCall::EcdsaSecp256k1 {
+                        public_key_x,
+                        public_key_y,
+                        signature,
+                        hashed_message,
+                        output,
+                    }
+                } else {
+                    BlackBoxFuncCall::EcdsaSecp256r1 {
+                        public_key_x,
+                        public_key_y,
+                        signature,
+                        hashed_message,
+                        output,
+                    }
+                }
+            }
+            BlackBoxFunc::MultiScalarMul => {
+                self.field_name("points")?;
+                let points = self.function_inputs()?;
+                self.field_name("scalars")?;
+                let scalars = self.function_inputs()?;
+                self.field_name("outputs")?;
+                BlackBoxFuncCall::MultiScalarMul {
+                    points,
+                    scalars,
+                    outputs: self.witness_triple()?,
+                }
+            }
+            BlackBoxFunc::EmbeddedCurveAdd => {
+                self.field_name("input1")?;
+                let input1 = self.function_input_array()?;
+                self.field_name("input2")?;
+                let input2 = self.function_input_array()?;
+                self.field_name("outputs")?;
+                BlackBoxFuncCall::EmbeddedCurveAdd {
+                    input1,
+                    input2,
+                    outputs: self.witness_triple()?,
+                }
+            }
+            BlackBoxFunc::Keccakf1600 => {
+                self.field_name("inputs")?;
+                let inputs = self.function_input_array()?;
+                self.field_name("outputs")?;
+                BlackBoxFuncCall::Keccakf1600 { inputs, outputs: self.witness_array()? }
+            }
+            BlackBoxFunc::RecursiveAggregation => {
+                self.field_name("verification_key")?;
+                let verification_key = self.function_inputs()?;
+                self.field_name("proof")?;
+                let proof = self.function_inputs()?;
+                self.field_name("public_inputs")?;
+                let public_inputs = self.function_inputs()?;
+                self.field_name("key_hash")?;
+                let key_hash = self.function_input()?;
+                self.field_name("proof_type")?;
+                BlackBoxFuncCall::RecursiveAggregation {
+                    verification_key,
+                    proof,
+                    public_inputs,
+                    key_hash,
+                    proof_type: self.int()?,
+                }
+            }
+            BlackBoxFunc::BigIntAdd
+            | BlackBoxFunc::BigIntSub
+            | BlackBoxFunc::BigIntMul
+            | BlackBoxFunc::BigIntDiv => {
+                self.field_name("lhs")?;
+                let lhs = self.int()?;
+                self.field_name("rhs")?;
+                let rhs = self.int()?;
+                self.field_name("output")?;
+                let output = self.int()?;
+                match func {
+                    BlackBoxFunc::BigIntAdd => BlackBoxFuncCall::BigIntAdd { lhs, rhs, output },
+                    BlackBoxFunc::BigIntSub => BlackBoxFuncCall::BigIntSub { lhs, rhs, output },
+                    BlackBoxFunc::BigIntMul => BlackBoxFuncCall::BigIntMul { lhs, rhs, output },
+                    _ => BlackBoxFuncCall::BigIntDiv { lhs, rhs, output },
+                }
+            }
+            BlackBoxFunc::BigIntFromLeBytes => {
+                self.field_name("inputs")?;
+                let inputs = self.function_inputs()?;
+                self.field_name("modulus")?;
+                let modulus = self.list('[', ']', Self::int::<u8>)?;
+                self.field_name("output")?;
+                BlackBoxFuncCall::BigIntFromLeBytes { inputs, modulus, output: self.int()? }
+            }
+            BlackBoxFunc::BigIntToLeBytes => {
+                self.field_name("input")?;
+                let input = self.int()?;
+                self.field_name("outputs")?;
+                BlackBoxFuncCall::BigIntToLeBytes { input, outputs: self.witnesses()? }
+            }
+            BlackBoxFunc::Poseidon2Permutation => {
+                self.field_name("inputs")?;
+                let inputs = self.function_inputs()?;
+                self.field_name("outputs")?;
+                let outputs = self.witnesses()?;
+                self.field_name("len")?;
+                BlackBoxFuncCall::Poseidon2Permutation { inputs, outputs, len: self.int()? }
+            }
+            BlackBoxFunc::Sha256Compression => {
+                self.field_name("inputs")?;
+                let inputs = self.function_input_array()?;
+                self.field_name("hash_values")?;
+                let hash_values = self.function_input_array()?;
+                self.field_name("outputs")?;
+                BlackBoxFuncCall::Sha256Compression {
+                    inputs,
+                    hash_values,
+                    outputs: self.witness_array()?,
+                }
+            }
+        };
+        Ok(call)
+    }
+
+    fn debug_value(&mut self) -> Result<DebugValue, ParserError> {
+        match self.peek() {
+            Token::Punct('[') => {
+                return Ok(DebugValue::List(self.list('[', ']', Self::debug_value)?))
+            }
+            Token::Punct('(') => {
+                return Ok(DebugValue::Tuple(self.list('(', ')', Self::debug_value)?))
+            }
+            _ => (),
+        }
+
+        let position = self.position();
+        match self.next() {
+            Token::Ident(name) => {
+                if self.peek() == &Token::Punct('(') {
+                    Ok(DebugValue::TupleStruct(name, self.list('(', ')', Self::debug_value)?))
+                } else if self.peek() == &Token::Punct('{') {
+                    let fields = self.list('{', '}', |parser| {
+                        let name = parser.ident()?;
+                        parser.expect_punct(':')?;
+                        Ok((name, parser.debug_value()?))
+                    })?;
+                    Ok(DebugValue::Struct(name, fields))
+                } else {
+                    Ok(DebugValue::Ident(name))
+                }
+            }
+            Token::Punct('-') => match self.next() {
+                Token::Int(digits) => Ok(DebugValue::Int { negative: true, digits }),
+                _ => Err(ParserError::new(position, "expected an integer after `-`")),
+            },
+            Token::Int(digits) => Ok(DebugValue::Int { negative: false, digits }),
+            Token::Str(string) => Ok(DebugValue::Str(string)),
+            token => Err(ParserError::new(position, format!("expected a value, found {token}"))),
+        }
+    }
+
+    /// Parses a value written in its derived `Debug` syntax and deserializes it into `T`.
+    fn deserialize_debug_value<F: AcirField, T: for<'a> Deserialize<'a>>(
+        &mut self,
+    ) -> Result<T, ParserError> {
+        let position = self.position();
+        let value = self.debug_value()?;
+        T::deserialize(DebugValueDeserializer::<F>::new(value))
+            .map_err(|error| ParserError::new(position, error.0))
+    }
+}
+
+fn fixed_length<T, const N: usize>(
+    position: Position,
+    items: Vec<T>,
+) -> Result<Box<[T; N]>, ParserError> {
+    let len = items.len();
+    items
+        .into_boxed_slice()
+        .try_into()
+        .map_err(|_| ParserError::new(position, format!("expected {N} elements but found {len}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use acir_field::{AcirField, FieldElement};
+    use brillig::{
+        BinaryIntOp, BitSize, BlackBoxOp, HeapArray, HeapValueType, HeapVector, IntegerBitSize,
+        MemoryAddress, Opcode as BrilligOpcode, ValueOrArray,
+    };
+
+    use crate::{
+        circuit::{
+            brillig::{BrilligBytecode, BrilligFunctionId, BrilligInputs, BrilligOutputs},
+            opcodes::{AcirFunctionId, BlackBoxFuncCall, BlockId, BlockType, FunctionInput, MemOp},
+            AssertionPayload, Circuit, ExpressionOrMemory, ExpressionWidth, Opcode, OpcodeLocation,
+            Program, PublicInputs,
+        },
+        native_types::{Expression, Witness},
+    };
+
+    fn witness_inputs<const N: usize>(start: u32) -> Box<[FunctionInput<FieldElement>; N]> {
+        Box::new(std::array::from_fn(|i| FunctionInput::witness(Witness(start + i as u32), 8)))
+    }
+
+    fn witnesses<const N: usize>(start: u32) -> Box<[Witness; N]> {
+        Box::new(std::array::from_fn(|i| Witness(start + i as u32)))
+    }
+
+    fn black_box_calls() -> Vec<BlackBoxFuncCall<FieldElement>> {
+        let input = FunctionInput::witness(Witness(1), 8);
+        let constant = FunctionInput::constant(-FieldElement::one(), 254).unwrap();
+        vec![
+            BlackBoxFuncCall::AES128Encrypt {
+                inputs: vec![input, constant],
+                iv: witness_inputs(2),
+                key: witness_inputs(18),
+                outputs: vec![Witness(40), Witness(41)],
+            },
+            BlackBoxFuncCall::AND { lhs: input, rhs: constant, output: Witness(2) },
+            BlackBoxFuncCall::XOR { lhs: constant, rhs: input, output: Witness(2) },
+            BlackBoxFuncCall::RANGE { input },
+            BlackBoxFuncCall::Blake2s { inputs: vec![input], outputs: witnesses(2) },
+            BlackBoxFuncCall::Blake3 { inputs: vec![], outputs: witnesses(2) },
+            BlackBoxFuncCall::SchnorrVerify {
+                public_key_x: input,
+                public_key_y: constant,
+                signature: witness_inputs(2),
+                message: vec![input],
+                output: Witness(70),
+            },
+            BlackBoxFuncCall::EcdsaSecp256k1 {
+                public_key_x: witness_inputs(1),
+                public_key_y: witness_inputs(33),
+                signature: witness_inputs(65),
+                hashed_message: witness_inputs(129),
+                output: Witness(161),
+            },
+            BlackBoxFuncCall::EcdsaSecp256r1 {
+                public_key_x: witness_inputs(1),
+                public_key_y: witness_inputs(33),
+                signature: witness_inputs(65),
+                hashed_message: witness_inputs(129),
+                output: Witness(161),
+            },
+            BlackBoxFuncCall::MultiScalarMul {
+                points: vec![input, input, constant],
+                scalars: vec![input, input],
+                outputs: (Witness(2), Witness(3), Witness(4)),
+            },
+            BlackBoxFuncCall::EmbeddedCurveAdd {
+                input1: witness_inputs(1),
+                input2: witness_inputs(4),
+                outputs: (Witness(7), Witness(8), Witness(9)),
+            },
+            BlackBoxFuncCall::Keccakf1600 { inputs: witness_inputs(1), outputs: witnesses(26) },
+            BlackBoxFuncCall::RecursiveAggregation {
+                verification_key: vec![input],
+                proof: vec![input, input],
+                public_inputs: vec![],
+                key_hash: constant,
+                proof_type: 1,
+            },
+            BlackBoxFuncCall::BigIntAdd { lhs: 0, rhs: 1, output: 2 },
+            BlackBoxFuncCall::BigIntSub { lhs: 0, rhs: 1, output: 2 },
+            BlackBoxFuncCall::BigIntMul { lhs: 0, rhs: 1, output: 2 },
+            BlackBoxFuncCall::BigIntDiv { lhs: 0, rhs: 1, output: 2 },
+            BlackBoxFuncCall::BigIntFromLeBytes {
+                inputs: vec![input],
+                modulus: vec![1, 255],
+                output: 3,
+            },
+            BlackBoxFuncCall::BigIntToLeBytes { input: 3, outputs: vec![Witness(2)] },
+            BlackBoxFuncCall::Poseidon2Permutation {
+                inputs: vec![input, constant],
+                outputs: vec![Witness(2), Witness(3)],
+                len: 2,
+            },
+            BlackBoxFuncCall::Sha256Compression {
+                inputs: witness_inputs(1),
+                hash_values: witness_inputs(17),
+                outputs: witnesses(25),
+            },
+        ]
+    }
+
+    fn main_circuit() -> Circuit<FieldElement> {
+        let x = Witness(1);
+        let y = Witness(2);
+        let z = Witness(3);
+        let mut product = Expression::default();
+        product.push_multiplication_term(FieldElement::from(2_u128), x, y);
+        product.push_addition_term(-FieldElement::one(), z);
+        product.q_c = FieldElement::from(5_u128);
+
+        let mut opcodes = vec![
+            Opcode::AssertZero(product.clone()),
+            Opcode::MemoryInit {
+                block_id: BlockId(0),
+                init: vec![x, y],
+                block_type: BlockType::Memory,
+            },
+            Opcode::MemoryInit {
+                block_id: BlockId(1),
+                init: vec![z],
+                block_type: BlockType::CallData(2),
+            },
+            Opcode::MemoryInit {
+                block_id: BlockId(2),
+                init: vec![],
+                block_type: BlockType::ReturnData,
+            },
+            Opcode::MemoryOp {
+                block_id: BlockId(0),
+                op: MemOp::read_at_mem_index(Expression::one(), z),
+                predicate: None,
+            },
+            Opcode::MemoryOp {
+                block_id: BlockId(0),
+                op: MemOp::write_to_mem_index(x.into(), product.clone()),
+                predicate: Some(y.into()),
+            },
+            Opcode::MemoryOp {
+                block_id: BlockId(0),
+                op: MemOp { operation: z.into(), index: Expression::zero(), value: x.into() },
+                predicate: None,
+            },
+            Opcode::BrilligCall {
+                id: BrilligFunctionId(0),
+                inputs: vec![
+                    BrilligInputs::Single(product),
+                    BrilligInputs::Array(vec![x.into(), Expression::one()]),
+                    BrilligInputs::MemoryArray(BlockId(0)),
+                ],
+                outputs: vec![
+                    BrilligOutputs::Simple(Witness(4)),
+                    BrilligOutputs::Array(vec![Witness(5), Witness(6)]),
+                ],
+                predicate: Some(Expression::one()),
+            },
+            Opcode::Call {
+                id: AcirFunctionId(1),
+                inputs: vec![x, y],
+                outputs: vec![Witness(7)],
+                predicate: None,
+            },
+        ];
+        opcodes.extend(black_box_calls().into_iter().map(Opcode::BlackBoxFuncCall));
+
+        Circuit {
+            current_witness_index: 200,
+            opcodes,
+            expression_width: ExpressionWidth::Bounded { width: 4 },
+            private_parameters: BTreeSet::from([x]),
+            public_parameters: PublicInputs(BTreeSet::from([y])),
+            return_values: PublicInputs(BTreeSet::from([z])),
+            assert_messages: vec![
+                (
+                    OpcodeLocation::Acir(0),
+                    AssertionPayload {
+                        error_selector: 1234,
+                        payload: vec![
+                            ExpressionOrMemory::Expression(x.into()),
+                            ExpressionOrMemory::Memory(BlockId(0)),
+                        ],
+                    },
+                ),
+                (
+                    OpcodeLocation::Brillig { acir_index: 7, brillig_index: 2 },
+                    AssertionPayload { error_selector: 5, payload: vec![] },
+                ),
+            ],
+        }
+    }
+
+    fn brillig_bytecode() -> BrilligBytecode<FieldElement> {
+        BrilligBytecode {
+            bytecode: vec![
+                BrilligOpcode::Const {
+                    destination: MemoryAddress::direct(0),
+                    bit_size: BitSize::Field,
+                    value: -FieldElement::from(3_u128),
+                },
+                BrilligOpcode::BinaryIntOp {
+                    destination: MemoryAddress::relative(1),
+                    op: BinaryIntOp::Add,
+                    bit_size: IntegerBitSize::U32,
+                    lhs: MemoryAddress::direct(0),
+                    rhs: MemoryAddress::relative(2),
+                },
+                BrilligOpcode::ForeignCall {
+                    function: "print \"value\"\n".into(),
+                    destinations: vec![ValueOrArray::HeapVector(HeapVector {
+                        pointer: MemoryAddress::direct(3),
+                        size: MemoryAddress::direct(4),
+                    })],
+                    destination_value_types: vec![HeapValueType::Vector {
+                        value_types: vec![HeapValueType::field()],
+                    }],
+                    inputs: vec![ValueOrArray::MemoryAddress(MemoryAddress::direct(0))],
+                    input_value_types: vec![HeapValueType::Array {
+                        value_types: vec![HeapValueType::Simple(BitSize::Integer(
+                            IntegerBitSize::U1,
+                        ))],
+                        size: 2,
+                    }],
+                },
+                BrilligOpcode::BlackBox(BlackBoxOp::ToRadix {
+                    input: MemoryAddress::direct(0),
+                    radix: MemoryAddress::direct(1),
+                    output: HeapArray { pointer: MemoryAddress::direct(2), size: 8 },
+                    output_bits: true,
+                }),
+                BrilligOpcode::Return,
+                BrilligOpcode::Stop {
+                    return_data: HeapVector {
+                        pointer: MemoryAddress::direct(0),
+                        size: MemoryAddress::direct(1),
+                    },
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn program_round_trips_through_text() {
+        let callee = Circuit {
+            current_witness_index: 3,
+            opcodes: vec![Opcode::AssertZero(Expression::zero())],
+            ..Circuit::default()
+        };
+        let program = Program {
+            functions: vec![main_circuit(), callee],
+            unconstrained_functions: vec![brillig_bytecode(), BrilligBytecode::default()],
+        };
+
+        let text = program.to_text();
+        let parsed: Program<FieldElement> = text.parse().unwrap_or_else(|error| panic!("{error}"));
+        assert_eq!(parsed, program);
+        assert_eq!(parsed.to_text(), text);
+    }
+
+    #[test]
+    fn circuit_round_trips_through_text() {
+        let circuit = main_circuit();
+        let parsed: Circuit<FieldElement> = circuit.to_text().parse().unwrap();
+        assert_eq!(parsed, circuit);
+    }
+
+    #[test]
+    fn display_abbreviates_long_lists_of_witnesses() {
+        let circuit: Circuit<FieldElement> = Circuit {
+            current_witness_index: 6,
+            opcodes: vec![Opcode::MemoryInit {
+                block_id: BlockId(0),
+                init: (1..=6).map(Witness).collect(),
+                block_type: BlockType::Memory,
+            }],
+            ..Circuit::default()
+        };
+
+        assert!(circuit.to_string().contains("(id: 0, len: 6, witnesses: [_1..._6])"));
+        assert!(circuit.to_text().contains("(id: 0, len: 6, witnesses: [_1, _2, _3, _4, _5, _6])"));
+    }
+
+    #[test]
+    fn parses_hand_written_circuit() {
+        let source = "
+            // x * y - z = 0
+            private parameters indices : [1, 2]
+            return value indices : [3]
+            EXPR [ (1, _1, _2) (-1, _3) 0 ]
+            BLACKBOX::RANGE input: (_3, 0x08)
+        ";
+        let result = source.parse::<Program<FieldElement>>();
+        let error = result.expect_err("hexadecimal bit sizes should be rejected");
+        assert_eq!((error.line, error.column), (6, 41));
+
+        let program: Program<FieldElement> =
+            source.replace("0x08", "8").parse().unwrap_or_else(|error| panic!("{error}"));
+        let circuit = &program.functions[0];
+        assert_eq!(circuit.current_witness_index, 3);
+        assert_eq!(circuit.expression_width, ExpressionWidth::Unbounded);
+        assert_eq!(circuit.private_parameters, BTreeSet::from([Witness(1), Witness(2)]));
+        assert!(circuit.public_parameters.0.is_empty());
+        assert_eq!(circuit.return_values.indices(), vec![3]);
+        assert_eq!(
+            circuit.opcodes[1],
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+                input: FunctionInput::witness(Witness(3), 8)
+            })
+        );
+    }
+
+    #[test]
+    fn reports_errors_with_positions() {
+        let error = "EXPR [ (1, _1) 0 ]\nMEM (id: 0, read at: _1)"
+            .parse::<Circuit<FieldElement>>()
+            .unwrap_err();
+        assert_eq!(error.to_string(), "expected `value`, found `)` at line 2, column 24");
+
+        let error =
+            "INIT (id: 0, len: 2, witnesses: [_1])".parse::<Circuit<FieldElement>>().unwrap_err();
+        assert_eq!(error.message, "memory block has length 2 but 1 witnesses");
+
+        let error = "func 1\nEXPR [ 0 ]".parse::<Program<FieldElement>>().unwrap_err();
+        assert_eq!(error.message, "expected function 0 but found function 1");
+    }
+}
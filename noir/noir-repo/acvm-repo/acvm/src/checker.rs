@@ -0,0 +1,522 @@
+//! Checks that a [`WitnessStack`] satisfies the constraints of an ACIR [`Program`].
+//!
+//! Unlike the [ACVM][crate::pwg::ACVM], which solves for unknown witnesses, the checker expects
+//! every witness to already be assigned and only verifies the assignments against each opcode.
+//! This allows validating witnesses produced by other tools before handing them to a prover.
+//!
+//! Brillig calls are not checked as their outputs are unconstrained by definition.
+use std::collections::{BTreeMap, HashMap};
+
+use acir::{
+    circuit::{
+        opcodes::{AcirFunctionId, BlockId, MemOp},
+        Circuit, Opcode, OpcodeLocation, Program, ResolvedOpcodeLocation,
+    },
+    native_types::{Expression, Witness, WitnessMap, WitnessStack},
+    AcirField,
+};
+use thiserror::Error;
+
+use crate::{
+    pwg::{
+        blackbox::{self, bigint::AcvmBigIntSolver},
+        get_value, is_predicate_false,
+        memory_op::MemoryOpSolver,
+        witness_to_value, ErrorLocation, OpcodeNotSolvable, OpcodeResolutionError,
+    },
+    BlackBoxFunctionSolver,
+};
+
+/// An opcode which is not satisfied by the witness.
+#[derive(Clone, Debug)]
+pub struct ConstraintViolation<F> {
+    /// The location of the violated opcode, preceded by the locations of the calls leading to it.
+    pub call_stack: Vec<ResolvedOpcodeLocation>,
+    pub kind: ViolationKind<F>,
+}
+
+impl<F> ConstraintViolation<F> {
+    /// Returns the location of the violated opcode.
+    pub fn location(&self) -> ResolvedOpcodeLocation {
+        *self.call_stack.last().expect("the call stack always contains the violated opcode")
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum ViolationKind<F> {
+    #[error("Expression evaluates to {0} rather than zero")]
+    NonZeroExpression(F),
+    #[error("Call input {index} does not match the initial witness of the called function")]
+    CallInputMismatch { index: usize },
+    #[error("Call output {index} does not match the return value of the called function")]
+    CallOutputMismatch { index: usize },
+    #[error("Memory index {index} is out of bounds for a block of {block_len} elements")]
+    MemoryIndexOutOfBounds { index: F, block_len: u32 },
+    #[error(transparent)]
+    Unsatisfied(#[from] OpcodeResolutionError<F>),
+}
+
+/// Errors raised when the witnesses on the stack do not match the calls made by the program,
+/// in which case the opcodes cannot be checked.
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum WitnessStackMismatch {
+    #[error("The witness stack is empty")]
+    EmptyStack,
+    #[error("Expected a witness for function {expected} but found one for function {found}")]
+    UnexpectedFunction { expected: AcirFunctionId, found: u32 },
+    #[error("No witness was provided for the call to function {0}")]
+    MissingCallWitness(AcirFunctionId),
+    #[error("The program has no function {0}")]
+    UnknownFunction(AcirFunctionId),
+    #[error("{0} witnesses on the stack do not belong to any call made by the program")]
+    UnusedWitnesses(usize),
+}
+
+/// Checks the opcodes of every function executed by `program` against the `witness_stack`.
+///
+/// The witness stack is expected to be laid out as produced by executing the program, i.e. the
+/// witness of each called function is pushed once the call completes and the witness of `main`
+/// is pushed last.
+///
+/// Returns the violated opcodes in execution order, stopping at the first one unless
+/// `find_all` is set.
+pub fn check_witness_stack<F: AcirField>(
+    program: &Program<F>,
+    witness_stack: &WitnessStack<F>,
+    backend: &impl BlackBoxFunctionSolver<F>,
+    find_all: bool,
+) -> Result<Vec<ConstraintViolation<F>>, WitnessStackMismatch> {
+    // Popping the stack visits each caller before its callees, with the callees in reverse order.
+    let mut stack = witness_stack.clone();
+    let main_id = AcirFunctionId(0);
+    let main = stack.pop().ok_or(WitnessStackMismatch::EmptyStack)?;
+    if main.index != main_id.0 {
+        return Err(WitnessStackMismatch::UnexpectedFunction {
+            expected: main_id,
+            found: main.index,
+        });
+    }
+    let main = CallFrame::new(program, main_id, main.witness, &mut stack)?;
+    if stack.length() != 0 {
+        return Err(WitnessStackMismatch::UnusedWitnesses(stack.length()));
+    }
+
+    let mut checker = Checker { program, backend, find_all, violations: Vec::new() };
+    checker.check_frame(main, &mut Vec::new());
+    Ok(checker.violations)
+}
+
+/// The witness of a single function execution, along with the executions of the functions it calls.
+struct CallFrame<F> {
+    function: AcirFunctionId,
+    witness: WitnessMap<F>,
+    /// The frames of the `Call` opcodes which were executed, keyed by their opcode index.
+    calls: BTreeMap<usize, CallFrame<F>>,
+}
+
+impl<F: AcirField> CallFrame<F> {
+    fn new(
+        program: &Program<F>,
+        function: AcirFunctionId,
+        witness: WitnessMap<F>,
+        stack: &mut WitnessStack<F>,
+    ) -> Result<Self, WitnessStackMismatch> {
+        let circuit = program
+            .functions
+            .get(function.as_usize())
+            .ok_or(WitnessStackMismatch::UnknownFunction(function))?;
+
+        // Calls with a false predicate are skipped and do not push a witness. Calls whose predicate
+        // cannot be evaluated are reported as violations when their opcode is checked.
+        let executed_calls: Vec<_> = circuit
+            .opcodes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, opcode)| match opcode {
+                Opcode::Call { id, predicate, .. } if *id != AcirFunctionId(0) => {
+                    (is_predicate_false(&witness, predicate) == Ok(false)).then_some((index, *id))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let mut calls = BTreeMap::new();
+        for (index, id) in executed_calls.into_iter().rev() {
+            let item = stack.pop().ok_or(WitnessStackMismatch::MissingCallWitness(id))?;
+            if item.index != id.0 {
+                return Err(WitnessStackMismatch::UnexpectedFunction {
+                    expected: id,
+                    found: item.index,
+                });
+            }
+            calls.insert(index, CallFrame::new(program, id, item.witness, stack)?);
+        }
+
+        Ok(CallFrame { function, witness, calls })
+    }
+}
+
+struct Checker<'a, F, B> {
+    program: &'a Program<F>,
+    backend: &'a B,
+    find_all: bool,
+    violations: Vec<ConstraintViolation<F>>,
+}
+
+impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> Checker<'a, F, B> {
+    fn is_done(&self) -> bool {
+        !self.find_all && !self.violations.is_empty()
+    }
+
+    fn check_frame(&mut self, frame: CallFrame<F>, call_stack: &mut Vec<ResolvedOpcodeLocation>) {
+        let CallFrame { function, mut witness, mut calls } = frame;
+        let program = self.program;
+        let circuit = &program.functions[function.as_usize()];
+
+        let mut memory: HashMap<BlockId, MemoryOpSolver<F>> = HashMap::new();
+        let mut bigint_solver = AcvmBigIntSolver::default();
+
+        for (index, opcode) in circuit.opcodes.iter().enumerate() {
+            if self.is_done() {
+                return;
+            }
+
+            let result = match opcode {
+                Opcode::AssertZero(expression) => match get_value(expression, &witness) {
+                    Ok(value) if value.is_zero() => Ok(()),
+                    Ok(value) => Err(ViolationKind::NonZeroExpression(value)),
+                    Err(error) => Err(error.into()),
+                },
+                // Black box functions insert their outputs into the witness map,
+                // which fails if these differ from the values already assigned.
+                Opcode::BlackBoxFuncCall(call) => {
+                    blackbox::solve(self.backend, &mut witness, call, &mut bigint_solver)
+                        .map_err(Into::into)
+                }
+                Opcode::MemoryInit { block_id, init, .. } => {
+                    let solver = memory.entry(*block_id).or_default();
+                    solver.init(init, &witness).map_err(Into::into)
+                }
+                Opcode::MemoryOp { block_id, op, predicate } => {
+                    let solver = memory.entry(*block_id).or_default();
+                    check_memory_op(solver, op, &mut witness, predicate)
+                }
+                Opcode::BrilligCall { .. } => Ok(()),
+                Opcode::Call { id, inputs, outputs, predicate } => {
+                    match is_predicate_false(&witness, predicate) {
+                        Err(error) => Err(error.into()),
+                        Ok(true) => Ok(()),
+                        Ok(false) if *id == AcirFunctionId(0) => {
+                            Err(OpcodeResolutionError::AcirMainCallAttempted {
+                                opcode_location: ErrorLocation::Unresolved,
+                            }
+                            .into())
+                        }
+                        Ok(false) => {
+                            if let Some(callee) = calls.remove(&index) {
+                                let location = resolved_location(function, index);
+                                let callee_circuit = &program.functions[id.as_usize()];
+                                let result =
+                                    check_call(callee_circuit, inputs, outputs, &witness, &callee);
+                                if let Err(kind) = result {
+                                    self.violation(call_stack, location, kind);
+                                }
+
+                                call_stack.push(location);
+                                self.check_frame(callee, call_stack);
+                                call_stack.pop();
+                            }
+                            Ok(())
+                        }
+                    }
+                }
+            };
+
+            if let Err(kind) = result {
+                self.violation(call_stack, resolved_location(function, index), kind);
+            }
+        }
+    }
+
+    fn violation(
+        &mut self,
+        call_stack: &[ResolvedOpcodeLocation],
+        location: ResolvedOpcodeLocation,
+        kind: ViolationKind<F>,
+    ) {
+        let mut call_stack = call_stack.to_vec();
+        call_stack.push(location);
+        self.violations.push(ConstraintViolation { call_stack, kind });
+    }
+}
+
+fn resolved_location(function: AcirFunctionId, index: usize) -> ResolvedOpcodeLocation {
+    ResolvedOpcodeLocation {
+        acir_function_index: function.as_usize(),
+        opcode_location: OpcodeLocation::Acir(index),
+    }
+}
+
+/// Checks a memory operation, comparing the value of a read against the witness it reads into.
+///
+/// The solver expects the index to be within the block and reads to be into a single witness,
+/// which the ACVM ensures before solving, so both are checked here rather than left to the solver.
+fn check_memory_op<F: AcirField>(
+    solver: &mut MemoryOpSolver<F>,
+    op: &MemOp<F>,
+    witness: &mut WitnessMap<F>,
+    predicate: &Option<Expression<F>>,
+) -> Result<(), ViolationKind<F>> {
+    let index = get_value(&op.index, witness)?;
+    let in_bounds = index.try_to_u64().is_some_and(|index| index < u64::from(solver.block_len));
+    if !in_bounds && !is_predicate_false(witness, predicate)? {
+        return Err(ViolationKind::MemoryIndexOutOfBounds { index, block_len: solver.block_len });
+    }
+    // Operations with a false predicate are skipped, whichever index they're at.
+    let in_bounds_op;
+    let op = if in_bounds {
+        op
+    } else {
+        in_bounds_op = MemOp { index: Expression::zero(), ..op.clone() };
+        &in_bounds_op
+    };
+
+    let is_read = get_value(&op.operation, witness)?.is_zero();
+    if !is_read {
+        return solver.solve_memory_op(op, witness, predicate).map_err(Into::into);
+    }
+    let Some(read_witness) = op.value.to_witness() else {
+        let error = OpcodeNotSolvable::ExpressionHasTooManyUnknowns(op.value.clone());
+        return Err(OpcodeResolutionError::from(error).into());
+    };
+
+    // The solver assigns the value read from memory to the witness, so its current value is
+    // removed beforehand and restored afterwards.
+    let Some(assigned_value) = witness.remove(&read_witness) else {
+        let error = OpcodeNotSolvable::MissingAssignment(read_witness.0);
+        return Err(OpcodeResolutionError::from(error).into());
+    };
+    let result = solver.solve_memory_op(op, witness, predicate);
+    let read_value = witness.insert(read_witness, assigned_value);
+    result?;
+
+    if read_value == Some(assigned_value) {
+        Ok(())
+    } else {
+        Err(OpcodeResolutionError::UnsatisfiedConstrain {
+            opcode_location: ErrorLocation::Unresolved,
+            payload: None,
+        }
+        .into())
+    }
+}
+
+/// Checks that the caller's inputs were passed as the callee's initial witness,
+/// and that the callee's return values were assigned to the caller's outputs.
+fn check_call<F: AcirField>(
+    callee_circuit: &Circuit<F>,
+    inputs: &[Witness],
+    outputs: &[Witness],
+    caller_witness: &WitnessMap<F>,
+    callee: &CallFrame<F>,
+) -> Result<(), ViolationKind<F>> {
+    for (index, input) in inputs.iter().enumerate() {
+        let caller_value = witness_to_value(caller_witness, *input)?;
+        let callee_value = witness_to_value(&callee.witness, Witness(index as u32))?;
+        if caller_value != callee_value {
+            return Err(ViolationKind::CallInputMismatch { index });
+        }
+    }
+
+    let return_values = callee_circuit.return_values.indices();
+    if return_values.len() != outputs.len() {
+        return Err(OpcodeResolutionError::AcirCallOutputsMismatch {
+            opcode_location: ErrorLocation::Unresolved,
+            results_size: return_values.len() as u32,
+            outputs_size: outputs.len() as u32,
+        }
+        .into());
+    }
+    for (index, (output, return_value)) in outputs.iter().zip(return_values).enumerate() {
+        let caller_value = witness_to_value(caller_witness, *output)?;
+        let callee_value = witness_to_value(&callee.witness, Witness(return_value))?;
+        if caller_value != callee_value {
+            return Err(ViolationKind::CallOutputMismatch { index });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use acir::{
+        circuit::{
+            opcodes::{AcirFunctionId, BlockId, BlockType, MemOp},
+            Circuit, Opcode, OpcodeLocation, Program, PublicInputs, ResolvedOpcodeLocation,
+        },
+        native_types::{Expression, Witness, WitnessMap, WitnessStack},
+        AcirField, FieldElement,
+    };
+    use acvm_blackbox_solver::StubbedBlackBoxSolver;
+
+    use super::{check_witness_stack, ViolationKind, WitnessStackMismatch};
+
+    fn witness_map(values: &[(u32, u128)]) -> WitnessMap<FieldElement> {
+        WitnessMap::from(BTreeMap::from_iter(
+            values.iter().map(|(index, value)| (Witness(*index), FieldElement::from(*value))),
+        ))
+    }
+
+    /// Constrains `w_{result} = w_{lhs} * w_{rhs}`
+    fn mul(lhs: u32, rhs: u32, result: u32) -> Opcode<FieldElement> {
+        Opcode::AssertZero(Expression {
+            mul_terms: vec![(FieldElement::one(), Witness(lhs), Witness(rhs))],
+            linear_combinations: vec![(-FieldElement::one(), Witness(result))],
+            q_c: FieldElement::zero(),
+        })
+    }
+
+    fn program(functions: Vec<Vec<Opcode<FieldElement>>>) -> Program<FieldElement> {
+        let functions = functions
+            .into_iter()
+            .map(|opcodes| Circuit {
+                opcodes,
+                return_values: PublicInputs(BTreeSet::from([Witness(2)])),
+                ..Circuit::default()
+            })
+            .collect();
+        Program { functions, unconstrained_functions: Vec::new() }
+    }
+
+    fn check(
+        program: &Program<FieldElement>,
+        witness_stack: &WitnessStack<FieldElement>,
+        find_all: bool,
+    ) -> Vec<(usize, usize)> {
+        check_witness_stack(program, witness_stack, &StubbedBlackBoxSolver, find_all)
+            .unwrap()
+            .iter()
+            .map(|violation| {
+                let ResolvedOpcodeLocation { acir_function_index, opcode_location } =
+                    violation.location();
+                let OpcodeLocation::Acir(index) = opcode_location else {
+                    panic!("expected an ACIR opcode location");
+                };
+                (acir_function_index, index)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_first_or_all_violations() {
+        let program = program(vec![vec![mul(0, 1, 2), mul(0, 0, 3), mul(1, 1, 4)]]);
+
+        let valid = WitnessStack::from(witness_map(&[(0, 2), (1, 3), (2, 6), (3, 4), (4, 9)]));
+        assert!(check(&program, &valid, true).is_empty());
+
+        let invalid = WitnessStack::from(witness_map(&[(0, 2), (1, 3), (2, 6), (3, 5), (4, 10)]));
+        assert_eq!(check(&program, &invalid, false), vec![(0, 1)]);
+        assert_eq!(check(&program, &invalid, true), vec![(0, 1), (0, 2)]);
+
+        let violations =
+            check_witness_stack(&program, &invalid, &StubbedBlackBoxSolver, false).unwrap();
+        assert_eq!(violations[0].kind, ViolationKind::NonZeroExpression(-FieldElement::one()));
+    }
+
+    #[test]
+    fn checks_memory_reads() {
+        let block_id = BlockId(0);
+        let program = program(vec![vec![
+            Opcode::MemoryInit {
+                block_id,
+                init: vec![Witness(0), Witness(1)],
+                block_type: BlockType::Memory,
+            },
+            Opcode::MemoryOp {
+                block_id,
+                op: MemOp::read_at_mem_index(FieldElement::one().into(), Witness(2)),
+                predicate: None,
+            },
+        ]]);
+
+        let valid = WitnessStack::from(witness_map(&[(0, 5), (1, 7), (2, 7)]));
+        assert!(check(&program, &valid, true).is_empty());
+
+        let invalid = WitnessStack::from(witness_map(&[(0, 5), (1, 7), (2, 5)]));
+        assert_eq!(check(&program, &invalid, true), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn reports_memory_indices_out_of_bounds() {
+        let block_id = BlockId(0);
+        let read_at =
+            |index: Witness, predicate: Option<Expression<FieldElement>>| Opcode::MemoryOp {
+                block_id,
+                op: MemOp::read_at_mem_index(index.into(), Witness(2)),
+                predicate,
+            };
+        let program = program(vec![vec![
+            Opcode::MemoryInit {
+                block_id,
+                init: vec![Witness(0), Witness(1)],
+                block_type: BlockType::Memory,
+            },
+            read_at(Witness(3), None),
+            read_at(Witness(3), Some(Expression::zero())),
+        ]]);
+
+        // Neither an index past the end of the block nor one which doesn't fit in a u64 panics.
+        for index in [FieldElement::from(2_u128), -FieldElement::one()] {
+            let mut witness = witness_map(&[(0, 5), (1, 7), (2, 0)]);
+            witness.insert(Witness(3), index);
+            let witness = WitnessStack::from(witness);
+            let violations =
+                check_witness_stack(&program, &witness, &StubbedBlackBoxSolver, true).unwrap();
+            // The read with a false predicate is skipped.
+            assert_eq!(violations.len(), 1);
+            assert_eq!(
+                violations[0].kind,
+                ViolationKind::MemoryIndexOutOfBounds { index, block_len: 2 }
+            );
+        }
+    }
+
+    #[test]
+    fn checks_calls_against_the_witness_stack() {
+        let call = Opcode::Call {
+            id: AcirFunctionId(1),
+            inputs: vec![Witness(0), Witness(1)],
+            outputs: vec![Witness(2)],
+            predicate: None,
+        };
+        let program = program(vec![vec![call], vec![mul(0, 1, 2)]]);
+
+        let mut valid = WitnessStack::default();
+        valid.push(1, witness_map(&[(0, 2), (1, 3), (2, 6)]));
+        valid.push(0, witness_map(&[(0, 2), (1, 3), (2, 6)]));
+        assert!(check(&program, &valid, true).is_empty());
+
+        // The callee is satisfied, but its result is not the one assigned to the call's output.
+        let mut invalid_output = WitnessStack::default();
+        invalid_output.push(1, witness_map(&[(0, 2), (1, 3), (2, 6)]));
+        invalid_output.push(0, witness_map(&[(0, 2), (1, 3), (2, 7)]));
+        assert_eq!(check(&program, &invalid_output, true), vec![(0, 0)]);
+
+        // Violations inside the callee are reported with the call leading to them.
+        let mut invalid_callee = WitnessStack::default();
+        invalid_callee.push(1, witness_map(&[(0, 2), (1, 3), (2, 7)]));
+        invalid_callee.push(0, witness_map(&[(0, 2), (1, 3), (2, 7)]));
+        let violations =
+            check_witness_stack(&program, &invalid_callee, &StubbedBlackBoxSolver, true).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].call_stack.len(), 2);
+        assert_eq!(violations[0].location().acir_function_index, 1);
+
+        let missing_call = WitnessStack::from(witness_map(&[(0, 2), (1, 3), (2, 6)]));
+        let error = check_witness_stack(&program, &missing_call, &StubbedBlackBoxSolver, true)
+            .expect_err("the callee has no witness");
+        assert_eq!(error, WitnessStackMismatch::MissingCallWitness(AcirFunctionId(1)));
+    }
+}
@@ -3,8 +3,10 @@
 #![warn(clippy::semicolon_if_nothing_returned)]
 #![cfg_attr(not(test), warn(unused_crate_dependencies, unused_extern_crates))]
 
+pub mod checker;
 pub mod compiler;
 pub mod pwg;
+pub mod r1cs;
 
 pub use acvm_blackbox_solver::{BlackBoxFunctionSolver, BlackBoxResolutionError};
 use pwg::OpcodeResolutionError;
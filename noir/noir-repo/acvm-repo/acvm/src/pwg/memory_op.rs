@@ -17,7 +17,7 @@ type MemoryIndex = u32;
 #[derive(Default)]
 pub(crate) struct MemoryOpSolver<F> {
     pub(super) block_value: HashMap<MemoryIndex, F>,
-    pub(super) block_len: u32,
+    pub(crate) block_len: u32,
 }
 
 impl<F: AcirField> MemoryOpSolver<F> {
@@ -0,0 +1,615 @@
+//! Exports ACIR circuits as rank-1 constraint systems in the binary formats used by circom and
+//! snarkjs, allowing Noir programs to be proven with R1CS-based provers.
+//!
+//! Every [`Opcode::AssertZero`] becomes one constraint `A * B = C`, with an auxiliary wire for
+//! each multiplication term beyond the first. The `RANGE`, `AND` and `XOR` black box functions
+//! are expanded into bit decompositions, whose bits are computed when exporting the witness.
+//! Any other black box function, as well as memory and call opcodes, is rejected. Brillig calls
+//! are skipped as their outputs are unconstrained.
+//!
+//! Wires are laid out as circom does: the constant one, then the return values (public outputs),
+//! the public parameters, the private parameters and finally every other witness.
+//! The label of a wire is the index of its ACIR witness plus one, or an index past
+//! `current_witness_index` for auxiliary wires.
+use std::collections::BTreeMap;
+
+use acir::{
+    circuit::{
+        opcodes::{BlackBoxFuncCall, ConstantOrWitnessEnum, FunctionInput},
+        Circuit, Opcode,
+    },
+    native_types::{Expression, Witness, WitnessMap},
+    AcirField, BlackBoxFunc,
+};
+use thiserror::Error;
+
+const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+const R1CS_VERSION: u32 = 1;
+const WTNS_MAGIC: &[u8; 4] = b"wtns";
+const WTNS_VERSION: u32 = 2;
+
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum R1csError {
+    #[error("Opcode {opcode_index} cannot be exported to R1CS: {kind} opcodes are not supported")]
+    UnsupportedOpcode { opcode_index: usize, kind: &'static str },
+    #[error("Opcode {opcode_index} cannot be exported to R1CS: black box function {func} is not supported")]
+    UnsupportedBlackBox { opcode_index: usize, func: BlackBoxFunc },
+    #[error("Witness {} has no value", .0.witness_index())]
+    MissingWitness(Witness),
+}
+
+/// A sparse linear combination of wires, as pairs of wire index and coefficient.
+pub type LinearCombination<F> = Vec<(u32, F)>;
+
+/// A constraint of the form `a * b = c`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct R1csConstraint<F> {
+    pub a: LinearCombination<F>,
+    pub b: LinearCombination<F>,
+    pub c: LinearCombination<F>,
+}
+
+/// A rank-1 constraint system derived from an ACIR [`Circuit`].
+#[derive(Clone, Debug)]
+pub struct R1cs<F> {
+    pub constraints: Vec<R1csConstraint<F>>,
+    pub num_public_outputs: u32,
+    pub num_public_inputs: u32,
+    pub num_private_inputs: u32,
+    /// The label of each wire, indexed by wire.
+    pub wire_labels: Vec<u64>,
+    num_labels: u64,
+    /// The signal assigned to each wire, indexed by wire.
+    wires: Vec<Signal>,
+    auxiliary_witnesses: Vec<AuxiliaryWitness<F>>,
+}
+
+/// A value in the constraint system before it is assigned a wire.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Signal {
+    One,
+    Witness(Witness),
+    Auxiliary(usize),
+}
+
+type Terms<F> = Vec<(F, Signal)>;
+
+/// How to compute an auxiliary wire introduced by the export from the values of earlier signals.
+#[derive(Clone, Debug)]
+enum AuxiliaryWitness<F> {
+    Bit { value: Terms<F>, bit: u32 },
+    Product(Signal, Signal),
+}
+
+impl<F: AcirField> R1cs<F> {
+    pub fn from_circuit(circuit: &Circuit<F>) -> Result<Self, R1csError> {
+        let mut builder = R1csBuilder { constraints: Vec::new(), auxiliary_witnesses: Vec::new() };
+        for (opcode_index, opcode) in circuit.opcodes.iter().enumerate() {
+            match opcode {
+                Opcode::AssertZero(expression) => builder.assert_zero(expression),
+                Opcode::BlackBoxFuncCall(call) => builder.black_box_call(call, opcode_index)?,
+                Opcode::BrilligCall { .. } => (),
+                Opcode::MemoryOp { .. } | Opcode::MemoryInit { .. } => {
+                    return Err(R1csError::UnsupportedOpcode { opcode_index, kind: "memory" })
+                }
+                Opcode::Call { .. } => {
+                    return Err(R1csError::UnsupportedOpcode { opcode_index, kind: "call" })
+                }
+            }
+        }
+        Ok(builder.finish(circuit))
+    }
+
+    pub fn num_wires(&self) -> u32 {
+        self.wires.len() as u32
+    }
+
+    /// Computes the value of every wire from the solved witnesses of the circuit.
+    pub fn assign_wires(&self, witness_map: &WitnessMap<F>) -> Result<Vec<F>, R1csError> {
+        let mut auxiliary_values = Vec::with_capacity(self.auxiliary_witnesses.len());
+        for auxiliary_witness in &self.auxiliary_witnesses {
+            let value = match auxiliary_witness {
+                AuxiliaryWitness::Bit { value, bit } => {
+                    let mut value_of_terms = F::zero();
+                    for (coefficient, signal) in value {
+                        value_of_terms +=
+                            *coefficient * signal_value(*signal, witness_map, &auxiliary_values)?;
+                    }
+                    let bytes = value_of_terms.to_be_bytes();
+                    let byte = bytes.iter().rev().nth((*bit / 8) as usize).copied().unwrap_or(0);
+                    F::from(u128::from((byte >> (bit % 8)) & 1))
+                }
+                AuxiliaryWitness::Product(lhs, rhs) => {
+                    signal_value(*lhs, witness_map, &auxiliary_values)?
+                        * signal_value(*rhs, witness_map, &auxiliary_values)?
+                }
+            };
+            auxiliary_values.push(value);
+        }
+        self.wires
+            .iter()
+            .map(|signal| signal_value(*signal, witness_map, &auxiliary_values))
+            .collect()
+    }
+
+    /// Serializes the constraint system in the `.r1cs` binary format.
+    pub fn serialize_r1cs(&self) -> Vec<u8> {
+        let field_size = field_size::<F>();
+
+        let mut header = Vec::new();
+        write_field_header::<F>(&mut header);
+        header.extend(self.num_wires().to_le_bytes());
+        header.extend(self.num_public_outputs.to_le_bytes());
+        header.extend(self.num_public_inputs.to_le_bytes());
+        header.extend(self.num_private_inputs.to_le_bytes());
+        header.extend(self.num_labels.to_le_bytes());
+        header.extend((self.constraints.len() as u32).to_le_bytes());
+
+        let mut constraints = Vec::new();
+        for constraint in &self.constraints {
+            for linear_combination in [&constraint.a, &constraint.b, &constraint.c] {
+                constraints.extend((linear_combination.len() as u32).to_le_bytes());
+                for (wire, coefficient) in linear_combination {
+                    constraints.extend(wire.to_le_bytes());
+                    constraints.extend(field_to_le_bytes(*coefficient, field_size));
+                }
+            }
+        }
+
+        let labels: Vec<u8> =
+            self.wire_labels.iter().flat_map(|label| label.to_le_bytes()).collect();
+
+        write_sections(R1CS_MAGIC, R1CS_VERSION, &[(1, header), (2, constraints), (3, labels)])
+    }
+
+    /// Serializes the values of every wire in the `.wtns` binary format.
+    pub fn serialize_witness(&self, witness_map: &WitnessMap<F>) -> Result<Vec<u8>, R1csError> {
+        let field_size = field_size::<F>();
+        let values = self.assign_wires(witness_map)?;
+
+        let mut header = Vec::new();
+        write_field_header::<F>(&mut header);
+        header.extend((values.len() as u32).to_le_bytes());
+
+        let witness: Vec<u8> =
+            values.into_iter().flat_map(|value| field_to_le_bytes(value, field_size)).collect();
+
+        Ok(write_sections(WTNS_MAGIC, WTNS_VERSION, &[(1, header), (2, witness)]))
+    }
+}
+
+fn signal_value<F: AcirField>(
+    signal: Signal,
+    witness_map: &WitnessMap<F>,
+    auxiliary_values: &[F],
+) -> Result<F, R1csError> {
+    match signal {
+        Signal::One => Ok(F::one()),
+        Signal::Witness(witness) => {
+            witness_map.get(&witness).copied().ok_or(R1csError::MissingWitness(witness))
+        }
+        Signal::Auxiliary(index) => Ok(auxiliary_values[index]),
+    }
+}
+
+struct R1csBuilder<F> {
+    constraints: Vec<[Terms<F>; 3]>,
+    auxiliary_witnesses: Vec<AuxiliaryWitness<F>>,
+}
+
+impl<F: AcirField> R1csBuilder<F> {
+    fn constrain(&mut self, a: Terms<F>, b: Terms<F>, c: Terms<F>) {
+        self.constraints.push([a, b, c]);
+    }
+
+    fn auxiliary(&mut self, auxiliary_witness: AuxiliaryWitness<F>) -> Signal {
+        self.auxiliary_witnesses.push(auxiliary_witness);
+        Signal::Auxiliary(self.auxiliary_witnesses.len() - 1)
+    }
+
+    fn product(&mut self, lhs: Signal, rhs: Signal) -> Signal {
+        let product = self.auxiliary(AuxiliaryWitness::Product(lhs, rhs));
+        self.constrain(vec![(F::one(), lhs)], vec![(F::one(), rhs)], vec![(F::one(), product)]);
+        product
+    }
+
+    fn assert_zero(&mut self, expression: &Expression<F>) {
+        let mut c: Terms<F> = expression
+            .linear_combinations
+            .iter()
+            .map(|(q, w)| (-*q, Signal::Witness(*w)))
+            .collect();
+        if !expression.q_c.is_zero() {
+            c.push((-expression.q_c, Signal::One));
+        }
+
+        let Some(((q, a, b), mul_terms)) = expression.mul_terms.split_first() else {
+            // A linear expression is constrained as `(-c) * 1 = 0`.
+            let a = c.into_iter().map(|(q, signal)| (-q, signal)).collect();
+            self.constrain(a, vec![(F::one(), Signal::One)], Vec::new());
+            return;
+        };
+        for (q, lhs, rhs) in mul_terms {
+            let product = self.product(Signal::Witness(*lhs), Signal::Witness(*rhs));
+            c.push((-*q, product));
+        }
+        self.constrain(vec![(*q, Signal::Witness(*a))], vec![(F::one(), Signal::Witness(*b))], c);
+    }
+
+    fn black_box_call(
+        &mut self,
+        call: &BlackBoxFuncCall<F>,
+        opcode_index: usize,
+    ) -> Result<(), R1csError> {
+        match call {
+            BlackBoxFuncCall::RANGE { input } => {
+                // Every field element fits in `max_num_bits`, so there is nothing to constrain.
+                if input.num_bits() < F::max_num_bits() {
+                    self.decompose(input);
+                }
+            }
+            BlackBoxFuncCall::AND { lhs, rhs, output } => {
+                let terms = self
+                    .bit_products(lhs, rhs)
+                    .into_iter()
+                    .map(|(power_of_two, _, _, product)| (power_of_two, product))
+                    .collect();
+                self.assert_equal(terms, Signal::Witness(*output));
+            }
+            BlackBoxFuncCall::XOR { lhs, rhs, output } => {
+                // a ^ b = a + b - 2ab for each pair of bits.
+                let terms = self
+                    .bit_products(lhs, rhs)
+                    .into_iter()
+                    .flat_map(|(power_of_two, lhs_bit, rhs_bit, product)| {
+                        [
+                            (power_of_two, lhs_bit),
+                            (power_of_two, rhs_bit),
+                            (-(power_of_two + power_of_two), product),
+                        ]
+                    })
+                    .collect();
+                self.assert_equal(terms, Signal::Witness(*output));
+            }
+            _ => {
+                return Err(R1csError::UnsupportedBlackBox {
+                    opcode_index,
+                    func: call.get_black_box_func(),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Constrains `terms` to equal `signal` as `terms * 1 = signal`.
+    fn assert_equal(&mut self, terms: Terms<F>, signal: Signal) {
+        self.constrain(terms, vec![(F::one(), Signal::One)], vec![(F::one(), signal)]);
+    }
+
+    /// Constrains `input` to fit in its number of bits, returning its bits from least significant.
+    fn decompose(&mut self, input: &FunctionInput<F>) -> Vec<Signal> {
+        let value = match input.input_ref() {
+            ConstantOrWitnessEnum::Constant(constant) => vec![(*constant, Signal::One)],
+            ConstantOrWitnessEnum::Witness(witness) => vec![(F::one(), Signal::Witness(*witness))],
+        };
+
+        let mut bits = Vec::with_capacity(input.num_bits() as usize);
+        let mut sum = Vec::with_capacity(input.num_bits() as usize);
+        let mut power_of_two = F::one();
+        for bit in 0..input.num_bits() {
+            let signal = self.auxiliary(AuxiliaryWitness::Bit { value: value.clone(), bit });
+            // bit * (bit - 1) = 0
+            self.constrain(
+                vec![(F::one(), signal)],
+                vec![(F::one(), signal), (-F::one(), Signal::One)],
+                Vec::new(),
+            );
+            bits.push(signal);
+            sum.push((power_of_two, signal));
+            power_of_two = power_of_two + power_of_two;
+        }
+        self.constrain(value, vec![(F::one(), Signal::One)], sum);
+        bits
+    }
+
+    /// Decomposes both operands of a bitwise operation into bits, returning the weight of each
+    /// pair of bits along with the bits and their product.
+    fn bit_products(
+        &mut self,
+        lhs: &FunctionInput<F>,
+        rhs: &FunctionInput<F>,
+    ) -> Vec<(F, Signal, Signal, Signal)> {
+        let lhs_bits = self.decompose(lhs);
+        let rhs_bits = self.decompose(rhs);
+
+        let mut power_of_two = F::one();
+        let mut products = Vec::with_capacity(lhs_bits.len());
+        for (lhs_bit, rhs_bit) in lhs_bits.into_iter().zip(rhs_bits) {
+            let product = self.product(lhs_bit, rhs_bit);
+            products.push((power_of_two, lhs_bit, rhs_bit, product));
+            power_of_two = power_of_two + power_of_two;
+        }
+        products
+    }
+
+    fn finish(self, circuit: &Circuit<F>) -> R1cs<F> {
+        let mut wire_indices: BTreeMap<Signal, u32> = BTreeMap::new();
+        let mut wires = Vec::new();
+        let mut assign_wire = |signal: Signal| -> bool {
+            if wire_indices.contains_key(&signal) {
+                return false;
+            }
+            wire_indices.insert(signal, wires.len() as u32);
+            wires.push(signal);
+            true
+        };
+
+        assign_wire(Signal::One);
+        let mut count = |witnesses: &mut dyn Iterator<Item = &Witness>| -> u32 {
+            witnesses.filter(|witness| assign_wire(Signal::Witness(**witness))).count() as u32
+        };
+        let num_public_outputs = count(&mut circuit.return_values.0.iter());
+        let num_public_inputs = count(&mut circuit.public_parameters.0.iter());
+        let num_private_inputs = count(&mut circuit.private_parameters.iter());
+
+        let mut signals: Vec<Signal> = self
+            .constraints
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|(_, signal)| *signal)
+            .filter(|signal| !wire_indices.contains_key(signal))
+            .collect();
+        signals.sort();
+        signals.dedup();
+        for signal in signals {
+            wire_indices.insert(signal, wires.len() as u32);
+            wires.push(signal);
+        }
+
+        let auxiliary_label_start = u64::from(circuit.current_witness_index) + 2;
+        let wire_labels = wires
+            .iter()
+            .map(|signal| match signal {
+                Signal::One => 0,
+                Signal::Witness(witness) => u64::from(witness.witness_index()) + 1,
+                Signal::Auxiliary(index) => auxiliary_label_start + *index as u64,
+            })
+            .collect();
+
+        let constraints = self
+            .constraints
+            .into_iter()
+            .map(|[a, b, c]| {
+                let to_wires = |terms: Terms<F>| {
+                    let mut combination: BTreeMap<u32, F> = BTreeMap::new();
+                    for (coefficient, signal) in terms {
+                        *combination.entry(wire_indices[&signal]).or_insert_with(F::zero) +=
+                            coefficient;
+                    }
+                    combination
+                        .into_iter()
+                        .filter(|(_, coefficient)| !coefficient.is_zero())
+                        .collect()
+                };
+                R1csConstraint { a: to_wires(a), b: to_wires(b), c: to_wires(c) }
+            })
+            .collect();
+
+        R1cs {
+            constraints,
+            num_public_outputs,
+            num_public_inputs,
+            num_private_inputs,
+            wire_labels,
+            num_labels: auxiliary_label_start + self.auxiliary_witnesses.len() as u64,
+            wires,
+            auxiliary_witnesses: self.auxiliary_witnesses,
+        }
+    }
+}
+
+/// The number of bytes used to encode a field element, rounded up to a multiple of 8.
+fn field_size<F: AcirField>() -> usize {
+    let bits = F::modulus().bits() as usize;
+    (bits - 1) / 64 * 8 + 8
+}
+
+fn field_to_le_bytes<F: AcirField>(value: F, field_size: usize) -> Vec<u8> {
+    let mut bytes = value.to_be_bytes();
+    bytes.reverse();
+    bytes.resize(field_size, 0);
+    bytes
+}
+
+/// Writes the size and modulus of the field, which both formats start their header with.
+fn write_field_header<F: AcirField>(header: &mut Vec<u8>) {
+    let field_size = field_size::<F>();
+    let mut modulus = F::modulus().to_bytes_le();
+    modulus.resize(field_size, 0);
+    header.extend((field_size as u32).to_le_bytes());
+    header.extend(modulus);
+}
+
+fn write_sections(magic: &[u8; 4], version: u32, sections: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend(magic);
+    bytes.extend(version.to_le_bytes());
+    bytes.extend((sections.len() as u32).to_le_bytes());
+    for (section_type, content) in sections {
+        bytes.extend(section_type.to_le_bytes());
+        bytes.extend((content.len() as u64).to_le_bytes());
+        bytes.extend(content);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use acir::{
+        circuit::{
+            opcodes::{BlackBoxFuncCall, BlockId, BlockType, FunctionInput},
+            Circuit, ExpressionWidth, Opcode, PublicInputs,
+        },
+        native_types::{Expression, Witness, WitnessMap},
+        AcirField, BlackBoxFunc, FieldElement,
+    };
+
+    use super::{LinearCombination, R1cs, R1csError};
+
+    fn witness_map(values: &[(u32, u128)]) -> WitnessMap<FieldElement> {
+        WitnessMap::from(BTreeMap::from_iter(
+            values.iter().map(|(index, value)| (Witness(*index), FieldElement::from(*value))),
+        ))
+    }
+
+    fn circuit(opcodes: Vec<Opcode<FieldElement>>) -> Circuit<FieldElement> {
+        Circuit {
+            current_witness_index: 5,
+            expression_width: ExpressionWidth::Bounded { width: 3 },
+            opcodes,
+            private_parameters: BTreeSet::from([Witness(0)]),
+            public_parameters: PublicInputs(BTreeSet::from([Witness(1)])),
+            return_values: PublicInputs(BTreeSet::from([Witness(5)])),
+            ..Circuit::default()
+        }
+    }
+
+    fn is_satisfied(r1cs: &R1cs<FieldElement>, values: &[FieldElement]) -> bool {
+        let evaluate = |linear_combination: &LinearCombination<FieldElement>| {
+            linear_combination.iter().fold(FieldElement::zero(), |sum, (wire, coefficient)| {
+                sum + *coefficient * values[*wire as usize]
+            })
+        };
+        r1cs.constraints.iter().all(|constraint| {
+            evaluate(&constraint.a) * evaluate(&constraint.b) == evaluate(&constraint.c)
+        })
+    }
+
+    /// Constrains `w0 * w1 + w0 * w0 + w2 - w3 = 0`, `w2 = w0 & w1`, `w3 = w0 ^ w1` and
+    /// `w5 = w2 + w3 + w4`, with `w0` fitting in 4 bits.
+    fn bitwise_circuit() -> Circuit<FieldElement> {
+        let one = FieldElement::one();
+        circuit(vec![
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![(one, Witness(0), Witness(1)), (one, Witness(0), Witness(0))],
+                linear_combinations: vec![(one, Witness(2)), (-one, Witness(4))],
+                q_c: FieldElement::zero(),
+            }),
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+                input: FunctionInput::witness(Witness(0), 4),
+            }),
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::AND {
+                lhs: FunctionInput::witness(Witness(0), 4),
+                rhs: FunctionInput::witness(Witness(1), 4),
+                output: Witness(2),
+            }),
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::XOR {
+                lhs: FunctionInput::witness(Witness(0), 4),
+                rhs: FunctionInput::witness(Witness(1), 4),
+                output: Witness(3),
+            }),
+            Opcode::AssertZero(Expression {
+                mul_terms: Vec::new(),
+                linear_combinations: vec![
+                    (one, Witness(2)),
+                    (one, Witness(3)),
+                    (one, Witness(4)),
+                    (-one, Witness(5)),
+                ],
+                q_c: FieldElement::zero(),
+            }),
+        ])
+    }
+
+    #[test]
+    fn exports_expressions_and_bitwise_black_boxes() {
+        let r1cs = R1cs::from_circuit(&bitwise_circuit()).unwrap();
+        assert_eq!(
+            (r1cs.num_public_outputs, r1cs.num_public_inputs, r1cs.num_private_inputs),
+            (1, 1, 1)
+        );
+        // The constant one is followed by the return value, then the public and private inputs.
+        assert_eq!(r1cs.wire_labels[..4], [0, 6, 2, 1]);
+
+        // 12 & 10 = 8, 12 ^ 10 = 6 and 12 * 10 + 12 * 12 + 8 = 272.
+        let witness = witness_map(&[(0, 12), (1, 10), (2, 8), (3, 6), (4, 272), (5, 286)]);
+        let values = r1cs.assign_wires(&witness).unwrap();
+        assert_eq!(values.len(), r1cs.num_wires() as usize);
+        assert!(is_satisfied(&r1cs, &values));
+
+        for (index, value) in [(2, 9), (3, 7), (4, 271)] {
+            let mut witness = witness.clone();
+            witness.insert(Witness(index), FieldElement::from(value as u128));
+            let values = r1cs.assign_wires(&witness).unwrap();
+            assert!(!is_satisfied(&r1cs, &values), "w{index} = {value} should be rejected");
+        }
+
+        // 16 doesn't fit in 4 bits so its decomposition can't satisfy the constraints.
+        let witness = witness_map(&[(0, 16), (1, 0), (2, 0), (3, 16), (4, 256), (5, 272)]);
+        let values = r1cs.assign_wires(&witness).unwrap();
+        assert!(!is_satisfied(&r1cs, &values));
+
+        assert_eq!(
+            r1cs.assign_wires(&witness_map(&[(0, 12), (1, 10)])),
+            Err(R1csError::MissingWitness(Witness(5)))
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_opcodes() {
+        let memory_init = Opcode::MemoryInit {
+            block_id: BlockId(0),
+            init: vec![Witness(0)],
+            block_type: BlockType::Memory,
+        };
+        assert_eq!(
+            R1cs::from_circuit(&circuit(vec![memory_init])).unwrap_err(),
+            R1csError::UnsupportedOpcode { opcode_index: 0, kind: "memory" }
+        );
+
+        let keccak = Opcode::BlackBoxFuncCall(BlackBoxFuncCall::Keccakf1600 {
+            inputs: Box::new([FunctionInput::witness(Witness(0), 64); 25]),
+            outputs: Box::new([Witness(1); 25]),
+        });
+        assert_eq!(
+            R1cs::from_circuit(&circuit(vec![keccak])).unwrap_err(),
+            R1csError::UnsupportedBlackBox { opcode_index: 0, func: BlackBoxFunc::Keccakf1600 }
+        );
+    }
+
+    #[test]
+    fn serializes_in_circom_binary_formats() {
+        let r1cs = R1cs::from_circuit(&bitwise_circuit()).unwrap();
+        let num_wires = r1cs.num_wires() as usize;
+        let num_constraints = r1cs.constraints.len();
+
+        let bytes = r1cs.serialize_r1cs();
+        assert_eq!(&bytes[..12], b"r1cs\x01\x00\x00\x00\x03\x00\x00\x00");
+        // The header section holds the field size and modulus followed by the wire counts.
+        assert_eq!(bytes[12..24], [1, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(bytes[24..28], 32u32.to_le_bytes());
+        assert_eq!(bytes[28..60], FieldElement::modulus().to_bytes_le()[..]);
+        assert_eq!(bytes[60..64], (num_wires as u32).to_le_bytes());
+        assert_eq!(bytes[64..76], [1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(bytes[84..88], (num_constraints as u32).to_le_bytes());
+        // The label section is last and holds a `u64` per wire.
+        let labels_start = bytes.len() - 8 * num_wires;
+        assert_eq!(bytes[labels_start - 12..labels_start - 8], 3u32.to_le_bytes());
+
+        let witness = witness_map(&[(0, 12), (1, 10), (2, 8), (3, 6), (4, 272), (5, 286)]);
+        let bytes = r1cs.serialize_witness(&witness).unwrap();
+        assert_eq!(&bytes[..12], b"wtns\x02\x00\x00\x00\x02\x00\x00\x00");
+        assert_eq!(bytes[12..24], [1, 0, 0, 0, 40, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(bytes[60..64], (num_wires as u32).to_le_bytes());
+        assert_eq!(bytes.len(), 64 + 12 + 32 * num_wires);
+        // The first wire is the constant one and the second is the return value.
+        assert_eq!(
+            bytes[76..108],
+            FieldElement::one().to_be_bytes().into_iter().rev().collect::<Vec<_>>()[..]
+        );
+        assert_eq!(bytes[108], 30);
+    }
+}
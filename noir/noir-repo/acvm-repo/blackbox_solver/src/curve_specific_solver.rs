@@ -36,6 +36,7 @@ pub trait BlackBoxFunctionSolver<F> {
     ) -> Result<Vec<F>, BlackBoxResolutionError>;
 }
 
+#[derive(Default)]
 pub struct StubbedBlackBoxSolver;
 
 impl StubbedBlackBoxSolver {
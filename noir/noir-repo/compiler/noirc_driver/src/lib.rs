@@ -4,25 +4,31 @@
 #![warn(clippy::semicolon_if_nothing_returned)]
 
 use abi_gen::{abi_type_from_hir_type, value_from_hir_expression};
-use acvm::acir::circuit::ExpressionWidth;
+use acvm::acir::circuit::{ErrorSelector, ExpressionWidth};
 use acvm::compiler::MIN_EXPRESSION_WIDTH;
 use clap::Args;
 use fm::{FileId, FileManager};
 use iter_extended::vecmap;
 use noirc_abi::{AbiParameter, AbiType, AbiValue};
 use noirc_errors::{CustomDiagnostic, FileDiagnostic};
-use noirc_evaluator::create_program;
 use noirc_evaluator::errors::RuntimeError;
-use noirc_evaluator::ssa::SsaProgramArtifact;
+use noirc_evaluator::ssa::{OptimizationLevel, SsaEvaluatorOptions, SsaProgramArtifact};
+use noirc_evaluator::{compile_ssa, create_program, generate_ssa, ErrorType};
+use noirc_frontend::ast::Visibility;
 use noirc_frontend::debug::build_debug_crate_file;
 use noirc_frontend::hir::def_map::{Contract, CrateDefMap};
 use noirc_frontend::hir::Context;
+use noirc_frontend::monomorphization::ast::Program;
 use noirc_frontend::monomorphization::{
     errors::MonomorphizationError, monomorphize, monomorphize_debug,
 };
 use noirc_frontend::node_interner::FuncId;
 use noirc_frontend::token::SecondaryAttribute;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::hash::Hash;
 use std::path::Path;
+use std::sync::OnceLock;
 use tracing::info;
 
 mod abi_gen;
@@ -36,6 +42,7 @@ use debug::filter_relevant_files;
 pub use contract::{CompiledContract, CompiledContractOutputs, ContractFunction};
 pub use debug::DebugFile;
 pub use noirc_frontend::graph::{CrateId, CrateName};
+pub use noirc_frontend::hir::def_collector::dc_mod::CHOSEN_FIELD;
 pub use program::CompiledProgram;
 
 const STD_CRATE_NAME: &str = "std";
@@ -56,6 +63,11 @@ pub struct CompileOptions {
     #[arg(long, value_parser = parse_expression_width)]
     pub expression_width: Option<ExpressionWidth>,
 
+    /// The field to compile for, either `bn254` or `bls12_381`.
+    /// This has to be the field the compiler was built for, which is also the default.
+    #[arg(long, value_parser = parse_field)]
+    pub field: Option<String>,
+
     /// Generate ACIR with the target backend expression width.
     /// The default is to generate ACIR without a bound and split expressions after code generation.
     /// Activating this flag can sometimes provide optimizations for certain programs.
@@ -76,6 +88,14 @@ pub struct CompileOptions {
     #[arg(long, hide = true)]
     pub emit_ssa: bool,
 
+    /// Emit a JSON report of the time taken by each compilation pass, the size of the SSA IR
+    /// before and after it, and the number of opcodes in each generated ACIR and Brillig function.
+    /// The report will be dumped into the workspace target directory,
+    /// under `[compiled-package].stats.json`, or `[compiled-package]-[function].stats.json`
+    /// for each function of a contract.
+    #[arg(long)]
+    pub emit_pass_stats: bool,
+
     #[arg(long, hide = true)]
     pub show_brillig: bool,
 
@@ -126,11 +146,63 @@ pub struct CompileOptions {
     #[arg(long)]
     pub skip_underconstrained_check: bool,
 
+    /// Whether to optimize the program for a smaller bytecode size or for faster execution.
+    /// Accepts `size` or `speed`.
+    /// This decides which Brillig functions are inlined.
+    #[arg(short = 'O', long, value_parser = parse_optimization_level, default_value = "speed")]
+    pub optimization_level: OptimizationLevel,
+
     /// Setting to decide on an inlining strategy for brillig functions.
     /// A more aggressive inliner should generate larger programs but more optimized
     /// A less aggressive inliner should generate smaller programs
-    #[arg(long, hide = true, allow_hyphen_values = true, default_value_t = i64::MAX)]
-    pub inliner_aggressiveness: i64,
+    /// Defaults to the aggressiveness of the chosen optimization level.
+    #[arg(long, hide = true, allow_hyphen_values = true)]
+    pub inliner_aggressiveness: Option<i64>,
+}
+
+impl CompileOptions {
+    /// Whether these options ask for intermediate steps of the compilation to be shown,
+    /// meaning the program must be compiled again even if it hasn't changed.
+    pub fn requires_recompilation(&self) -> bool {
+        self.print_acir
+            || self.show_brillig
+            || self.force_brillig
+            || self.show_ssa
+            || self.emit_ssa
+            || self.emit_pass_stats
+    }
+
+    /// The options which can change the outcome of checking or compiling a program, to be hashed
+    /// when deciding whether a program which hasn't changed needs to be compiled again.
+    ///
+    /// Options which only control what is printed along the way are left out, as are those which
+    /// force the compilation to run regardless (see [`Self::requires_recompilation`]).
+    pub fn cache_key(&self) -> impl Hash {
+        let expression_width = self.expression_width.map(|width| match width {
+            ExpressionWidth::Unbounded => 0,
+            ExpressionWidth::Bounded { width } => width,
+        });
+        (
+            expression_width,
+            self.bounded_codegen,
+            self.disable_macros,
+            self.instrument_debug,
+            self.force_brillig,
+            self.skip_underconstrained_check,
+            self.optimization_level,
+            self.inliner_aggressiveness,
+        )
+    }
+
+    /// Whether the functions of a contract must be compiled one at a time, as the steps they
+    /// print or the files they write would otherwise be interleaved.
+    fn requires_sequential_compilation(&self) -> bool {
+        self.show_ssa
+            || self.show_brillig
+            || self.benchmark_codegen
+            || self.emit_ssa
+            || self.emit_pass_stats
+    }
 }
 
 pub fn parse_expression_width(input: &str) -> Result<ExpressionWidth, std::io::Error> {
@@ -149,6 +221,31 @@ pub fn parse_expression_width(input: &str) -> Result<ExpressionWidth, std::io::E
     }
 }
 
+/// The fields the compiler can be built for, each selected through the cargo feature of the same name.
+const SUPPORTED_FIELDS: [&str; 2] = ["bn254", "bls12_381"];
+
+pub fn parse_field(input: &str) -> Result<String, std::io::Error> {
+    use std::io::{Error, ErrorKind};
+    if !SUPPORTED_FIELDS.contains(&input) {
+        let expected = SUPPORTED_FIELDS.join(" or ");
+        return Err(Error::new(ErrorKind::InvalidInput, format!("has to be {expected}")));
+    }
+    if input != CHOSEN_FIELD {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "the compiler was built for {CHOSEN_FIELD}, rebuild it with the `{input}` feature to target {input}"
+            ),
+        ));
+    }
+    Ok(input.to_string())
+}
+
+pub fn parse_optimization_level(input: &str) -> Result<OptimizationLevel, std::io::Error> {
+    use std::io::{Error, ErrorKind};
+    input.parse().map_err(|err| Error::new(ErrorKind::InvalidInput, err))
+}
+
 #[derive(Debug)]
 pub enum CompileError {
     MonomorphizationError(MonomorphizationError),
@@ -418,37 +515,108 @@ fn has_errors(errors: &[FileDiagnostic], deny_warnings: bool) -> bool {
     }
 }
 
+/// The stack size of the threads which compile the functions of a contract, as code generation
+/// recurses deeply. This is the same as the one `nargo` runs the compiler with.
+const CONTRACT_COMPILATION_STACK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Returns the thread pool to compile the functions of a contract in, or `None` if they must be
+/// compiled one at a time on the current thread, as is the case where threads aren't supported.
+///
+/// The pool is built once and shared by all contracts. A dedicated pool is used rather than the
+/// one the caller may run in, whose threads may not have a large enough stack.
+fn contract_compilation_pool(options: &CompileOptions) -> Option<&'static rayon::ThreadPool> {
+    static POOL: OnceLock<Option<rayon::ThreadPool>> = OnceLock::new();
+
+    if options.requires_sequential_compilation() {
+        return None;
+    }
+    POOL.get_or_init(|| {
+        rayon::ThreadPoolBuilder::new().stack_size(CONTRACT_COMPILATION_STACK_SIZE).build().ok()
+    })
+    .as_ref()
+}
+
 /// Compile all of the functions associated with a Noir contract.
+///
+/// Monomorphization needs mutable access to the `context` so each function is converted into SSA
+/// one at a time, after which the functions are optimized and compiled into ACIR in parallel.
 fn compile_contract_inner(
     context: &mut Context,
     contract: Contract,
     options: &CompileOptions,
 ) -> Result<CompiledContract, ErrorsAndWarnings> {
-    let mut functions = Vec::new();
+    let ssa_evaluator_options = ssa_evaluator_options(context, options);
+
+    let mut generated_functions = Vec::new();
     let mut errors = Vec::new();
-    let mut warnings = Vec::new();
     for contract_function in &contract.functions {
         let function_id = contract_function.function_id;
-        let is_entry_point = contract_function.is_entry_point;
-
-        let name = context.function_name(&function_id).to_owned();
 
         // We assume that functions have already been type checked.
         // This is the exact same assumption that compile_no_check makes.
         // If it is not an entry-point point, we can then just skip the
         // compilation step. It will also not be added to the ABI.
-        if !is_entry_point {
+        if !contract_function.is_entry_point {
             continue;
         }
 
-        let function = match compile_no_check(context, options, function_id, None, true) {
-            Ok(function) => function,
+        let generated = monomorphize_function(context, options, function_id).and_then(|program| {
+            let hash = fxhash::hash64(&program);
+            let return_visibility = program.return_visibility;
+            let (ssa, error_types) = generate_ssa(program, &ssa_evaluator_options)?;
+            Ok((function_id, hash, return_visibility, ssa, error_types))
+        });
+        match generated {
+            Ok(generated) => generated_functions.push(generated),
+            Err(new_error) => errors.push(FileDiagnostic::from(new_error)),
+        }
+    }
+
+    // The SSA programs hold no frontend types so they can be compiled independently of each other.
+    // Collecting the results preserves the order of the functions, keeping the artifact deterministic.
+    let (generated_functions, ssa_programs): (Vec<_>, Vec<_>) = generated_functions
+        .into_iter()
+        .map(|(function_id, hash, return_visibility, ssa, error_types)| {
+            // Each function reports its pass stats to its own file rather than overwriting the others.
+            let function_name = context.function_name(&function_id);
+            let function_options = SsaEvaluatorOptions {
+                emit_pass_stats: ssa_evaluator_options.emit_pass_stats.as_ref().map(|path| {
+                    let mut file_name = path.file_stem().unwrap_or_default().to_owned();
+                    file_name.push(format!("-{function_name}"));
+                    path.with_file_name(file_name)
+                }),
+                ..ssa_evaluator_options.clone()
+            };
+            ((function_id, hash, return_visibility, error_types), (ssa, function_options))
+        })
+        .unzip();
+    let compile = |(ssa, function_options)| compile_ssa(ssa, &function_options);
+    let artifacts: Vec<_> = match contract_compilation_pool(options) {
+        Some(pool) => pool.install(|| ssa_programs.into_par_iter().map(compile).collect()),
+        None => ssa_programs.into_iter().map(compile).collect(),
+    };
+
+    let mut functions = Vec::new();
+    let mut warnings = Vec::new();
+    for ((function_id, hash, return_visibility, error_types), artifact) in
+        generated_functions.into_iter().zip(artifacts)
+    {
+        let function = match artifact {
+            Ok(artifact) => into_compiled_program(
+                context,
+                function_id,
+                hash,
+                return_visibility,
+                error_types,
+                artifact,
+            ),
             Err(new_error) => {
                 errors.push(FileDiagnostic::from(new_error));
                 continue;
             }
         };
         warnings.extend(function.warnings);
+        let name = context.function_name(&function_id).to_owned();
         let modifiers = context.def_interner.function_modifiers(&function_id);
 
         let custom_attributes = modifiers
@@ -550,33 +718,53 @@ pub fn compile_no_check(
     cached_program: Option<CompiledProgram>,
     force_compile: bool,
 ) -> Result<CompiledProgram, CompileError> {
-    let program = if options.instrument_debug {
-        monomorphize_debug(main_function, &mut context.def_interner, &context.debug_instrumenter)?
-    } else {
-        monomorphize(main_function, &mut context.def_interner)?
-    };
+    let program = monomorphize_function(context, options, main_function)?;
 
     let hash = fxhash::hash64(&program);
     let hashes_match = cached_program.as_ref().map_or(false, |program| program.hash == hash);
-    if options.show_monomorphized {
-        println!("{program}");
-    }
 
     // If user has specified that they want to see intermediate steps printed then we should
     // force compilation even if the program hasn't changed.
-    let force_compile = force_compile
-        || options.print_acir
-        || options.show_brillig
-        || options.force_brillig
-        || options.show_ssa
-        || options.emit_ssa;
+    let force_compile = force_compile || options.requires_recompilation();
 
     if !force_compile && hashes_match {
         info!("Program matches existing artifact, returning early");
         return Ok(cached_program.expect("cache must exist for hashes to match"));
     }
     let return_visibility = program.return_visibility;
-    let ssa_evaluator_options = noirc_evaluator::ssa::SsaEvaluatorOptions {
+    let ssa_evaluator_options = ssa_evaluator_options(context, options);
+
+    let (artifact, error_types) = create_program(program, &ssa_evaluator_options)?;
+
+    Ok(into_compiled_program(
+        context,
+        main_function,
+        hash,
+        return_visibility,
+        error_types,
+        artifact,
+    ))
+}
+
+fn monomorphize_function(
+    context: &mut Context,
+    options: &CompileOptions,
+    main_function: FuncId,
+) -> Result<Program, CompileError> {
+    let program = if options.instrument_debug {
+        monomorphize_debug(main_function, &mut context.def_interner, &context.debug_instrumenter)?
+    } else {
+        monomorphize(main_function, &mut context.def_interner)?
+    };
+
+    if options.show_monomorphized {
+        println!("{program}");
+    }
+    Ok(program)
+}
+
+fn ssa_evaluator_options(context: &Context, options: &CompileOptions) -> SsaEvaluatorOptions {
+    SsaEvaluatorOptions {
         enable_ssa_logging: options.show_ssa,
         enable_brillig_logging: options.show_brillig,
         force_brillig_output: options.force_brillig,
@@ -587,17 +775,42 @@ pub fn compile_no_check(
             ExpressionWidth::default()
         },
         emit_ssa: if options.emit_ssa { Some(context.package_build_path.clone()) } else { None },
+        emit_pass_stats: if options.emit_pass_stats {
+            Some(context.package_build_path.clone())
+        } else {
+            None
+        },
         skip_underconstrained_check: options.skip_underconstrained_check,
-        inliner_aggressiveness: options.inliner_aggressiveness,
-    };
-
-    let SsaProgramArtifact { program, debug, warnings, names, brillig_names, error_types, .. } =
-        create_program(program, &ssa_evaluator_options)?;
+        optimization_level: options.optimization_level,
+        inliner_aggressiveness: options
+            .inliner_aggressiveness
+            .unwrap_or(options.optimization_level.default_inliner_aggressiveness()),
+    }
+}
 
+/// Builds the ABI of the compiled function, merging the dynamic error types found during
+/// SSA generation with the error messages generated along with the ACIR.
+fn into_compiled_program(
+    context: &Context,
+    main_function: FuncId,
+    hash: u64,
+    return_visibility: Visibility,
+    mut error_types: BTreeMap<ErrorSelector, ErrorType>,
+    artifact: SsaProgramArtifact,
+) -> CompiledProgram {
+    let SsaProgramArtifact {
+        program, debug, warnings, names, brillig_names, error_messages, ..
+    } = artifact;
+
+    error_types.extend(
+        error_messages
+            .into_iter()
+            .map(|(selector, message)| (selector, ErrorType::String(message))),
+    );
     let abi = abi_gen::gen_abi(context, &main_function, return_visibility, error_types);
     let file_map = filter_relevant_files(&debug, &context.file_manager);
 
-    Ok(CompiledProgram {
+    CompiledProgram {
         hash,
         program,
         debug,
@@ -607,5 +820,5 @@ pub fn compile_no_check(
         warnings,
         names,
         brillig_names,
-    })
+    }
 }
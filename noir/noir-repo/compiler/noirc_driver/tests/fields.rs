@@ -0,0 +1,12 @@
+use noirc_driver::{parse_field, CHOSEN_FIELD};
+
+#[test]
+fn only_accepts_the_field_the_compiler_was_built_for() {
+    assert_eq!(parse_field(CHOSEN_FIELD).unwrap(), CHOSEN_FIELD);
+
+    let other_field = if CHOSEN_FIELD == "bn254" { "bls12_381" } else { "bn254" };
+    let error = parse_field(other_field).unwrap_err();
+    assert!(error.to_string().contains(&format!("the compiler was built for {CHOSEN_FIELD}")));
+
+    assert!(parse_field("goldilocks").is_err());
+}
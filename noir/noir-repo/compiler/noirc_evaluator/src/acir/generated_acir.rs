@@ -63,8 +63,8 @@ pub(crate) struct GeneratedAcir<F: AcirField> {
     /// Correspondence between an opcode index and the error message associated with it.
     pub(crate) assertion_payloads: BTreeMap<OpcodeLocation, AssertionPayload<F>>,
 
-    /// Correspondence between error selectors and types associated with them.
-    pub(crate) error_types: BTreeMap<ErrorSelector, ErrorType>,
+    /// Correspondence between error selectors and the messages of the string errors associated with them.
+    pub(crate) error_messages: BTreeMap<ErrorSelector, String>,
 
     pub(crate) warnings: Vec<SsaReport>,
 
@@ -632,8 +632,8 @@ impl<F: AcirField> GeneratedAcir<F> {
             return;
         }
 
-        for (error_selector, error_type) in generated_brillig.error_types.iter() {
-            self.record_error_type(*error_selector, error_type.clone());
+        for (error_selector, message) in generated_brillig.error_messages.iter() {
+            self.record_error_message(*error_selector, message.clone());
         }
 
         if inserted_func_before {
@@ -677,17 +677,16 @@ impl<F: AcirField> GeneratedAcir<F> {
         OpcodeLocation::Acir(self.opcodes.len() - 1)
     }
 
-    pub(crate) fn record_error_type(&mut self, selector: ErrorSelector, typ: ErrorType) {
-        self.error_types.insert(selector, typ);
+    pub(crate) fn record_error_message(&mut self, selector: ErrorSelector, message: String) {
+        self.error_messages.insert(selector, message);
     }
 
     pub(crate) fn generate_assertion_message_payload(
         &mut self,
         message: String,
     ) -> AssertionPayload<F> {
-        let error_type = ErrorType::String(message);
-        let error_selector = error_type.selector();
-        self.record_error_type(error_selector, error_type);
+        let error_selector = ErrorType::String(message.clone()).selector();
+        self.record_error_message(error_selector, message);
         AssertionPayload { error_selector: error_selector.as_u64(), payload: Vec::new() }
     }
 }
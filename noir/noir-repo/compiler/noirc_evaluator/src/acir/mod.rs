@@ -9,13 +9,12 @@ use acvm::acir::{
     circuit::{
         brillig::{BrilligBytecode, BrilligFunctionId},
         opcodes::{AcirFunctionId, BlockType},
-        AssertionPayload, ErrorSelector, ExpressionWidth, OpcodeLocation,
+        AssertionPayload, ExpressionWidth, OpcodeLocation,
     },
     native_types::Witness,
     BlackBoxFunc,
 };
 use acvm::{acir::circuit::opcodes::BlockId, acir::AcirField, FieldElement};
-use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use iter_extended::{try_vecmap, vecmap};
 use noirc_frontend::monomorphization::ast::InlineType;
 
@@ -52,7 +51,15 @@ use crate::ssa::{
 use acir_variable::{AcirContext, AcirType, AcirVar};
 use generated_acir::BrilligStdlibFunc;
 pub(crate) use generated_acir::GeneratedAcir;
-use noirc_frontend::hir_def::types::Type as HirType;
+
+// Constant black box calls are only solved at compile time for bn254.
+cfg_if::cfg_if! {
+    if #[cfg(feature = "bls12_381")] {
+        type BlackBoxSolver = acvm::blackbox_solver::StubbedBlackBoxSolver;
+    } else {
+        type BlackBoxSolver = bn254_blackbox_solver::Bn254BlackBoxSolver;
+    }
+}
 
 #[derive(Default)]
 struct SharedContext<F> {
@@ -169,7 +176,7 @@ struct Context<'a> {
     current_side_effects_enabled_var: AcirVar,
 
     /// Manages and builds the `AcirVar`s to which the converted SSA values refer.
-    acir_context: AcirContext<FieldElement, Bn254BlackBoxSolver>,
+    acir_context: AcirContext<FieldElement, BlackBoxSolver>,
 
     /// Track initialized acir dynamic arrays
     ///
@@ -292,12 +299,8 @@ impl AcirValue {
     }
 }
 
-pub(crate) type Artifacts = (
-    Vec<GeneratedAcir<FieldElement>>,
-    Vec<BrilligBytecode<FieldElement>>,
-    Vec<String>,
-    BTreeMap<ErrorSelector, HirType>,
-);
+pub(crate) type Artifacts =
+    (Vec<GeneratedAcir<FieldElement>>, Vec<BrilligBytecode<FieldElement>>, Vec<String>);
 
 impl Ssa {
     #[tracing::instrument(level = "trace", skip_all)]
@@ -355,7 +358,7 @@ impl Ssa {
             .map(|brillig| (BrilligBytecode { bytecode: brillig.byte_code }, brillig.name))
             .unzip();
 
-        Ok((acirs, brillig_bytecode, brillig_names, self.error_selector_to_type))
+        Ok((acirs, brillig_bytecode, brillig_names))
     }
 }
 
@@ -3020,7 +3023,7 @@ mod test {
 
         let ssa = builder.finish();
 
-        let (acir_functions, _, _, _) = ssa
+        let (acir_functions, _, _) = ssa
             .into_acir(&Brillig::default(), ExpressionWidth::default())
             .expect("Should compile manually written SSA into ACIR");
         // Expected result:
@@ -3125,7 +3128,7 @@ mod test {
 
         let ssa = builder.finish();
 
-        let (acir_functions, _, _, _) = ssa
+        let (acir_functions, _, _) = ssa
             .into_acir(&Brillig::default(), ExpressionWidth::default())
             .expect("Should compile manually written SSA into ACIR");
         // The expected result should look very similar to the above test expect that the input witnesses of the `Call`
@@ -3225,7 +3228,7 @@ mod test {
 
         let ssa = builder.finish();
 
-        let (acir_functions, _, _, _) = ssa
+        let (acir_functions, _, _) = ssa
             .into_acir(&Brillig::default(), ExpressionWidth::default())
             .expect("Should compile manually written SSA into ACIR");
 
@@ -3349,7 +3352,7 @@ mod test {
         let ssa = builder.finish();
         let brillig = ssa.to_brillig(false);
 
-        let (acir_functions, brillig_functions, _, _) = ssa
+        let (acir_functions, brillig_functions, _) = ssa
             .into_acir(&brillig, ExpressionWidth::default())
             .expect("Should compile manually written SSA into ACIR");
 
@@ -3413,7 +3416,7 @@ mod test {
 
         // The Brillig bytecode we insert for the stdlib is hardcoded so we do not need to provide any
         // Brillig artifacts to the ACIR gen pass.
-        let (acir_functions, brillig_functions, _, _) = ssa
+        let (acir_functions, brillig_functions, _) = ssa
             .into_acir(&Brillig::default(), ExpressionWidth::default())
             .expect("Should compile manually written SSA into ACIR");
 
@@ -3487,7 +3490,7 @@ mod test {
         let brillig = ssa.to_brillig(false);
         println!("{}", ssa);
 
-        let (acir_functions, brillig_functions, _, _) = ssa
+        let (acir_functions, brillig_functions, _) = ssa
             .into_acir(&brillig, ExpressionWidth::default())
             .expect("Should compile manually written SSA into ACIR");
 
@@ -3575,7 +3578,7 @@ mod test {
         let brillig = ssa.to_brillig(false);
         println!("{}", ssa);
 
-        let (acir_functions, brillig_functions, _, _) = ssa
+        let (acir_functions, brillig_functions, _) = ssa
             .into_acir(&brillig, ExpressionWidth::default())
             .expect("Should compile manually written SSA into ACIR");
 
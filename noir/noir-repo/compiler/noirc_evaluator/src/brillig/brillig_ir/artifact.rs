@@ -3,7 +3,6 @@ use acvm::acir::circuit::ErrorSelector;
 use std::collections::{BTreeMap, HashMap};
 
 use crate::ssa::ir::{basic_block::BasicBlockId, dfg::CallStack, function::FunctionId};
-use crate::ErrorType;
 
 use super::procedures::ProcedureId;
 
@@ -25,7 +24,7 @@ pub(crate) enum BrilligParameter {
 pub(crate) struct GeneratedBrillig<F> {
     pub(crate) byte_code: Vec<BrilligOpcode<F>>,
     pub(crate) locations: BTreeMap<OpcodeLocation, CallStack>,
-    pub(crate) error_types: BTreeMap<ErrorSelector, ErrorType>,
+    pub(crate) error_messages: BTreeMap<ErrorSelector, String>,
     pub(crate) name: String,
     pub(crate) procedure_locations: HashMap<ProcedureId, (OpcodeLocation, OpcodeLocation)>,
 }
@@ -35,7 +34,8 @@ pub(crate) struct GeneratedBrillig<F> {
 /// It includes the bytecode of the function and all the metadata that allows linking with other functions.
 pub(crate) struct BrilligArtifact<F> {
     pub(crate) byte_code: Vec<BrilligOpcode<F>>,
-    pub(crate) error_types: BTreeMap<ErrorSelector, ErrorType>,
+    /// The messages of the string errors the bytecode can revert with.
+    pub(crate) error_messages: BTreeMap<ErrorSelector, String>,
     /// The set of jumps that need to have their locations
     /// resolved.
     unresolved_jumps: Vec<(JumpInstructionPosition, UnresolvedJumpLocation)>,
@@ -156,7 +156,7 @@ impl<F: Clone + std::fmt::Debug> BrilligArtifact<F> {
         GeneratedBrillig {
             byte_code: self.byte_code,
             locations: self.locations,
-            error_types: self.error_types,
+            error_messages: self.error_messages,
             name: self.name,
             procedure_locations: self.procedure_locations,
         }
@@ -176,8 +176,8 @@ impl<F: Clone + std::fmt::Debug> BrilligArtifact<F> {
         // Add the unresolved jumps of the linked function to this artifact.
         self.add_unresolved_jumps_and_calls(obj);
 
-        for (error_selector, error_type) in &obj.error_types {
-            self.error_types.insert(*error_selector, error_type.clone());
+        for (error_selector, message) in &obj.error_messages {
+            self.error_messages.insert(*error_selector, message.clone());
         }
 
         self.byte_code.append(&mut obj.byte_code.clone());
@@ -281,9 +281,8 @@ impl<F: AcirField + DebugToString, Registers: RegisterAllocator> BrilligContext<
         if self.can_call_procedures {
             self.call_revert_with_string_procedure(revert_string);
         } else {
-            let error_type = ErrorType::String(revert_string);
-            let error_selector = error_type.selector();
-            self.obj.error_types.insert(error_selector, error_type);
+            let error_selector = ErrorType::String(revert_string.clone()).selector();
+            self.obj.error_messages.insert(error_selector, revert_string);
             self.indirect_const_instruction(
                 ReservedRegisters::free_memory_pointer(),
                 64,
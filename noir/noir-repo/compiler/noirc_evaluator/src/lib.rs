@@ -9,8 +9,8 @@ mod acir;
 pub mod brillig;
 pub mod ssa;
 
-pub use ssa::create_program;
 pub use ssa::ir::instruction::ErrorType;
+pub use ssa::{compile_ssa, create_program, generate_ssa, SsaProgram};
 
 /// Trims leading whitespace from each line of the input string, according to
 /// how much leading whitespace there is on the first non-empty line.
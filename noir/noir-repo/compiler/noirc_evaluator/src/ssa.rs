@@ -12,6 +12,7 @@ use std::{
     fs::File,
     io::Write,
     path::{Path, PathBuf},
+    time::Instant,
 };
 
 use crate::errors::{RuntimeError, SsaReport};
@@ -27,11 +28,13 @@ use acvm::{
 };
 
 use ir::instruction::ErrorType;
+use iter_extended::vecmap;
 use noirc_errors::debug_info::{DebugFunctions, DebugInfo, DebugTypes, DebugVariables};
 
 use noirc_frontend::ast::Visibility;
-use noirc_frontend::{hir_def::function::FunctionSignature, monomorphization::ast::Program};
+use noirc_frontend::monomorphization::ast::Program;
 use ssa_gen::Ssa;
+use stats::{CompilationStats, SsaSize};
 use tracing::{span, Level};
 
 use crate::acir::{Artifacts, GeneratedAcir};
@@ -43,7 +46,9 @@ mod opt;
 #[cfg(test)]
 pub(crate) mod parser;
 pub mod ssa_gen;
+mod stats;
 
+#[derive(Clone)]
 pub struct SsaEvaluatorOptions {
     /// Emit debug information for the intermediate SSA IR
     pub enable_ssa_logging: bool,
@@ -62,13 +67,76 @@ pub struct SsaEvaluatorOptions {
     /// Dump the unoptimized SSA to the supplied path if it exists
     pub emit_ssa: Option<PathBuf>,
 
+    /// Dump a JSON report of the time taken and the SSA size after each pass,
+    /// along with the opcode count of each generated function, to the supplied path if it exists
+    pub emit_pass_stats: Option<PathBuf>,
+
     /// Skip the check for under constrained values
     pub skip_underconstrained_check: bool,
 
+    /// Whether the optimizer should favor smaller bytecode or faster execution
+    /// when a pass has to trade one off against the other.
+    pub optimization_level: OptimizationLevel,
+
     /// The higher the value, the more inlined brillig functions will be.
     pub inliner_aggressiveness: i64,
 }
 
+/// Decides how the size vs. speed trade-off of inlining Brillig functions is resolved: whether the
+/// inliner keeps a function as a separate call or duplicates its body at every call site.
+/// The inliner's cost model is consulted at either level; no other pass depends on it so far.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptimizationLevel {
+    /// Prefer smaller programs: functions are only inlined when that is estimated to not
+    /// grow the bytecode, or when they are called from hot loops.
+    Size,
+    /// Prefer faster programs: functions are inlined unless that duplicates a lot of bytecode,
+    /// and calls from loops are weighted more than at `Size`.
+    #[default]
+    Speed,
+}
+
+impl OptimizationLevel {
+    /// The inliner aggressiveness to use when none has been supplied explicitly,
+    /// which is how much an inlined function may grow the estimated bytecode size.
+    pub fn default_inliner_aggressiveness(self) -> i64 {
+        match self {
+            OptimizationLevel::Size => 0,
+            OptimizationLevel::Speed => 1000,
+        }
+    }
+
+    /// How much weight the inliner gives to the call overhead saved on each estimated
+    /// execution of a call site, relative to the bytecode size it would add.
+    pub(crate) fn call_frequency_weight(self) -> i64 {
+        match self {
+            OptimizationLevel::Size => 1,
+            OptimizationLevel::Speed => 4,
+        }
+    }
+}
+
+impl std::str::FromStr for OptimizationLevel {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "size" => Ok(OptimizationLevel::Size),
+            "speed" => Ok(OptimizationLevel::Speed),
+            _ => Err(format!("unknown optimization level `{input}`, expected `size` or `speed`")),
+        }
+    }
+}
+
+impl std::fmt::Display for OptimizationLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptimizationLevel::Size => write!(f, "size"),
+            OptimizationLevel::Speed => write!(f, "speed"),
+        }
+    }
+}
+
 pub(crate) struct ArtifactsAndWarnings(Artifacts, Vec<SsaReport>);
 
 /// Optimize the given program by converting it into SSA
@@ -77,24 +145,26 @@ pub(crate) struct ArtifactsAndWarnings(Artifacts, Vec<SsaReport>);
 /// An ACIR program is made up of both ACIR functions
 /// and Brillig functions for unconstrained execution.
 pub(crate) fn optimize_into_acir(
-    program: Program,
+    ssa: Ssa,
     options: &SsaEvaluatorOptions,
 ) -> Result<ArtifactsAndWarnings, RuntimeError> {
     let ssa_gen_span = span!(Level::TRACE, "ssa_generation");
     let ssa_gen_span_guard = ssa_gen_span.enter();
 
-    let mut ssa = SsaBuilder::new(
-        program,
+    let (mut ssa, mut stats) = SsaBuilder::new(
+        ssa,
         options.enable_ssa_logging,
-        options.force_brillig_output,
         options.print_codegen_timings,
-        &options.emit_ssa,
-    )?
+        options.emit_pass_stats.is_some(),
+    )
     .run_pass(Ssa::defunctionalize, "After Defunctionalization:")
     .run_pass(Ssa::remove_paired_rc, "After Removing Paired rc_inc & rc_decs:")
     .run_pass(Ssa::separate_runtime, "After Runtime Separation:")
     .run_pass(Ssa::resolve_is_unconstrained, "After Resolving IsUnconstrained:")
-    .run_pass(|ssa| ssa.inline_functions(options.inliner_aggressiveness), "After Inlining (1st):")
+    .run_pass(
+        |ssa| ssa.inline_functions(options.inliner_aggressiveness, options.optimization_level),
+        "After Inlining (1st):",
+    )
     // Run mem2reg with the CFG separated into blocks
     .run_pass(Ssa::mem2reg, "After Mem2Reg (1st):")
     .run_pass(Ssa::simplify_cfg, "After Simplifying (1st):")
@@ -105,6 +175,8 @@ pub(crate) fn optimize_into_acir(
     )?
     .try_run_pass(Ssa::unroll_loops_iteratively, "After Unrolling:")?
     .run_pass(Ssa::simplify_cfg, "After Simplifying (2nd):")
+    // Redundant computations are removed across blocks before they're merged into one by flattening.
+    .run_pass(Ssa::global_value_numbering, "After Global Value Numbering:")
     .run_pass(Ssa::flatten_cfg, "After Flattening:")
     .run_pass(Ssa::remove_bit_shifts, "After Removing Bit Shifts:")
     // Run mem2reg once more with the flattened CFG to catch any remaining loads/stores
@@ -114,7 +186,12 @@ pub(crate) fn optimize_into_acir(
     // This pass must come immediately following `mem2reg` as the succeeding passes
     // may create an SSA which inlining fails to handle.
     .run_pass(
-        |ssa| ssa.inline_functions_with_no_predicates(options.inliner_aggressiveness),
+        |ssa| {
+            ssa.inline_functions_with_no_predicates(
+                options.inliner_aggressiveness,
+                options.optimization_level,
+            )
+        },
         "After Inlining (2nd):",
     )
     .run_pass(Ssa::remove_if_else, "After Remove IfElse:")
@@ -129,31 +206,49 @@ pub(crate) fn optimize_into_acir(
     let ssa_level_warnings = if options.skip_underconstrained_check {
         vec![]
     } else {
-        time("After Check for Underconstrained Values", options.print_codegen_timings, || {
-            ssa.check_for_underconstrained_values()
-        })
+        time(
+            "After Check for Underconstrained Values",
+            options.print_codegen_timings,
+            stats.as_mut(),
+            || ssa.check_for_underconstrained_values(),
+        )
     };
 
     drop(ssa_gen_span_guard);
 
-    let brillig = time("SSA to Brillig", options.print_codegen_timings, || {
+    let brillig = time("SSA to Brillig", options.print_codegen_timings, stats.as_mut(), || {
         ssa.to_brillig(options.enable_brillig_logging)
     });
 
-    let artifacts = time("SSA to ACIR", options.print_codegen_timings, || {
+    let artifacts = time("SSA to ACIR", options.print_codegen_timings, stats.as_mut(), || {
         ssa.into_acir(&brillig, options.expression_width)
     })?;
+
+    if let (Some(mut stats), Some(emit_pass_stats)) = (stats, &options.emit_pass_stats) {
+        let (generated_acirs, generated_brillig, brillig_function_names) = &artifacts;
+        stats.add_artifacts(generated_acirs, generated_brillig, brillig_function_names);
+        emit_json(&stats, &emit_pass_stats.with_extension("stats.json"));
+    }
+
     Ok(ArtifactsAndWarnings(artifacts, ssa_level_warnings))
 }
 
 // Helper to time SSA passes
-fn time<T>(name: &str, print_timings: bool, f: impl FnOnce() -> T) -> T {
-    let start_time = chrono::Utc::now().time();
+fn time<T>(
+    name: &str,
+    print_timings: bool,
+    stats: Option<&mut CompilationStats>,
+    f: impl FnOnce() -> T,
+) -> T {
+    let start_time = Instant::now();
     let result = f();
+    let duration = start_time.elapsed();
 
     if print_timings {
-        let end_time = chrono::Utc::now().time();
-        println!("{name}: {} ms", (end_time - start_time).num_milliseconds());
+        println!("{name}: {} ms", duration.as_millis());
+    }
+    if let Some(stats) = stats {
+        stats.add_step(name, duration);
     }
 
     result
@@ -168,14 +263,15 @@ pub struct SsaProgramArtifact {
     pub main_return_witnesses: Vec<Witness>,
     pub names: Vec<String>,
     pub brillig_names: Vec<String>,
-    pub error_types: BTreeMap<ErrorSelector, ErrorType>,
+    /// The messages of the string errors which the program can fail with.
+    ///
+    /// Errors with dynamic payloads are not included, as their types are only known to the
+    /// frontend. These are returned from [`generate_ssa`] instead.
+    pub error_messages: BTreeMap<ErrorSelector, String>,
 }
 
 impl SsaProgramArtifact {
-    fn new(
-        unconstrained_functions: Vec<BrilligBytecode<FieldElement>>,
-        error_types: BTreeMap<ErrorSelector, ErrorType>,
-    ) -> Self {
+    fn new(unconstrained_functions: Vec<BrilligBytecode<FieldElement>>) -> Self {
         let program = AcirProgram { functions: Vec::default(), unconstrained_functions };
         Self {
             program,
@@ -185,7 +281,7 @@ impl SsaProgramArtifact {
             main_return_witnesses: Vec::default(),
             names: Vec::default(),
             brillig_names: Vec::default(),
-            error_types,
+            error_messages: BTreeMap::default(),
         }
     }
 
@@ -198,9 +294,7 @@ impl SsaProgramArtifact {
             self.main_return_witnesses = circuit_artifact.return_witnesses;
         }
         self.names.push(circuit_artifact.name);
-        // Acir and brillig both generate new error types, so we need to merge them
-        // With the ones found during ssa generation.
-        self.error_types.extend(circuit_artifact.error_types);
+        self.error_messages.extend(circuit_artifact.error_messages);
     }
 
     fn add_warnings(&mut self, mut warnings: Vec<SsaReport>) {
@@ -208,24 +302,72 @@ impl SsaProgramArtifact {
     }
 }
 
-/// Compiles the [`Program`] into [`ACIR``][acvm::acir::circuit::Program].
+/// A program which has been converted into SSA but not yet optimized.
 ///
-/// The output ACIR is backend-agnostic and so must go through a transformation pass before usage in proof generation.
+/// Unlike the monomorphized [`Program`] it was generated from, this holds no types from the
+/// frontend, so it can be sent to another thread to be compiled by [`compile_ssa`].
+pub struct SsaProgram {
+    ssa: Ssa,
+    /// The visibility and number of fields of each parameter of each ACIR function.
+    parameters: Vec<Vec<(Visibility, usize)>>,
+    debug_variables: DebugVariables,
+    debug_functions: DebugFunctions,
+    debug_types: DebugTypes,
+}
+
+/// Converts the monomorphized [`Program`] into SSA, ready to be compiled by [`compile_ssa`].
+///
+/// The types of the errors which the program raises with dynamic payloads are returned alongside
+/// it, as they can only be represented with the frontend's types.
 #[tracing::instrument(level = "trace", skip_all)]
-pub fn create_program(
+pub fn generate_ssa(
     program: Program,
     options: &SsaEvaluatorOptions,
-) -> Result<SsaProgramArtifact, RuntimeError> {
+) -> Result<(SsaProgram, BTreeMap<ErrorSelector, ErrorType>), RuntimeError> {
     let debug_variables = program.debug_variables.clone();
     let debug_types = program.debug_types.clone();
     let debug_functions = program.debug_functions.clone();
 
-    let func_sigs = program.function_signatures.clone();
+    let parameters = vecmap(&program.function_signatures, |(parameters, _)| {
+        vecmap(parameters, |(pattern, typ, visibility)| {
+            (*visibility, typ.field_count(&pattern.location()) as usize)
+        })
+    });
+
+    let (ssa, error_types) = ssa_gen::generate_ssa(program, options.force_brillig_output)?;
+    if let Some(emit_ssa) = &options.emit_ssa {
+        let mut emit_ssa_dir = emit_ssa.clone();
+        // We expect the full package artifact path to be passed in here,
+        // and attempt to create the target directory if it does not exist.
+        emit_ssa_dir.pop();
+        create_named_dir(emit_ssa_dir.as_ref(), "target");
+        let ssa_path = emit_ssa.with_extension("ssa.json");
+        write_to_file(&serde_json::to_vec(&ssa).unwrap(), &ssa_path);
+    }
+
+    let error_types = error_types
+        .into_iter()
+        .map(|(selector, hir_type)| (selector, ErrorType::Dynamic(hir_type)))
+        .collect();
+
+    let program = SsaProgram { ssa, parameters, debug_variables, debug_functions, debug_types };
+    Ok((program, error_types))
+}
+
+/// Compiles the [`SsaProgram`] into [`ACIR``][acvm::acir::circuit::Program].
+///
+/// The output ACIR is backend-agnostic and so must go through a transformation pass before usage in proof generation.
+#[tracing::instrument(level = "trace", skip_all)]
+pub fn compile_ssa(
+    program: SsaProgram,
+    options: &SsaEvaluatorOptions,
+) -> Result<SsaProgramArtifact, RuntimeError> {
+    let SsaProgram { ssa, parameters, debug_variables, debug_functions, debug_types } = program;
 
     let ArtifactsAndWarnings(
-        (generated_acirs, generated_brillig, brillig_function_names, error_types),
+        (generated_acirs, generated_brillig, brillig_function_names),
         ssa_level_warnings,
-    ) = optimize_into_acir(program, options)?;
+    ) = optimize_into_acir(ssa, options)?;
     if options.force_brillig_output {
         assert_eq!(
             generated_acirs.len(),
@@ -235,26 +377,21 @@ pub fn create_program(
     } else {
         assert_eq!(
             generated_acirs.len(),
-            func_sigs.len(),
+            parameters.len(),
             "The generated ACIRs should match the supplied function signatures"
         );
     }
 
-    let error_types = error_types
-        .into_iter()
-        .map(|(selector, hir_type)| (selector, ErrorType::Dynamic(hir_type)))
-        .collect();
-
-    let mut program_artifact = SsaProgramArtifact::new(generated_brillig, error_types);
+    let mut program_artifact = SsaProgramArtifact::new(generated_brillig);
 
     // Add warnings collected at the Ssa stage
     program_artifact.add_warnings(ssa_level_warnings);
     // For setting up the ABI we need separately specify main's input and return witnesses
     let mut is_main = true;
-    for (acir, func_sig) in generated_acirs.into_iter().zip(func_sigs) {
+    for (acir, parameters) in generated_acirs.into_iter().zip(parameters) {
         let circuit_artifact = convert_generated_acir_into_circuit(
             acir,
-            func_sig,
+            &parameters,
             // TODO: get rid of these clones
             debug_variables.clone(),
             debug_functions.clone(),
@@ -268,6 +405,20 @@ pub fn create_program(
     Ok(program_artifact)
 }
 
+/// Compiles the [`Program`] into [`ACIR``][acvm::acir::circuit::Program], by converting it into SSA
+/// with [`generate_ssa`] then compiling it with [`compile_ssa`] on the current thread.
+///
+/// The types of the errors which the program raises with dynamic payloads are returned alongside
+/// the artifact, which only holds the string errors.
+pub fn create_program(
+    program: Program,
+    options: &SsaEvaluatorOptions,
+) -> Result<(SsaProgramArtifact, BTreeMap<ErrorSelector, ErrorType>), RuntimeError> {
+    let (ssa, error_types) = generate_ssa(program, options)?;
+    let artifact = compile_ssa(ssa, options)?;
+    Ok((artifact, error_types))
+}
+
 pub struct SsaCircuitArtifact {
     name: String,
     circuit: Circuit<FieldElement>,
@@ -275,12 +426,12 @@ pub struct SsaCircuitArtifact {
     warnings: Vec<SsaReport>,
     input_witnesses: Vec<Witness>,
     return_witnesses: Vec<Witness>,
-    error_types: BTreeMap<ErrorSelector, ErrorType>,
+    error_messages: BTreeMap<ErrorSelector, String>,
 }
 
 fn convert_generated_acir_into_circuit(
     mut generated_acir: GeneratedAcir<FieldElement>,
-    func_sig: FunctionSignature,
+    parameters: &[(Visibility, usize)],
     debug_variables: DebugVariables,
     debug_functions: DebugFunctions,
     debug_types: DebugTypes,
@@ -300,7 +451,7 @@ fn convert_generated_acir_into_circuit(
     } = generated_acir;
 
     let (public_parameter_witnesses, private_parameters) =
-        split_public_and_private_inputs(&func_sig, &input_witnesses);
+        split_public_and_private_inputs(parameters, &input_witnesses);
 
     let public_parameters = PublicInputs(public_parameter_witnesses);
     let return_values = PublicInputs(return_witnesses.iter().copied().collect());
@@ -352,13 +503,13 @@ fn convert_generated_acir_into_circuit(
         warnings,
         input_witnesses,
         return_witnesses,
-        error_types: generated_acir.error_types,
+        error_messages: generated_acir.error_messages,
     }
 }
 
 // Takes each function argument and partitions the circuit's inputs witnesses according to its visibility.
 fn split_public_and_private_inputs(
-    func_sig: &FunctionSignature,
+    parameters: &[(Visibility, usize)],
     input_witnesses: &[Witness],
 ) -> (BTreeSet<Witness>, BTreeSet<Witness>) {
     let mut idx = 0_usize;
@@ -366,18 +517,16 @@ fn split_public_and_private_inputs(
         return (BTreeSet::new(), BTreeSet::new());
     }
 
-    func_sig
-        .0
+    parameters
         .iter()
-        .map(|(pattern, typ, visibility)| {
-            let num_field_elements_needed = typ.field_count(&pattern.location()) as usize;
+        .map(|&(visibility, num_field_elements_needed)| {
             let witnesses = input_witnesses[idx..idx + num_field_elements_needed].to_vec();
             idx += num_field_elements_needed;
             (visibility, witnesses)
         })
         .fold((BTreeSet::new(), BTreeSet::new()), |mut acc, (vis, witnesses)| {
             // Split witnesses into sets based on their visibility.
-            if *vis == Visibility::Public {
+            if vis == Visibility::Public {
                 for witness in witnesses {
                     acc.0.insert(witness);
                 }
@@ -395,31 +544,23 @@ struct SsaBuilder {
     ssa: Ssa,
     print_ssa_passes: bool,
     print_codegen_timings: bool,
+    /// Statistics of the passes run so far, if they are being collected.
+    stats: Option<CompilationStats>,
 }
 
 impl SsaBuilder {
     fn new(
-        program: Program,
+        ssa: Ssa,
         print_ssa_passes: bool,
-        force_brillig_runtime: bool,
         print_codegen_timings: bool,
-        emit_ssa: &Option<PathBuf>,
-    ) -> Result<SsaBuilder, RuntimeError> {
-        let ssa = ssa_gen::generate_ssa(program, force_brillig_runtime)?;
-        if let Some(emit_ssa) = emit_ssa {
-            let mut emit_ssa_dir = emit_ssa.clone();
-            // We expect the full package artifact path to be passed in here,
-            // and attempt to create the target directory if it does not exist.
-            emit_ssa_dir.pop();
-            create_named_dir(emit_ssa_dir.as_ref(), "target");
-            let ssa_path = emit_ssa.with_extension("ssa.json");
-            write_to_file(&serde_json::to_vec(&ssa).unwrap(), &ssa_path);
-        }
-        Ok(SsaBuilder { print_ssa_passes, print_codegen_timings, ssa }.print("Initial SSA:"))
+        collect_stats: bool,
+    ) -> SsaBuilder {
+        let stats = collect_stats.then(CompilationStats::default);
+        SsaBuilder { print_ssa_passes, print_codegen_timings, ssa, stats }.print("Initial SSA:")
     }
 
-    fn finish(self) -> Ssa {
-        self.ssa
+    fn finish(self) -> (Ssa, Option<CompilationStats>) {
+        (self.ssa, self.stats)
     }
 
     /// Runs the given SSA pass and prints the SSA afterward if `print_ssa_passes` is true.
@@ -427,8 +568,10 @@ impl SsaBuilder {
     where
         F: FnOnce(Ssa) -> Ssa,
     {
-        self.ssa = time(msg, self.print_codegen_timings, || pass(self.ssa));
-        self.print(msg)
+        let size_before = self.stats.is_some().then(|| SsaSize::of(&self.ssa));
+        let start_time = Instant::now();
+        self.ssa = pass(self.ssa);
+        self.finish_pass(msg, start_time, size_before)
     }
 
     /// The same as `run_pass` but for passes that may fail
@@ -437,8 +580,22 @@ impl SsaBuilder {
         pass: fn(Ssa) -> Result<Ssa, RuntimeError>,
         msg: &str,
     ) -> Result<Self, RuntimeError> {
-        self.ssa = time(msg, self.print_codegen_timings, || pass(self.ssa))?;
-        Ok(self.print(msg))
+        let size_before = self.stats.is_some().then(|| SsaSize::of(&self.ssa));
+        let start_time = Instant::now();
+        self.ssa = pass(self.ssa)?;
+        Ok(self.finish_pass(msg, start_time, size_before))
+    }
+
+    /// Reports the time taken by the pass which just ran and prints the resulting SSA.
+    fn finish_pass(mut self, msg: &str, start_time: Instant, size_before: Option<SsaSize>) -> Self {
+        let duration = start_time.elapsed();
+        if self.print_codegen_timings {
+            println!("{msg}: {} ms", duration.as_millis());
+        }
+        if let (Some(stats), Some(size_before)) = (&mut self.stats, size_before) {
+            stats.add_pass(msg, duration, size_before, SsaSize::of(&self.ssa));
+        }
+        self.print(msg)
     }
 
     fn print(mut self, msg: &str) -> Self {
@@ -457,6 +614,13 @@ fn create_named_dir(named_dir: &Path, name: &str) -> PathBuf {
     PathBuf::from(named_dir)
 }
 
+fn emit_json<T: serde::Serialize>(value: &T, path: &Path) {
+    let mut dir = path.to_path_buf();
+    dir.pop();
+    create_named_dir(&dir, "target");
+    write_to_file(&serde_json::to_vec_pretty(value).unwrap(), path);
+}
+
 fn write_to_file(bytes: &[u8], path: &Path) {
     let display = path.display();
 
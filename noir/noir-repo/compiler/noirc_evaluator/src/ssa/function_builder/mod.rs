@@ -106,9 +106,15 @@ impl FunctionBuilder {
     }
 
     /// Consume the FunctionBuilder returning all the functions it has generated.
-    pub(crate) fn finish(mut self) -> Ssa {
+    pub(crate) fn finish(self) -> Ssa {
+        self.finish_with_error_types().0
+    }
+
+    /// Consume the FunctionBuilder returning all the functions it has generated,
+    /// along with the types of the dynamic errors recorded while generating them.
+    pub(crate) fn finish_with_error_types(mut self) -> (Ssa, BTreeMap<ErrorSelector, HirType>) {
         self.finished_functions.push(self.current_function);
-        Ssa::new(self.finished_functions, self.error_types)
+        (Ssa::new(self.finished_functions), self.error_types)
     }
 
     /// Add a parameter to the current function with the given parameter type.
@@ -0,0 +1,420 @@
+//! The global value numbering pass removes computations which are redundant across blocks.
+//!
+//! Whereas [constant folding][super::constant_folding] only deduplicates instructions within a
+//! single block, this pass walks the dominator tree of each function and reuses the results of
+//! an instruction in every block dominated by the block the instruction was first found in.
+//! Every path to a dominated block must go through the dominating one, so the results are
+//! guaranteed to already have been computed when the duplicate instruction would execute.
+//!
+//! The pass works as follows:
+//! - Instructions are assigned a value number by hashing the instruction with its inputs resolved.
+//!   Binary operations which are commutative have their operands put in a canonical order first,
+//!   so that e.g. `add v0, v1` and `add v1, v0` receive the same value number.
+//! - Instructions which depend on the side-effects predicate in ACIR are numbered together with
+//!   the predicate active at the point they are found, as is done in constant folding.
+//! - Loads are numbered together with a version of the memory. The version changes whenever memory
+//!   may have been written to: on stores and function calls, and when entering a block which can
+//!   be reached from its immediate dominator through a path writing to memory.
+//!
+//! Arrays in Brillig are reference counted and mutated in place when their reference count is one,
+//! so creating or updating arrays is never deduplicated for Brillig functions.
+use std::collections::HashSet;
+
+use acvm::{acir::AcirField, FieldElement};
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        cfg::ControlFlowGraph,
+        dfg::DataFlowGraph,
+        dom::DominatorTree,
+        function::Function,
+        instruction::{Binary, BinaryOp, Instruction, InstructionId},
+        post_order::PostOrder,
+        types::Type,
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+use fxhash::FxHashMap as HashMap;
+
+impl Ssa {
+    /// Removes instructions whose results were already computed in a dominating block.
+    ///
+    /// See [`gvn`][self] module for more information.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn global_value_numbering(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            function.global_value_numbering();
+        }
+        self
+    }
+}
+
+/// The value number of an instruction: the instruction with its inputs resolved,
+/// the predicate it is executed under (if it depends on it) and, for loads, the memory version.
+type ValueNumber = (Instruction, Option<ValueId>, Option<u32>);
+
+/// The value numbers available at the start of a block, inherited from its immediate dominator.
+type ValueTable = im::HashMap<ValueNumber, Vec<ValueId>>;
+
+impl Function {
+    pub(crate) fn global_value_numbering(&mut self) {
+        let cfg = ControlFlowGraph::with_function(self);
+        let post_order = PostOrder::with_function(self);
+        let dom_tree = DominatorTree::with_cfg_and_post_order(&cfg, &post_order);
+
+        let mut reverse_post_order = post_order.into_vec();
+        reverse_post_order.reverse();
+
+        // Children of each block in the dominator tree, in reverse post-order.
+        let mut dominated_blocks: HashMap<BasicBlockId, Vec<BasicBlockId>> = HashMap::default();
+        for block in &reverse_post_order {
+            if let Some(dominator) = dom_tree.immediate_dominator(*block) {
+                dominated_blocks.entry(dominator).or_default().push(*block);
+            }
+        }
+
+        let blocks_writing_memory: HashSet<BasicBlockId> = reverse_post_order
+            .iter()
+            .copied()
+            .filter(|block| {
+                self.dfg[*block]
+                    .instructions()
+                    .iter()
+                    .any(|instruction| writes_to_memory(&self.dfg, &self.dfg[*instruction]))
+            })
+            .collect();
+
+        let mut context =
+            Context { next_memory_version: 1, is_brillig: self.runtime().is_brillig() };
+
+        let mut stack = vec![(self.entry_block(), ValueTable::default(), 0)];
+        while let Some((block, table, memory_version)) = stack.pop() {
+            let (table, memory_version) =
+                context.number_block(&mut self.dfg, block, table, memory_version);
+
+            for dominated in dominated_blocks.remove(&block).unwrap_or_default().into_iter().rev() {
+                let memory_version =
+                    if may_write_memory_between(block, dominated, &cfg, &blocks_writing_memory) {
+                        context.new_memory_version()
+                    } else {
+                        memory_version
+                    };
+                stack.push((dominated, table.clone(), memory_version));
+            }
+        }
+    }
+}
+
+struct Context {
+    next_memory_version: u32,
+    is_brillig: bool,
+}
+
+impl Context {
+    /// Numbers each instruction of the block, removing those which have already been computed.
+    /// Returns the value numbers available to the blocks dominated by this one.
+    fn number_block(
+        &mut self,
+        dfg: &mut DataFlowGraph,
+        block: BasicBlockId,
+        mut table: ValueTable,
+        mut memory_version: u32,
+    ) -> (ValueTable, u32) {
+        let instructions = dfg[block].take_instructions();
+        let mut side_effects_enabled_var = dfg.make_constant(FieldElement::one(), Type::bool());
+
+        for instruction_id in instructions {
+            let instruction = Self::resolve_instruction(dfg, instruction_id);
+
+            if writes_to_memory(dfg, &instruction) {
+                memory_version = self.new_memory_version();
+            }
+
+            let value_number =
+                self.value_number(dfg, instruction, side_effects_enabled_var, memory_version);
+
+            if let Some(value_number) = value_number {
+                let results = dfg.instruction_results(instruction_id).to_vec();
+                if let Some(existing_results) = table.get(&value_number) {
+                    for (result, existing_result) in results.into_iter().zip(existing_results) {
+                        dfg.set_value_from_id(result, *existing_result);
+                    }
+                    continue;
+                }
+                table.insert(value_number, results);
+            }
+
+            if let Instruction::EnableSideEffectsIf { condition } = &dfg[instruction_id] {
+                side_effects_enabled_var = dfg.resolve(*condition);
+            }
+            dfg[block].instructions_mut().push(instruction_id);
+        }
+
+        (table, memory_version)
+    }
+
+    /// Returns the value number of the instruction if its results can be reused by later instructions.
+    fn value_number(
+        &self,
+        dfg: &DataFlowGraph,
+        instruction: Instruction,
+        side_effects_enabled_var: ValueId,
+        memory_version: u32,
+    ) -> Option<ValueNumber> {
+        match &instruction {
+            Instruction::Load { .. } => return Some((instruction, None, Some(memory_version))),
+            Instruction::MakeArray { .. } | Instruction::ArraySet { .. } if self.is_brillig => {
+                return None;
+            }
+            _ => (),
+        }
+
+        if !instruction.can_be_deduplicated(dfg, true) {
+            return None;
+        }
+
+        let predicate =
+            instruction.requires_acir_gen_predicate(dfg).then_some(side_effects_enabled_var);
+        Some((normalize_commutative_operands(instruction), predicate, None))
+    }
+
+    /// Fetches an [`Instruction`] by its [`InstructionId`] and fully resolves its inputs.
+    fn resolve_instruction(dfg: &DataFlowGraph, instruction_id: InstructionId) -> Instruction {
+        dfg[instruction_id].clone().map_values(|value| dfg.resolve(value))
+    }
+
+    fn new_memory_version(&mut self) -> u32 {
+        let version = self.next_memory_version;
+        self.next_memory_version += 1;
+        version
+    }
+}
+
+/// Puts the operands of commutative binary operations in a canonical order.
+fn normalize_commutative_operands(instruction: Instruction) -> Instruction {
+    match instruction {
+        Instruction::Binary(Binary { lhs, rhs, operator }) if is_commutative(operator) => {
+            let (lhs, rhs) = if lhs <= rhs { (lhs, rhs) } else { (rhs, lhs) };
+            Instruction::Binary(Binary { lhs, rhs, operator })
+        }
+        instruction => instruction,
+    }
+}
+
+fn is_commutative(operator: BinaryOp) -> bool {
+    use BinaryOp::*;
+    match operator {
+        Add | Mul | Eq | And | Or | Xor => true,
+        Sub | Div | Mod | Lt | Shl | Shr => false,
+    }
+}
+
+/// True if the instruction may change the value stored behind a reference.
+fn writes_to_memory(dfg: &DataFlowGraph, instruction: &Instruction) -> bool {
+    match instruction {
+        Instruction::Store { .. } => true,
+        Instruction::Call { func, .. } => !matches!(dfg[*func], Value::Intrinsic(_)),
+        _ => false,
+    }
+}
+
+/// True if there is a path from `dominator` to `block` which goes through a block writing to memory.
+///
+/// All paths from the entry to `block` go through `dominator`, so searching backwards from the
+/// predecessors of `block` until `dominator` is reached visits all blocks on such paths.
+fn may_write_memory_between(
+    dominator: BasicBlockId,
+    block: BasicBlockId,
+    cfg: &ControlFlowGraph,
+    blocks_writing_memory: &HashSet<BasicBlockId>,
+) -> bool {
+    let mut visited = HashSet::new();
+    let mut stack: Vec<_> = cfg.predecessors(block).collect();
+
+    while let Some(predecessor) = stack.pop() {
+        if predecessor == dominator || !visited.insert(predecessor) {
+            continue;
+        }
+        if blocks_writing_memory.contains(&predecessor) {
+            return true;
+        }
+        stack.extend(cfg.predecessors(predecessor));
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa::{opt::assert_normalized_ssa_equals, Ssa};
+
+    #[test]
+    fn removes_computation_from_dominated_block() {
+        let src = "
+            brillig(inline) fn main f0 {
+              b0(v0: u32, v1: u32, v2: u1):
+                v3 = add v0, v1
+                jmpif v2 then: b1, else: b2
+              b1():
+                v4 = add v0, v1
+                jmp b3(v4)
+              b3(v5: u32):
+                return v5
+              b2():
+                jmp b3(v3)
+            }
+            ";
+        let ssa = Ssa::from_str(src).unwrap();
+
+        let expected = "
+            brillig(inline) fn main f0 {
+              b0(v0: u32, v1: u32, v2: u1):
+                v4 = add v0, v1
+                jmpif v2 then: b2, else: b1
+              b2():
+                jmp b3(v4)
+              b3(v3: u32):
+                return v3
+              b1():
+                jmp b3(v4)
+            }
+            ";
+        let ssa = ssa.global_value_numbering();
+        assert_normalized_ssa_equals(ssa, expected);
+    }
+
+    #[test]
+    fn removes_computation_from_dominated_block_in_acir() {
+        // The pass runs before flattening so that ACIR functions benefit from it too.
+        let src = "
+            acir(inline) fn main f0 {
+              b0(v0: Field, v1: Field, v2: u1):
+                v3 = mul v0, v1
+                jmpif v2 then: b1, else: b2
+              b1():
+                v4 = mul v1, v0
+                constrain v4 == Field 0
+                jmp b2()
+              b2():
+                return v3
+            }
+            ";
+        let ssa = Ssa::from_str(src).unwrap();
+
+        let expected = "
+            acir(inline) fn main f0 {
+              b0(v0: Field, v1: Field, v2: u1):
+                v3 = mul v0, v1
+                jmpif v2 then: b1, else: b2
+              b1():
+                constrain v3 == Field 0
+                jmp b2()
+              b2():
+                return v3
+            }
+            ";
+        let ssa = ssa.global_value_numbering();
+        assert_normalized_ssa_equals(ssa, expected);
+    }
+
+    #[test]
+    fn does_not_reuse_computation_from_sibling_block() {
+        let src = "
+            brillig(inline) fn main f0 {
+              b0(v0: u32, v1: u32, v2: u1):
+                jmpif v2 then: b2, else: b1
+              b2():
+                v5 = add v0, v1
+                jmp b3(v5)
+              b3(v3: u32):
+                return v3
+              b1():
+                v4 = add v0, v1
+                jmp b3(v4)
+            }
+            ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.global_value_numbering();
+        assert_normalized_ssa_equals(ssa, src);
+    }
+
+    #[test]
+    fn normalizes_commutative_operands() {
+        let src = "
+            acir(inline) fn main f0 {
+              b0(v0: Field, v1: Field):
+                v2 = mul v0, v1
+                v3 = mul v1, v0
+                v4 = sub v0, v1
+                v5 = sub v1, v0
+                return v2, v3, v4, v5
+            }
+            ";
+        let ssa = Ssa::from_str(src).unwrap();
+
+        let expected = "
+            acir(inline) fn main f0 {
+              b0(v0: Field, v1: Field):
+                v2 = mul v0, v1
+                v3 = sub v0, v1
+                v4 = sub v1, v0
+                return v2, v2, v3, v4
+            }
+            ";
+        let ssa = ssa.global_value_numbering();
+        assert_normalized_ssa_equals(ssa, expected);
+    }
+
+    #[test]
+    fn reuses_loads_if_memory_is_unchanged() {
+        let src = "
+            brillig(inline) fn main f0 {
+              b0(v0: &mut Field, v1: u1):
+                v2 = load v0 -> Field
+                jmpif v1 then: b1, else: b2
+              b1():
+                v3 = load v0 -> Field
+                jmp b2()
+              b2():
+                v4 = load v0 -> Field
+                return v2, v4
+            }
+            ";
+        let ssa = Ssa::from_str(src).unwrap();
+
+        let expected = "
+            brillig(inline) fn main f0 {
+              b0(v0: &mut Field, v1: u1):
+                v2 = load v0 -> Field
+                jmpif v1 then: b1, else: b2
+              b1():
+                jmp b2()
+              b2():
+                return v2, v2
+            }
+            ";
+        let ssa = ssa.global_value_numbering();
+        assert_normalized_ssa_equals(ssa, expected);
+    }
+
+    #[test]
+    fn does_not_reuse_loads_after_a_store_on_another_path() {
+        let src = "
+            brillig(inline) fn main f0 {
+              b0(v0: &mut Field, v1: u1):
+                v2 = load v0 -> Field
+                jmpif v1 then: b1, else: b2
+              b1():
+                store Field 1 at v0
+                jmp b2()
+              b2():
+                v4 = load v0 -> Field
+                return v2, v4
+            }
+            ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let ssa = ssa.global_value_numbering();
+        assert_normalized_ssa_equals(ssa, src);
+    }
+}
@@ -17,9 +17,14 @@ use crate::ssa::{
         value::{Value, ValueId},
     },
     ssa_gen::Ssa,
+    OptimizationLevel,
 };
 use fxhash::FxHashMap as HashMap;
 
+mod cost_model;
+
+use cost_model::{call_frequencies, estimate_brillig_size};
+
 /// An arbitrary limit to the maximum number of recursive call
 /// frames at any point in time.
 const RECURSION_LIMIT: u32 = 1000;
@@ -42,24 +47,41 @@ impl Ssa {
     /// Currently this is just `InlineType::NoPredicates` for which we have a flag indicating
     /// whether treating that inline functions. The default is to treat these functions as entry points.
     ///
+    /// Which Brillig functions get inlined is decided by a cost model weighing their estimated
+    /// bytecode size against how often they are called,
+    /// see [`RetainContext::should_retain_recursive`].
+    ///
     /// This step should run after runtime separation, since it relies on the runtime of the called functions being final.
     #[tracing::instrument(level = "trace", skip(self))]
-    pub(crate) fn inline_functions(self, aggressiveness: i64) -> Ssa {
-        Self::inline_functions_inner(self, aggressiveness, false)
+    pub(crate) fn inline_functions(
+        self,
+        aggressiveness: i64,
+        optimization_level: OptimizationLevel,
+    ) -> Ssa {
+        Self::inline_functions_inner(self, aggressiveness, optimization_level, false)
     }
 
     // Run the inlining pass where functions marked with `InlineType::NoPredicates` as not entry points
-    pub(crate) fn inline_functions_with_no_predicates(self, aggressiveness: i64) -> Ssa {
-        Self::inline_functions_inner(self, aggressiveness, true)
+    pub(crate) fn inline_functions_with_no_predicates(
+        self,
+        aggressiveness: i64,
+        optimization_level: OptimizationLevel,
+    ) -> Ssa {
+        Self::inline_functions_inner(self, aggressiveness, optimization_level, true)
     }
 
     fn inline_functions_inner(
         mut self,
         aggressiveness: i64,
+        optimization_level: OptimizationLevel,
         inline_no_predicates_functions: bool,
     ) -> Ssa {
-        let inline_sources =
-            get_functions_to_inline_into(&self, inline_no_predicates_functions, aggressiveness);
+        let inline_sources = get_functions_to_inline_into(
+            &self,
+            inline_no_predicates_functions,
+            aggressiveness,
+            optimization_level,
+        );
         self.functions = btree_map(&inline_sources, |entry_point| {
             let new_function = InlineContext::new(
                 &self,
@@ -162,6 +184,7 @@ fn get_functions_to_inline_into(
     ssa: &Ssa,
     inline_no_predicates_functions: bool,
     aggressiveness: i64,
+    optimization_level: OptimizationLevel,
 ) -> BTreeSet<FunctionId> {
     let mut brillig_entry_points = BTreeSet::default();
     let mut acir_entry_points = BTreeSet::default();
@@ -191,15 +214,14 @@ fn get_functions_to_inline_into(
         }
     }
 
-    let times_called = compute_times_called(ssa);
-
-    let brillig_functions_to_retain: BTreeSet<_> = compute_functions_to_retain(
+    let context = RetainContext {
         ssa,
-        &brillig_entry_points,
-        &times_called,
+        call_counts: compute_call_counts(ssa),
         inline_no_predicates_functions,
         aggressiveness,
-    );
+        optimization_level,
+    };
+    let brillig_functions_to_retain = compute_functions_to_retain(&context, &brillig_entry_points);
 
     acir_entry_points
         .into_iter()
@@ -208,113 +230,137 @@ fn get_functions_to_inline_into(
         .collect()
 }
 
-fn compute_times_called(ssa: &Ssa) -> HashMap<FunctionId, usize> {
-    ssa.functions
-        .iter()
-        .flat_map(|(_caller_id, function)| {
-            let called_functions_vec = called_functions_vec(function);
-            called_functions_vec.into_iter()
-        })
-        .chain(std::iter::once(ssa.main_id))
-        .fold(HashMap::default(), |mut map, func_id| {
-            *map.entry(func_id).or_insert(0) += 1;
-            map
-        })
+/// How many times a function is called across the program.
+#[derive(Default, Clone, Copy)]
+struct CallCount {
+    /// The number of call sites of the function.
+    call_sites: u64,
+    /// The estimated number of times these call sites are executed per execution of their
+    /// callers, taking into account the loops they are found in.
+    frequency: u64,
 }
 
-fn should_retain_recursive(
-    ssa: &Ssa,
-    func: FunctionId,
-    times_called: &HashMap<FunctionId, usize>,
-    should_retain_function: &mut HashMap<FunctionId, (bool, i64)>,
-    mut explored_functions: im::HashSet<FunctionId>,
-    inline_no_predicates_functions: bool,
-    aggressiveness: i64,
-) {
-    // We have already decided on this function
-    if should_retain_function.get(&func).is_some() {
-        return;
-    }
-    // Recursive, this function won't be inlined
-    if explored_functions.contains(&func) {
-        should_retain_function.insert(func, (true, 0));
-        return;
-    }
-    explored_functions.insert(func);
-
-    // Decide on dependencies first
-    let called_functions = called_functions(&ssa.functions[&func]);
-    for function in called_functions.iter() {
-        should_retain_recursive(
-            ssa,
-            *function,
-            times_called,
-            should_retain_function,
-            explored_functions.clone(),
-            inline_no_predicates_functions,
-            aggressiveness,
-        );
-    }
-    // We could have decided on this function while deciding on dependencies
-    // If the function is recursive
-    if should_retain_function.get(&func).is_some() {
-        return;
-    }
+fn compute_call_counts(ssa: &Ssa) -> HashMap<FunctionId, CallCount> {
+    let mut call_counts: HashMap<FunctionId, CallCount> = HashMap::default();
+    call_counts.insert(ssa.main_id, CallCount { call_sites: 1, frequency: 1 });
 
-    // We'll use some heuristics to decide whether to inline or not.
-    // We compute the weight (roughly the number of instructions) of the function after inlining
-    // And the interface cost of the function (the inherent cost at the callsite, roughly the number of args and returns)
-    // We then can compute an approximation of the cost of inlining vs the cost of retaining the function
-    // We do this computation using saturating i64s to avoid overflows
-    let inlined_function_weights: i64 = called_functions.iter().fold(0, |acc, called_function| {
-        let (should_retain, weight) = should_retain_function[called_function];
-        if should_retain {
-            acc
-        } else {
-            acc.saturating_add(weight)
+    for function in ssa.functions.values() {
+        for called_function_id in called_functions_vec(function) {
+            call_counts.entry(called_function_id).or_default().call_sites += 1;
         }
-    });
-
-    let this_function_weight = inlined_function_weights
-        .saturating_add(compute_function_own_weight(&ssa.functions[&func]) as i64);
-
-    let interface_cost = compute_function_interface_cost(&ssa.functions[&func]) as i64;
-
-    let times_called = times_called[&func] as i64;
+        for (called_function_id, frequency) in call_frequencies(function) {
+            let call_count = call_counts.entry(called_function_id).or_default();
+            call_count.frequency = call_count.frequency.saturating_add(frequency);
+        }
+    }
+    call_counts
+}
 
-    let inline_cost = times_called.saturating_mul(this_function_weight);
-    let retain_cost = times_called.saturating_mul(interface_cost) + this_function_weight;
+/// The inputs to the inliner's decision on whether to retain each Brillig function,
+/// which are the same for every function it decides on.
+struct RetainContext<'ssa> {
+    ssa: &'ssa Ssa,
+    call_counts: HashMap<FunctionId, CallCount>,
+    inline_no_predicates_functions: bool,
+    aggressiveness: i64,
+    optimization_level: OptimizationLevel,
+}
 
-    let runtime = ssa.functions[&func].runtime();
-    // We inline if the aggressiveness is higher than inline cost minus the retain cost
-    // If aggressiveness is infinite, we'll always inline
-    // If aggressiveness is 0, we'll inline when the inline cost is lower than the retain cost
-    // If aggressiveness is minus infinity, we'll never inline (other than in the mandatory cases)
-    let should_inline = ((inline_cost.saturating_sub(retain_cost)) < aggressiveness)
-        || runtime.is_inline_always()
-        || (runtime.is_no_predicates() && inline_no_predicates_functions);
+impl RetainContext<'_> {
+    fn should_retain_recursive(
+        &self,
+        func: FunctionId,
+        should_retain_function: &mut HashMap<FunctionId, (bool, i64)>,
+        mut explored_functions: im::HashSet<FunctionId>,
+    ) {
+        // We have already decided on this function
+        if should_retain_function.get(&func).is_some() {
+            return;
+        }
+        // Recursive, this function won't be inlined
+        if explored_functions.contains(&func) {
+            should_retain_function.insert(func, (true, 0));
+            return;
+        }
+        explored_functions.insert(func);
+
+        // Decide on dependencies first
+        let called_functions = called_functions(&self.ssa.functions[&func]);
+        for function in called_functions.iter() {
+            self.should_retain_recursive(
+                *function,
+                should_retain_function,
+                explored_functions.clone(),
+            );
+        }
+        // We could have decided on this function while deciding on dependencies
+        // If the function is recursive
+        if should_retain_function.get(&func).is_some() {
+            return;
+        }
 
-    should_retain_function.insert(func, (!should_inline, this_function_weight));
+        // We'll use some heuristics to decide whether to inline or not.
+        // We compute the weight (the estimated Brillig bytecode size) of the function after inlining
+        // And the interface cost of the function (the inherent cost at the callsite, roughly the number of args and returns)
+        // We then can compute an approximation of the cost of inlining vs the cost of retaining the function
+        // We do this computation using saturating i64s to avoid overflows
+        let inlined_function_weights: i64 =
+            called_functions.iter().fold(0, |acc, called_function| {
+                let (should_retain, weight) = should_retain_function[called_function];
+                if should_retain {
+                    acc
+                } else {
+                    acc.saturating_add(weight)
+                }
+            });
+
+        let function = &self.ssa.functions[&func];
+        let this_function_weight =
+            inlined_function_weights.saturating_add(compute_function_own_weight(function) as i64);
+
+        let interface_cost = compute_function_interface_cost(function) as i64;
+
+        let CallCount { call_sites, frequency } = self.call_counts[&func];
+        let call_sites = call_sites as i64;
+
+        let inline_cost = call_sites.saturating_mul(this_function_weight);
+        let retain_cost =
+            call_sites.saturating_mul(interface_cost).saturating_add(this_function_weight);
+
+        // Calls made from within loops are executed more often than their number of call sites suggests.
+        // Each of these extra executions pays the interface cost at runtime, which inlining saves.
+        let extra_executions = (frequency as i64).saturating_sub(call_sites).max(0);
+        let runtime_savings = extra_executions
+            .saturating_mul(interface_cost)
+            .saturating_mul(self.optimization_level.call_frequency_weight());
+
+        let runtime = function.runtime();
+        // We inline if the aggressiveness is higher than inline cost minus the retain cost,
+        // discounted by the runtime savings of inlining calls in hot loops.
+        // If aggressiveness is infinite, we'll always inline
+        // If aggressiveness is 0, we'll inline when the inline cost is lower than the retain cost
+        // If aggressiveness is minus infinity, we'll never inline (other than in the mandatory cases)
+        let inline_penalty =
+            inline_cost.saturating_sub(retain_cost).saturating_sub(runtime_savings);
+        let should_inline = (inline_penalty < self.aggressiveness)
+            || runtime.is_inline_always()
+            || (runtime.is_no_predicates() && self.inline_no_predicates_functions);
+
+        should_retain_function.insert(func, (!should_inline, this_function_weight));
+    }
 }
 
 fn compute_functions_to_retain(
-    ssa: &Ssa,
+    context: &RetainContext,
     entry_points: &BTreeSet<FunctionId>,
-    times_called: &HashMap<FunctionId, usize>,
-    inline_no_predicates_functions: bool,
-    aggressiveness: i64,
 ) -> BTreeSet<FunctionId> {
     let mut should_retain_function = HashMap::default();
 
     for entry_point in entry_points.iter() {
-        should_retain_recursive(
-            ssa,
+        context.should_retain_recursive(
             *entry_point,
-            times_called,
             &mut should_retain_function,
             im::HashSet::default(),
-            inline_no_predicates_functions,
-            aggressiveness,
         );
     }
 
@@ -333,13 +379,9 @@ fn compute_functions_to_retain(
 }
 
 fn compute_function_own_weight(func: &Function) -> usize {
-    let mut weight = 0;
-    for block_id in func.reachable_blocks() {
-        weight += func.dfg[block_id].instructions().len() + 1; // We add one for the terminator
-    }
-    // We use an approximation of the average increase in instruction ratio from SSA to Brillig
-    // In order to get the actual weight we'd need to codegen this function to brillig.
-    weight
+    // In order to get the actual weight we'd need to codegen this function to brillig,
+    // so we estimate it from the SSA instead.
+    estimate_brillig_size(func)
 }
 
 fn compute_function_interface_cost(func: &Function) -> usize {
@@ -819,6 +861,8 @@ mod test {
             map::Id,
             types::{NumericType, Type},
         },
+        ssa_gen::Ssa,
+        OptimizationLevel,
     };
 
     #[test]
@@ -848,7 +892,7 @@ mod test {
         let ssa = builder.finish();
         assert_eq!(ssa.functions.len(), 2);
 
-        let inlined = ssa.inline_functions(i64::MAX);
+        let inlined = ssa.inline_functions(i64::MAX, OptimizationLevel::default());
         assert_eq!(inlined.functions.len(), 1);
     }
 
@@ -914,7 +958,7 @@ mod test {
         let ssa = builder.finish();
         assert_eq!(ssa.functions.len(), 4);
 
-        let inlined = ssa.inline_functions(i64::MAX);
+        let inlined = ssa.inline_functions(i64::MAX, OptimizationLevel::default());
         assert_eq!(inlined.functions.len(), 1);
     }
 
@@ -988,7 +1032,7 @@ mod test {
         //   b6():
         //     return Field 120
         // }
-        let inlined = ssa.inline_functions(i64::MAX);
+        let inlined = ssa.inline_functions(i64::MAX, OptimizationLevel::default());
         assert_eq!(inlined.functions.len(), 1);
 
         let main = inlined.main();
@@ -1071,7 +1115,7 @@ mod test {
         builder.switch_to_block(join_block);
         builder.terminate_with_return(vec![join_param]);
 
-        let ssa = builder.finish().inline_functions(i64::MAX);
+        let ssa = builder.finish().inline_functions(i64::MAX, OptimizationLevel::default());
         // Expected result:
         // fn main f3 {
         //   b0(v0: u1):
@@ -1108,7 +1152,7 @@ mod test {
         let ssa = builder.finish();
         assert_eq!(ssa.functions.len(), 1);
 
-        let inlined = ssa.inline_functions(i64::MAX);
+        let inlined = ssa.inline_functions(i64::MAX, OptimizationLevel::default());
         assert_eq!(inlined.functions.len(), 0);
     }
 
@@ -1140,7 +1184,7 @@ mod test {
         let ssa = builder.finish();
         assert_eq!(ssa.functions.len(), 2);
 
-        let inlined = ssa.inline_functions(i64::MIN);
+        let inlined = ssa.inline_functions(i64::MIN, OptimizationLevel::default());
         // No inlining has happened
         assert_eq!(inlined.functions.len(), 2);
     }
@@ -1196,8 +1240,154 @@ mod test {
         let ssa = builder.finish();
         assert_eq!(ssa.functions.len(), 2);
 
-        let inlined = ssa.inline_functions(0);
+        let inlined = ssa.inline_functions(0, OptimizationLevel::Size);
         // No inlining has happened
         assert_eq!(inlined.functions.len(), 2);
     }
+
+    #[test]
+    fn inlines_functions_called_in_loops() {
+        // `bar` is called twice, which is too often to inline it based on its size alone,
+        // but as the calls happen inside of a loop the inliner should still inline it.
+        let src = "
+        brillig(inline) fn foo f0 {
+          b0(v0: u32):
+            jmp b1(u32 0)
+          b1(v1: u32):
+            v3 = lt v1, u32 4
+            jmpif v3 then: b3, else: b2
+          b2():
+            return v0
+          b3():
+            v4 = call f1(v1) -> u32
+            v5 = call f1(v4) -> u32
+            v7 = add v1, u32 1
+            jmp b1(v7)
+        }
+        brillig(inline) fn bar f1 {
+          b0(v0: u32):
+            v1 = add v0, u32 1
+            v2 = add v1, u32 2
+            v3 = add v2, u32 3
+            return v3
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let inlined = ssa.inline_functions(0, OptimizationLevel::Size);
+        assert_eq!(inlined.functions.len(), 1);
+
+        // The same calls outside of a loop are kept as calls.
+        let src = "
+        brillig(inline) fn foo f0 {
+          b0(v0: u32):
+            v1 = call f1(v0) -> u32
+            v2 = call f1(v1) -> u32
+            return v2
+        }
+        brillig(inline) fn bar f1 {
+          b0(v0: u32):
+            v1 = add v0, u32 1
+            v2 = add v1, u32 2
+            v3 = add v2, u32 3
+            return v3
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let inlined = ssa.inline_functions(0, OptimizationLevel::Size);
+        assert_eq!(inlined.functions.len(), 2);
+    }
+
+    #[test]
+    fn speed_weights_calls_in_loops_more_than_size() {
+        // With the same aggressiveness, the runtime saved by inlining `bar` into the loop
+        // outweighs the bytecode it adds at `Speed`, but not at `Size`.
+        let src = "
+        brillig(inline) fn foo f0 {
+          b0(v0: u32):
+            jmp b1(u32 0)
+          b1(v1: u32):
+            v3 = lt v1, u32 4
+            jmpif v3 then: b3, else: b2
+          b2():
+            return v0
+          b3():
+            v4 = call f1(v1) -> u32
+            v5 = call f1(v4) -> u32
+            v7 = add v1, u32 1
+            jmp b1(v7)
+        }
+        brillig(inline) fn bar f1 {
+          b0(v0: u32):
+            v1 = add v0, u32 1
+            v2 = add v1, u32 2
+            v3 = add v2, u32 3
+            v4 = add v3, u32 4
+            v5 = add v4, u32 5
+            v6 = add v5, u32 6
+            v7 = add v6, u32 7
+            v8 = add v7, u32 8
+            v9 = add v8, u32 9
+            v10 = add v9, u32 10
+            v11 = add v10, u32 11
+            v12 = add v11, u32 12
+            v13 = add v12, u32 13
+            v14 = add v13, u32 14
+            v15 = add v14, u32 15
+            return v15
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let inlined = ssa.inline_functions(0, OptimizationLevel::Size);
+        assert_eq!(inlined.functions.len(), 2);
+
+        let ssa = Ssa::from_str(src).unwrap();
+        let inlined = ssa.inline_functions(0, OptimizationLevel::Speed);
+        assert_eq!(inlined.functions.len(), 1);
+    }
+
+    #[test]
+    fn both_levels_use_the_cost_model_by_default() {
+        let bar = "
+        brillig(inline) fn bar f1 {
+          b0(v0: u32):
+            v1 = add v0, u32 1
+            v2 = add v1, u32 2
+            v3 = add v2, u32 3
+            v4 = add v3, u32 4
+            v5 = add v4, u32 5
+            v6 = add v5, u32 6
+            v7 = add v6, u32 7
+            v8 = add v7, u32 8
+            v9 = add v8, u32 9
+            v10 = add v9, u32 10
+            v11 = add v10, u32 11
+            v12 = add v11, u32 12
+            v13 = add v12, u32 13
+            v14 = add v13, u32 14
+            v15 = add v14, u32 15
+            return v15
+        }
+        ";
+        // `foo` calls `bar` from `call_sites` places in a row.
+        let program = |call_sites: usize| {
+            let calls: String =
+                (1..=call_sites).map(|i| format!("v{i} = call f1(v{}) -> u32\n", i - 1)).collect();
+            let src = format!(
+                "brillig(inline) fn foo f0 {{\n b0(v0: u32):\n {calls} return v{call_sites}\n}}\n{bar}"
+            );
+            Ssa::from_str(&src).unwrap()
+        };
+        let size = OptimizationLevel::Size;
+        let speed = OptimizationLevel::Speed;
+
+        // Duplicating `bar` once grows the bytecode, which is only worth it for speed.
+        let inlined = program(2).inline_functions(size.default_inliner_aggressiveness(), size);
+        assert_eq!(inlined.functions.len(), 2);
+        let inlined = program(2).inline_functions(speed.default_inliner_aggressiveness(), speed);
+        assert_eq!(inlined.functions.len(), 1);
+
+        // Duplicating it at a hundred call sites grows the bytecode too much even for speed.
+        let inlined = program(100).inline_functions(speed.default_inliner_aggressiveness(), speed);
+        assert_eq!(inlined.functions.len(), 2);
+    }
 }
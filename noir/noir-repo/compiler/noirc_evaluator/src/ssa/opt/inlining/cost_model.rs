@@ -0,0 +1,282 @@
+//! Cost model used by the inliner to decide which Brillig functions are worth inlining.
+//!
+//! The model is made of two estimates:
+//! 1. The Brillig bytecode size of a function, approximated from the SSA instructions it contains.
+//!    Most instructions map onto a single Brillig opcode, but some (e.g. checked integer arithmetic
+//!    or copying arrays) expand into several, so each instruction is given its own weight.
+//! 2. How frequently each call site is expected to run, derived from the loop nesting depth of the
+//!    block containing the call. Loop bounds are generally not known before unrolling, so every
+//!    level of nesting is assumed to multiply the number of executions by [LOOP_ITERATIONS_ESTIMATE].
+use std::collections::BTreeSet;
+
+use crate::ssa::ir::{
+    basic_block::BasicBlockId,
+    cfg::ControlFlowGraph,
+    dfg::DataFlowGraph,
+    dom::DominatorTree,
+    function::{Function, FunctionId},
+    instruction::{BinaryOp, Instruction, TerminatorInstruction},
+    post_order::PostOrder,
+    types::{NumericType, Type},
+    value::{Value, ValueId},
+};
+use fxhash::FxHashMap as HashMap;
+
+/// The number of iterations assumed for each loop when estimating how often a call site runs.
+const LOOP_ITERATIONS_ESTIMATE: u64 = 10;
+
+/// Loops nested deeper than this are considered just as hot as loops at this depth.
+/// This keeps the frequency estimates from overflowing.
+const MAX_LOOP_DEPTH: u32 = 8;
+
+/// Estimated number of Brillig opcodes the given function will be compiled into.
+pub(super) fn estimate_brillig_size(function: &Function) -> usize {
+    let dfg = &function.dfg;
+    let mut size = 0;
+    for block_id in function.reachable_blocks() {
+        let block = &dfg[block_id];
+        size += block
+            .instructions()
+            .iter()
+            .map(|instruction_id| {
+                instruction_cost(
+                    dfg,
+                    &dfg[*instruction_id],
+                    dfg.instruction_results(*instruction_id),
+                )
+            })
+            .sum::<usize>();
+        size += block.terminator().map_or(0, terminator_cost);
+    }
+    size
+}
+
+fn instruction_cost(dfg: &DataFlowGraph, instruction: &Instruction, results: &[ValueId]) -> usize {
+    match instruction {
+        Instruction::Binary(binary) => {
+            let is_field = dfg.type_of_value(binary.lhs) == Type::Numeric(NumericType::NativeField);
+            match binary.operator {
+                // Integer arithmetic is checked for overflow which needs a comparison and a jump.
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul if !is_field => 4,
+                // Integer division also checks for division by zero.
+                BinaryOp::Div | BinaryOp::Mod if !is_field => 3,
+                _ => 1,
+            }
+        }
+        Instruction::Cast(..)
+        | Instruction::Not(..)
+        | Instruction::Allocate
+        | Instruction::Load { .. }
+        | Instruction::Store { .. } => 1,
+        Instruction::Truncate { .. } => 2,
+        // An equality check followed by a conditional jump to a trap.
+        Instruction::Constrain(..) => 3,
+        Instruction::RangeCheck { .. } => 4,
+        // Only meaningful in ACIR
+        Instruction::EnableSideEffectsIf { .. } => 0,
+        Instruction::Call { func, arguments } => match dfg[*func] {
+            // Calling another function needs its arguments and results moved into place,
+            // plus the call itself.
+            Value::Function(_) => arguments.len() + results.len() + 1,
+            _ => arguments.len() + results.len(),
+        },
+        // Array accesses add the offset of the items in the array's memory.
+        Instruction::ArrayGet { .. } => 2,
+        Instruction::ArraySet { mutable: true, .. } => 2,
+        // Non-mutable sets copy the array if its reference count is above one.
+        Instruction::ArraySet { mutable: false, .. } => 6,
+        Instruction::IncrementRc { .. } | Instruction::DecrementRc { .. } => 3,
+        Instruction::IfElse { .. } => 4,
+        Instruction::MakeArray { elements, .. } => elements.len() + 3,
+    }
+}
+
+fn terminator_cost(terminator: &TerminatorInstruction) -> usize {
+    match terminator {
+        TerminatorInstruction::JmpIf { .. } => 1,
+        // Block arguments are passed by moving them into the parameter registers.
+        TerminatorInstruction::Jmp { arguments, .. } => arguments.len() + 1,
+        TerminatorInstruction::Return { return_values, .. } => return_values.len() + 1,
+    }
+}
+
+/// Returns the estimated number of times each function is called per execution of the caller,
+/// weighting each call site by the loop depth of the block it is found in.
+pub(super) fn call_frequencies(function: &Function) -> HashMap<FunctionId, u64> {
+    let loop_depths = compute_loop_depths(function);
+    let mut frequencies = HashMap::default();
+
+    for block_id in function.reachable_blocks() {
+        let depth = loop_depths.get(&block_id).copied().unwrap_or_default().min(MAX_LOOP_DEPTH);
+        let executions = LOOP_ITERATIONS_ESTIMATE.pow(depth);
+
+        for instruction_id in function.dfg[block_id].instructions() {
+            let Instruction::Call { func, .. } = &function.dfg[*instruction_id] else {
+                continue;
+            };
+            if let Value::Function(function_id) = function.dfg[*func] {
+                let frequency: &mut u64 = frequencies.entry(function_id).or_default();
+                *frequency = frequency.saturating_add(executions);
+            }
+        }
+    }
+
+    frequencies
+}
+
+/// Computes how many loops each block of the function is nested in.
+///
+/// Each natural loop is identified by its header, a block which dominates one of its predecessors.
+/// The body of the loop is made of the blocks found along all of the back-edges to the header, so
+/// that a loop which jumps back to its header from several blocks (e.g. on `continue`) is only
+/// counted once.
+fn compute_loop_depths(function: &Function) -> HashMap<BasicBlockId, u32> {
+    let cfg = ControlFlowGraph::with_function(function);
+    let post_order = PostOrder::with_function(function);
+    let mut dom_tree = DominatorTree::with_cfg_and_post_order(&cfg, &post_order);
+
+    let mut depths = HashMap::default();
+    for header in function.reachable_blocks() {
+        let mut loop_body = BTreeSet::new();
+        for back_edge_start in cfg.predecessors(header) {
+            if dom_tree.is_reachable(back_edge_start) && dom_tree.dominates(header, back_edge_start)
+            {
+                loop_body.extend(blocks_in_loop(header, back_edge_start, &cfg));
+            }
+        }
+        for block in loop_body {
+            *depths.entry(block).or_default() += 1;
+        }
+    }
+    depths
+}
+
+/// Returns each block in the loop with the given header and back-edge.
+fn blocks_in_loop(
+    header: BasicBlockId,
+    back_edge_start: BasicBlockId,
+    cfg: &ControlFlowGraph,
+) -> BTreeSet<BasicBlockId> {
+    let mut blocks = BTreeSet::from([header]);
+    let mut stack = vec![];
+    if blocks.insert(back_edge_start) {
+        stack.push(back_edge_start);
+    }
+
+    while let Some(block) = stack.pop() {
+        for predecessor in cfg.predecessors(block) {
+            if blocks.insert(predecessor) {
+                stack.push(predecessor);
+            }
+        }
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::{ir::map::Id, ssa_gen::Ssa};
+
+    use super::{call_frequencies, estimate_brillig_size, LOOP_ITERATIONS_ESTIMATE};
+
+    #[test]
+    fn weights_calls_by_loop_depth() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: u32):
+            v2 = call f1(v0) -> u32
+            jmp b1(u32 0)
+          b1(v1: u32):
+            v4 = lt v1, u32 4
+            jmpif v4 then: b3, else: b2
+          b2():
+            return v2
+          b3():
+            v5 = call f1(v1) -> u32
+            v6 = call f2(v1) -> u32
+            v8 = add v1, u32 1
+            jmp b1(v8)
+        }
+        brillig(inline) fn one f1 {
+          b0(v0: u32):
+            return v0
+        }
+        brillig(inline) fn two f2 {
+          b0(v0: u32):
+            return v0
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let frequencies = call_frequencies(ssa.main());
+
+        assert_eq!(frequencies[&Id::test_new(1)], 1 + LOOP_ITERATIONS_ESTIMATE);
+        assert_eq!(frequencies[&Id::test_new(2)], LOOP_ITERATIONS_ESTIMATE);
+    }
+
+    #[test]
+    fn counts_each_loop_once_regardless_of_its_back_edges() {
+        // The inner loop jumps back to its header from two blocks, as it would on a `continue`.
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: u32):
+            jmp b1(u32 0)
+          b1(v1: u32):
+            v3 = lt v1, u32 4
+            jmpif v3 then: b3, else: b2
+          b2():
+            return v0
+          b3():
+            v4 = call f2(v1) -> u32
+            jmp b4(u32 0)
+          b4(v5: u32):
+            v6 = lt v5, u32 4
+            jmpif v6 then: b6, else: b5
+          b5():
+            v8 = add v1, u32 1
+            jmp b1(v8)
+          b6():
+            v9 = call f1(v5) -> u32
+            v10 = eq v9, u32 0
+            v11 = add v5, u32 1
+            jmpif v10 then: b7, else: b8
+          b7():
+            jmp b4(v11)
+          b8():
+            jmp b4(v11)
+        }
+        brillig(inline) fn one f1 {
+          b0(v0: u32):
+            return v0
+        }
+        brillig(inline) fn two f2 {
+          b0(v0: u32):
+            return v0
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let frequencies = call_frequencies(ssa.main());
+
+        assert_eq!(frequencies[&Id::test_new(1)], LOOP_ITERATIONS_ESTIMATE.pow(2));
+        assert_eq!(frequencies[&Id::test_new(2)], LOOP_ITERATIONS_ESTIMATE);
+    }
+
+    #[test]
+    fn checked_arithmetic_is_more_expensive() {
+        let src = "
+        brillig(inline) fn main f0 {
+          b0(v0: u32, v1: u32):
+            v2 = add v0, v1
+            return v2
+        }
+        brillig(inline) fn field f1 {
+          b0(v0: Field, v1: Field):
+            v2 = add v0, v1
+            return v2
+        }
+        ";
+        let ssa = Ssa::from_str(src).unwrap();
+        let unsigned_size = estimate_brillig_size(&ssa.functions[&Id::test_new(0)]);
+        let field_size = estimate_brillig_size(&ssa.functions[&Id::test_new(1)]);
+        assert!(unsigned_size > field_size);
+    }
+}
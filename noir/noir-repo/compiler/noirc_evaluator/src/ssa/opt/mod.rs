@@ -11,6 +11,7 @@ mod constant_folding;
 mod defunctionalize;
 mod die;
 pub(crate) mod flatten_cfg;
+mod gvn;
 mod inlining;
 mod mem2reg;
 mod normalize_value_ids;
@@ -4,6 +4,9 @@ mod value;
 
 pub(crate) use program::Ssa;
 
+use std::collections::BTreeMap;
+
+use acvm::acir::circuit::ErrorSelector;
 use context::SharedContext;
 use iter_extended::{try_vecmap, vecmap};
 use noirc_errors::Location;
@@ -37,10 +40,12 @@ pub(crate) const SSA_WORD_SIZE: u32 = 32;
 /// Generates SSA for the given monomorphized program.
 ///
 /// This function will generate the SSA but does not perform any optimizations on it.
+/// The types of the errors raised with dynamic payloads are returned alongside it,
+/// as they are only needed to build the ABI.
 pub(crate) fn generate_ssa(
     program: Program,
     force_brillig_runtime: bool,
-) -> Result<Ssa, RuntimeError> {
+) -> Result<(Ssa, BTreeMap<ErrorSelector, HirType>), RuntimeError> {
     // see which parameter has call_data/return_data attribute
     let is_databus = DataBusBuilder::is_databus(&program.main_function_signature);
 
@@ -118,7 +123,7 @@ pub(crate) fn generate_ssa(
         function_context.codegen_function_body(&function.body)?;
     }
 
-    Ok(function_context.builder.finish())
+    Ok(function_context.builder.finish_with_error_types())
 }
 
 impl<'a> FunctionContext<'a> {
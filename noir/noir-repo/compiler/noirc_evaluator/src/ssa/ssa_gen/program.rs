@@ -1,6 +1,5 @@
 use std::{collections::BTreeMap, fmt::Display};
 
-use acvm::acir::circuit::ErrorSelector;
 use iter_extended::btree_map;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -9,7 +8,6 @@ use crate::ssa::ir::{
     function::{Function, FunctionId, RuntimeType},
     map::AtomicCounter,
 };
-use noirc_frontend::hir_def::types::Type as HirType;
 
 /// Contains the entire SSA representation of the program.
 #[serde_as]
@@ -26,19 +24,12 @@ pub(crate) struct Ssa {
     /// as the final program artifact will be a list of only entry point functions.
     #[serde(skip)]
     pub(crate) entry_point_to_generated_index: BTreeMap<FunctionId, u32>,
-    // We can skip serializing this field as the error selector types end up as part of the
-    // ABI not the actual SSA IR.
-    #[serde(skip)]
-    pub(crate) error_selector_to_type: BTreeMap<ErrorSelector, HirType>,
 }
 
 impl Ssa {
     /// Create a new Ssa object from the given SSA functions.
     /// The first function in this vector is expected to be the main function.
-    pub(crate) fn new(
-        functions: Vec<Function>,
-        error_types: BTreeMap<ErrorSelector, HirType>,
-    ) -> Self {
+    pub(crate) fn new(functions: Vec<Function>) -> Self {
         let main_id = functions.first().expect("Expected at least 1 SSA function").id();
         let mut max_id = main_id;
 
@@ -66,7 +57,6 @@ impl Ssa {
             main_id,
             next_id: AtomicCounter::starting_after(max_id),
             entry_point_to_generated_index,
-            error_selector_to_type: error_types,
         }
     }
 
@@ -0,0 +1,147 @@
+//! Statistics collected while compiling a program into ACIR, used to track how long each
+//! compilation pass takes and how it affects the size of the program.
+use std::time::Duration;
+
+use acvm::{acir::circuit::brillig::BrilligBytecode, FieldElement};
+use serde::Serialize;
+
+use crate::acir::GeneratedAcir;
+
+use super::ssa_gen::Ssa;
+
+/// A report of the passes run by [`optimize_into_acir`][super::optimize_into_acir]
+/// and of the size of the code they generated.
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct CompilationStats {
+    /// The SSA optimization passes, in the order they were run.
+    pub(crate) ssa_passes: Vec<PassStats>,
+    /// The steps run on the final SSA, such as generating Brillig and ACIR.
+    pub(crate) codegen_steps: Vec<StepStats>,
+    /// The number of opcodes in each generated ACIR function.
+    pub(crate) acir_functions: Vec<FunctionStats>,
+    /// The number of opcodes in each generated Brillig function.
+    pub(crate) brillig_functions: Vec<FunctionStats>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PassStats {
+    pub(crate) name: String,
+    pub(crate) time_ms: f64,
+    pub(crate) before: SsaSize,
+    pub(crate) after: SsaSize,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct StepStats {
+    pub(crate) name: String,
+    pub(crate) time_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct FunctionStats {
+    pub(crate) name: String,
+    pub(crate) opcodes: usize,
+}
+
+/// The size of an SSA program, only counting the blocks reachable from each function's entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub(crate) struct SsaSize {
+    pub(crate) functions: usize,
+    pub(crate) blocks: usize,
+    pub(crate) instructions: usize,
+}
+
+impl SsaSize {
+    pub(crate) fn of(ssa: &Ssa) -> Self {
+        let mut size = SsaSize { functions: ssa.functions.len(), blocks: 0, instructions: 0 };
+        for function in ssa.functions.values() {
+            for block in function.reachable_blocks() {
+                size.blocks += 1;
+                size.instructions += function.dfg[block].instructions().len();
+            }
+        }
+        size
+    }
+}
+
+impl CompilationStats {
+    pub(crate) fn add_pass(
+        &mut self,
+        name: &str,
+        duration: Duration,
+        before: SsaSize,
+        after: SsaSize,
+    ) {
+        let name = pass_name(name);
+        self.ssa_passes.push(PassStats { name, time_ms: as_millis(duration), before, after });
+    }
+
+    pub(crate) fn add_step(&mut self, name: &str, duration: Duration) {
+        self.codegen_steps.push(StepStats { name: pass_name(name), time_ms: as_millis(duration) });
+    }
+
+    /// Records the number of opcodes in each of the generated functions.
+    pub(crate) fn add_artifacts(
+        &mut self,
+        acirs: &[GeneratedAcir<FieldElement>],
+        brillig: &[BrilligBytecode<FieldElement>],
+        brillig_names: &[String],
+    ) {
+        self.acir_functions = acirs
+            .iter()
+            .map(|acir| FunctionStats { name: acir.name.clone(), opcodes: acir.opcodes().len() })
+            .collect();
+        self.brillig_functions = brillig
+            .iter()
+            .zip(brillig_names)
+            .map(|(bytecode, name)| FunctionStats {
+                name: name.clone(),
+                opcodes: bytecode.bytecode.len(),
+            })
+            .collect();
+    }
+}
+
+/// Turns messages such as "After Mem2Reg (1st):" into the name of the pass, "Mem2Reg (1st)".
+fn pass_name(msg: &str) -> String {
+    let name = msg.strip_prefix("After ").unwrap_or(msg);
+    name.trim_end_matches(':').to_string()
+}
+
+fn as_millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa::ssa_gen::Ssa;
+
+    use super::{pass_name, SsaSize};
+
+    #[test]
+    fn counts_reachable_blocks_and_instructions() {
+        let src = "
+            acir(inline) fn main f0 {
+              b0(v0: Field):
+                v1 = add v0, Field 1
+                jmp b1()
+              b1():
+                v2 = mul v1, v1
+                return v2
+            }
+            brillig(inline) fn foo f1 {
+              b0(v0: Field):
+                return v0
+            }
+            ";
+        let ssa = Ssa::from_str(src).unwrap();
+        assert_eq!(SsaSize::of(&ssa), SsaSize { functions: 2, blocks: 3, instructions: 2 });
+    }
+
+    #[test]
+    fn strips_pass_messages() {
+        assert_eq!(pass_name("After Mem2Reg (1st):"), "Mem2Reg (1st)");
+        assert_eq!(pass_name("After `as_slice` optimization"), "`as_slice` optimization");
+        assert_eq!(pass_name("SSA to ACIR"), "SSA to ACIR");
+    }
+}
@@ -1,7 +1,6 @@
 use acvm::{
     acir::BlackBoxFunc, blackbox_solver::BlackBoxFunctionSolver, AcirField, BlackBoxResolutionError,
 };
-use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use im::Vector;
 use iter_extended::try_vecmap;
 use noirc_errors::Location;
@@ -67,8 +66,15 @@ fn poseidon2_permutation(
 
     let input = try_vecmap(input, |integer| get_field((integer, input_location)))?;
 
-    // Currently locked to only bn254!
-    let fields = Bn254BlackBoxSolver
+    // Poseidon2 is only implemented for bn254.
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "bls12_381")] {
+            let solver = acvm::blackbox_solver::StubbedBlackBoxSolver;
+        } else {
+            let solver = bn254_blackbox_solver::Bn254BlackBoxSolver;
+        }
+    };
+    let fields = solver
         .poseidon2_permutation(&input, state_length)
         .map_err(|error| InterpreterError::BlackBoxError(error, location))?;
 
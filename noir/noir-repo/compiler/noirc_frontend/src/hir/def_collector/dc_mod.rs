@@ -195,6 +195,28 @@ impl<'a> ModCollector<'a> {
         let mut errors = Vec::new();
 
         for mut trait_impl in impls {
+            // An impl can't be missing some of the trait's methods, so if any of them is
+            // specific to another field the whole impl is left out.
+            let method_for_other_field =
+                trait_impl.items.iter().find_map(|item| match &item.item.kind {
+                    TraitImplItemKind::Function(function) if !is_for_native_field(function) => {
+                        Some(function)
+                    }
+                    _ => None,
+                });
+            if let Some(method) = method_for_other_field {
+                // The standard library only provides some impls for the fields which support them.
+                if !krate.is_stdlib() {
+                    let error = DefCollectorErrorKind::TraitImplForOtherField {
+                        trait_name: trait_impl.trait_name.clone(),
+                        method_name: method.name_ident().clone(),
+                        field: method.attributes().get_field_attribute().unwrap_or_default(),
+                    };
+                    errors.push((error.into(), self.file_id));
+                }
+                continue;
+            }
+
             let trait_name = trait_impl.trait_name.clone();
 
             let (mut unresolved_functions, associated_types, associated_constants) =
@@ -930,10 +952,8 @@ pub fn collect_function(
     doc_comments: Vec<String>,
     errors: &mut Vec<(CompilationError, FileId)>,
 ) -> Option<crate::node_interner::FuncId> {
-    if let Some(field) = function.attributes().get_field_attribute() {
-        if !is_native_field(&field) {
-            return None;
-        }
+    if !is_for_native_field(function) {
+        return None;
     }
 
     let module_data = &mut def_map.modules[module.local_id.0];
@@ -1174,6 +1194,10 @@ cfg_if::cfg_if! {
     }
 }
 
+fn is_for_native_field(function: &NoirFunction) -> bool {
+    function.attributes().get_field_attribute().map_or(true, |field| is_native_field(&field))
+}
+
 fn is_native_field(str: &str) -> bool {
     let big_num = if let Some(hex) = str.strip_prefix("0x") {
         BigUint::from_str_radix(hex, 16)
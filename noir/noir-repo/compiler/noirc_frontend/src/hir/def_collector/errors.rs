@@ -2,6 +2,8 @@ use crate::ast::{Ident, ItemVisibility, Path, UnsupportedNumericGenericType};
 use crate::hir::resolution::import::PathResolutionError;
 use crate::hir::type_check::generics::TraitGenerics;
 
+use super::dc_mod::CHOSEN_FIELD;
+
 use noirc_errors::CustomDiagnostic as Diagnostic;
 use noirc_errors::FileDiagnostic;
 use noirc_errors::Span;
@@ -84,6 +86,8 @@ pub enum DefCollectorErrorKind {
     UnsupportedNumericGenericType(#[from] UnsupportedNumericGenericType),
     #[error("The `#[test]` attribute may only be used on a non-associated function")]
     TestOnAssociatedFunction { span: Span },
+    #[error("Trait impl is left out as one of its methods is for another field")]
+    TraitImplForOtherField { trait_name: Path, method_name: Ident, field: String },
 }
 
 impl DefCollectorErrorKind {
@@ -298,6 +302,13 @@ impl<'a> From<&'a DefCollectorErrorKind> for Diagnostic {
                 String::new(),
                 *span,
             ),
+            DefCollectorErrorKind::TraitImplForOtherField { trait_name, method_name, field } => {
+                Diagnostic::simple_warning(
+                    format!("impl of trait `{trait_name}` is left out as method `{method_name}` is only defined for the `{field}` field"),
+                    format!("the compiler was built for the `{CHOSEN_FIELD}` field"),
+                    method_name.span(),
+                )
+            }
 
         }
     }
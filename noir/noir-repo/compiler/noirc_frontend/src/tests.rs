@@ -3740,3 +3740,39 @@ fn disallows_test_attribute_on_trait_impl_method() {
         })
     ));
 }
+
+// The method is for a field other than the one the compiler is built for.
+#[cfg(not(feature = "bls12_381"))]
+#[test]
+fn warns_when_leaving_out_trait_impl_with_method_for_another_field() {
+    let src = r#"
+    pub trait Trait {
+        fn foo() -> Field;
+    }
+
+    pub struct Foo {}
+    impl Trait for Foo {
+        #[field(bls12_381)]
+        fn foo() -> Field {
+            bar()
+        }
+    }
+
+    #[field(bls12_381)]
+    fn bar() -> Field {
+        1
+    }
+
+    fn main() {}
+    "#;
+    let errors = get_program_errors(src);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        &errors[0].0,
+        CompilationError::DefinitionError(DefCollectorErrorKind::TraitImplForOtherField {
+            method_name,
+            field,
+            ..
+        }) if method_name.0.contents == "foo" && field == "bls12_381"
+    ));
+}
@@ -0,0 +1,53 @@
+use acvm::checker::check_witness_stack;
+use bn254_blackbox_solver::Bn254BlackBoxSolver;
+use clap::Args;
+
+use crate::cli::fs::inputs::read_program_from_file;
+use crate::errors::CliError;
+
+use super::fs::witness::read_witness_stack_from_file;
+
+/// Checks a solved witness stack against a circuit without executing it
+#[derive(Debug, Clone, Args)]
+pub(crate) struct CheckCommand {
+    /// The name of the file containing the serialized witness stack
+    #[clap(long)]
+    witness: String,
+
+    /// The name of the file containing the circuit, either as binary bytecode or in textual form
+    #[clap(long, short)]
+    bytecode: String,
+
+    /// The working directory
+    #[clap(long, short)]
+    working_directory: String,
+
+    /// Report every violated opcode rather than stopping at the first one
+    #[clap(long, short)]
+    all: bool,
+}
+
+pub(crate) fn run(args: CheckCommand) -> Result<String, CliError> {
+    let program = read_program_from_file(&args.working_directory, &args.bytecode)?;
+    let witness_stack = read_witness_stack_from_file(&args.working_directory, &args.witness)?;
+
+    let violations = check_witness_stack(&program, &witness_stack, &Bn254BlackBoxSolver, args.all)?;
+    if violations.is_empty() {
+        let message = "Witness satisfies all constraints".to_string();
+        println!("{message}");
+        return Ok(message);
+    }
+
+    for violation in &violations {
+        let call_stack = violation
+            .call_stack
+            .iter()
+            .map(|location| {
+                format!("{}:{}", location.acir_function_index, location.opcode_location)
+            })
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        eprintln!("Opcode {call_stack} is not satisfied: {}", violation.kind);
+    }
+    Err(CliError::ConstraintViolations(violations.len()))
+}
@@ -6,9 +6,10 @@ use acir::FieldElement;
 use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
 
-use crate::cli::fs::inputs::{read_bytecode_from_file, read_inputs_from_file};
+use crate::cli::fs::inputs::{read_inputs_from_file, read_program_from_file};
 use crate::errors::CliError;
 use nargo::ops::{execute_program, DefaultForeignCallExecutor};
+use noirc_abi::input_parser::Format;
 
 use super::fs::witness::{create_output_witness_string, save_witness_to_dir};
 
@@ -23,7 +24,12 @@ pub(crate) struct ExecuteCommand {
     #[clap(long, short)]
     input_witness: String,
 
-    /// The name of the binary file containing circuit bytecode
+    /// The format of the input witness: toml (a map from witness indices to values), fields (a list of
+    /// field elements for consecutive witnesses) or calldata (the same field elements as a hex string)
+    #[clap(long, default_value = "toml")]
+    input_format: Format,
+
+    /// The name of the file containing the circuit, either as binary bytecode or in textual form
     #[clap(long, short)]
     bytecode: String,
 
@@ -37,9 +43,10 @@ pub(crate) struct ExecuteCommand {
 }
 
 fn run_command(args: ExecuteCommand) -> Result<String, CliError> {
-    let bytecode = read_bytecode_from_file(&args.working_directory, &args.bytecode)?;
-    let circuit_inputs = read_inputs_from_file(&args.working_directory, &args.input_witness)?;
-    let output_witness = execute_program_from_witness(circuit_inputs, &bytecode)?;
+    let program = read_program_from_file(&args.working_directory, &args.bytecode)?;
+    let circuit_inputs =
+        read_inputs_from_file(&args.working_directory, &args.input_witness, args.input_format)?;
+    let output_witness = execute_program_from_witness(circuit_inputs, &program)?;
     assert_eq!(output_witness.length(), 1, "ACVM CLI only supports a witness stack of size 1");
     let output_witness_string = create_output_witness_string(
         &output_witness.peek().expect("Should have a witness stack item").witness,
@@ -65,12 +72,10 @@ pub(crate) fn run(args: ExecuteCommand) -> Result<String, CliError> {
 
 pub(crate) fn execute_program_from_witness(
     inputs_map: WitnessMap<FieldElement>,
-    bytecode: &[u8],
+    program: &Program<FieldElement>,
 ) -> Result<WitnessStack<FieldElement>, CliError> {
-    let program: Program<FieldElement> = Program::deserialize_program(bytecode)
-        .map_err(|_| CliError::CircuitDeserializationError())?;
     execute_program(
-        &program,
+        program,
         inputs_map,
         &Bn254BlackBoxSolver,
         &mut DefaultForeignCallExecutor::new(true, None, None, None),
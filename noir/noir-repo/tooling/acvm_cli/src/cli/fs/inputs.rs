@@ -1,16 +1,22 @@
 use acir::{
+    circuit::Program,
     native_types::{Witness, WitnessMap},
     AcirField, FieldElement,
 };
+use noirc_abi::input_parser::{parse_calldata, parse_fields, Format};
 use toml::Table;
 
 use crate::errors::{CliError, FilesystemError};
-use std::{fs::read, path::Path};
+use std::{collections::BTreeMap, fs::read, path::Path};
 
-/// Returns the circuit's parameters parsed from a toml file at the given location
+/// Returns the circuit's parameters parsed from a file at the given location.
+///
+/// A toml file maps witness indices to their values, while a list of field elements
+/// assigns them to consecutive witnesses starting from the first.
 pub(crate) fn read_inputs_from_file<P: AsRef<Path>>(
     working_directory: P,
     file_name: &String,
+    format: Format,
 ) -> Result<WitnessMap<FieldElement>, CliError> {
     let file_path = working_directory.as_ref().join(file_name);
     if !file_path.exists() {
@@ -22,9 +28,28 @@ pub(crate) fn read_inputs_from_file<P: AsRef<Path>>(
 
     let input_string = std::fs::read_to_string(file_path)
         .map_err(|_| FilesystemError::InvalidTomlFile(file_name.clone()))?;
+    let fields = match format {
+        Format::Toml => return parse_witness_map(&input_string, file_name),
+        Format::Json => return Err(CliError::UnsupportedInputFormat(format.ext())),
+        Format::Fields => parse_fields(&input_string)?,
+        Format::Calldata => parse_calldata(&input_string)?,
+    };
+
+    Ok(fields
+        .into_iter()
+        .enumerate()
+        .map(|(index, field)| (Witness(index as u32), field))
+        .collect::<BTreeMap<_, _>>()
+        .into())
+}
+
+fn parse_witness_map(
+    input_string: &str,
+    file_name: &str,
+) -> Result<WitnessMap<FieldElement>, CliError> {
     let input_map = input_string
         .parse::<Table>()
-        .map_err(|_| FilesystemError::InvalidTomlFile(file_name.clone()))?;
+        .map_err(|_| FilesystemError::InvalidTomlFile(file_name.to_owned()))?;
     let mut witnesses: WitnessMap<FieldElement> = WitnessMap::new();
     for (key, value) in input_map.into_iter() {
         let index =
@@ -52,3 +77,22 @@ pub(crate) fn read_bytecode_from_file<P: AsRef<Path>>(
         read(file_path).map_err(|_| FilesystemError::InvalidBytecodeFile(file_name.clone()))?;
     Ok(bytecode)
 }
+
+/// Returns the program read from the file at the given location, which may either contain
+/// serialized bytecode or the textual representation of the program.
+pub(crate) fn read_program_from_file<P: AsRef<Path>>(
+    working_directory: P,
+    file_name: &String,
+) -> Result<Program<FieldElement>, CliError> {
+    let bytecode = read_bytecode_from_file(working_directory, file_name)?;
+
+    // Serialized programs are gzip compressed so can be distinguished by the gzip magic number.
+    if bytecode.starts_with(&[0x1f, 0x8b]) {
+        return Program::deserialize_program(&bytecode)
+            .map_err(|_| CliError::CircuitDeserializationError());
+    }
+
+    let source = String::from_utf8(bytecode)
+        .map_err(|_| FilesystemError::InvalidBytecodeFile(file_name.clone()))?;
+    Ok(source.parse()?)
+}
@@ -61,3 +61,17 @@ pub(crate) fn save_witness_to_dir<P: AsRef<Path>>(
 
     Ok(witness_path)
 }
+
+pub(crate) fn read_witness_stack_from_file<P: AsRef<Path>>(
+    working_directory: P,
+    file_name: &String,
+) -> Result<WitnessStack<FieldElement>, FilesystemError> {
+    let file_path = working_directory.as_ref().join(file_name);
+    if !file_path.exists() {
+        return Err(FilesystemError::MissingWitnessFile(file_name.to_owned(), file_path));
+    }
+    let buf = std::fs::read(file_path)
+        .map_err(|_| FilesystemError::InvalidWitnessFile(file_name.clone()))?;
+    WitnessStack::try_from(buf.as_slice())
+        .map_err(|_| FilesystemError::InvalidWitnessFile(file_name.clone()))
+}
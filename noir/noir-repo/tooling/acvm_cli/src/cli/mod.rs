@@ -2,6 +2,7 @@ use clap::{Parser, Subcommand};
 use color_eyre::eyre;
 use const_format::formatcp;
 
+mod check_cmd;
 mod execute_cmd;
 mod fs;
 
@@ -19,6 +20,7 @@ struct ACVMCli {
 #[non_exhaustive]
 #[derive(Subcommand, Clone, Debug)]
 enum ACVMCommand {
+    Check(check_cmd::CheckCommand),
     Execute(execute_cmd::ExecuteCommand),
 }
 
@@ -27,6 +29,7 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
     let ACVMCli { command } = ACVMCli::parse();
 
     match command {
+        ACVMCommand::Check(args) => check_cmd::run(args),
         ACVMCommand::Execute(args) => execute_cmd::run(args),
     }?;
 
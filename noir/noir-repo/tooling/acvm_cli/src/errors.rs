@@ -1,5 +1,7 @@
-use acir::FieldElement;
+use acir::{FieldElement, ParserError};
+use acvm::checker::WitnessStackMismatch;
 use nargo::NargoError;
+use noirc_abi::errors::InputParserError;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -21,6 +23,14 @@ pub(crate) enum FilesystemError {
 
     #[error(" Error: failed to create output witness file {0}.")]
     OutputWitnessCreationFailed(String),
+
+    #[error(
+      " Error: cannot find {0} in expected location {1:?}.\n Please generate this file at the expected location."
+    )]
+    MissingWitnessFile(String, PathBuf),
+
+    #[error(" Error: failed to read witness file {0}.")]
+    InvalidWitnessFile(String),
 }
 
 #[derive(Debug, Error)]
@@ -33,6 +43,10 @@ pub(crate) enum CliError {
     #[error("Error: failed to deserialize circuit in ACVM CLI")]
     CircuitDeserializationError(),
 
+    /// Error related to parsing a textual circuit
+    #[error("Error: failed to parse circuit: {0}")]
+    CircuitParsingError(#[from] ParserError),
+
     /// Error related to circuit execution
     #[error(transparent)]
     CircuitExecutionError(#[from] NargoError<FieldElement>),
@@ -45,6 +59,22 @@ pub(crate) enum CliError {
     #[error("Error: failed to parse witness index {0}")]
     WitnessIndexError(String),
 
+    /// Error related to parsing an input witness given as a list of field elements
+    #[error("Error: failed to parse input witness: {0}")]
+    InputParserError(#[from] InputParserError),
+
+    /// Input witness format which can't describe a witness map
+    #[error("Error: input witnesses can't be read from {0} files")]
+    UnsupportedInputFormat(&'static str),
+
     #[error(" Error: failed to serialize output witness.")]
     OutputWitnessSerializationFailed(),
+
+    /// Witness stack does not line up with the circuit's calls
+    #[error(transparent)]
+    WitnessStackMismatch(#[from] WitnessStackMismatch),
+
+    /// Witness does not satisfy the circuit
+    #[error("Error: witness violates {0} constraint{}", if *.0 == 1 { "" } else { "s" })]
+    ConstraintViolations(usize),
 }
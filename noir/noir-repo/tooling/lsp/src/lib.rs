@@ -51,6 +51,7 @@ use notifications::{
     on_did_change_configuration, on_did_change_text_document, on_did_close_text_document,
     on_did_open_text_document, on_did_save_text_document, on_exit, on_initialized,
 };
+use opcode_counts::{on_opcode_counts_compiled, OpcodeCountsCache, OpcodeCountsCompiled};
 use requests::{
     on_code_action_request, on_code_lens_request, on_completion_request,
     on_document_symbol_request, on_formatting, on_goto_declaration_request,
@@ -66,6 +67,7 @@ use tower::Service;
 mod attribute_reference_finder;
 mod modules;
 mod notifications;
+mod opcode_counts;
 mod requests;
 mod solver;
 mod tests;
@@ -103,6 +105,9 @@ pub struct LspState {
 
     // Tracks files that currently have errors, by package root.
     files_with_errors: HashMap<PathBuf, HashSet<Url>>,
+
+    // Opcode counts of the packages compiled in the background, when enabled.
+    opcode_counts: OpcodeCountsCache,
 }
 
 struct WorkspaceCacheData {
@@ -134,6 +139,7 @@ impl LspState {
             open_documents_count: 0,
             options: Default::default(),
             files_with_errors: HashMap::new(),
+            opcode_counts: OpcodeCountsCache::default(),
         }
     }
 }
@@ -174,7 +180,8 @@ impl NargoLspService {
             .notification::<notification::DidChangeTextDocument>(on_did_change_text_document)
             .notification::<notification::DidCloseTextDocument>(on_did_close_text_document)
             .notification::<notification::DidSaveTextDocument>(on_did_save_text_document)
-            .notification::<notification::Exit>(on_exit);
+            .notification::<notification::Exit>(on_exit)
+            .event::<OpcodeCountsCompiled>(on_opcode_counts_compiled);
         Self { router }
     }
 }
@@ -274,6 +281,7 @@ pub(crate) fn resolve_workspace_for_source_path(file_path: &Path) -> Result<Work
             &toml_path,
             PackageSelection::All,
             Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+            false,
         ) {
             Ok(workspace) => return Ok(workspace),
             Err(error) => {
@@ -84,6 +84,7 @@ pub(super) fn on_did_close_text_document(
     if state.open_documents_count == 0 {
         state.package_cache.clear();
         state.workspace_cache.clear();
+        state.opcode_counts.clear();
     }
 
     let document_uri = params.text_document.uri;
@@ -111,6 +112,7 @@ pub(super) fn on_did_save_text_document(
 // Given a Noir document, find the workspace it's contained in (an assumed workspace is created if
 // it's only contained in a package), then type-checks the workspace's packages,
 // caching code lenses and type definitions, and notifying about compilation errors.
+// When opcode counts are enabled, the document's package is then compiled in the background.
 pub(crate) fn process_workspace_for_noir_document(
     state: &mut LspState,
     document_uri: Url,
@@ -178,6 +180,17 @@ pub(crate) fn process_workspace_for_noir_document(
         }
     }
 
+    if state.options.enable_opcode_counts && output_diagnostics {
+        if let Some(package) = crate::workspace_package_for_file(&workspace, &file_path) {
+            crate::opcode_counts::compile_in_background(
+                state,
+                &workspace,
+                package,
+                &workspace_file_manager,
+            );
+        }
+    }
+
     state.workspace_cache.insert(
         workspace.root_dir.clone(),
         WorkspaceCacheData { file_manager: workspace_file_manager },
@@ -0,0 +1,466 @@
+//! ACIR and Brillig opcode counts of a package, compiled in the background while editing.
+//!
+//! When `enableOpcodeCounts` is set, opening or saving a document compiles its package on a worker thread
+//! of the package, which coalesces the compilations requested while it is busy into the latest one.
+//! Each opcode of the compiled program is mapped to the source locations of its call stack through the
+//! program's debug info, as in `noir-profiler`. The counts are then shown as an inlay hint at the end of
+//! each line, for the opcodes generated by the line itself, and as a code lens on each function, for the
+//! opcodes generated by the function and what it calls.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+use acvm::{
+    acir::circuit::{brillig::BrilligFunctionId, BrilligOpcodeLocation, OpcodeLocation, Program},
+    FieldElement,
+};
+use async_lsp::{ClientSocket, LanguageClient};
+use fm::FileManager;
+use lsp_types::Position;
+use nargo::{
+    ops::{compile_contract, compile_program, transform_contract, transform_program},
+    package::Package,
+    parse_all,
+    workspace::Workspace,
+};
+use noirc_driver::{CompileOptions, DEFAULT_EXPRESSION_WIDTH};
+use noirc_errors::{debug_info::DebugInfo, Location, Span};
+
+use crate::LspState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OpcodeKind {
+    Acir,
+    Brillig,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OpcodeCounts {
+    pub(crate) acir: usize,
+    pub(crate) brillig: usize,
+}
+
+impl OpcodeCounts {
+    fn add(&mut self, kind: OpcodeKind) {
+        match kind {
+            OpcodeKind::Acir => self.acir += 1,
+            OpcodeKind::Brillig => self.brillig += 1,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.acir == 0 && self.brillig == 0
+    }
+
+    /// The counts as shown to the user, leaving out the kinds of opcodes which there are none of
+    pub(crate) fn label(&self) -> String {
+        let mut parts = Vec::new();
+        if self.acir > 0 {
+            parts.push(format!("{} ACIR", self.acir));
+        }
+        if self.brillig > 0 {
+            parts.push(format!("{} Brillig", self.brillig));
+        }
+        format!("{} opcodes", parts.join(", "))
+    }
+}
+
+/// The locations of an opcode within one file
+#[derive(Debug)]
+struct OpcodeSpans {
+    kind: OpcodeKind,
+    /// The innermost location of the opcode's call stack, if it is in the file
+    span: Option<Span>,
+    /// The locations of the opcode's call stack which are in the file
+    call_stack: Vec<Span>,
+}
+
+/// The opcodes of a compiled package which were generated from one of its files
+#[derive(Debug)]
+pub(crate) struct FileOpcodeCounts {
+    /// The contents of the file when it was compiled
+    source: String,
+    opcodes: Vec<OpcodeSpans>,
+}
+
+impl FileOpcodeCounts {
+    /// The opcode counts of each line with opcodes, at the end of the line.
+    /// Opcodes are counted on the line of the innermost location of their call stack.
+    pub(crate) fn line_counts(&self) -> Vec<(Position, OpcodeCounts)> {
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(self.source.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        let mut counts: BTreeMap<usize, OpcodeCounts> = BTreeMap::new();
+        for opcode in &self.opcodes {
+            if let Some(span) = opcode.span {
+                let offset = span.start() as usize;
+                let line = line_starts.partition_point(|start| *start <= offset) - 1;
+                counts.entry(line).or_default().add(opcode.kind);
+            }
+        }
+
+        counts
+            .into_iter()
+            .map(|(line, counts)| {
+                let start = line_starts[line];
+                let end = line_starts.get(line + 1).map_or(self.source.len(), |next| next - 1);
+                let text = self.source[start..end].trim_end_matches('\r');
+                let character = text.encode_utf16().count() as u32;
+                (Position { line: line as u32, character }, counts)
+            })
+            .collect()
+    }
+
+    /// The counts of the opcodes generated by code within a span, including the code it calls
+    pub(crate) fn counts_in_span(&self, span: Span) -> OpcodeCounts {
+        let mut counts = OpcodeCounts::default();
+        for opcode in &self.opcodes {
+            if opcode.call_stack.iter().any(|location| span.contains(location)) {
+                counts.add(opcode.kind);
+            }
+        }
+        counts
+    }
+}
+
+/// The opcode counts of the latest compilation of each package
+#[derive(Debug, Default)]
+pub(crate) struct OpcodeCountsCache {
+    /// The number of compilations started, which identifies each compilation
+    compilations: usize,
+    /// The latest compilation started for each package, by package root
+    latest_compilations: HashMap<PathBuf, usize>,
+    /// The opcode counts of each package by file, by package root
+    packages: HashMap<PathBuf, HashMap<PathBuf, FileOpcodeCounts>>,
+    /// The compilation requests of the worker thread of each package, by package root
+    workers: HashMap<PathBuf, Sender<CompilationRequest>>,
+}
+
+impl OpcodeCountsCache {
+    fn start_compilation(&mut self, package_root: &Path) -> usize {
+        self.compilations += 1;
+        self.latest_compilations.insert(package_root.to_path_buf(), self.compilations);
+        self.compilations
+    }
+
+    /// Stores the counts of a compilation, unless a later compilation of the package was started since.
+    /// Returns whether the counts were stored.
+    fn finish_compilation(&mut self, compiled: OpcodeCountsCompiled) -> bool {
+        if self.latest_compilations.get(&compiled.package_root) != Some(&compiled.compilation) {
+            return false;
+        }
+        self.packages.insert(compiled.package_root, compiled.files);
+        true
+    }
+
+    /// The opcode counts of a file, if it hasn't changed since it was compiled.
+    /// Files of a package's dependencies can be in several packages, in which case the counts of the
+    /// package containing the file are preferred.
+    pub(crate) fn file_counts(&self, file_path: &Path, source: &str) -> Option<&FileOpcodeCounts> {
+        self.packages
+            .iter()
+            .filter_map(|(package_root, files)| Some((package_root, files.get(file_path)?)))
+            .max_by_key(|(package_root, _)| file_path.starts_with(package_root))
+            .map(|(_, counts)| counts)
+            .filter(|counts| counts.source == source)
+    }
+
+    /// Sends a compilation to the worker thread of its package, starting one if there is none.
+    /// A worker stops once its package is no longer in the cache, after any compilation it is running.
+    fn request_compilation(&mut self, client: &ClientSocket, request: CompilationRequest) {
+        let package_root = request.package.root_dir.clone();
+        let request = match self.workers.get(&package_root) {
+            Some(worker) => match worker.send(request) {
+                Ok(()) => return,
+                // The worker stopped, for instance because the compiler panicked
+                Err(mpsc::SendError(request)) => request,
+            },
+            None => request,
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let client = client.clone();
+        let worker = std::thread::Builder::new()
+            .name(format!("opcode counts of {}", request.package.name))
+            .stack_size(COMPILATION_STACK_SIZE)
+            .spawn(move || run_worker(&receiver, &client));
+        if worker.is_ok() && sender.send(request).is_ok() {
+            self.workers.insert(package_root, sender);
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.latest_compilations.clear();
+        self.packages.clear();
+        self.workers.clear();
+    }
+}
+
+/// The stack size of the worker threads, the same as the one `nargo` compiles with
+/// as the compiler recurses deeply.
+const COMPILATION_STACK_SIZE: usize = 4 * 1024 * 1024;
+
+/// A compilation of a package, requested when one of its documents is opened or saved
+struct CompilationRequest {
+    compilation: usize,
+    workspace: Workspace,
+    package: Package,
+    file_manager: FileManager,
+}
+
+/// Compiles the requests of a package one at a time, until the package's sender is dropped.
+/// A compilation which is running isn't interrupted, but only the latest of the requests made
+/// in the meantime is compiled after it.
+fn run_worker(requests: &Receiver<CompilationRequest>, client: &ClientSocket) {
+    while let Ok(request) = requests.recv() {
+        let CompilationRequest { compilation, workspace, package, file_manager } =
+            latest_request(request, requests);
+        if let Some(files) = compile_opcode_counts(&file_manager, &workspace, &package) {
+            let _ = client.emit(OpcodeCountsCompiled {
+                package_root: package.root_dir,
+                compilation,
+                files,
+            });
+        }
+    }
+}
+
+/// Returns the last of the requests which are waiting, discarding the others
+fn latest_request<T>(request: T, requests: &Receiver<T>) -> T {
+    requests.try_iter().last().unwrap_or(request)
+}
+
+/// The event emitted to the LSP's main loop when the background compilation of a package finishes
+pub(crate) struct OpcodeCountsCompiled {
+    package_root: PathBuf,
+    compilation: usize,
+    files: HashMap<PathBuf, FileOpcodeCounts>,
+}
+
+/// Compiles a package on its worker thread, which then emits its opcode counts to the LSP's main loop.
+/// Packages which fail to compile keep the counts of their last successful compilation.
+pub(crate) fn compile_in_background(
+    state: &mut LspState,
+    workspace: &Workspace,
+    package: &Package,
+    file_manager: &FileManager,
+) {
+    if !package.is_binary() && !package.is_contract() {
+        return;
+    }
+
+    let request = CompilationRequest {
+        compilation: state.opcode_counts.start_compilation(&package.root_dir),
+        workspace: workspace.clone(),
+        package: package.clone(),
+        file_manager: file_manager.clone(),
+    };
+    state.opcode_counts.request_compilation(&state.client, request);
+}
+
+pub(crate) fn on_opcode_counts_compiled(
+    state: &mut LspState,
+    compiled: OpcodeCountsCompiled,
+) -> ControlFlow<Result<(), async_lsp::Error>> {
+    if state.opcode_counts.finish_compilation(compiled) {
+        // The refresh requests are sent as they are made, and their responses aren't needed
+        drop(state.client.inlay_hint_refresh(()));
+        if state.options.enable_code_lens {
+            drop(state.client.code_lens_refresh(()));
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+/// Compiles a package as `nargo info` does, and maps its opcodes to the files they were generated from
+fn compile_opcode_counts(
+    file_manager: &FileManager,
+    workspace: &Workspace,
+    package: &Package,
+) -> Option<HashMap<PathBuf, FileOpcodeCounts>> {
+    let parsed_files = parse_all(file_manager);
+    let compile_options = CompileOptions::default();
+    let expression_width = package.expression_width.unwrap_or(DEFAULT_EXPRESSION_WIDTH);
+
+    let mut call_stacks = Vec::new();
+    if package.is_contract() {
+        let (contract, _warnings) =
+            compile_contract(file_manager, &parsed_files, workspace, package, &compile_options)
+                .ok()?;
+        let contract = transform_contract(contract, expression_width);
+        for function in &contract.functions {
+            collect_call_stacks(&function.bytecode, &function.debug, &mut call_stacks);
+        }
+    } else {
+        let (program, _warnings) = compile_program(
+            file_manager,
+            &parsed_files,
+            workspace,
+            package,
+            &compile_options,
+            None,
+        )
+        .ok()?;
+        let program = transform_program(program, expression_width);
+        collect_call_stacks(&program.program, &program.debug, &mut call_stacks);
+    }
+
+    let mut files: HashMap<PathBuf, FileOpcodeCounts> = HashMap::new();
+    for (kind, call_stack) in call_stacks {
+        let mut file_ids = Vec::new();
+        for location in &call_stack {
+            if !file_ids.contains(&location.file) {
+                file_ids.push(location.file);
+            }
+        }
+
+        for file_id in file_ids {
+            let (Some(path), Some(source)) =
+                (file_manager.path(file_id), file_manager.fetch_file(file_id))
+            else {
+                continue;
+            };
+            let opcode = OpcodeSpans {
+                kind,
+                span: call_stack
+                    .last()
+                    .filter(|location| location.file == file_id)
+                    .map(|location| location.span),
+                call_stack: call_stack
+                    .iter()
+                    .filter(|location| location.file == file_id)
+                    .map(|location| location.span)
+                    .collect(),
+            };
+            files
+                .entry(path.to_path_buf())
+                .or_insert_with(|| FileOpcodeCounts {
+                    source: source.to_string(),
+                    opcodes: Vec::new(),
+                })
+                .opcodes
+                .push(opcode);
+        }
+    }
+    Some(files)
+}
+
+/// Collects the call stack of each ACIR and Brillig opcode of a program.
+/// Brillig opcodes without a location, such as those of the procedures shared by Brillig functions,
+/// aren't attributed to any source.
+fn collect_call_stacks(
+    program: &Program<FieldElement>,
+    debug: &[DebugInfo],
+    call_stacks: &mut Vec<(OpcodeKind, Vec<Location>)>,
+) {
+    for (function, debug_info) in program.functions.iter().zip(debug) {
+        for index in 0..function.opcodes.len() {
+            let call_stack =
+                debug_info.opcode_location(&OpcodeLocation::Acir(index)).unwrap_or_default();
+            call_stacks.push((OpcodeKind::Acir, call_stack));
+        }
+    }
+
+    for (index, function) in program.unconstrained_functions.iter().enumerate() {
+        let function_id = BrilligFunctionId(index as u32);
+        let locations =
+            debug.iter().find_map(|debug_info| debug_info.brillig_locations.get(&function_id));
+        for index in 0..function.bytecode.len() {
+            let call_stack = locations
+                .and_then(|locations| locations.get(&BrilligOpcodeLocation(index)))
+                .cloned()
+                .unwrap_or_default();
+            call_stacks.push((OpcodeKind::Brillig, call_stack));
+        }
+    }
+}
+
+#[cfg(test)]
+mod opcode_counts_tests {
+    use super::*;
+
+    const SOURCE: &str = "fn main(x: u8) {\n    assert(x == 1);\n    foo(x);\r\n}\nfn foo(x: u8) {\n    assert(x != 2);\n}\n";
+
+    fn span_of(text: &str) -> Span {
+        let start = SOURCE.find(text).unwrap() as u32;
+        Span::from(start..start + text.len() as u32)
+    }
+
+    fn file_counts() -> FileOpcodeCounts {
+        let opcode = |kind, call_stack: Vec<Span>| OpcodeSpans {
+            kind,
+            span: call_stack.last().copied(),
+            call_stack,
+        };
+        FileOpcodeCounts {
+            source: SOURCE.to_string(),
+            opcodes: vec![
+                opcode(OpcodeKind::Acir, vec![span_of("x == 1")]),
+                opcode(OpcodeKind::Acir, vec![span_of("x == 1")]),
+                opcode(OpcodeKind::Acir, vec![span_of("foo(x)"), span_of("x != 2")]),
+                opcode(OpcodeKind::Brillig, vec![span_of("foo(x)")]),
+            ],
+        }
+    }
+
+    #[test]
+    fn counts_opcodes_at_the_end_of_their_line() {
+        let counts = file_counts().line_counts();
+        assert_eq!(
+            counts,
+            vec![
+                (Position { line: 1, character: 19 }, OpcodeCounts { acir: 2, brillig: 0 }),
+                (Position { line: 2, character: 11 }, OpcodeCounts { acir: 0, brillig: 1 }),
+                (Position { line: 5, character: 19 }, OpcodeCounts { acir: 1, brillig: 0 }),
+            ]
+        );
+        assert_eq!(counts[0].1.label(), "2 ACIR opcodes");
+        assert_eq!(OpcodeCounts { acir: 3, brillig: 1 }.label(), "3 ACIR, 1 Brillig opcodes");
+    }
+
+    #[test]
+    fn counts_opcodes_of_functions_including_their_callees() {
+        let file_counts = file_counts();
+        let main = span_of("fn main(x: u8) {\n    assert(x == 1);\n    foo(x);\r\n}");
+        let foo = span_of("fn foo(x: u8) {\n    assert(x != 2);\n}");
+        assert_eq!(file_counts.counts_in_span(main), OpcodeCounts { acir: 3, brillig: 1 });
+        assert_eq!(file_counts.counts_in_span(foo), OpcodeCounts { acir: 1, brillig: 0 });
+    }
+
+    #[test]
+    fn only_keeps_the_counts_of_the_latest_compilation() {
+        let package_root = PathBuf::from("/package");
+        let file_path = package_root.join("src/main.nr");
+        let compiled = |compilation| {
+            let files = HashMap::from([(file_path.clone(), file_counts())]);
+            OpcodeCountsCompiled { package_root: package_root.clone(), compilation, files }
+        };
+
+        let mut cache = OpcodeCountsCache::default();
+        let first = cache.start_compilation(&package_root);
+        let second = cache.start_compilation(&package_root);
+        assert!(!cache.finish_compilation(compiled(first)));
+        assert!(cache.file_counts(&file_path, SOURCE).is_none());
+
+        assert!(cache.finish_compilation(compiled(second)));
+        assert!(cache.file_counts(&file_path, SOURCE).is_some());
+        assert!(cache.file_counts(&file_path, "fn main() {}").is_none());
+    }
+
+    #[test]
+    fn compiles_the_latest_of_the_waiting_requests() {
+        let (sender, receiver) = mpsc::channel();
+        assert_eq!(latest_request(1, &receiver), 1);
+
+        for request in 2..5 {
+            sender.send(request).unwrap();
+        }
+        assert_eq!(latest_request(1, &receiver), 4);
+        assert!(receiver.try_recv().is_err());
+    }
+}
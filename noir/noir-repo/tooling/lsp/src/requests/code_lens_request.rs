@@ -1,10 +1,18 @@
-use std::future::{self, Future};
+use std::{
+    future::{self, Future},
+    path::Path,
+};
 
 use async_lsp::{ErrorCode, ResponseError};
 
+use fm::codespan_files::SimpleFile;
 use nargo::{package::Package, workspace::Workspace};
 use noirc_driver::check_crate;
-use noirc_frontend::hir::FunctionNameMatch;
+use noirc_errors::Span;
+use noirc_frontend::{
+    ast::{NoirFunction, Visitor},
+    hir::FunctionNameMatch,
+};
 
 use crate::{
     byte_span_to_range, prepare_source, resolve_workspace_for_source_path,
@@ -52,8 +60,11 @@ fn on_code_lens_request_inner(
         ResponseError::new(ErrorCode::REQUEST_FAILED, "URI is not a valid file path")
     })?;
 
+    let opcode_count_lenses =
+        collect_opcode_count_lenses(state, params.text_document.uri.as_ref(), &file_path);
+
     if let Some(collected_lenses) = state.cached_lenses.get(&params.text_document.uri.to_string()) {
-        return Ok(Some(collected_lenses.clone()));
+        return Ok(Some([collected_lenses.clone(), opcode_count_lenses].concat()));
     }
 
     let source_string = std::fs::read_to_string(&file_path).map_err(|_| {
@@ -76,12 +87,80 @@ fn on_code_lens_request_inner(
 
     if collected_lenses.is_empty() {
         state.cached_lenses.remove(&params.text_document.uri.to_string());
-        Ok(None)
     } else {
         state
             .cached_lenses
             .insert(params.text_document.uri.to_string().clone(), collected_lenses.clone());
-        Ok(Some(collected_lenses))
+    }
+
+    let lenses = [collected_lenses, opcode_count_lenses].concat();
+    if lenses.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(lenses))
+    }
+}
+
+/// Collects a lens with the opcode counts of each function of the file, including the functions they
+/// call, if the file hasn't changed since its package was last compiled.
+/// The lenses run `nargo info` on the package.
+fn collect_opcode_count_lenses(state: &LspState, uri: &str, file_path: &Path) -> Vec<CodeLens> {
+    if !state.options.enable_opcode_counts {
+        return Vec::new();
+    }
+
+    let source = match state.input_files.get(uri) {
+        Some(source) => source.clone(),
+        None => match std::fs::read_to_string(file_path) {
+            Ok(source) => source,
+            Err(_) => return Vec::new(),
+        },
+    };
+    let Some(file_counts) = state.opcode_counts.file_counts(file_path, &source) else {
+        return Vec::new();
+    };
+    let Ok(workspace) = resolve_workspace_for_source_path(file_path) else {
+        return Vec::new();
+    };
+    let Some(package) = crate::workspace_package_for_file(&workspace, file_path) else {
+        return Vec::new();
+    };
+
+    let (parsed_module, _errors) = noirc_frontend::parse_program(&source);
+    let mut collector = FunctionSpanCollector::default();
+    parsed_module.accept(&mut collector);
+
+    let file = SimpleFile::new(file_path.display().to_string(), source.as_str());
+    collector
+        .functions
+        .into_iter()
+        .filter_map(|(name_span, span)| {
+            let counts = file_counts.counts_in_span(span);
+            if counts.is_empty() {
+                return None;
+            }
+
+            let range = byte_span_to_range(&file, (), name_span.into())?;
+            let command = Command {
+                title: counts.label(),
+                command: INFO_COMMAND.into(),
+                arguments: Some(package_selection_args(&workspace, package)),
+            };
+            Some(CodeLens { range, command: Some(command), data: None })
+        })
+        .collect()
+}
+
+/// Collects the span of the name and of the whole definition of each function in a file
+#[derive(Default)]
+struct FunctionSpanCollector {
+    functions: Vec<(Span, Span)>,
+}
+
+impl Visitor for FunctionSpanCollector {
+    fn visit_noir_function(&mut self, noir_function: &NoirFunction, span: Span) -> bool {
+        self.functions.push((noir_function.name_ident().span(), span));
+        false
     }
 }
 
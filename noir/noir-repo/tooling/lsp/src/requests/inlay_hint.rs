@@ -51,9 +51,53 @@ pub(crate) fn on_inlay_hint_request(
             collector.inlay_hints
         })
     });
+
+    let opcode_count_hints = collect_opcode_count_hints(state, &params);
+    let result = result.map(|inlay_hints| {
+        if opcode_count_hints.is_empty() {
+            return inlay_hints;
+        }
+        Some(inlay_hints.into_iter().flatten().chain(opcode_count_hints).collect())
+    });
     future::ready(result)
 }
 
+/// Shows the opcode counts of each line of the range at the end of the line, if the document hasn't
+/// changed since its package was last compiled
+fn collect_opcode_count_hints(state: &LspState, params: &InlayHintParams) -> Vec<InlayHint> {
+    if !state.options.enable_opcode_counts {
+        return Vec::new();
+    }
+
+    let Ok(file_path) = params.text_document.uri.to_file_path() else {
+        return Vec::new();
+    };
+    let Some(source) = state.input_files.get(&params.text_document.uri.to_string()) else {
+        return Vec::new();
+    };
+    let Some(file_counts) = state.opcode_counts.file_counts(&file_path, source) else {
+        return Vec::new();
+    };
+
+    file_counts
+        .line_counts()
+        .into_iter()
+        .filter(|(position, _)| {
+            params.range.start.line <= position.line && position.line <= params.range.end.line
+        })
+        .map(|(position, counts)| InlayHint {
+            position,
+            label: InlayHintLabel::String(counts.label()),
+            kind: None,
+            text_edits: None,
+            tooltip: None,
+            padding_left: Some(true),
+            padding_right: None,
+            data: None,
+        })
+        .collect()
+}
+
 pub(crate) struct InlayHintCollector<'a> {
     files: &'a FileMap,
     file_id: FileId,
@@ -76,6 +76,12 @@ pub(crate) struct LspInitializationOptions {
 
     #[serde(rename = "inlayHints", default = "default_inlay_hints")]
     pub(crate) inlay_hints: InlayHintsOptions,
+
+    /// Controls whether packages are compiled in the background on open and save, to show the ACIR
+    /// and Brillig opcode counts of each line as inlay hints and of each function as code lenses.
+    /// By default this will be set to false (disabled).
+    #[serde(rename = "enableOpcodeCounts", default = "default_enable_opcode_counts")]
+    pub(crate) enable_opcode_counts: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize, Copy, Clone)]
@@ -119,6 +125,10 @@ fn default_enable_parsing_cache() -> bool {
     true
 }
 
+fn default_enable_opcode_counts() -> bool {
+    false
+}
+
 fn default_inlay_hints() -> InlayHintsOptions {
     InlayHintsOptions {
         type_hints: default_type_hints(),
@@ -164,6 +174,7 @@ impl Default for LspInitializationOptions {
             enable_code_lens: default_enable_code_lens(),
             enable_parsing_cache: default_enable_parsing_cache(),
             inlay_hints: default_inlay_hints(),
+            enable_opcode_counts: default_enable_opcode_counts(),
         }
     }
 }
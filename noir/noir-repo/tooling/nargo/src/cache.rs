@@ -0,0 +1,259 @@
+//! Records which packages have already been checked or compiled, so that commands can skip
+//! running the frontend over packages whose sources haven't changed since.
+//!
+//! Each package is given a fingerprint derived from the compiler version, the field it was built
+//! for, the sources of its crate and the fingerprints of its dependencies, so editing any crate invalidates exactly the
+//! packages which depend on it. Once a command succeeds on a package it stores the fingerprint,
+//! along with the artifacts it wrote, under the target directory. The next run of that command
+//! can then skip the package entirely as long as the fingerprint matches and the artifacts exist.
+//!
+//! Only runs which produced no warnings are recorded, as warnings are not stored and would
+//! otherwise be silently dropped on later runs.
+//!
+//! Caching is done per package rather than per crate: the elaborated state of dependencies isn't
+//! stored, so a package which did change is elaborated again together with all of its
+//! dependencies, including those which haven't changed.
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use fm::FileManager;
+use noirc_driver::{CHOSEN_FIELD, NOIR_ARTIFACT_VERSION_STRING};
+
+use crate::{
+    constants::CACHE_DIR,
+    package::{Dependency, Package},
+    workspace::Workspace,
+};
+
+/// A hash identifying the sources of a package and of all of its dependencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fingerprint(u64);
+
+/// Computes the [`Fingerprint`] of `package`.
+///
+/// `options` should contain any settings which change the outcome of the command being cached,
+/// e.g. the compile options used.
+///
+/// The sources are read from the `file_manager`, which is expected to contain all files
+/// of the package and its dependencies.
+pub fn package_fingerprint(
+    file_manager: &FileManager,
+    package: &Package,
+    options: &impl Hash,
+) -> Fingerprint {
+    let mut hasher = fxhash::FxHasher64::default();
+    NOIR_ARTIFACT_VERSION_STRING.hash(&mut hasher);
+    CHOSEN_FIELD.hash(&mut hasher);
+    options.hash(&mut hasher);
+    crate_fingerprint(file_manager, package, &mut HashMap::new()).hash(&mut hasher);
+    Fingerprint(hasher.finish())
+}
+
+fn crate_fingerprint(
+    file_manager: &FileManager,
+    package: &Package,
+    fingerprints: &mut HashMap<PathBuf, Fingerprint>,
+) -> Fingerprint {
+    if let Some(fingerprint) = fingerprints.get(&package.entry_path) {
+        return *fingerprint;
+    }
+
+    let mut hasher = fxhash::FxHasher64::default();
+    package.name.to_string().hash(&mut hasher);
+    package.package_type.to_string().hash(&mut hasher);
+    format!("{:?}", package.expression_width).hash(&mut hasher);
+
+    // All files under the directory of the entry point make up the crate,
+    // as this is how they are added to the file manager.
+    let crate_dir = package.entry_path.parent().unwrap_or(&package.root_dir);
+    let mut sources: Vec<(&Path, &str)> = file_manager
+        .as_file_map()
+        .all_file_ids()
+        .filter_map(|file_id| {
+            let path = file_manager.path(*file_id)?;
+            let source = file_manager.fetch_file(*file_id)?;
+            path.starts_with(crate_dir).then_some((path, source))
+        })
+        .collect();
+    sources.sort_unstable();
+    for (path, source) in sources {
+        path.strip_prefix(crate_dir).unwrap_or(path).hash(&mut hasher);
+        source.hash(&mut hasher);
+    }
+
+    for (name, dependency) in &package.dependencies {
+        let (Dependency::Local { package } | Dependency::Remote { package }) = dependency;
+        name.to_string().hash(&mut hasher);
+        crate_fingerprint(file_manager, package, fingerprints).hash(&mut hasher);
+    }
+
+    let fingerprint = Fingerprint(hasher.finish());
+    fingerprints.insert(package.entry_path.clone(), fingerprint);
+    fingerprint
+}
+
+/// The fingerprints of the packages a command last succeeded on, stored in the target directory.
+pub struct FrontendCache {
+    directory: PathBuf,
+}
+
+impl FrontendCache {
+    pub fn new(workspace: &Workspace) -> Self {
+        FrontendCache { directory: workspace.target_directory_path().join(CACHE_DIR) }
+    }
+
+    /// Returns true if `command` last succeeded on `package` when it had the same fingerprint,
+    /// and all the artifacts it wrote then still exist.
+    pub fn is_fresh(&self, command: &str, package: &Package, fingerprint: Fingerprint) -> bool {
+        let Ok(contents) = std::fs::read_to_string(self.entry_path(command, package)) else {
+            return false;
+        };
+        let mut lines = contents.lines();
+        if lines.next() != Some(fingerprint.to_string().as_str()) {
+            return false;
+        }
+        lines.all(|artifact| Path::new(artifact).is_file())
+    }
+
+    /// Returns the artifacts which `command` wrote when it last succeeded on `package`.
+    pub fn artifacts(&self, command: &str, package: &Package) -> Vec<PathBuf> {
+        let Ok(contents) = std::fs::read_to_string(self.entry_path(command, package)) else {
+            return Vec::new();
+        };
+        contents.lines().skip(1).map(PathBuf::from).collect()
+    }
+
+    /// Records that `command` succeeded on `package`, writing the given artifacts.
+    ///
+    /// Failing to write the cache only means the work will be redone next time,
+    /// so any errors are ignored.
+    pub fn record(
+        &self,
+        command: &str,
+        package: &Package,
+        fingerprint: Fingerprint,
+        artifacts: &[PathBuf],
+    ) {
+        let mut contents = fingerprint.to_string();
+        for artifact in artifacts {
+            contents.push('\n');
+            contents.push_str(&artifact.to_string_lossy());
+        }
+        if std::fs::create_dir_all(&self.directory).is_ok() {
+            let _ = std::fs::write(self.entry_path(command, package), contents);
+        }
+    }
+
+    fn entry_path(&self, command: &str, package: &Package) -> PathBuf {
+        self.directory.join(format!("{}.{command}", package.name))
+    }
+}
+
+impl std::fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::Path};
+
+    use fm::FileManager;
+    use noirc_driver::CompileOptions;
+
+    use crate::package::{Dependency, Package, PackageType};
+
+    use super::package_fingerprint;
+
+    fn package(root: &str, name: &str, dependencies: Vec<Package>) -> Package {
+        let root_dir = Path::new(root).to_path_buf();
+        Package {
+            version: None,
+            compiler_required_version: None,
+            entry_path: root_dir.join("src").join("main.nr"),
+            root_dir,
+            package_type: PackageType::Library,
+            name: name.parse().unwrap(),
+            dependencies: dependencies
+                .into_iter()
+                .map(|package| (package.name.clone(), Dependency::Local { package }))
+                .collect::<BTreeMap<_, _>>(),
+            expression_width: None,
+        }
+    }
+
+    fn file_manager(files: &[(&str, &str)]) -> FileManager {
+        let mut file_manager = FileManager::new(Path::new("/"));
+        for (path, source) in files {
+            file_manager.add_file_with_source(Path::new(path), source.to_string());
+        }
+        file_manager
+    }
+
+    #[test]
+    fn changes_when_a_dependency_changes() {
+        let dep = package("/dep", "dep", vec![]);
+        let other = package("/other", "other", vec![]);
+        let root = package("/root", "root", vec![dep.clone(), other.clone()]);
+
+        let before = file_manager(&[
+            ("/root/src/main.nr", "fn main() {}"),
+            ("/dep/src/main.nr", "fn foo() {}"),
+            ("/other/src/main.nr", "fn bar() {}"),
+        ]);
+        let after = file_manager(&[
+            ("/root/src/main.nr", "fn main() {}"),
+            ("/dep/src/main.nr", "fn foo() { }"),
+            ("/other/src/main.nr", "fn bar() {}"),
+        ]);
+
+        assert_ne!(
+            package_fingerprint(&before, &root, &()),
+            package_fingerprint(&after, &root, &())
+        );
+        assert_ne!(package_fingerprint(&before, &dep, &()), package_fingerprint(&after, &dep, &()));
+        assert_eq!(
+            package_fingerprint(&before, &other, &()),
+            package_fingerprint(&after, &other, &())
+        );
+    }
+
+    #[test]
+    fn changes_with_the_options() {
+        let root = package("/root", "root", vec![]);
+        let files = file_manager(&[("/root/src/main.nr", "fn main() {}")]);
+
+        assert_eq!(package_fingerprint(&files, &root, &1), package_fingerprint(&files, &root, &1));
+        assert_ne!(package_fingerprint(&files, &root, &1), package_fingerprint(&files, &root, &2));
+    }
+
+    #[test]
+    fn only_depends_on_options_changing_the_outcome() {
+        let root = package("/root", "root", vec![]);
+        let files = file_manager(&[("/root/src/main.nr", "fn main() {}")]);
+        let fingerprint =
+            |options: CompileOptions| package_fingerprint(&files, &root, &options.cache_key());
+
+        let default = fingerprint(CompileOptions::default());
+        assert_eq!(
+            default,
+            fingerprint(CompileOptions { silence_warnings: true, ..Default::default() })
+        );
+        assert_eq!(
+            default,
+            fingerprint(CompileOptions { force_compile: true, ..Default::default() })
+        );
+        assert_ne!(
+            default,
+            fingerprint(CompileOptions { force_brillig: true, ..Default::default() })
+        );
+        assert_ne!(
+            default,
+            fingerprint(CompileOptions { inliner_aggressiveness: Some(0), ..Default::default() })
+        );
+    }
+}
@@ -9,6 +9,8 @@ pub const SRC_DIR: &str = "src";
 pub const TARGET_DIR: &str = "target";
 /// The directory to store serialized ACIR representations of exported library functions.
 pub const EXPORT_DIR: &str = "export";
+/// The directory within the target directory recording which packages are up to date.
+pub const CACHE_DIR: &str = "cache";
 
 // Files
 /// The file from which Nargo pulls prover inputs
@@ -21,3 +23,7 @@ pub const PKG_FILE: &str = "Nargo.toml";
 pub const PROOF_EXT: &str = "proof";
 /// The extension for files containing proof witnesses.
 pub const WITNESS_EXT: &str = "gz";
+/// The extension for files containing circuits exported as rank-1 constraint systems.
+pub const R1CS_EXT: &str = "r1cs";
+/// The extension for files containing witnesses to rank-1 constraint systems.
+pub const R1CS_WITNESS_EXT: &str = "wtns";
@@ -5,6 +5,7 @@ use acvm::{
         brillig::BrilligFunctionId, ErrorSelector, OpcodeLocation, RawAssertionPayload,
         ResolvedAssertionPayload, ResolvedOpcodeLocation,
     },
+    checker::ConstraintViolation,
     pwg::{ErrorLocation, OpcodeResolutionError},
     AcirField, FieldElement,
 };
@@ -52,6 +53,10 @@ pub enum NargoError<F: AcirField> {
     /// Oracle handling error
     #[error(transparent)]
     ForeignCallError(#[from] ForeignCallError),
+
+    /// A snapshot was resumed on an execution which doesn't make the foreign calls it recorded
+    #[error("Foreign call {index} made while resuming the snapshot does not match the recorded call to `{function}`")]
+    SnapshotMismatch { index: usize, function: String },
 }
 
 impl<F: AcirField> NargoError<F> {
@@ -243,3 +248,48 @@ pub fn try_to_diagnose_runtime_error(
     let error = CustomDiagnostic::simple_error(message, String::new(), location.span);
     Some(error.with_call_stack(source_locations).in_file(location.file))
 }
+
+/// Generates a diagnostic pointing at the source of an opcode which a witness failed to satisfy.
+pub fn diagnose_constraint_violation(
+    violation: &ConstraintViolation<FieldElement>,
+    debug: &[DebugInfo],
+) -> Option<FileDiagnostic> {
+    // A call stack which doesn't match the debug info comes from a mismatched artifact,
+    // in which case the violation is reported without a diagnostic.
+    let mut source_locations: Vec<Location> = Vec::new();
+    for resolved_location in &violation.call_stack {
+        let function_debug = debug.get(resolved_location.acir_function_index)?;
+        source_locations.extend(
+            function_debug.opcode_location(&resolved_location.opcode_location).unwrap_or_default(),
+        );
+    }
+    let location = *source_locations.last()?;
+    let message = format!("Witness does not satisfy constraint: {}", violation.kind);
+    let error = CustomDiagnostic::simple_error(message, String::new(), location.span);
+    Some(error.with_call_stack(source_locations).in_file(location.file))
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{
+        acir::circuit::{OpcodeLocation, ResolvedOpcodeLocation},
+        checker::{ConstraintViolation, ViolationKind},
+        AcirField, FieldElement,
+    };
+    use noirc_errors::debug_info::DebugInfo;
+
+    use super::diagnose_constraint_violation;
+
+    #[test]
+    fn constraint_violation_of_unknown_function_is_not_diagnosed() {
+        let violation = ConstraintViolation {
+            call_stack: vec![ResolvedOpcodeLocation {
+                acir_function_index: 1,
+                opcode_location: OpcodeLocation::Acir(0),
+            }],
+            kind: ViolationKind::NonZeroExpression(FieldElement::one()),
+        };
+
+        assert!(diagnose_constraint_violation(&violation, &[DebugInfo::default()]).is_none());
+    }
+}
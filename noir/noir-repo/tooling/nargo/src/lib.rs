@@ -7,6 +7,7 @@
 //! This name was used because it sounds like `cargo` and
 //! Noir Package Manager abbreviated is npm, which is already taken.
 
+pub mod cache;
 pub mod constants;
 pub mod errors;
 pub mod ops;
@@ -37,7 +37,9 @@ pub fn compile_workspace(
         .collect();
     let contract_results: Vec<CompilationResult<CompiledContract>> = contract_packages
         .par_iter()
-        .map(|package| compile_contract(file_manager, parsed_files, package, compile_options))
+        .map(|package| {
+            compile_contract(file_manager, parsed_files, workspace, package, compile_options)
+        })
         .collect();
 
     // Collate any warnings/errors which were encountered during compilation.
@@ -97,10 +99,12 @@ pub fn compile_program_with_debug_instrumenter(
 pub fn compile_contract(
     file_manager: &FileManager,
     parsed_files: &ParsedFiles,
+    workspace: &Workspace,
     package: &Package,
     compile_options: &CompileOptions,
 ) -> CompilationResult<CompiledContract> {
     let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
+    context.package_build_path = workspace.package_build_path(package);
     noirc_driver::compile_contract(&mut context, crate_id, compile_options)
 }
 
@@ -1,201 +1,314 @@
+use std::future::Future;
+
 use acvm::acir::circuit::brillig::BrilligBytecode;
+use acvm::acir::circuit::opcodes::AcirFunctionId;
 use acvm::acir::circuit::{
     OpcodeLocation, Program, ResolvedAssertionPayload, ResolvedOpcodeLocation,
 };
 use acvm::acir::native_types::WitnessStack;
+use acvm::brillig_vm::brillig::ForeignCallResult;
 use acvm::pwg::{
-    ACVMStatus, ErrorLocation, OpcodeNotSolvable, OpcodeResolutionError, ProfilingSamples, ACVM,
+    ACVMStatus, ErrorLocation, ForeignCallWaitInfo, OpcodeNotSolvable, OpcodeResolutionError,
+    ProfilingSamples, ACVM,
 };
 use acvm::{acir::circuit::Circuit, acir::native_types::WitnessMap};
 use acvm::{AcirField, BlackBoxFunctionSolver};
+use noirc_printable_type::ForeignCallError;
+use serde::{Deserialize, Serialize};
 
 use crate::errors::ExecutionError;
 use crate::NargoError;
 
 use super::foreign_calls::ForeignCallExecutor;
 
-struct ProgramExecutor<'a, F, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>> {
+/// What a [ProgramExecutor] is waiting on after running as far as it can.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecutionStatus<F> {
+    /// Every function has been executed and the witness stack can be taken with [ProgramExecutor::finalize].
+    Solved,
+    /// Execution is suspended until the result of this foreign call is passed to
+    /// [ProgramExecutor::resolve_foreign_call].
+    RequiresForeignCall(ForeignCallWaitInfo<F>),
+}
+
+/// A suspended execution which can be stored and later picked up again with [ProgramExecutor::resume].
+///
+/// Execution is deterministic given the results of its foreign calls, so rather than the state
+/// of the virtual machines this records the inputs needed to replay the execution up to where it was suspended.
+/// Calls between ACIR functions don't suspend the execution, so a snapshot can only be taken at a foreign call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionSnapshot<F> {
+    pub initial_witness: WitnessMap<F>,
+    pub foreign_calls: Vec<RecordedForeignCall<F>>,
+}
+
+/// A foreign call made during an execution, along with the result it was resolved with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedForeignCall<F> {
+    pub call: ForeignCallWaitInfo<F>,
+    pub result: ForeignCallResult<F>,
+}
+
+/// An ACIR function which is being executed.
+struct Frame<'a, F, B: BlackBoxFunctionSolver<F>> {
+    function_id: AcirFunctionId,
+    acvm: ACVM<'a, F, B>,
+}
+
+/// Executes a [Program], handing control back to the caller whenever a foreign call has to be resolved.
+///
+/// Calls between ACIR functions are kept on an explicit stack rather than being executed recursively,
+/// so the executor can be suspended at any foreign call regardless of how deeply it is nested.
+/// The ACIR calls themselves need nothing from the caller and are run within [ProgramExecutor::step]
+/// without handing back control.
+pub struct ProgramExecutor<'a, F, B: BlackBoxFunctionSolver<F>> {
     functions: &'a [Circuit<F>],
 
     unconstrained_functions: &'a [BrilligBytecode<F>],
 
+    blackbox_solver: &'a B,
+
+    // The functions currently being executed, with the most recently called one on top.
+    // The Noir compiler codegens per function and call stacks are not shared across ACIR function calls,
+    // so these are also used to rebuild the call stack of any failure.
+    frames: Vec<Frame<'a, F, B>>,
+
     // This gets built as we run through the program looking at each function call
     witness_stack: WitnessStack<F>,
 
-    blackbox_solver: &'a B,
+    initial_witness: WitnessMap<F>,
 
-    foreign_call_executor: &'a mut E,
+    // Every foreign call resolved so far, which is all that's needed to replay the execution.
+    foreign_calls: Vec<RecordedForeignCall<F>>,
 
-    // The Noir compiler codegens per function and call stacks are not shared across ACIR function calls.
-    // We must rebuild a call stack when executing a program of many circuits.
-    call_stack: Vec<ResolvedOpcodeLocation>,
+    // The foreign call returned by the last step which is waiting on its result.
+    pending_foreign_call: Option<ForeignCallWaitInfo<F>>,
 
-    // Tracks the index of the current function we are executing.
-    // This is used to fetch the function we want to execute
-    // and to resolve call stack locations across many function calls.
-    current_function_index: usize,
+    // Foreign calls from a snapshot which are resolved again as the execution is replayed.
+    replayed_calls: std::vec::IntoIter<RecordedForeignCall<F>>,
 
-    // Flag that states whether we want to profile the VM. Profiling can add extra
-    // execution costs so we want to make sure we only trigger it explicitly.
-    profiling_active: bool,
+    profiling_samples: ProfilingSamples,
 }
 
-impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
-    ProgramExecutor<'a, F, B, E>
-{
-    fn new(
-        functions: &'a [Circuit<F>],
-        unconstrained_functions: &'a [BrilligBytecode<F>],
+impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> ProgramExecutor<'a, F, B> {
+    pub fn new(
+        program: &'a Program<F>,
+        initial_witness: WitnessMap<F>,
         blackbox_solver: &'a B,
-        foreign_call_executor: &'a mut E,
-        profiling_active: bool,
     ) -> Self {
-        ProgramExecutor {
-            functions,
-            unconstrained_functions,
-            witness_stack: WitnessStack::default(),
+        let mut executor = ProgramExecutor {
+            functions: &program.functions,
+            unconstrained_functions: &program.unconstrained_functions,
             blackbox_solver,
-            foreign_call_executor,
-            call_stack: Vec::default(),
-            current_function_index: 0,
-            profiling_active,
+            frames: Vec::new(),
+            witness_stack: WitnessStack::default(),
+            initial_witness: initial_witness.clone(),
+            foreign_calls: Vec::new(),
+            pending_foreign_call: None,
+            replayed_calls: Vec::new().into_iter(),
+            profiling_samples: Vec::new(),
+        };
+        executor.push_frame(AcirFunctionId(0), initial_witness);
+        executor
+    }
+
+    /// Picks up an execution from a snapshot taken with [ProgramExecutor::snapshot] on the same program.
+    ///
+    /// The foreign calls recorded in the snapshot are resolved with their recorded results
+    /// rather than being returned from [ProgramExecutor::step] again. [ProgramExecutor::step] fails with
+    /// [NargoError::SnapshotMismatch] if the program makes a call other than the one recorded.
+    pub fn resume(
+        program: &'a Program<F>,
+        snapshot: ExecutionSnapshot<F>,
+        blackbox_solver: &'a B,
+    ) -> Self {
+        let mut executor = Self::new(program, snapshot.initial_witness, blackbox_solver);
+        executor.replayed_calls = snapshot.foreign_calls.into_iter();
+        executor
+    }
+
+    /// Profiles the execution of the main function. Profiling adds extra execution costs
+    /// so we want to make sure we only trigger it explicitly.
+    pub fn with_profiler(mut self, profiling_active: bool) -> Self {
+        if let Some(main) = self.frames.first_mut() {
+            main.acvm.with_profiler(profiling_active);
+        }
+        self
+    }
+
+    /// Executes the program until it either finishes or needs the result of a foreign call.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn step(&mut self) -> Result<ExecutionStatus<F>, NargoError<F>> {
+        while let Some(frame) = self.frames.last_mut() {
+            match frame.acvm.solve() {
+                ACVMStatus::Solved => self.pop_frame()?,
+                ACVMStatus::InProgress => {
+                    unreachable!("Execution should not stop while in `InProgress` state.")
+                }
+                ACVMStatus::Failure(error) => return Err(self.resolve_error(error)),
+                ACVMStatus::RequiresForeignCall(foreign_call) => {
+                    self.pending_foreign_call = Some(foreign_call.clone());
+                    match self.replayed_calls.next() {
+                        Some(RecordedForeignCall { call, result }) => {
+                            if call != foreign_call {
+                                return Err(NargoError::SnapshotMismatch {
+                                    index: self.foreign_calls.len(),
+                                    function: call.function,
+                                });
+                            }
+                            self.resolve_foreign_call(result);
+                        }
+                        None => return Ok(ExecutionStatus::RequiresForeignCall(foreign_call)),
+                    }
+                }
+                ACVMStatus::RequiresAcirCall(call_info) => {
+                    self.push_frame(call_info.id, call_info.initial_witness);
+                }
+            }
         }
+        Ok(ExecutionStatus::Solved)
+    }
+
+    /// Passes in the result of the foreign call returned by the last [ProgramExecutor::step].
+    ///
+    /// # Panics
+    ///
+    /// If no foreign call is pending.
+    pub fn resolve_foreign_call(&mut self, foreign_call_result: ForeignCallResult<F>) {
+        let call = self.pending_foreign_call.take().expect("No foreign call is pending");
+        let frame = self.frames.last_mut().expect("No function is being executed");
+        frame.acvm.resolve_pending_foreign_call(foreign_call_result.clone());
+        self.foreign_calls.push(RecordedForeignCall { call, result: foreign_call_result });
     }
 
-    fn finalize(self) -> WitnessStack<F> {
+    /// Records everything needed to resume this execution from its current point.
+    pub fn snapshot(&self) -> ExecutionSnapshot<F> {
+        let foreign_calls =
+            self.foreign_calls.iter().chain(self.replayed_calls.as_slice()).cloned();
+        ExecutionSnapshot {
+            initial_witness: self.initial_witness.clone(),
+            foreign_calls: foreign_calls.collect(),
+        }
+    }
+
+    pub fn take_profiling_samples(&mut self) -> ProfilingSamples {
+        std::mem::take(&mut self.profiling_samples)
+    }
+
+    pub fn finalize(self) -> WitnessStack<F> {
         self.witness_stack
     }
 
-    #[tracing::instrument(level = "trace", skip_all)]
-    fn execute_circuit(
-        &mut self,
-        initial_witness: WitnessMap<F>,
-    ) -> Result<(WitnessMap<F>, ProfilingSamples), NargoError<F>> {
-        let circuit = &self.functions[self.current_function_index];
-        let mut acvm = ACVM::new(
+    fn push_frame(&mut self, function_id: AcirFunctionId, initial_witness: WitnessMap<F>) {
+        let circuit = &self.functions[function_id.as_usize()];
+        let acvm = ACVM::new(
             self.blackbox_solver,
             &circuit.opcodes,
             initial_witness,
             self.unconstrained_functions,
             &circuit.assert_messages,
         );
-        acvm.with_profiler(self.profiling_active);
+        self.frames.push(Frame { function_id, acvm });
+    }
 
-        loop {
-            let solver_status = acvm.solve();
+    /// Removes the solved function on top of the stack and returns its outputs to its caller.
+    fn pop_frame(&mut self) -> Result<(), NargoError<F>> {
+        let Frame { function_id, mut acvm } =
+            self.frames.pop().expect("Should have a frame to pop");
 
-            match solver_status {
-                ACVMStatus::Solved => break,
-                ACVMStatus::InProgress => {
-                    unreachable!("Execution should not stop while in `InProgress` state.")
-                }
-                ACVMStatus::Failure(error) => {
-                    let call_stack = match &error {
-                        OpcodeResolutionError::UnsatisfiedConstrain {
-                            opcode_location: ErrorLocation::Resolved(opcode_location),
-                            ..
-                        }
-                        | OpcodeResolutionError::IndexOutOfBounds {
-                            opcode_location: ErrorLocation::Resolved(opcode_location),
-                            ..
-                        }
-                        | OpcodeResolutionError::InvalidInputBitSize {
-                            opcode_location: ErrorLocation::Resolved(opcode_location),
-                            ..
-                        } => {
-                            let resolved_location = ResolvedOpcodeLocation {
-                                acir_function_index: self.current_function_index,
-                                opcode_location: *opcode_location,
-                            };
-                            self.call_stack.push(resolved_location);
-                            Some(self.call_stack.clone())
-                        }
-                        OpcodeResolutionError::BrilligFunctionFailed { call_stack, .. } => {
-                            let brillig_call_stack =
-                                call_stack.iter().map(|location| ResolvedOpcodeLocation {
-                                    acir_function_index: self.current_function_index,
-                                    opcode_location: *location,
-                                });
-                            self.call_stack.extend(brillig_call_stack);
-                            Some(self.call_stack.clone())
-                        }
-                        _ => None,
-                    };
+        let Some(caller) = self.frames.last_mut() else {
+            self.profiling_samples = acvm.take_profiling_samples();
+            self.witness_stack.push(function_id.0, acvm.finalize());
+            return Ok(());
+        };
 
-                    let assertion_payload: Option<ResolvedAssertionPayload<F>> = match &error {
-                        OpcodeResolutionError::BrilligFunctionFailed { payload, .. }
-                        | OpcodeResolutionError::UnsatisfiedConstrain { payload, .. } => {
-                            payload.clone()
-                        }
-                        _ => None,
-                    };
+        let call_solved_witness = acvm.finalize();
+        let mut call_resolved_outputs = Vec::new();
+        for return_witness_index in self.functions[function_id.as_usize()].return_values.indices() {
+            if let Some(return_value) = call_solved_witness.get_index(return_witness_index) {
+                call_resolved_outputs.push(*return_value);
+            } else {
+                return Err(ExecutionError::SolvingError(
+                    OpcodeNotSolvable::MissingAssignment(return_witness_index).into(),
+                    None, // Missing assignment errors do not supply user-facing diagnostics so we do not need to attach a call stack
+                )
+                .into());
+            }
+        }
+        caller.acvm.resolve_pending_acir_call(call_resolved_outputs);
+        self.witness_stack.push(function_id.0, call_solved_witness);
+        Ok(())
+    }
 
-                    let brillig_function_id = match &error {
-                        OpcodeResolutionError::BrilligFunctionFailed { function_id, .. } => {
-                            Some(*function_id)
-                        }
-                        _ => None,
-                    };
-
-                    return Err(NargoError::ExecutionError(match assertion_payload {
-                        Some(payload) => ExecutionError::AssertionFailed(
-                            payload,
-                            call_stack.expect("Should have call stack for an assertion failure"),
-                            brillig_function_id,
-                        ),
-                        None => ExecutionError::SolvingError(error, call_stack),
-                    }));
-                }
-                ACVMStatus::RequiresForeignCall(foreign_call) => {
-                    let foreign_call_result = self.foreign_call_executor.execute(&foreign_call)?;
-                    acvm.resolve_pending_foreign_call(foreign_call_result);
-                }
-                ACVMStatus::RequiresAcirCall(call_info) => {
-                    // Store the parent function index whose context we are currently executing
-                    let acir_function_caller = self.current_function_index;
-                    // Add call opcode to the call stack with a reference to the parent function index
-                    self.call_stack.push(ResolvedOpcodeLocation {
-                        acir_function_index: acir_function_caller,
-                        opcode_location: OpcodeLocation::Acir(acvm.instruction_pointer()),
-                    });
-
-                    // Set current function to the circuit we are about to execute
-                    self.current_function_index = call_info.id.as_usize();
-                    // Execute the ACIR call
-                    let acir_to_call = &self.functions[call_info.id.as_usize()];
-                    let initial_witness = call_info.initial_witness;
-                    // TODO: Profiling among multiple circuits is not supported
-                    let (call_solved_witness, _) = self.execute_circuit(initial_witness)?;
-
-                    // Set tracking index back to the parent function after ACIR call execution
-                    self.current_function_index = acir_function_caller;
-
-                    let mut call_resolved_outputs = Vec::new();
-                    for return_witness_index in acir_to_call.return_values.indices() {
-                        if let Some(return_value) =
-                            call_solved_witness.get_index(return_witness_index)
-                        {
-                            call_resolved_outputs.push(*return_value);
-                        } else {
-                            return Err(ExecutionError::SolvingError(
-                                OpcodeNotSolvable::MissingAssignment(return_witness_index).into(),
-                                None, // Missing assignment errors do not supply user-facing diagnostics so we do not need to attach a call stack
-                            )
-                            .into());
-                        }
+    /// The locations of the calls leading to the function currently being executed.
+    fn call_stack(&self) -> Vec<ResolvedOpcodeLocation> {
+        let callers = &self.frames[..self.frames.len().saturating_sub(1)];
+        callers
+            .iter()
+            .map(|frame| ResolvedOpcodeLocation {
+                acir_function_index: frame.function_id.as_usize(),
+                opcode_location: OpcodeLocation::Acir(frame.acvm.instruction_pointer()),
+            })
+            .collect()
+    }
+
+    fn resolve_error(&self, error: OpcodeResolutionError<F>) -> NargoError<F> {
+        let current_function_index =
+            self.frames.last().map_or(0, |frame| frame.function_id.as_usize());
+        let call_stack = match &error {
+            OpcodeResolutionError::UnsatisfiedConstrain {
+                opcode_location: ErrorLocation::Resolved(opcode_location),
+                ..
+            }
+            | OpcodeResolutionError::IndexOutOfBounds {
+                opcode_location: ErrorLocation::Resolved(opcode_location),
+                ..
+            }
+            | OpcodeResolutionError::InvalidInputBitSize {
+                opcode_location: ErrorLocation::Resolved(opcode_location),
+                ..
+            } => {
+                let mut call_stack = self.call_stack();
+                call_stack.push(ResolvedOpcodeLocation {
+                    acir_function_index: current_function_index,
+                    opcode_location: *opcode_location,
+                });
+                Some(call_stack)
+            }
+            OpcodeResolutionError::BrilligFunctionFailed {
+                call_stack: brillig_call_stack, ..
+            } => {
+                let mut call_stack = self.call_stack();
+                call_stack.extend(brillig_call_stack.iter().map(|location| {
+                    ResolvedOpcodeLocation {
+                        acir_function_index: current_function_index,
+                        opcode_location: *location,
                     }
-                    acvm.resolve_pending_acir_call(call_resolved_outputs);
-                    self.witness_stack.push(call_info.id.0, call_solved_witness);
-                }
+                }));
+                Some(call_stack)
             }
-        }
-        // Clear the call stack if we have succeeded in executing the circuit.
-        // This needs to be done or else all successful ACIR call stacks will also be
-        // included in a failure case.
-        self.call_stack.clear();
+            _ => None,
+        };
+
+        let assertion_payload: Option<ResolvedAssertionPayload<F>> = match &error {
+            OpcodeResolutionError::BrilligFunctionFailed { payload, .. }
+            | OpcodeResolutionError::UnsatisfiedConstrain { payload, .. } => payload.clone(),
+            _ => None,
+        };
 
-        let profiling_samples = acvm.take_profiling_samples();
-        Ok((acvm.finalize(), profiling_samples))
+        let brillig_function_id = match &error {
+            OpcodeResolutionError::BrilligFunctionFailed { function_id, .. } => Some(*function_id),
+            _ => None,
+        };
+
+        NargoError::ExecutionError(match assertion_payload {
+            Some(payload) => ExecutionError::AssertionFailed(
+                payload,
+                call_stack.expect("Should have call stack for an assertion failure"),
+                brillig_function_id,
+            ),
+            None => ExecutionError::SolvingError(error, call_stack),
+        })
     }
 }
 
@@ -246,15 +359,253 @@ fn execute_program_inner<F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignC
     foreign_call_executor: &mut E,
     profiling_active: bool,
 ) -> Result<(WitnessStack<F>, ProfilingSamples), NargoError<F>> {
-    let mut executor = ProgramExecutor::new(
-        &program.functions,
-        &program.unconstrained_functions,
-        blackbox_solver,
-        foreign_call_executor,
-        profiling_active,
-    );
-    let (main_witness, profiling_samples) = executor.execute_circuit(initial_witness)?;
-    executor.witness_stack.push(0, main_witness);
+    let mut executor = ProgramExecutor::new(program, initial_witness, blackbox_solver)
+        .with_profiler(profiling_active);
+    while let ExecutionStatus::RequiresForeignCall(foreign_call) = executor.step()? {
+        let foreign_call_result = foreign_call_executor.execute(&foreign_call)?;
+        executor.resolve_foreign_call(foreign_call_result);
+    }
+    let profiling_samples = executor.take_profiling_samples();
 
     Ok((executor.finalize(), profiling_samples))
 }
+
+/// Executes a program, awaiting the future returned by `foreign_call_executor` for each foreign call
+/// rather than blocking on it.
+pub async fn execute_program_async<F, B, E, Fut>(
+    program: &Program<F>,
+    initial_witness: WitnessMap<F>,
+    blackbox_solver: &B,
+    mut foreign_call_executor: E,
+) -> Result<WitnessStack<F>, NargoError<F>>
+where
+    F: AcirField,
+    B: BlackBoxFunctionSolver<F>,
+    E: FnMut(ForeignCallWaitInfo<F>) -> Fut,
+    Fut: Future<Output = Result<ForeignCallResult<F>, ForeignCallError>>,
+{
+    let mut executor = ProgramExecutor::new(program, initial_witness, blackbox_solver);
+    while let ExecutionStatus::RequiresForeignCall(foreign_call) = executor.step()? {
+        let foreign_call_result = foreign_call_executor(foreign_call).await?;
+        executor.resolve_foreign_call(foreign_call_result);
+    }
+
+    Ok(executor.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    use acvm::acir::brillig::{
+        BitSize, HeapValueType, HeapVector, IntegerBitSize, MemoryAddress, Opcode as BrilligOpcode,
+        ValueOrArray,
+    };
+    use acvm::acir::circuit::brillig::{
+        BrilligBytecode, BrilligFunctionId, BrilligInputs, BrilligOutputs,
+    };
+    use acvm::acir::circuit::opcodes::AcirFunctionId;
+    use acvm::acir::circuit::{Circuit, Opcode, Program, PublicInputs};
+    use acvm::acir::native_types::{Witness, WitnessMap};
+    use acvm::blackbox_solver::StubbedBlackBoxSolver;
+    use acvm::brillig_vm::brillig::ForeignCallResult;
+    use acvm::pwg::ForeignCallWaitInfo;
+    use acvm::{AcirField, FieldElement};
+
+    use super::{execute_program_async, ExecutionStatus, ProgramExecutor};
+    use crate::NargoError;
+
+    /// An unconstrained function returning the result of the `increment` oracle on its input.
+    fn increment_oracle() -> BrilligBytecode<FieldElement> {
+        let one_usize = MemoryAddress::direct(10);
+        let zero_usize = MemoryAddress::direct(11);
+        let usize_constant = |destination, value: u64| BrilligOpcode::Const {
+            destination,
+            bit_size: BitSize::Integer(IntegerBitSize::U32),
+            value: FieldElement::from(value),
+        };
+        BrilligBytecode {
+            bytecode: vec![
+                usize_constant(one_usize, 1),
+                usize_constant(zero_usize, 0),
+                BrilligOpcode::CalldataCopy {
+                    destination_address: MemoryAddress::direct(0),
+                    size_address: one_usize,
+                    offset_address: zero_usize,
+                },
+                BrilligOpcode::ForeignCall {
+                    function: "increment".into(),
+                    destinations: vec![ValueOrArray::MemoryAddress(MemoryAddress::direct(1))],
+                    destination_value_types: vec![HeapValueType::field()],
+                    inputs: vec![ValueOrArray::MemoryAddress(MemoryAddress::direct(0))],
+                    input_value_types: vec![HeapValueType::field()],
+                },
+                usize_constant(MemoryAddress::direct(12), 1),
+                BrilligOpcode::Stop {
+                    return_data: HeapVector { pointer: MemoryAddress::direct(12), size: one_usize },
+                },
+            ],
+        }
+    }
+
+    fn increment(input: Witness, output: Witness) -> Opcode<FieldElement> {
+        Opcode::BrilligCall {
+            id: BrilligFunctionId(0),
+            inputs: vec![BrilligInputs::Single(input.into())],
+            outputs: vec![BrilligOutputs::Simple(output)],
+            predicate: None,
+        }
+    }
+
+    /// A program incrementing its input once in `main` and once more in a nested ACIR call.
+    fn program() -> Program<FieldElement> {
+        let main = Circuit {
+            current_witness_index: 2,
+            opcodes: vec![
+                increment(Witness(0), Witness(1)),
+                Opcode::Call {
+                    id: AcirFunctionId(1),
+                    inputs: vec![Witness(1)],
+                    outputs: vec![Witness(2)],
+                    predicate: None,
+                },
+            ],
+            private_parameters: [Witness(0)].into(),
+            return_values: PublicInputs([Witness(2)].into()),
+            ..Circuit::default()
+        };
+        let callee = Circuit {
+            current_witness_index: 1,
+            opcodes: vec![increment(Witness(0), Witness(1))],
+            private_parameters: [Witness(0)].into(),
+            return_values: PublicInputs([Witness(1)].into()),
+            ..Circuit::default()
+        };
+        Program { functions: vec![main, callee], unconstrained_functions: vec![increment_oracle()] }
+    }
+
+    fn initial_witness() -> WitnessMap<FieldElement> {
+        BTreeMap::from([(Witness(0), FieldElement::from(5u128))]).into()
+    }
+
+    fn resolve(
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> ForeignCallResult<FieldElement> {
+        assert_eq!(foreign_call.function, "increment");
+        let input = foreign_call.inputs[0].unwrap_field();
+        (input + FieldElement::one()).into()
+    }
+
+    /// Polls a future which never has to wait to completion.
+    fn block_on<T>(future: impl Future<Output = T>) -> T {
+        struct NoopWaker;
+        impl Wake for NoopWaker {
+            fn wake(self: Arc<Self>) {}
+        }
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut context = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn yields_foreign_calls_from_nested_acir_calls() {
+        let program = program();
+        let mut executor =
+            ProgramExecutor::new(&program, initial_witness(), &StubbedBlackBoxSolver);
+
+        let mut inputs = Vec::new();
+        while let ExecutionStatus::RequiresForeignCall(foreign_call) = executor.step().unwrap() {
+            inputs.push(foreign_call.inputs[0].unwrap_field());
+            executor.resolve_foreign_call(resolve(&foreign_call));
+        }
+        assert_eq!(inputs, vec![FieldElement::from(5u128), FieldElement::from(6u128)]);
+
+        let mut witness_stack = executor.finalize();
+        let main = witness_stack.pop().unwrap();
+        assert_eq!(main.index, 0);
+        assert_eq!(main.witness[&Witness(2)], FieldElement::from(7u128));
+        let callee = witness_stack.pop().unwrap();
+        assert_eq!(callee.index, 1);
+        assert_eq!(callee.witness[&Witness(1)], FieldElement::from(7u128));
+    }
+
+    #[test]
+    fn resumes_from_a_snapshot() {
+        let program = program();
+        let mut executor =
+            ProgramExecutor::new(&program, initial_witness(), &StubbedBlackBoxSolver);
+        let ExecutionStatus::RequiresForeignCall(first_call) = executor.step().unwrap() else {
+            panic!("Expected a foreign call");
+        };
+        executor.resolve_foreign_call(resolve(&first_call));
+        let ExecutionStatus::RequiresForeignCall(second_call) = executor.step().unwrap() else {
+            panic!("Expected a foreign call");
+        };
+        let snapshot = executor.snapshot();
+        assert_eq!(snapshot.foreign_calls.len(), 1);
+        assert_eq!(snapshot.foreign_calls[0].call, first_call);
+
+        // The resumed execution only asks for the foreign call it was suspended on.
+        let mut resumed =
+            ProgramExecutor::resume(&program, snapshot.clone(), &StubbedBlackBoxSolver);
+        assert_eq!(
+            resumed.step().unwrap(),
+            ExecutionStatus::RequiresForeignCall(second_call.clone())
+        );
+        assert_eq!(resumed.snapshot(), snapshot);
+
+        resumed.resolve_foreign_call(resolve(&second_call));
+        assert_eq!(resumed.step().unwrap(), ExecutionStatus::Solved);
+        executor.resolve_foreign_call(resolve(&second_call));
+        assert_eq!(executor.step().unwrap(), ExecutionStatus::Solved);
+        assert_eq!(resumed.finalize(), executor.finalize());
+    }
+
+    #[test]
+    fn rejects_a_snapshot_of_another_execution() {
+        let program = program();
+        let mut executor =
+            ProgramExecutor::new(&program, initial_witness(), &StubbedBlackBoxSolver);
+        let ExecutionStatus::RequiresForeignCall(first_call) = executor.step().unwrap() else {
+            panic!("Expected a foreign call");
+        };
+        executor.resolve_foreign_call(resolve(&first_call));
+        let mut snapshot = executor.snapshot();
+
+        // The program would now make its first call on a different input than the one recorded.
+        snapshot.initial_witness = BTreeMap::from([(Witness(0), FieldElement::from(9u128))]).into();
+        let mut resumed = ProgramExecutor::resume(&program, snapshot, &StubbedBlackBoxSolver);
+        assert!(matches!(
+            resumed.step(),
+            Err(NargoError::SnapshotMismatch { index: 0, function }) if function == "increment"
+        ));
+    }
+
+    #[test]
+    fn awaits_foreign_calls() {
+        let program = program();
+        let witness_stack = block_on(execute_program_async(
+            &program,
+            initial_witness(),
+            &StubbedBlackBoxSolver,
+            |foreign_call| async move { Ok(resolve(&foreign_call)) },
+        ))
+        .unwrap();
+
+        let mut executor =
+            ProgramExecutor::new(&program, initial_witness(), &StubbedBlackBoxSolver);
+        while let ExecutionStatus::RequiresForeignCall(foreign_call) = executor.step().unwrap() {
+            executor.resolve_foreign_call(resolve(&foreign_call));
+        }
+        assert_eq!(witness_stack, executor.finalize());
+    }
+}
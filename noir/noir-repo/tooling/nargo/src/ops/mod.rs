@@ -3,7 +3,10 @@ pub use self::compile::{
     collect_errors, compile_contract, compile_program, compile_program_with_debug_instrumenter,
     compile_workspace, report_errors,
 };
-pub use self::execute::{execute_program, execute_program_with_profiling};
+pub use self::execute::{
+    execute_program, execute_program_async, execute_program_with_profiling, ExecutionSnapshot,
+    ExecutionStatus, ProgramExecutor, RecordedForeignCall,
+};
 pub use self::foreign_calls::{DefaultForeignCallExecutor, ForeignCall, ForeignCallExecutor};
 pub use self::optimize::{optimize_contract, optimize_program};
 pub use self::transform::{transform_contract, transform_program};
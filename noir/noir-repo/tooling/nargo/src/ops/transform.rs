@@ -2,9 +2,9 @@ use acvm::{
     acir::circuit::{ExpressionWidth, Program},
     FieldElement,
 };
-use iter_extended::vecmap;
 use noirc_driver::{CompiledContract, CompiledProgram};
 use noirc_errors::debug_info::DebugInfo;
+use rayon::prelude::*;
 
 pub fn transform_program(
     mut compiled_program: CompiledProgram,
@@ -22,12 +22,16 @@ pub fn transform_contract(
     contract: CompiledContract,
     expression_width: ExpressionWidth,
 ) -> CompiledContract {
-    let functions = vecmap(contract.functions, |mut func| {
-        func.bytecode =
-            transform_program_internal(func.bytecode, &mut func.debug, expression_width);
+    let functions = contract
+        .functions
+        .into_par_iter()
+        .map(|mut func| {
+            func.bytecode =
+                transform_program_internal(func.bytecode, &mut func.debug, expression_width);
 
-        func
-    });
+            func
+        })
+        .collect();
 
     CompiledContract { functions, ..contract }
 }
@@ -4,6 +4,7 @@ use clap::Args;
 use fm::FileManager;
 use iter_extended::btree_map;
 use nargo::{
+    cache::{package_fingerprint, Fingerprint, FrontendCache},
     errors::CompileError,
     insert_all_files_for_workspace_into_file_manager,
     ops::report_errors,
@@ -49,19 +50,39 @@ pub(crate) fn run(args: CheckCommand, config: NargoConfig) -> Result<(), CliErro
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        config.offline,
     )?;
 
     let mut workspace_file_manager = workspace.new_file_manager();
     insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+
+    // Packages which were checked successfully and haven't changed since can be skipped,
+    // unless their `Prover.toml` should be overwritten.
+    let cache = FrontendCache::new(&workspace);
+    let use_cache = !args.allow_overwrite && !args.compile_options.force_compile;
+    let options = args.compile_options.cache_key();
+    let packages: Vec<_> = workspace
+        .into_iter()
+        .filter_map(|package| {
+            let fingerprint = package_fingerprint(&workspace_file_manager, package, &options);
+            let is_fresh = use_cache && cache.is_fresh(CHECK_CACHE_KEY, package, fingerprint);
+            (!is_fresh).then_some((package, fingerprint))
+        })
+        .collect();
+    if packages.is_empty() {
+        return Ok(());
+    }
     let parsed_files = parse_all(&workspace_file_manager);
 
-    for package in &workspace {
+    for (package, fingerprint) in packages {
         let any_file_written = check_package(
             &workspace_file_manager,
             &parsed_files,
             package,
             &args.compile_options,
             args.allow_overwrite,
+            &cache,
+            fingerprint,
         )?;
         if any_file_written {
             println!("[{}] Constraint system successfully built!", package.name);
@@ -70,6 +91,9 @@ pub(crate) fn run(args: CheckCommand, config: NargoConfig) -> Result<(), CliErro
     Ok(())
 }
 
+/// The name under which successful checks are recorded in the [`FrontendCache`].
+const CHECK_CACHE_KEY: &str = "check";
+
 /// Evaluates the necessity to create or update Prover.toml and Verifier.toml based on the allow_overwrite flag and files' existence.
 /// Returns `true` if any file was generated or updated, `false` otherwise.
 fn check_package(
@@ -78,12 +102,24 @@ fn check_package(
     package: &Package,
     compile_options: &CompileOptions,
     allow_overwrite: bool,
+    cache: &FrontendCache,
+    fingerprint: Fingerprint,
 ) -> Result<bool, CompileError> {
     let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
-    check_crate_and_report_errors(&mut context, crate_id, compile_options)?;
+    let result = check_crate(&mut context, crate_id, compile_options);
+    let has_warnings = result.as_ref().map_or(true, |(_, warnings)| !warnings.is_empty());
+    report_errors(
+        result,
+        &context.file_manager,
+        compile_options.deny_warnings,
+        compile_options.silence_warnings,
+    )?;
 
     if package.is_library() || package.is_contract() {
         // Libraries do not have ABIs while contracts have many, so we cannot generate a `Prover.toml` file.
+        if !has_warnings {
+            cache.record(CHECK_CACHE_KEY, package, fingerprint, &[]);
+        }
         Ok(false)
     } else {
         // XXX: We can have a --overwrite flag to determine if you want to overwrite the Prover/Verifier.toml files
@@ -102,6 +138,10 @@ fn check_package(
 
             let any_file_written = should_write_prover;
 
+            if !has_warnings {
+                cache.record(CHECK_CACHE_KEY, package, fingerprint, &[path_to_prover_input]);
+            }
+
             Ok(any_file_written)
         } else {
             Err(CompileError::MissingMainFunction(package.name.clone()))
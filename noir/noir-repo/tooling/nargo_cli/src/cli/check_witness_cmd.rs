@@ -0,0 +1,87 @@
+use acvm::checker::check_witness_stack;
+use clap::Args;
+
+use nargo::errors::diagnose_constraint_violation;
+use nargo::package::CrateName;
+use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use noirc_artifacts::debug::DebugArtifact;
+use noirc_driver::{CompiledProgram, NOIR_ARTIFACT_VERSION_STRING};
+
+use super::fs::{program::read_program_from_file, witness::read_witness_from_dir};
+use super::{NargoConfig, NativeBlackBoxSolver};
+use crate::errors::CliError;
+
+/// Checks a previously generated witness against the compiled circuit
+/// without re-executing it.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct CheckWitnessCommand {
+    /// The name of the witness file to check
+    ///
+    /// Defaults to the name of the package being checked.
+    witness_name: Option<String>,
+
+    /// The name of the package to check
+    #[clap(long, conflicts_with = "workspace")]
+    package: Option<CrateName>,
+
+    /// Check all packages in the workspace
+    #[clap(long, conflicts_with = "package")]
+    workspace: bool,
+
+    /// Report every violated opcode rather than stopping at the first one
+    #[clap(long)]
+    all: bool,
+}
+
+pub(crate) fn run(args: CheckWitnessCommand, config: NargoConfig) -> Result<(), CliError> {
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let default_selection =
+        if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
+    let selection = args.package.map_or(default_selection, PackageSelection::Selected);
+    let workspace = resolve_workspace_from_toml(
+        &toml_path,
+        selection,
+        Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        config.offline,
+    )?;
+    let target_dir = &workspace.target_directory_path();
+
+    let mut violation_count = 0;
+    let binary_packages = workspace.into_iter().filter(|package| package.is_binary());
+    for package in binary_packages {
+        let program: CompiledProgram =
+            read_program_from_file(workspace.package_build_path(package))?.into();
+
+        let package_name = package.name.clone().into();
+        let witness_name = args.witness_name.as_ref().unwrap_or(&package_name);
+        let witness_stack = read_witness_from_dir(witness_name, target_dir)?;
+
+        let violations =
+            check_witness_stack(&program.program, &witness_stack, &NativeBlackBoxSolver, args.all)
+                .map_err(|error| CliError::Generic(format!("[{}] {error}", package.name)))?;
+
+        if violations.is_empty() {
+            println!("[{}] Witness satisfies all constraints", package.name);
+            continue;
+        }
+
+        let debug_artifact = DebugArtifact {
+            debug_symbols: program.debug.clone(),
+            file_map: program.file_map.clone(),
+        };
+        for violation in &violations {
+            match diagnose_constraint_violation(violation, &program.debug) {
+                Some(diagnostic) => {
+                    diagnostic.report(&debug_artifact, false);
+                }
+                None => eprintln!("[{}] {}", package.name, violation.kind),
+            }
+        }
+        violation_count += violations.len();
+    }
+
+    if violation_count > 0 {
+        return Err(CliError::ConstraintViolations(violation_count));
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use clap::Args;
+use nargo::package::{CrateName, Package};
+use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use noirc_artifacts::bindings::{generate_contract_bindings, generate_program_bindings};
+use noirc_driver::{CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
+
+use crate::errors::CliError;
+
+use super::compile_cmd::compile_workspace_full;
+use super::fs::bindings::save_bindings_to_dir;
+use super::fs::program::{read_contract_from_file, read_program_from_file};
+use super::NargoConfig;
+
+/// The languages which bindings can be generated in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BindingsLanguage {
+    Rust,
+}
+
+impl FromStr for BindingsLanguage {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "rust" => Ok(BindingsLanguage::Rust),
+            _ => Err(format!("Invalid language '{input}', expected 'rust'")),
+        }
+    }
+}
+
+/// Generates typed bindings for the inputs and return values of programs and contracts
+#[derive(Debug, Clone, Args)]
+pub(crate) struct CodegenCommand {
+    /// The language to generate bindings in
+    #[clap(long, default_value = "rust")]
+    lang: BindingsLanguage,
+
+    /// The directory to write the bindings to, relative to the workspace root
+    #[clap(long, short, default_value = "codegen")]
+    output_dir: PathBuf,
+
+    /// The name of the package to generate bindings for
+    #[clap(long, conflicts_with = "workspace")]
+    package: Option<CrateName>,
+
+    /// Generate bindings for all packages in the workspace
+    #[clap(long, conflicts_with = "package")]
+    workspace: bool,
+
+    #[clap(flatten)]
+    compile_options: CompileOptions,
+}
+
+pub(crate) fn run(args: CodegenCommand, config: NargoConfig) -> Result<(), CliError> {
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let default_selection =
+        if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
+    let selection = args.package.map_or(default_selection, PackageSelection::Selected);
+
+    let workspace = resolve_workspace_from_toml(
+        &toml_path,
+        selection,
+        Some(NOIR_ARTIFACT_VERSION_STRING.to_owned()),
+        config.offline,
+    )?;
+
+    let artifacts = compile_workspace_full(&workspace, &args.compile_options)?;
+
+    let output_dir = workspace.root_dir.join(&args.output_dir);
+    for package in &workspace {
+        // Library packages have no ABI, so no bindings are generated for them.
+        let Some(artifact_path) = artifacts.get(&package.name) else {
+            continue;
+        };
+        let (module_name, bindings) = match args.lang {
+            BindingsLanguage::Rust => generate_rust_bindings(package, artifact_path)?,
+        };
+        let bindings_path = save_bindings_to_dir(&bindings, &module_name, &output_dir);
+        println!("[{}] Bindings saved to {}", package.name, bindings_path.display());
+    }
+
+    Ok(())
+}
+
+/// Generates the Rust bindings of a package from the artifact it was compiled to, along with the
+/// name of the module to place them in.
+fn generate_rust_bindings(
+    package: &Package,
+    artifact_path: &Path,
+) -> Result<(String, String), CliError> {
+    if package.is_contract() {
+        let contract = read_contract_from_file(artifact_path)?;
+        let module_name = format!("{}_{}", package.name, contract.name.to_lowercase());
+        Ok((module_name, generate_contract_bindings(&contract)))
+    } else {
+        let program = read_program_from_file(artifact_path)?;
+        Ok((
+            package.name.to_string(),
+            generate_program_bindings(&package.name.to_string(), &program),
+        ))
+    }
+}
@@ -1,9 +1,11 @@
+use std::collections::BTreeMap;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use acvm::acir::circuit::ExpressionWidth;
 use fm::FileManager;
+use nargo::cache::{package_fingerprint, Fingerprint, FrontendCache};
 use nargo::ops::{collect_errors, compile_contract, compile_program, report_errors};
 use nargo::package::{CrateName, Package};
 use nargo::workspace::Workspace;
@@ -53,6 +55,7 @@ pub(crate) fn run(args: CompileCommand, config: NargoConfig) -> Result<(), CliEr
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_owned()),
+        config.offline,
     )?;
 
     if args.watch {
@@ -108,54 +111,120 @@ fn watch_workspace(workspace: &Workspace, compile_options: &CompileOptions) -> n
     Ok(())
 }
 
+/// The name under which successful compilations are recorded in the [`FrontendCache`].
+const COMPILE_CACHE_KEY: &str = "compile";
+
+/// Compiles the packages of the workspace which aren't libraries, returning the path of the artifact
+/// of each.
 pub(super) fn compile_workspace_full(
     workspace: &Workspace,
     compile_options: &CompileOptions,
-) -> Result<(), CliError> {
+) -> Result<BTreeMap<CrateName, PathBuf>, CliError> {
     let mut workspace_file_manager = workspace.new_file_manager();
     insert_all_files_for_workspace_into_file_manager(workspace, &mut workspace_file_manager);
+
+    let cache = FrontendCache::new(workspace);
+    let packages = stale_packages(&workspace_file_manager, workspace, &cache, compile_options);
+
+    // Packages which are up to date keep the artifacts they were last compiled to.
+    let mut artifacts: BTreeMap<CrateName, PathBuf> = workspace
+        .into_iter()
+        .filter(|package| !package.is_library())
+        .filter(|package| !packages.iter().any(|(stale, _)| stale.name == package.name))
+        .filter_map(|package| {
+            let artifact = cache.artifacts(COMPILE_CACHE_KEY, package).pop()?;
+            Some((package.name.clone(), artifact))
+        })
+        .collect();
+    if packages.is_empty() {
+        // Every package is up to date so there's no need to parse anything.
+        return Ok(artifacts);
+    }
     let parsed_files = parse_all(&workspace_file_manager);
 
-    let compiled_workspace =
-        compile_workspace(&workspace_file_manager, &parsed_files, workspace, compile_options);
+    let compiled_workspace = compile_workspace(
+        &workspace_file_manager,
+        &parsed_files,
+        workspace,
+        &packages,
+        &cache,
+        compile_options,
+    );
 
-    report_errors(
+    let compiled_artifacts = report_errors(
         compiled_workspace,
         &workspace_file_manager,
         compile_options.deny_warnings,
         compile_options.silence_warnings,
     )?;
+    artifacts.extend(compiled_artifacts);
 
-    Ok(())
+    Ok(artifacts)
 }
 
-fn compile_workspace(
+/// Returns the packages of the workspace which need to be compiled, along with their fingerprints.
+///
+/// Packages which haven't changed since they were last compiled with the same options are skipped,
+/// unless the options ask for the compilation to be forced or for intermediate steps to be shown.
+fn stale_packages(
     file_manager: &FileManager,
-    parsed_files: &ParsedFiles,
     workspace: &Workspace,
+    cache: &FrontendCache,
     compile_options: &CompileOptions,
-) -> CompilationResult<()> {
-    let (binary_packages, contract_packages): (Vec<_>, Vec<_>) = workspace
+) -> Vec<(Package, Fingerprint)> {
+    let use_cache = !compile_options.force_compile
+        && !compile_options.show_monomorphized
+        && !compile_options.requires_recompilation();
+    let options = compile_options.cache_key();
+
+    workspace
         .into_iter()
         .filter(|package| !package.is_library())
-        .cloned()
-        .partition(|package| package.is_binary());
+        .filter_map(|package| {
+            let fingerprint = package_fingerprint(file_manager, package, &options);
+            let is_fresh = use_cache && cache.is_fresh(COMPILE_CACHE_KEY, package, fingerprint);
+            (!is_fresh).then(|| (package.clone(), fingerprint))
+        })
+        .collect()
+}
+
+fn compile_workspace(
+    file_manager: &FileManager,
+    parsed_files: &ParsedFiles,
+    workspace: &Workspace,
+    packages: &[(Package, Fingerprint)],
+    cache: &FrontendCache,
+    compile_options: &CompileOptions,
+) -> CompilationResult<Vec<(CrateName, PathBuf)>> {
+    let (binary_packages, contract_packages): (Vec<_>, Vec<_>) =
+        packages.iter().cloned().partition(|(package, _)| package.is_binary());
 
     // Compile all of the packages in parallel.
-    let program_warnings_or_errors: CompilationResult<()> =
-        compile_programs(file_manager, parsed_files, workspace, &binary_packages, compile_options);
-    let contract_warnings_or_errors: CompilationResult<()> = compiled_contracts(
+    let program_warnings_or_errors = compile_programs(
+        file_manager,
+        parsed_files,
+        workspace,
+        &binary_packages,
+        cache,
+        compile_options,
+    );
+    let contract_warnings_or_errors = compiled_contracts(
         file_manager,
         parsed_files,
         &contract_packages,
+        cache,
         compile_options,
-        &workspace.target_directory_path(),
+        workspace,
     );
 
     match (program_warnings_or_errors, contract_warnings_or_errors) {
-        (Ok((_, program_warnings)), Ok((_, contract_warnings))) => {
+        (
+            Ok((program_artifacts, program_warnings)),
+            Ok((contract_artifacts, contract_warnings)),
+        ) => {
+            let artifacts = [program_artifacts, contract_artifacts].concat();
             let warnings = [program_warnings, contract_warnings].concat();
-            Ok(((), warnings))
+            Ok((artifacts, warnings))
         }
         (Err(program_errors), Err(contract_errors)) => {
             Err([program_errors, contract_errors].concat())
@@ -168,10 +237,11 @@ fn compile_programs(
     file_manager: &FileManager,
     parsed_files: &ParsedFiles,
     workspace: &Workspace,
-    binary_packages: &[Package],
+    binary_packages: &[(Package, Fingerprint)],
+    cache: &FrontendCache,
     compile_options: &CompileOptions,
-) -> CompilationResult<()> {
-    let load_cached_program = |package| {
+) -> CompilationResult<Vec<(CrateName, PathBuf)>> {
+    let load_cached_program = |package: &Package| {
         let program_artifact_path = workspace.package_build_path(package);
         read_program_from_file(program_artifact_path)
             .ok()
@@ -179,7 +249,7 @@ fn compile_programs(
             .map(|p| p.into())
     };
 
-    let compile_package = |package| {
+    let compile_package = |(package, fingerprint): &(Package, Fingerprint)| {
         let (program, warnings) = compile_program(
             file_manager,
             parsed_files,
@@ -193,43 +263,66 @@ fn compile_programs(
             get_target_width(package.expression_width, compile_options.expression_width);
         let program = nargo::ops::transform_program(program, target_width);
         nargo::ops::check_program(&program)?;
-        save_program_to_file(&program.into(), &package.name, workspace.target_directory_path());
+        let artifact_path =
+            save_program_to_file(&program.into(), &package.name, workspace.target_directory_path());
+        if warnings.is_empty() {
+            cache.record(
+                COMPILE_CACHE_KEY,
+                package,
+                *fingerprint,
+                std::slice::from_ref(&artifact_path),
+            );
+        }
 
-        Ok(((), warnings))
+        Ok(((package.name.clone(), artifact_path), warnings))
     };
 
     // Configure a thread pool with a larger stack size to prevent overflowing stack in large programs.
     // Default is 2MB.
     let pool = rayon::ThreadPoolBuilder::new().stack_size(4 * 1024 * 1024).build().unwrap();
-    let program_results: Vec<CompilationResult<()>> =
+    let program_results: Vec<CompilationResult<(CrateName, PathBuf)>> =
         pool.install(|| binary_packages.par_iter().map(compile_package).collect());
 
     // Collate any warnings/errors which were encountered during compilation.
-    collect_errors(program_results).map(|(_, warnings)| ((), warnings))
+    collect_errors(program_results)
 }
 
 fn compiled_contracts(
     file_manager: &FileManager,
     parsed_files: &ParsedFiles,
-    contract_packages: &[Package],
+    contract_packages: &[(Package, Fingerprint)],
+    cache: &FrontendCache,
     compile_options: &CompileOptions,
-    target_dir: &Path,
-) -> CompilationResult<()> {
-    let contract_results: Vec<CompilationResult<()>> = contract_packages
+    workspace: &Workspace,
+) -> CompilationResult<Vec<(CrateName, PathBuf)>> {
+    let contract_results: Vec<CompilationResult<(CrateName, PathBuf)>> = contract_packages
         .par_iter()
-        .map(|package| {
+        .map(|(package, fingerprint)| {
             let (contract, warnings) =
-                compile_contract(file_manager, parsed_files, package, compile_options)?;
+                compile_contract(file_manager, parsed_files, workspace, package, compile_options)?;
             let target_width =
                 get_target_width(package.expression_width, compile_options.expression_width);
             let contract = nargo::ops::transform_contract(contract, target_width);
-            save_contract(contract, package, target_dir, compile_options.show_artifact_paths);
-            Ok(((), warnings))
+            let artifact_path = save_contract(
+                contract,
+                package,
+                &workspace.target_directory_path(),
+                compile_options.show_artifact_paths,
+            );
+            if warnings.is_empty() {
+                cache.record(
+                    COMPILE_CACHE_KEY,
+                    package,
+                    *fingerprint,
+                    std::slice::from_ref(&artifact_path),
+                );
+            }
+            Ok(((package.name.clone(), artifact_path), warnings))
         })
         .collect();
 
     // Collate any warnings/errors which were encountered during compilation.
-    collect_errors(contract_results).map(|(_, warnings)| ((), warnings))
+    collect_errors(contract_results)
 }
 
 fn save_contract(
@@ -237,7 +330,7 @@ fn save_contract(
     package: &Package,
     target_dir: &Path,
     show_artifact_paths: bool,
-) {
+) -> PathBuf {
     let contract_name = contract.name.clone();
     let artifact_path = save_contract_to_file(
         &contract.into(),
@@ -247,6 +340,7 @@ fn save_contract(
     if show_artifact_paths {
         println!("Saved contract artifact to: {}", artifact_path.display());
     }
+    artifact_path
 }
 
 /// If a target width was not specified in the CLI we can safely override the default.
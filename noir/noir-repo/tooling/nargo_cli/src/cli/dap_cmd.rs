@@ -1,7 +1,6 @@
 use acvm::acir::circuit::ExpressionWidth;
 use acvm::acir::native_types::WitnessMap;
 use acvm::FieldElement;
-use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
 use nargo::constants::PROVER_INPUT_FILE;
 use nargo::workspace::Workspace;
@@ -21,6 +20,7 @@ use serde_json::Value;
 
 use super::debug_cmd::compile_bin_package_for_debugging;
 use super::fs::inputs::read_inputs_from_file;
+use super::NativeBlackBoxSolver;
 use crate::errors::CliError;
 
 use super::NargoConfig;
@@ -65,7 +65,7 @@ fn parse_expression_width(input: &str) -> Result<ExpressionWidth, std::io::Error
     }
 }
 
-fn find_workspace(project_folder: &str, package: Option<&str>) -> Option<Workspace> {
+fn find_workspace(project_folder: &str, package: Option<&str>, offline: bool) -> Option<Workspace> {
     let Ok(toml_path) = get_package_manifest(Path::new(project_folder)) else {
         eprintln!("ERROR: Failed to get package manifest");
         return None;
@@ -76,6 +76,7 @@ fn find_workspace(project_folder: &str, package: Option<&str>) -> Option<Workspa
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        offline,
     ) {
         Ok(workspace) => Some(workspace),
         Err(err) => {
@@ -102,8 +103,9 @@ fn load_and_compile_project(
     expression_width: ExpressionWidth,
     acir_mode: bool,
     skip_instrumentation: bool,
+    offline: bool,
 ) -> Result<(CompiledProgram, WitnessMap<FieldElement>), LoadError> {
-    let workspace = find_workspace(project_folder, package)
+    let workspace = find_workspace(project_folder, package, offline)
         .ok_or(LoadError::Generic(workspace_not_found_error_msg(project_folder, package)))?;
     let package = workspace
         .into_iter()
@@ -137,6 +139,7 @@ fn load_and_compile_project(
 fn loop_uninitialized_dap<R: Read, W: Write>(
     mut server: Server<R, W>,
     expression_width: ExpressionWidth,
+    offline: bool,
 ) -> Result<(), DapError> {
     loop {
         let req = match server.poll_request()? {
@@ -191,13 +194,14 @@ fn loop_uninitialized_dap<R: Read, W: Write>(
                     expression_width,
                     generate_acir,
                     skip_instrumentation,
+                    offline,
                 ) {
                     Ok((compiled_program, initial_witness)) => {
                         server.respond(req.ack()?)?;
 
                         noir_debugger::run_dap_loop(
                             server,
-                            &Bn254BlackBoxSolver,
+                            &NativeBlackBoxSolver,
                             compiled_program,
                             initial_witness,
                         )?;
@@ -226,6 +230,7 @@ fn loop_uninitialized_dap<R: Read, W: Write>(
 fn run_preflight_check(
     expression_width: ExpressionWidth,
     args: DapCommand,
+    offline: bool,
 ) -> Result<(), DapError> {
     let project_folder = if let Some(project_folder) = args.preflight_project_folder {
         project_folder
@@ -243,12 +248,13 @@ fn run_preflight_check(
         expression_width,
         args.preflight_generate_acir,
         args.preflight_skip_instrumentation,
+        offline,
     )?;
 
     Ok(())
 }
 
-pub(crate) fn run(args: DapCommand, _config: NargoConfig) -> Result<(), CliError> {
+pub(crate) fn run(args: DapCommand, config: NargoConfig) -> Result<(), CliError> {
     // When the --preflight-check flag is present, we run Noir's DAP server in "pre-flight mode", which test runs
     // the DAP initialization code without actually starting the DAP server.
     //
@@ -262,12 +268,14 @@ pub(crate) fn run(args: DapCommand, _config: NargoConfig) -> Result<(), CliError
     // the DAP loop is established, which otherwise are considered "out of band" by the maintainers of the DAP spec.
     // More details here: https://github.com/microsoft/vscode/issues/108138
     if args.preflight_check {
-        return run_preflight_check(args.expression_width, args).map_err(CliError::DapError);
+        return run_preflight_check(args.expression_width, args, config.offline)
+            .map_err(CliError::DapError);
     }
 
     let output = BufWriter::new(std::io::stdout());
     let input = BufReader::new(std::io::stdin());
     let server = Server::new(input, output);
 
-    loop_uninitialized_dap(server, args.expression_width).map_err(CliError::DapError)
+    loop_uninitialized_dap(server, args.expression_width, config.offline)
+        .map_err(CliError::DapError)
 }
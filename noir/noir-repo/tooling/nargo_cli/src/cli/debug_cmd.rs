@@ -2,7 +2,6 @@ use std::path::PathBuf;
 
 use acvm::acir::native_types::WitnessStack;
 use acvm::FieldElement;
-use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
 
 use fm::FileManager;
@@ -23,7 +22,7 @@ use noirc_frontend::hir::ParsedFiles;
 
 use super::compile_cmd::get_target_width;
 use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
-use super::NargoConfig;
+use super::{NargoConfig, NativeBlackBoxSolver};
 use crate::errors::CliError;
 
 /// Executes a circuit in debug mode
@@ -62,6 +61,7 @@ pub(crate) fn run(args: DebugCommand, config: NargoConfig) -> Result<(), CliErro
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        config.offline,
     )?;
     let target_dir = &workspace.target_directory_path();
 
@@ -232,6 +232,6 @@ pub(crate) fn debug_program(
 ) -> Result<Option<WitnessStack<FieldElement>>, CliError> {
     let initial_witness = compiled_program.abi.encode(inputs_map, None)?;
 
-    noir_debugger::run_repl_session(&Bn254BlackBoxSolver, compiled_program, initial_witness)
+    noir_debugger::run_repl_session(&NativeBlackBoxSolver, compiled_program, initial_witness)
         .map_err(CliError::from)
 }
@@ -2,7 +2,6 @@ use std::path::PathBuf;
 
 use acvm::acir::native_types::WitnessStack;
 use acvm::FieldElement;
-use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
 
 use nargo::constants::PROVER_INPUT_FILE;
@@ -17,7 +16,7 @@ use noirc_driver::{CompileOptions, CompiledProgram, NOIR_ARTIFACT_VERSION_STRING
 
 use super::compile_cmd::compile_workspace_full;
 use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
-use super::NargoConfig;
+use super::{NargoConfig, NativeBlackBoxSolver};
 use crate::cli::fs::program::read_program_from_file;
 use crate::errors::CliError;
 
@@ -30,10 +29,15 @@ pub(crate) struct ExecuteCommand {
     /// Defaults to the name of the package being executed.
     witness_name: Option<String>,
 
-    /// The name of the toml file which contains the inputs for the prover
+    /// The name of the file which contains the inputs for the prover
     #[clap(long, short, default_value = PROVER_INPUT_FILE)]
     prover_name: String,
 
+    /// The format of the prover's inputs: toml, json, fields (a list of field elements as the circuit
+    /// receives them) or calldata (the same field elements as a hex string of 32 byte words)
+    #[clap(long, default_value = "toml")]
+    input_format: Format,
+
     /// The name of the package to execute
     #[clap(long, conflicts_with = "workspace")]
     package: Option<CrateName>,
@@ -59,6 +63,7 @@ pub(crate) fn run(args: ExecuteCommand, config: NargoConfig) -> Result<(), CliEr
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        config.offline,
     )?;
     let target_dir = &workspace.target_directory_path();
 
@@ -75,6 +80,7 @@ pub(crate) fn run(args: ExecuteCommand, config: NargoConfig) -> Result<(), CliEr
             program,
             package,
             &args.prover_name,
+            args.input_format,
             args.oracle_resolver.as_deref(),
             Some(workspace.root_dir.clone()),
             Some(package.name.to_string()),
@@ -97,13 +103,14 @@ fn execute_program_and_decode(
     program: CompiledProgram,
     package: &Package,
     prover_name: &str,
+    input_format: Format,
     foreign_call_resolver_url: Option<&str>,
     root_path: Option<PathBuf>,
     package_name: Option<String>,
 ) -> Result<(Option<InputValue>, WitnessStack<FieldElement>), CliError> {
-    // Parse the initial witness values from Prover.toml
+    // Parse the initial witness values from the prover's inputs
     let (inputs_map, _) =
-        read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &program.abi)?;
+        read_inputs_from_file(&package.root_dir, prover_name, input_format, &program.abi)?;
     let witness_stack =
         execute_program(&program, &inputs_map, foreign_call_resolver_url, root_path, package_name)?;
     // Get the entry point witness for the ABI
@@ -126,7 +133,7 @@ pub(crate) fn execute_program(
     let solved_witness_stack_err = nargo::ops::execute_program(
         &compiled_program.program,
         initial_witness,
-        &Bn254BlackBoxSolver,
+        &NativeBlackBoxSolver,
         &mut DefaultForeignCallExecutor::new(
             true,
             foreign_call_resolver_url,
@@ -1,5 +1,11 @@
+use std::str::FromStr;
+
+use acvm::acir::circuit::Program;
+use acvm::r1cs::R1cs;
+use acvm::FieldElement;
 use nargo::errors::CompileError;
 use nargo::ops::report_errors;
+use noirc_abi::input_parser::Format;
 use noirc_errors::FileDiagnostic;
 use noirc_frontend::hir::ParsedFiles;
 use rayon::prelude::*;
@@ -22,13 +28,50 @@ use clap::Args;
 use crate::errors::CliError;
 
 use super::check_cmd::check_crate_and_report_errors;
+use super::compile_cmd::compile_workspace_full;
+use super::execute_cmd::execute_program;
 
-use super::fs::program::save_program_to_file;
+use super::fs::inputs::read_inputs_from_file;
+use super::fs::program::{read_program_from_file, save_program_to_file};
+use super::fs::r1cs::{save_r1cs_to_dir, save_r1cs_witness_to_dir};
 use super::NargoConfig;
 
+/// The format in which circuits are exported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExportFormat {
+    /// JSON program artifacts containing the ACIR of each function.
+    Acir,
+    /// The binary `.r1cs` format used by circom and snarkjs.
+    R1cs,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "acir" => Ok(ExportFormat::Acir),
+            "r1cs" => Ok(ExportFormat::R1cs),
+            _ => Err(format!("Invalid export format '{input}', expected 'acir' or 'r1cs'")),
+        }
+    }
+}
+
 /// Exports functions marked with #[export] attribute
+///
+/// With `--format r1cs` the `main` function of binary packages is exported as well,
+/// along with a `.wtns` witness solved from the prover inputs if `--prover-name` is given.
 #[derive(Debug, Clone, Args)]
 pub(crate) struct ExportCommand {
+    /// The format to export circuits in, either `acir` or `r1cs`
+    #[clap(long, default_value = "acir")]
+    format: ExportFormat,
+
+    /// The name of the toml file which contains the inputs for the prover, e.g. `Prover`,
+    /// from which the witness of binary packages is exported in R1CS format
+    #[clap(long)]
+    prover_name: Option<String>,
+
     /// The name of the package to compile
     #[clap(long, conflicts_with = "workspace")]
     package: Option<CrateName>,
@@ -51,6 +94,7 @@ pub(crate) fn run(args: ExportCommand, config: NargoConfig) -> Result<(), CliErr
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_owned()),
+        config.offline,
     )?;
 
     let mut workspace_file_manager = workspace.new_file_manager();
@@ -69,9 +113,15 @@ pub(crate) fn run(args: ExportCommand, config: NargoConfig) -> Result<(), CliErr
                 &workspace,
                 package,
                 &args.compile_options,
+                args.format,
             )
         })
-        .collect()
+        .collect::<Result<(), CliError>>()?;
+
+    if args.format == ExportFormat::R1cs {
+        export_binary_packages_to_r1cs(&workspace, &args.compile_options, args.prover_name)?;
+    }
+    Ok(())
 }
 
 fn compile_exported_functions(
@@ -80,6 +130,7 @@ fn compile_exported_functions(
     workspace: &Workspace,
     package: &Package,
     compile_options: &CompileOptions,
+    format: ExportFormat,
 ) -> Result<(), CliError> {
     let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
     check_crate_and_report_errors(&mut context, crate_id, compile_options)?;
@@ -106,7 +157,65 @@ fn compile_exported_functions(
 
     let export_dir = workspace.export_directory_path();
     for (function_name, program) in exported_programs {
-        save_program_to_file(&program.into(), &function_name.parse().unwrap(), &export_dir);
+        match format {
+            ExportFormat::Acir => {
+                save_program_to_file(&program.into(), &function_name.parse().unwrap(), &export_dir);
+            }
+            ExportFormat::R1cs => {
+                let r1cs = program_to_r1cs(&program.program, &function_name)?;
+                save_r1cs_to_dir(&r1cs, &function_name, &export_dir);
+            }
+        }
     }
     Ok(())
 }
+
+/// Exports the `main` function of each binary package to R1CS, solving the witness from the inputs
+/// in `prover_name` if it is given.
+fn export_binary_packages_to_r1cs(
+    workspace: &Workspace,
+    compile_options: &CompileOptions,
+    prover_name: Option<String>,
+) -> Result<(), CliError> {
+    compile_workspace_full(workspace, compile_options)?;
+
+    let export_dir = workspace.export_directory_path();
+    for package in workspace.into_iter().filter(|package| package.is_binary()) {
+        let program: CompiledProgram =
+            read_program_from_file(workspace.package_build_path(package))?.into();
+        let package_name = package.name.to_string();
+
+        let r1cs = program_to_r1cs(&program.program, &package_name)?;
+        let r1cs_path = save_r1cs_to_dir(&r1cs, &package_name, &export_dir);
+        println!("[{}] R1CS saved to {}", package.name, r1cs_path.display());
+
+        let Some(prover_name) = &prover_name else {
+            continue;
+        };
+        let (inputs_map, _) =
+            read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &program.abi)?;
+        let witness_stack = execute_program(
+            &program,
+            &inputs_map,
+            None,
+            Some(workspace.root_dir.clone()),
+            Some(package_name.clone()),
+        )?;
+        let main_witness =
+            &witness_stack.peek().expect("Should have at least one witness on the stack").witness;
+        let witness_path =
+            save_r1cs_witness_to_dir(&r1cs, main_witness, &package_name, &export_dir)
+                .map_err(|err| CliError::R1csExportError(package_name.clone(), err))?;
+        println!("[{}] Witness saved to {}", package.name, witness_path.display());
+    }
+    Ok(())
+}
+
+/// Converts the entry point of `program` into R1CS. Calls to other functions are rejected.
+fn program_to_r1cs(
+    program: &Program<FieldElement>,
+    name: &str,
+) -> Result<R1cs<FieldElement>, CliError> {
+    R1cs::from_circuit(&program.functions[0])
+        .map_err(|err| CliError::R1csExportError(name.to_string(), err))
+}
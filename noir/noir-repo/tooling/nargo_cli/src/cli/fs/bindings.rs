@@ -0,0 +1,15 @@
+use std::path::{Path, PathBuf};
+
+use super::{create_named_dir, write_to_file};
+
+pub(crate) fn save_bindings_to_dir<P: AsRef<Path>>(
+    bindings: &str,
+    module_name: &str,
+    bindings_dir: P,
+) -> PathBuf {
+    create_named_dir(bindings_dir.as_ref(), "codegen");
+    let bindings_path = bindings_dir.as_ref().join(module_name).with_extension("rs");
+    write_to_file(bindings.as_bytes(), &bindings_path);
+
+    bindings_path
+}
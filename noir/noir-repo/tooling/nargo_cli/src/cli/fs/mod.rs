@@ -4,8 +4,10 @@ use std::{
     path::{Path, PathBuf},
 };
 
+pub(super) mod bindings;
 pub(super) mod inputs;
 pub(super) mod program;
+pub(super) mod r1cs;
 pub(super) mod witness;
 
 pub(super) fn create_named_dir(named_dir: &Path, name: &str) -> PathBuf {
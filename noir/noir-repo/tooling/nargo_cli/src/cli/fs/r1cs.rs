@@ -0,0 +1,37 @@
+use std::path::{Path, PathBuf};
+
+use acvm::{
+    acir::native_types::WitnessMap,
+    r1cs::{R1cs, R1csError},
+    FieldElement,
+};
+use nargo::constants::{R1CS_EXT, R1CS_WITNESS_EXT};
+
+use super::{create_named_dir, write_to_file};
+
+pub(crate) fn save_r1cs_to_dir<P: AsRef<Path>>(
+    r1cs: &R1cs<FieldElement>,
+    r1cs_name: &str,
+    r1cs_dir: P,
+) -> PathBuf {
+    create_named_dir(r1cs_dir.as_ref(), "export");
+    let r1cs_path = r1cs_dir.as_ref().join(r1cs_name).with_extension(R1CS_EXT);
+    write_to_file(&r1cs.serialize_r1cs(), &r1cs_path);
+
+    r1cs_path
+}
+
+pub(crate) fn save_r1cs_witness_to_dir<P: AsRef<Path>>(
+    r1cs: &R1cs<FieldElement>,
+    witness_map: &WitnessMap<FieldElement>,
+    witness_name: &str,
+    witness_dir: P,
+) -> Result<PathBuf, R1csError> {
+    let buf = r1cs.serialize_witness(witness_map)?;
+
+    create_named_dir(witness_dir.as_ref(), "export");
+    let witness_path = witness_dir.as_ref().join(witness_name).with_extension(R1CS_WITNESS_EXT);
+    write_to_file(&buf, &witness_path);
+
+    Ok(witness_path)
+}
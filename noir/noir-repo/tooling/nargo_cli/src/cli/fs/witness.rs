@@ -20,3 +20,15 @@ pub(crate) fn save_witness_to_dir<P: AsRef<Path>>(
 
     Ok(witness_path)
 }
+
+pub(crate) fn read_witness_from_dir<P: AsRef<Path>>(
+    witness_name: &str,
+    witness_dir: P,
+) -> Result<WitnessStack<FieldElement>, FilesystemError> {
+    let witness_path = witness_dir.as_ref().join(witness_name).with_extension(WITNESS_EXT);
+
+    let buf = std::fs::read(&witness_path)
+        .map_err(|_| FilesystemError::PathNotValid(witness_path.clone()))?;
+
+    Ok(WitnessStack::try_from(buf.as_slice())?)
+}
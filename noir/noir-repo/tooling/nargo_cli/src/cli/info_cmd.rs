@@ -1,5 +1,4 @@
 use acvm::acir::circuit::ExpressionWidth;
-use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
 use iter_extended::vecmap;
 use nargo::{
@@ -20,7 +19,7 @@ use crate::{cli::fs::inputs::read_inputs_from_file, errors::CliError};
 use super::{
     compile_cmd::{compile_workspace_full, get_target_width},
     fs::program::read_program_from_file,
-    NargoConfig,
+    NargoConfig, NativeBlackBoxSolver,
 };
 
 /// Provides detailed information on each of a program's function (represented by a single circuit)
@@ -63,6 +62,7 @@ pub(crate) fn run(mut args: InfoCommand, config: NargoConfig) -> Result<(), CliE
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        config.offline,
     )?;
 
     if args.profile_execution {
@@ -262,7 +262,7 @@ fn profile_brillig_execution(
         let (_, profiling_samples) = nargo::ops::execute_program_with_profiling(
             &program_artifact.bytecode,
             initial_witness,
-            &Bn254BlackBoxSolver,
+            &NativeBlackBoxSolver,
             &mut DefaultForeignCallExecutor::new(false, None, None, None),
         )?;
 
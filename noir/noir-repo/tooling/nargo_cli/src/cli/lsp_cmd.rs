@@ -2,12 +2,11 @@ use async_lsp::{
     concurrency::ConcurrencyLayer, panic::CatchUnwindLayer, server::LifecycleLayer,
     tracing::TracingLayer,
 };
-use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
 use noir_lsp::NargoLspService;
 use tower::ServiceBuilder;
 
-use super::NargoConfig;
+use super::{NargoConfig, NativeBlackBoxSolver};
 use crate::errors::CliError;
 
 /// Starts the Noir LSP server
@@ -25,7 +24,7 @@ pub(crate) fn run(_args: LspCommand, _config: NargoConfig) -> Result<(), CliErro
 
     runtime.block_on(async {
         let (server, _) = async_lsp::MainLoop::new_server(|client| {
-            let router = NargoLspService::new(&client, Bn254BlackBoxSolver);
+            let router = NargoLspService::new(&client, NativeBlackBoxSolver);
 
             ServiceBuilder::new()
                 .layer(TracingLayer::default())
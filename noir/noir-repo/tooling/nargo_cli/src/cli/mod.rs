@@ -1,7 +1,7 @@
 use clap::{Args, Parser, Subcommand};
 use const_format::formatcp;
 use nargo_toml::find_package_root;
-use noirc_driver::NOIR_ARTIFACT_VERSION_STRING;
+use noirc_driver::{CHOSEN_FIELD, NOIR_ARTIFACT_VERSION_STRING};
 use std::path::PathBuf;
 
 use color_eyre::eyre;
@@ -9,6 +9,8 @@ use color_eyre::eyre;
 mod fs;
 
 mod check_cmd;
+mod check_witness_cmd;
+mod codegen_cmd;
 mod compile_cmd;
 mod dap_cmd;
 mod debug_cmd;
@@ -21,15 +23,24 @@ mod init_cmd;
 mod lsp_cmd;
 mod new_cmd;
 mod test_cmd;
+mod update_cmd;
+mod vendor_cmd;
+
+// Black box functions are only solved natively for bn254 and are otherwise unsupported.
+#[cfg(feature = "bls12_381")]
+use acvm::blackbox_solver::StubbedBlackBoxSolver as NativeBlackBoxSolver;
+#[cfg(not(feature = "bls12_381"))]
+use bn254_blackbox_solver::Bn254BlackBoxSolver as NativeBlackBoxSolver;
 
 const GIT_HASH: &str = env!("GIT_COMMIT");
 const IS_DIRTY: &str = env!("GIT_DIRTY");
 const NARGO_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 static VERSION_STRING: &str = formatcp!(
-    "version = {}\nnoirc version = {}\n(git version hash: {}, is dirty: {})",
+    "version = {}\nnoirc version = {}\nfield = {}\n(git version hash: {}, is dirty: {})",
     NARGO_VERSION,
     NOIR_ARTIFACT_VERSION_STRING,
+    CHOSEN_FIELD,
     GIT_HASH,
     IS_DIRTY
 );
@@ -50,12 +61,18 @@ pub(crate) struct NargoConfig {
     // REMINDER: Also change this flag in the LSP test lens if renamed
     #[arg(long, hide = true, global = true, default_value = "./")]
     program_dir: PathBuf,
+
+    /// Only use git dependencies which are vendored or have already been fetched
+    #[arg(long, global = true, env = "NARGO_OFFLINE")]
+    offline: bool,
 }
 
 #[non_exhaustive]
 #[derive(Subcommand, Clone, Debug)]
 enum NargoCommand {
     Check(check_cmd::CheckCommand),
+    CheckWitness(check_witness_cmd::CheckWitnessCommand),
+    Codegen(codegen_cmd::CodegenCommand),
     Fmt(fmt_cmd::FormatCommand),
     #[command(alias = "build")]
     Compile(compile_cmd::CompileCommand),
@@ -67,6 +84,8 @@ enum NargoCommand {
     Debug(debug_cmd::DebugCommand),
     Test(test_cmd::TestCommand),
     Info(info_cmd::InfoCommand),
+    Update(update_cmd::UpdateCommand),
+    Vendor(vendor_cmd::VendorCommand),
     Lsp(lsp_cmd::LspCommand),
     #[command(hide = true)]
     Dap(dap_cmd::DapCommand),
@@ -85,13 +104,17 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
     // Search through parent directories to find package root if necessary.
     match &command {
         NargoCommand::Check(..)
+        | NargoCommand::CheckWitness(..)
+        | NargoCommand::Codegen(..)
         | NargoCommand::Fmt(..)
         | NargoCommand::Compile(..)
         | NargoCommand::Execute(..)
         | NargoCommand::Export(..)
         | NargoCommand::Debug(..)
         | NargoCommand::Test(..)
-        | NargoCommand::Info(..) => {
+        | NargoCommand::Info(..)
+        | NargoCommand::Update(..)
+        | NargoCommand::Vendor(..) => {
             config.program_dir = find_package_root(&config.program_dir)?;
         }
         NargoCommand::New(..)
@@ -105,12 +128,16 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
         NargoCommand::New(args) => new_cmd::run(args, config),
         NargoCommand::Init(args) => init_cmd::run(args, config),
         NargoCommand::Check(args) => check_cmd::run(args, config),
+        NargoCommand::CheckWitness(args) => check_witness_cmd::run(args, config),
+        NargoCommand::Codegen(args) => codegen_cmd::run(args, config),
         NargoCommand::Compile(args) => compile_cmd::run(args, config),
         NargoCommand::Debug(args) => debug_cmd::run(args, config),
         NargoCommand::Execute(args) => execute_cmd::run(args, config),
         NargoCommand::Export(args) => export_cmd::run(args, config),
         NargoCommand::Test(args) => test_cmd::run(args, config),
         NargoCommand::Info(args) => info_cmd::run(args, config),
+        NargoCommand::Update(args) => update_cmd::run(args, config),
+        NargoCommand::Vendor(args) => vendor_cmd::run(args, config),
         NargoCommand::Lsp(args) => lsp_cmd::run(args, config),
         NargoCommand::Dap(args) => dap_cmd::run(args, config),
         NargoCommand::Fmt(args) => fmt_cmd::run(args, config),
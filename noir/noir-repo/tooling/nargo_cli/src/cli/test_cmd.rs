@@ -1,7 +1,6 @@
 use std::{io::Write, path::PathBuf};
 
 use acvm::{BlackBoxFunctionSolver, FieldElement};
-use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
 use fm::FileManager;
 use nargo::{
@@ -18,7 +17,7 @@ use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
 use crate::{cli::check_cmd::check_crate_and_report_errors, errors::CliError};
 
-use super::NargoConfig;
+use super::{NargoConfig, NativeBlackBoxSolver};
 
 /// Run the tests for this program
 #[derive(Debug, Clone, Args)]
@@ -60,6 +59,7 @@ pub(crate) fn run(args: TestCommand, config: NargoConfig) -> Result<(), CliError
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        config.offline,
     )?;
 
     let mut workspace_file_manager = workspace.new_file_manager();
@@ -85,7 +85,7 @@ pub(crate) fn run(args: TestCommand, config: NargoConfig) -> Result<(), CliError
             .into_iter()
             .par_bridge()
             .map(|package| {
-                run_tests::<Bn254BlackBoxSolver>(
+                run_tests::<NativeBlackBoxSolver>(
                     &workspace_file_manager,
                     &parsed_files,
                     package,
@@ -0,0 +1,29 @@
+use clap::Args;
+use nargo_toml::{get_package_manifest, update_lockfile, LOCKFILE_NAME};
+
+use crate::errors::CliError;
+
+use super::NargoConfig;
+
+/// Updates the git dependencies recorded in Nargo.lock to the commits their tags currently point to
+#[derive(Debug, Clone, Args)]
+pub(crate) struct UpdateCommand;
+
+pub(crate) fn run(_args: UpdateCommand, config: NargoConfig) -> Result<(), CliError> {
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let changes = update_lockfile(&toml_path, config.offline)?;
+
+    if changes.is_empty() {
+        println!("{LOCKFILE_NAME} is up to date");
+    }
+    for change in changes {
+        match change.previous_commit {
+            Some(previous_commit) => println!(
+                "Updated {} (tag {}) from {previous_commit} to {}",
+                change.git, change.tag, change.commit
+            ),
+            None => println!("Locked {} (tag {}) to {}", change.git, change.tag, change.commit),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use clap::Args;
+use nargo_toml::{get_package_manifest, vendor_dependencies, VENDOR_DIR};
+
+use crate::errors::CliError;
+
+use super::NargoConfig;
+
+/// Copies the git dependencies of the workspace into its vendor directory for building offline
+#[derive(Debug, Clone, Args)]
+pub(crate) struct VendorCommand;
+
+pub(crate) fn run(_args: VendorCommand, config: NargoConfig) -> Result<(), CliError> {
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let vendored = vendor_dependencies(&toml_path, config.offline)?;
+
+    if vendored.is_empty() {
+        println!("There are no git dependencies to vendor");
+        return Ok(());
+    }
+    for dir in &vendored {
+        println!("Vendored {}", dir.display());
+    }
+    println!("Dependencies in {VENDOR_DIR}/ are used in place of fetching them");
+    Ok(())
+}
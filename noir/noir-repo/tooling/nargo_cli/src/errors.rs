@@ -1,4 +1,4 @@
-use acvm::{acir::native_types::WitnessStackError, FieldElement};
+use acvm::{acir::native_types::WitnessStackError, r1cs::R1csError, FieldElement};
 use nargo::{errors::CompileError, NargoError};
 use nargo_toml::ManifestError;
 use noir_debugger::errors::DapError;
@@ -38,6 +38,12 @@ pub(crate) enum CliError {
     #[error("Invalid package name {0}. Did you mean to use `--name`?")]
     InvalidPackageName(String),
 
+    #[error("Failed to export `{0}` to R1CS: {1}")]
+    R1csExportError(String, R1csError),
+
+    #[error("Witness violates {0} constraint{}", if *.0 == 1 { "" } else { "s" })]
+    ConstraintViolations(usize),
+
     /// ABI encoding/decoding error
     #[error(transparent)]
     AbiError(#[from] AbiError),
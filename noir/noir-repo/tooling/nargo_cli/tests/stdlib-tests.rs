@@ -90,7 +90,11 @@ fn run_stdlib_tests(force_brillig: bool, inliner_aggressiveness: i64) {
                 None,
                 Some(dummy_package.root_dir.clone()),
                 Some(dummy_package.name.to_string()),
-                &CompileOptions { force_brillig, inliner_aggressiveness, ..Default::default() },
+                &CompileOptions {
+                    force_brillig,
+                    inliner_aggressiveness: Some(inliner_aggressiveness),
+                    ..Default::default()
+                },
             );
             (test_name, status)
         })
@@ -75,6 +75,33 @@ pub enum ManifestError {
 
     #[error("Failed to parse expression width with the following error: {0}")]
     ParseExpressionWidth(String),
+
+    #[error("{0} is badly formed, could not parse.\n\n {1}")]
+    MalformedLockfile(PathBuf, String),
+
+    #[error("{0} has version {1} which is not supported by this version of nargo")]
+    UnsupportedLockfileVersion(PathBuf, u32),
+
+    #[error("Cannot write file {0}")]
+    WriteFailed(PathBuf),
+
+    #[error("Cannot check out commit {commit} of {git} (tag {tag}) which is recorded in Nargo.lock: {error}")]
+    LockedCommitUnavailable { git: String, tag: String, commit: String, error: String },
+
+    #[error("The contents of {git} (tag {tag}) do not match the checksum recorded in Nargo.lock. Expected {expected} but found {found}")]
+    ChecksumMismatch { git: String, tag: String, expected: String, found: String },
+
+    #[error("{git} (tag {tag}) is not available offline. Run `nargo vendor` or build once without `--offline` to fetch it")]
+    MissingOfflineDependency { git: String, tag: String },
+
+    #[error("Dependencies cannot be updated offline")]
+    OfflineUpdate,
+
+    #[error("{git} (tag {tag}) is vendored but not recorded in Nargo.lock. Run `nargo vendor` again to record it")]
+    UnlockedVendoredDependency { git: String, tag: String },
+
+    #[error("Cannot find the home directory, which git dependencies are cloned into")]
+    MissingHomeDirectory,
 }
 
 #[allow(clippy::enum_variant_names)]
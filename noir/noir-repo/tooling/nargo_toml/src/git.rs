@@ -1,22 +1,35 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// Creates a unique folder name for a GitHub repo
 /// by using its URL and tag
 fn resolve_folder_name(base: &url::Url, tag: &str) -> String {
-    let mut folder_name = base.domain().unwrap().to_owned();
+    // Repositories on the local filesystem have no domain
+    let mut folder_name = base.domain().unwrap_or(base.scheme()).to_owned();
     folder_name.push_str(base.path());
     folder_name.push_str(tag);
     folder_name
 }
 
-fn nargo_crates() -> PathBuf {
-    dirs::home_dir().unwrap().join("nargo")
+/// The directory which git dependencies are cloned into, if the home directory can be found.
+pub(crate) fn nargo_crates() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join("nargo"))
 }
 
-fn git_dep_location(base: &url::Url, tag: &str) -> PathBuf {
-    let folder_name = resolve_folder_name(base, tag);
+/// Returns the directory within `dir` which holds the repo at `url` checked out at `tag`.
+pub(crate) fn git_dep_location(dir: &Path, url: &str, tag: &str) -> Result<PathBuf, String> {
+    let base = url::Url::parse(url).map_err(|err| err.to_string())?;
+    let folder_name = resolve_folder_name(&base, tag);
 
-    nargo_crates().join(folder_name)
+    Ok(dir.join(folder_name))
+}
+
+/// Returns the directory within `dir` which holds the files of the repo at `url` at `commit`.
+pub(crate) fn git_commit_location(dir: &Path, url: &str, commit: &str) -> Result<PathBuf, String> {
+    let base = url::Url::parse(url).map_err(|err| err.to_string())?;
+    let folder_name = resolve_folder_name(&base, "");
+
+    Ok(dir.join(folder_name).join(commit))
 }
 
 /// XXX: I'd prefer to use a GitHub library however, there
@@ -25,20 +38,13 @@ fn git_dep_location(base: &url::Url, tag: &str) -> PathBuf {
 /// github-rs looks promising, however it seems to require an API token
 ///
 /// One advantage of using "git clone" is that there is effectively no rate limit
-pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
-    use std::process::Command;
-
-    let base = match url::Url::parse(url) {
-        Ok(base) => base,
-        Err(err) => return Err(err.to_string()),
-    };
-
-    let loc = git_dep_location(&base, tag);
+pub(crate) fn clone_git_repo(crates_dir: &Path, url: &str, tag: &str) -> Result<PathBuf, String> {
+    let loc = git_dep_location(crates_dir, url, tag)?;
     if loc.exists() {
         return Ok(loc);
     }
 
-    Command::new("git")
+    let status = Command::new("git")
         .arg("-c")
         .arg("advice.detachedHead=false")
         .arg("clone")
@@ -46,10 +52,62 @@ pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
         .arg("1")
         .arg("--branch")
         .arg(tag)
-        .arg(base.as_str())
+        .arg(url)
         .arg(&loc)
         .status()
-        .expect("git clone command failed to start");
+        .map_err(|err| format!("Failed to run git to clone {url}: {err}"))?;
+    if !status.success() {
+        return Err(format!("Failed to clone tag {tag} of {url}"));
+    }
 
     Ok(loc)
 }
+
+/// Clones the repo afresh, so that it's checked out at the commit the tag currently points to
+/// rather than the one it pointed to when it was first cloned.
+pub(crate) fn reclone_git_repo(crates_dir: &Path, url: &str, tag: &str) -> Result<PathBuf, String> {
+    let loc = git_dep_location(crates_dir, url, tag)?;
+    if loc.exists() {
+        std::fs::remove_dir_all(&loc)
+            .map_err(|err| format!("Failed to remove {}: {err}", loc.display()))?;
+    }
+    clone_git_repo(crates_dir, url, tag)
+}
+
+/// Returns the commit which the repo at `repo_dir` has checked out.
+pub(crate) fn head_commit(repo_dir: &Path) -> Result<String, String> {
+    run_git(repo_dir, &["rev-parse", "HEAD"])
+}
+
+/// Fetches just `commit` of the repo at `url` into a new repo at `repo_dir`, with it checked out.
+pub(crate) fn fetch_commit(url: &str, commit: &str, repo_dir: &Path) -> Result<PathBuf, String> {
+    if repo_dir.exists() {
+        std::fs::remove_dir_all(repo_dir)
+            .map_err(|err| format!("Failed to remove {}: {err}", repo_dir.display()))?;
+    }
+    std::fs::create_dir_all(repo_dir)
+        .map_err(|err| format!("Failed to create {}: {err}", repo_dir.display()))?;
+    run_git(repo_dir, &["init", "--quiet"])?;
+    run_git(repo_dir, &["fetch", "--quiet", "--depth", "1", url, commit])?;
+    run_git(repo_dir, &["checkout", "--quiet", "FETCH_HEAD"])?;
+    Ok(repo_dir.to_path_buf())
+}
+
+fn run_git(repo_dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_dir)
+        .args(["-c", "advice.detachedHead=false"])
+        .args(args)
+        .output()
+        .map_err(|err| format!("Failed to run git: {err}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "`git {}` failed in {}: {}",
+            args.join(" "),
+            repo_dir.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -20,10 +20,12 @@ use serde::Deserialize;
 
 mod errors;
 mod git;
+mod lock;
 mod semver;
 
 pub use errors::ManifestError;
-use git::clone_git_repo;
+use lock::{DependencyLock, LockMode, Sources};
+pub use lock::{LockChange, LockedDependency, LOCKFILE_NAME, VENDOR_DIR};
 
 /// Searches for a `Nargo.toml` file in the current directory and all parent directories.
 /// For example, if the current directory is `/workspace/package/src`, then this function
@@ -125,6 +127,7 @@ impl PackageConfig {
         &self,
         root_dir: &Path,
         processed: &mut Vec<String>,
+        lock: &mut DependencyLock,
     ) -> Result<Package, ManifestError> {
         let name: CrateName = if let Some(name) = &self.package.name {
             name.parse().map_err(|_| ManifestError::InvalidPackageName {
@@ -141,7 +144,7 @@ impl PackageConfig {
                 toml: root_dir.join("Nargo.toml"),
                 name: name.into(),
             })?;
-            let resolved_dep = dep_config.resolve_to_dependency(root_dir, processed)?;
+            let resolved_dep = dep_config.resolve_to_dependency(root_dir, processed, lock)?;
 
             dependencies.insert(name, resolved_dep);
         }
@@ -304,10 +307,11 @@ impl DependencyConfig {
         &self,
         pkg_root: &Path,
         processed: &mut Vec<String>,
+        lock: &mut DependencyLock,
     ) -> Result<Dependency, ManifestError> {
         let dep = match self {
             Self::Github { git, tag, directory } => {
-                let dir_path = clone_git_repo(git, tag).map_err(ManifestError::GitError)?;
+                let dir_path = lock.resolve_git_dependency(git, tag)?;
                 let project_path = if let Some(directory) = directory {
                     let internal_path = dir_path.join(directory).normalize();
                     if !internal_path.starts_with(&dir_path) {
@@ -321,13 +325,13 @@ impl DependencyConfig {
                     dir_path
                 };
                 let toml_path = project_path.join("Nargo.toml");
-                let package = resolve_package_from_toml(&toml_path, processed)?;
+                let package = resolve_package_from_toml(&toml_path, processed, lock)?;
                 Dependency::Remote { package }
             }
             Self::Path { path } => {
                 let dir_path = pkg_root.join(path);
                 let toml_path = dir_path.join("Nargo.toml");
-                let package = resolve_package_from_toml(&toml_path, processed)?;
+                let package = resolve_package_from_toml(&toml_path, processed, lock)?;
                 Dependency::Local { package }
             }
         };
@@ -345,11 +349,13 @@ impl DependencyConfig {
 fn toml_to_workspace(
     nargo_toml: NargoToml,
     package_selection: PackageSelection,
+    lock: &mut DependencyLock,
 ) -> Result<Workspace, ManifestError> {
     let mut resolved = Vec::new();
     let workspace = match nargo_toml.config {
         Config::Package { package_config } => {
-            let member = package_config.resolve_to_package(&nargo_toml.root_dir, &mut resolved)?;
+            let member =
+                package_config.resolve_to_package(&nargo_toml.root_dir, &mut resolved, lock)?;
             match &package_selection {
                 PackageSelection::Selected(selected_name) if selected_name != &member.name => {
                     return Err(ManifestError::MissingSelectedPackage(member.name))
@@ -368,7 +374,7 @@ fn toml_to_workspace(
             for (index, member_path) in workspace_config.members.into_iter().enumerate() {
                 let package_root_dir = nargo_toml.root_dir.join(&member_path);
                 let package_toml_path = package_root_dir.join("Nargo.toml");
-                let member = resolve_package_from_toml(&package_toml_path, &mut resolved)?;
+                let member = resolve_package_from_toml(&package_toml_path, &mut resolved, lock)?;
 
                 match &package_selection {
                     PackageSelection::Selected(selected_name) => {
@@ -433,6 +439,7 @@ fn read_toml(toml_path: &Path) -> Result<NargoToml, ManifestError> {
 fn resolve_package_from_toml(
     toml_path: &Path,
     processed: &mut Vec<String>,
+    lock: &mut DependencyLock,
 ) -> Result<Package, ManifestError> {
     // Checks for cyclic dependencies
     let str_path = toml_path.to_str().expect("ICE - path is empty");
@@ -457,7 +464,7 @@ fn resolve_package_from_toml(
 
     let result = match nargo_toml.config {
         Config::Package { package_config } => {
-            package_config.resolve_to_package(&nargo_toml.root_dir, processed)
+            package_config.resolve_to_package(&nargo_toml.root_dir, processed, lock)
         }
         Config::Workspace { .. } => {
             Err(ManifestError::UnexpectedWorkspace(toml_path.to_path_buf()))
@@ -477,19 +484,56 @@ pub enum PackageSelection {
 }
 
 /// Resolves a Nargo.toml file into a `Workspace` struct as defined by our `nargo` core.
+///
+/// Git dependencies are checked out at the commits recorded in the workspace's `Nargo.lock`,
+/// and any which aren't recorded there yet are added to it.
+/// If `offline`, only git dependencies which are vendored or have already been cloned are used.
 pub fn resolve_workspace_from_toml(
     toml_path: &Path,
     package_selection: PackageSelection,
     current_compiler_version: Option<String>,
+    offline: bool,
 ) -> Result<Workspace, ManifestError> {
     let nargo_toml = read_toml(toml_path)?;
-    let workspace = toml_to_workspace(nargo_toml, package_selection)?;
+    let sources = Sources::for_workspace(&nargo_toml.root_dir, offline);
+    let mut lock = DependencyLock::load(&nargo_toml.root_dir, sources, LockMode::Locked)?;
+    let workspace = toml_to_workspace(nargo_toml, package_selection, &mut lock)?;
+    lock.save()?;
     if let Some(current_compiler_version) = current_compiler_version {
         semver::semver_check_workspace(&workspace, current_compiler_version)?;
     }
     Ok(workspace)
 }
 
+/// Resolves the git dependencies of the workspace of a Nargo.toml file to the commits which their tags
+/// currently point to, recording them in `Nargo.lock`.
+///
+/// Returns the dependencies which are now locked to a different commit.
+/// This needs the network, so fails if `offline`.
+pub fn update_lockfile(toml_path: &Path, offline: bool) -> Result<Vec<LockChange>, ManifestError> {
+    let nargo_toml = read_toml(toml_path)?;
+    let sources = Sources::for_workspace(&nargo_toml.root_dir, offline);
+    let mut lock = DependencyLock::load(&nargo_toml.root_dir, sources, LockMode::Update)?;
+    toml_to_workspace(nargo_toml, PackageSelection::All, &mut lock)?;
+    lock.save()
+}
+
+/// Copies the git dependencies of the workspace of a Nargo.toml file, at their locked commits,
+/// into the workspace's `vendor` directory so that it can be built without network access.
+///
+/// Returns the directories which the dependencies were copied to.
+pub fn vendor_dependencies(toml_path: &Path, offline: bool) -> Result<Vec<PathBuf>, ManifestError> {
+    let nargo_toml = read_toml(toml_path)?;
+    let root_dir = nargo_toml.root_dir.clone();
+    // The dependencies being vendored shouldn't be taken from the copies they're replacing.
+    let sources = Sources { vendor_dir: None, ..Sources::for_workspace(&root_dir, offline) };
+    let mut lock = DependencyLock::load(&root_dir, sources, LockMode::Locked)?;
+    toml_to_workspace(nargo_toml, PackageSelection::All, &mut lock)?;
+    let vendored = lock.vendor(&root_dir.join(VENDOR_DIR))?;
+    lock.save()?;
+    Ok(vendored)
+}
+
 #[test]
 fn parse_standard_toml() {
     let src = r#"
@@ -0,0 +1,633 @@
+//! `Nargo.lock` records the commit which each git dependency's tag resolved to along with a checksum
+//! of its contents, so that a tag being moved can't silently change what a workspace is built from.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::git::{
+    clone_git_repo, fetch_commit, git_commit_location, git_dep_location, head_commit, nargo_crates,
+    reclone_git_repo,
+};
+use crate::ManifestError;
+
+pub const LOCKFILE_NAME: &str = "Nargo.lock";
+
+/// The directory within a workspace which `nargo vendor` copies git dependencies into.
+pub const VENDOR_DIR: &str = "vendor";
+
+const LOCKFILE_VERSION: u32 = 1;
+
+const LOCKFILE_HEADER: &str =
+    "# This file is automatically generated by nargo.\n# It is not intended for manual editing.\n";
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Lockfile {
+    version: u32,
+    #[serde(default, rename = "package")]
+    packages: Vec<LockedDependency>,
+}
+
+/// A git dependency as recorded in `Nargo.lock`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedDependency {
+    pub git: String,
+    pub tag: String,
+    /// The commit which the tag pointed to when the dependency was locked.
+    pub commit: String,
+    /// A SHA-256 hash of the files of the dependency.
+    pub checksum: String,
+}
+
+/// Whether git dependencies should be resolved to the commits in `Nargo.lock`
+/// or to the commits which their tags currently point to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LockMode {
+    Locked,
+    Update,
+}
+
+/// Where git dependencies can be taken from.
+pub(crate) struct Sources {
+    /// The directory which git dependencies are cloned into, if the home directory can be found.
+    /// It's only needed once a git dependency has to be cloned or checked out.
+    pub(crate) crates_dir: Option<PathBuf>,
+    /// A directory of vendored git dependencies, which are used in preference to cloned ones.
+    pub(crate) vendor_dir: Option<PathBuf>,
+    /// Whether dependencies which haven't been cloned or vendored already may be fetched.
+    pub(crate) offline: bool,
+}
+
+impl Sources {
+    /// The usual sources for the workspace at `root_dir`: its vendor directory and nargo's own clones,
+    /// with the network only being used if not `offline`.
+    pub(crate) fn for_workspace(root_dir: &Path, offline: bool) -> Self {
+        Sources { crates_dir: nargo_crates(), vendor_dir: Some(root_dir.join(VENDOR_DIR)), offline }
+    }
+
+    fn crates_dir(&self) -> Result<&Path, ManifestError> {
+        self.crates_dir.as_deref().ok_or(ManifestError::MissingHomeDirectory)
+    }
+}
+
+/// Resolves the git dependencies of a workspace, keeping track of what they resolve to
+/// so that `Nargo.lock` can be written out once the whole workspace has been resolved.
+pub(crate) struct DependencyLock {
+    lockfile_path: PathBuf,
+    sources: Sources,
+    mode: LockMode,
+    locked: BTreeMap<(String, String), LockedDependency>,
+    resolved: BTreeMap<(String, String), (LockedDependency, PathBuf)>,
+    /// The contents of `Nargo.lock` as it was loaded, if it exists.
+    contents: Option<String>,
+}
+
+impl DependencyLock {
+    /// Reads the `Nargo.lock` in `root_dir`, if there is one.
+    pub(crate) fn load(
+        root_dir: &Path,
+        sources: Sources,
+        mode: LockMode,
+    ) -> Result<Self, ManifestError> {
+        let lockfile_path = root_dir.join(LOCKFILE_NAME);
+        let contents = if lockfile_path.exists() {
+            Some(
+                std::fs::read_to_string(&lockfile_path)
+                    .map_err(|_| ManifestError::ReadFailed(lockfile_path.clone()))?,
+            )
+        } else {
+            None
+        };
+        let lockfile = if let Some(contents) = &contents {
+            let lockfile: Lockfile = toml::from_str(contents).map_err(|err| {
+                ManifestError::MalformedLockfile(lockfile_path.clone(), err.to_string())
+            })?;
+            if lockfile.version != LOCKFILE_VERSION {
+                return Err(ManifestError::UnsupportedLockfileVersion(
+                    lockfile_path,
+                    lockfile.version,
+                ));
+            }
+            lockfile
+        } else {
+            Lockfile::default()
+        };
+
+        let locked = lockfile
+            .packages
+            .into_iter()
+            .map(|dependency| ((dependency.git.clone(), dependency.tag.clone()), dependency))
+            .collect();
+        Ok(DependencyLock {
+            lockfile_path,
+            sources,
+            mode,
+            locked,
+            resolved: BTreeMap::new(),
+            contents,
+        })
+    }
+
+    /// Returns the directory holding the git dependency at `tag`, checked out at its locked commit
+    /// unless the lock is being updated.
+    ///
+    /// The files of the dependency are hashed every time and checked against the locked checksum,
+    /// so a checkout or vendored copy which has been edited since it was locked is rejected.
+    pub(crate) fn resolve_git_dependency(
+        &mut self,
+        git: &str,
+        tag: &str,
+    ) -> Result<PathBuf, ManifestError> {
+        let key = (git.to_string(), tag.to_string());
+        let locked = match self.mode {
+            LockMode::Locked => self.locked.get(&key),
+            LockMode::Update => None,
+        };
+
+        if let Some((_, repo_dir)) = self.resolved.get(&key) {
+            return Ok(repo_dir.clone());
+        }
+
+        let vendored = match self.mode {
+            LockMode::Locked => self.vendored(git, tag)?,
+            // Updating needs to find out what the tag points to now, so vendored copies can't be used.
+            LockMode::Update => None,
+        };
+        let (repo_dir, commit, checksum) = match (vendored, locked) {
+            // Vendored copies can only be trusted to be the locked commit through their checksum.
+            (Some(repo_dir), Some(locked)) => {
+                let checksum = checksum_dependency(&repo_dir, git)?;
+                (repo_dir, locked.commit.clone(), checksum)
+            }
+            (Some(_), None) => {
+                return Err(ManifestError::UnlockedVendoredDependency {
+                    git: git.to_string(),
+                    tag: tag.to_string(),
+                })
+            }
+            (None, Some(locked)) => {
+                let repo_dir = self.checkout(git, tag, &locked.commit)?;
+                let checksum = checksum_dependency(&repo_dir, git)?;
+                (repo_dir, locked.commit.clone(), checksum)
+            }
+            (None, None) => {
+                let tag_dir = self.clone_tag(git, tag)?;
+                let commit = head_commit(&tag_dir).map_err(ManifestError::GitError)?;
+                let repo_dir = self.checkout(git, tag, &commit)?;
+                let checksum = checksum_dependency(&repo_dir, git)?;
+                (repo_dir, commit, checksum)
+            }
+        };
+
+        if let Some(locked) = locked {
+            if locked.checksum != checksum {
+                return Err(ManifestError::ChecksumMismatch {
+                    git: git.to_string(),
+                    tag: tag.to_string(),
+                    expected: locked.checksum.clone(),
+                    found: checksum,
+                });
+            }
+        }
+
+        let dependency =
+            LockedDependency { git: key.0.clone(), tag: key.1.clone(), commit, checksum };
+        self.resolved.insert(key, (dependency, repo_dir.clone()));
+        Ok(repo_dir)
+    }
+
+    /// Returns the vendored copy of the git dependency at `tag`, if there is one.
+    fn vendored(&self, git: &str, tag: &str) -> Result<Option<PathBuf>, ManifestError> {
+        let Some(vendor_dir) = &self.sources.vendor_dir else {
+            return Ok(None);
+        };
+        let vendored = git_dep_location(vendor_dir, git, tag).map_err(ManifestError::GitError)?;
+        Ok(vendored.exists().then_some(vendored))
+    }
+
+    /// Returns nargo's clone of the git dependency at `tag`, cloning it if needed and allowed.
+    /// When updating, the dependency is cloned afresh to find out what the tag points to now.
+    fn clone_tag(&self, git: &str, tag: &str) -> Result<PathBuf, ManifestError> {
+        let crates_dir = self.sources.crates_dir()?;
+        if self.mode == LockMode::Update {
+            if self.sources.offline {
+                return Err(ManifestError::OfflineUpdate);
+            }
+            return reclone_git_repo(crates_dir, git, tag).map_err(ManifestError::GitError);
+        }
+
+        let cloned = git_dep_location(crates_dir, git, tag).map_err(ManifestError::GitError)?;
+        if self.sources.offline && !cloned.exists() {
+            return Err(ManifestError::MissingOfflineDependency {
+                git: git.to_string(),
+                tag: tag.to_string(),
+            });
+        }
+        clone_git_repo(crates_dir, git, tag).map_err(ManifestError::GitError)
+    }
+
+    /// Returns the directory holding the files of the git dependency at `commit`, creating it from
+    /// the clone of `tag` or, if the tag no longer points to the commit, by fetching the commit.
+    fn checkout(&self, git: &str, tag: &str, commit: &str) -> Result<PathBuf, ManifestError> {
+        let crates_dir = self.sources.crates_dir()?;
+        let location =
+            git_commit_location(crates_dir, git, commit).map_err(ManifestError::GitError)?;
+        if location.exists() {
+            return Ok(location);
+        }
+
+        let unavailable = |error| ManifestError::LockedCommitUnavailable {
+            git: git.to_string(),
+            tag: tag.to_string(),
+            commit: commit.to_string(),
+            error,
+        };
+        let tag_dir = self.clone_tag(git, tag)?;
+        let fetched = if head_commit(&tag_dir).map_err(ManifestError::GitError)? == commit {
+            None
+        } else if self.sources.offline {
+            return Err(unavailable("fetching it isn't allowed offline".to_string()));
+        } else {
+            let fetch_dir = location.with_extension("fetch");
+            Some(fetch_commit(git, commit, &fetch_dir).map_err(unavailable)?)
+        };
+
+        // The files are copied alongside and then moved into place, so that a build which is
+        // interrupted never leaves behind a partial checkout.
+        let partial = location.with_extension("partial");
+        let write_failed = |_| ManifestError::WriteFailed(location.clone());
+        if partial.exists() {
+            std::fs::remove_dir_all(&partial).map_err(write_failed)?;
+        }
+        copy_dir(fetched.as_ref().unwrap_or(&tag_dir), &partial).map_err(write_failed)?;
+        std::fs::rename(&partial, &location).map_err(write_failed)?;
+        if let Some(fetched) = fetched {
+            std::fs::remove_dir_all(fetched).map_err(write_failed)?;
+        }
+        Ok(location)
+    }
+
+    /// Copies the git dependencies which have been resolved into `vendor_dir`,
+    /// replacing anything which was vendored before.
+    pub(crate) fn vendor(&self, vendor_dir: &Path) -> Result<Vec<PathBuf>, ManifestError> {
+        let write_failed = |_| ManifestError::WriteFailed(vendor_dir.to_path_buf());
+        if vendor_dir.exists() {
+            std::fs::remove_dir_all(vendor_dir).map_err(write_failed)?;
+        }
+
+        let mut vendored = Vec::new();
+        for ((git, tag), (_, repo_dir)) in &self.resolved {
+            let destination =
+                git_dep_location(vendor_dir, git, tag).map_err(ManifestError::GitError)?;
+            copy_dir(repo_dir, &destination).map_err(write_failed)?;
+            vendored.push(destination);
+        }
+        Ok(vendored)
+    }
+
+    /// Writes out `Nargo.lock` with the dependencies which have been resolved, if that changes
+    /// its contents, returning the dependencies whose commits changed.
+    ///
+    /// Workspaces without git dependencies aren't given a lockfile.
+    pub(crate) fn save(self) -> Result<Vec<LockChange>, ManifestError> {
+        let changes: Vec<LockChange> = self
+            .resolved
+            .iter()
+            .filter_map(|(key, (dependency, _))| {
+                let previous_commit = self.locked.get(key).map(|locked| locked.commit.clone());
+                (previous_commit.as_ref() != Some(&dependency.commit)).then(|| LockChange {
+                    git: dependency.git.clone(),
+                    tag: dependency.tag.clone(),
+                    previous_commit,
+                    commit: dependency.commit.clone(),
+                })
+            })
+            .collect();
+
+        if self.resolved.is_empty() && self.contents.is_none() {
+            return Ok(changes);
+        }
+
+        let packages = self.resolved.into_values().map(|(dependency, _)| dependency).collect();
+        let lockfile = Lockfile { version: LOCKFILE_VERSION, packages };
+        let contents = toml::to_string(&lockfile).expect("lockfile can be serialized");
+        let contents = format!("{LOCKFILE_HEADER}{contents}");
+        if self.contents.as_ref() != Some(&contents) {
+            std::fs::write(&self.lockfile_path, contents)
+                .map_err(|_| ManifestError::WriteFailed(self.lockfile_path.clone()))?;
+        }
+
+        Ok(changes)
+    }
+}
+
+/// A git dependency which was locked to a different commit than before.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockChange {
+    pub git: String,
+    pub tag: String,
+    /// The commit the dependency was previously locked to, if it was locked at all.
+    pub previous_commit: Option<String>,
+    pub commit: String,
+}
+
+/// Copies `source` into `destination` along with everything in it, leaving out git's own files
+/// as only the checked out files are needed to build a dependency.
+fn copy_dir(source: &Path, destination: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(destination)?;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        let target = destination.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the checksum of the files of the git dependency `git` held in `dir`.
+fn checksum_dependency(dir: &Path, git: &str) -> Result<String, ManifestError> {
+    checksum_dir(dir).map_err(|err| ManifestError::GitError(format!("Failed to read {git}: {err}")))
+}
+
+/// Hashes the paths and contents of all the files in `dir`, leaving out git's own files.
+fn checksum_dir(dir: &Path) -> std::io::Result<String> {
+    let mut files = Vec::new();
+    collect_files(dir, dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for (relative_path, path) in files {
+        let contents = std::fs::read(path)?;
+        hasher.update(relative_path.as_bytes());
+        hasher.update([0]);
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(&contents);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    files: &mut Vec<(String, PathBuf)>,
+) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            collect_files(root, &path, files)?;
+        } else {
+            // Paths are joined with `/` so that the checksum is the same on every platform.
+            let relative_path = path
+                .strip_prefix(root)
+                .expect("path is inside root")
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((relative_path, path));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::process::Command;
+
+    use tempfile::TempDir;
+
+    use super::{DependencyLock, LockMode, Sources, LOCKFILE_NAME, VENDOR_DIR};
+    use crate::ManifestError;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    /// Creates a repo with a library tagged as `v1`, returning its `file://` URL.
+    fn create_repo(dir: &Path) -> String {
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(
+            dir.join("Nargo.toml"),
+            "[package]\nname = \"dep\"\ntype = \"lib\"\nauthors = [\"\"]\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("src/lib.nr"), "pub fn foo() {}\n").unwrap();
+        git(dir, &["init", "--quiet"]);
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "--quiet", "-m", "initial"]);
+        git(dir, &["tag", "v1"]);
+        url::Url::from_directory_path(dir).unwrap().to_string()
+    }
+
+    fn move_tag(dir: &Path) {
+        std::fs::write(dir.join("src/lib.nr"), "pub fn bar() {}\n").unwrap();
+        git(dir, &["commit", "--quiet", "-am", "change"]);
+        git(dir, &["tag", "--force", "v1"]);
+    }
+
+    fn sources(root: &Path, crates: &Path, offline: bool) -> Sources {
+        Sources {
+            crates_dir: Some(crates.to_path_buf()),
+            vendor_dir: Some(root.join(VENDOR_DIR)),
+            offline,
+        }
+    }
+
+    fn resolve(
+        root: &Path,
+        crates: &Path,
+        url: &str,
+        mode: LockMode,
+    ) -> Result<String, ManifestError> {
+        let mut lock = DependencyLock::load(root, sources(root, crates, false), mode)?;
+        let dir = lock.resolve_git_dependency(url, "v1")?;
+        lock.save()?;
+        Ok(std::fs::read_to_string(dir.join("src/lib.nr")).unwrap())
+    }
+
+    #[test]
+    fn keeps_dependencies_at_their_locked_commit() {
+        let temp = TempDir::new().unwrap();
+        let (repo, root, crates) =
+            (temp.path().join("repo"), temp.path().join("root"), temp.path().join("crates"));
+        std::fs::create_dir_all(&root).unwrap();
+        let url = create_repo(&repo);
+
+        assert_eq!(resolve(&root, &crates, &url, LockMode::Locked).unwrap(), "pub fn foo() {}\n");
+        let lockfile = std::fs::read_to_string(root.join(LOCKFILE_NAME)).unwrap();
+        assert!(lockfile.contains(&format!("git = \"{url}\"")));
+
+        // Even once the tag is moved and the dependency is cloned again,
+        // the commit in the lockfile is used until the lock is updated.
+        move_tag(&repo);
+        std::fs::remove_dir_all(&crates).unwrap();
+        assert_eq!(resolve(&root, &crates, &url, LockMode::Locked).unwrap(), "pub fn foo() {}\n");
+        assert_eq!(std::fs::read_to_string(root.join(LOCKFILE_NAME)).unwrap(), lockfile);
+
+        assert_eq!(resolve(&root, &crates, &url, LockMode::Update).unwrap(), "pub fn bar() {}\n");
+        assert_ne!(std::fs::read_to_string(root.join(LOCKFILE_NAME)).unwrap(), lockfile);
+    }
+
+    #[test]
+    fn rejects_dependencies_which_do_not_match_their_checksum() {
+        let temp = TempDir::new().unwrap();
+        let (repo, root, crates) =
+            (temp.path().join("repo"), temp.path().join("root"), temp.path().join("crates"));
+        std::fs::create_dir_all(&root).unwrap();
+        let url = create_repo(&repo);
+        resolve(&root, &crates, &url, LockMode::Locked).unwrap();
+
+        let lockfile_path = root.join(LOCKFILE_NAME);
+        let lockfile = std::fs::read_to_string(&lockfile_path).unwrap();
+        let checksum = lockfile.lines().find(|line| line.starts_with("checksum")).unwrap();
+        let tampered = lockfile.replace(checksum, &format!("checksum = \"{}\"", "0".repeat(64)));
+        std::fs::write(&lockfile_path, tampered).unwrap();
+
+        let error = resolve(&root, &crates, &url, LockMode::Locked).unwrap_err();
+        assert!(matches!(error, ManifestError::ChecksumMismatch { .. }));
+    }
+
+    #[test]
+    fn rejects_checkouts_which_were_edited_after_locking() {
+        let temp = TempDir::new().unwrap();
+        let (repo, root, crates) =
+            (temp.path().join("repo"), temp.path().join("root"), temp.path().join("crates"));
+        std::fs::create_dir_all(&root).unwrap();
+        let url = create_repo(&repo);
+
+        let mut lock =
+            DependencyLock::load(&root, sources(&root, &crates, false), LockMode::Locked).unwrap();
+        let dir = lock.resolve_git_dependency(&url, "v1").unwrap();
+        lock.save().unwrap();
+
+        std::fs::write(dir.join("src/lib.nr"), "pub fn baz() {}\n").unwrap();
+        let error = resolve(&root, &crates, &url, LockMode::Locked).unwrap_err();
+        assert!(matches!(error, ManifestError::ChecksumMismatch { .. }));
+    }
+
+    #[test]
+    fn checks_out_each_commit_into_its_own_directory() {
+        let temp = TempDir::new().unwrap();
+        let (repo, crates) = (temp.path().join("repo"), temp.path().join("crates"));
+        let (first, second) = (temp.path().join("first"), temp.path().join("second"));
+        std::fs::create_dir_all(&first).unwrap();
+        std::fs::create_dir_all(&second).unwrap();
+        let url = create_repo(&repo);
+
+        let mut lock =
+            DependencyLock::load(&first, sources(&first, &crates, false), LockMode::Locked)
+                .unwrap();
+        let first_dir = lock.resolve_git_dependency(&url, "v1").unwrap();
+        lock.save().unwrap();
+
+        // Another workspace using the tag once it's moved gets the new commit without changing
+        // the checkout of the first workspace, which keeps using it without fetching anything.
+        move_tag(&repo);
+        let mut lock =
+            DependencyLock::load(&second, sources(&second, &crates, false), LockMode::Update)
+                .unwrap();
+        let second_dir = lock.resolve_git_dependency(&url, "v1").unwrap();
+        lock.save().unwrap();
+        assert_ne!(first_dir, second_dir);
+        assert_eq!(
+            std::fs::read_to_string(second_dir.join("src/lib.nr")).unwrap(),
+            "pub fn bar() {}\n"
+        );
+
+        let mut lock =
+            DependencyLock::load(&first, sources(&first, &crates, true), LockMode::Locked).unwrap();
+        assert_eq!(lock.resolve_git_dependency(&url, "v1").unwrap(), first_dir);
+        assert_eq!(
+            std::fs::read_to_string(first_dir.join("src/lib.nr")).unwrap(),
+            "pub fn foo() {}\n"
+        );
+    }
+
+    #[test]
+    fn does_not_create_lockfile_without_git_dependencies() {
+        let temp = TempDir::new().unwrap();
+        let crates = temp.path().join("crates");
+        let lock = DependencyLock::load(
+            temp.path(),
+            sources(temp.path(), &crates, false),
+            LockMode::Locked,
+        )
+        .unwrap();
+        assert!(lock.save().unwrap().is_empty());
+        assert!(!temp.path().join(LOCKFILE_NAME).exists());
+    }
+
+    #[test]
+    fn resolves_vendored_dependencies_offline() {
+        let temp = TempDir::new().unwrap();
+        let (repo, root, crates) =
+            (temp.path().join("repo"), temp.path().join("root"), temp.path().join("crates"));
+        std::fs::create_dir_all(&root).unwrap();
+        let url = create_repo(&repo);
+
+        let mut lock =
+            DependencyLock::load(&root, sources(&root, &crates, true), LockMode::Locked).unwrap();
+        let error = lock.resolve_git_dependency(&url, "v1").unwrap_err();
+        assert!(matches!(error, ManifestError::MissingOfflineDependency { .. }));
+
+        let mut lock =
+            DependencyLock::load(&root, sources(&root, &crates, false), LockMode::Locked).unwrap();
+        lock.resolve_git_dependency(&url, "v1").unwrap();
+        let vendored = lock.vendor(&root.join(VENDOR_DIR)).unwrap();
+        lock.save().unwrap();
+        assert_eq!(vendored.len(), 1);
+        assert!(vendored[0].join("src/lib.nr").exists());
+        assert!(!vendored[0].join(".git").exists());
+
+        // With the repo and nargo's clone gone, only the vendored copy is left to use.
+        std::fs::remove_dir_all(&repo).unwrap();
+        std::fs::remove_dir_all(&crates).unwrap();
+        let mut lock =
+            DependencyLock::load(&root, sources(&root, &crates, true), LockMode::Locked).unwrap();
+        assert_eq!(lock.resolve_git_dependency(&url, "v1").unwrap(), vendored[0]);
+        assert!(lock.save().unwrap().is_empty());
+    }
+
+    #[test]
+    fn only_needs_a_home_directory_for_dependencies_which_are_not_vendored() {
+        let temp = TempDir::new().unwrap();
+        let (repo, root, crates) =
+            (temp.path().join("repo"), temp.path().join("root"), temp.path().join("crates"));
+        std::fs::create_dir_all(&root).unwrap();
+        let url = create_repo(&repo);
+        let homeless = |root: &Path| Sources { crates_dir: None, ..sources(root, &crates, false) };
+
+        let mut lock = DependencyLock::load(&root, homeless(&root), LockMode::Locked).unwrap();
+        let error = lock.resolve_git_dependency(&url, "v1").unwrap_err();
+        assert!(matches!(error, ManifestError::MissingHomeDirectory));
+
+        let mut lock =
+            DependencyLock::load(&root, sources(&root, &crates, false), LockMode::Locked).unwrap();
+        lock.resolve_git_dependency(&url, "v1").unwrap();
+        let vendored = lock.vendor(&root.join(VENDOR_DIR)).unwrap();
+        lock.save().unwrap();
+
+        let mut lock = DependencyLock::load(&root, homeless(&root), LockMode::Locked).unwrap();
+        assert_eq!(lock.resolve_git_dependency(&url, "v1").unwrap(), vendored[0]);
+    }
+}
@@ -0,0 +1,342 @@
+//! Support code for the Rust bindings generated from a program's ABI.
+//!
+//! Generated bindings mirror the program's inputs and return value with plain Rust types
+//! and use the traits in this module to lay them out as the field elements the circuit works with.
+
+pub use acvm::{acir::native_types::WitnessMap, FieldElement};
+
+use acvm::{acir::native_types::Witness, AcirField};
+
+use crate::{errors::AbiError, MAIN_RETURN_NAME};
+
+/// A Rust type which can be passed to a program as a value of the corresponding ABI type.
+pub trait AbiEncode {
+    /// Appends the field elements representing this value to `fields`.
+    fn encode(&self, fields: &mut Vec<FieldElement>);
+}
+
+/// A Rust type which can be read back from the field elements of a value of the corresponding ABI type.
+pub trait AbiDecode: Sized {
+    /// The number of field elements taken up by a value of this type.
+    const FIELD_COUNT: u32;
+
+    /// Reads a value from the next [`Self::FIELD_COUNT`] field elements of `fields`.
+    fn decode(fields: &mut impl Iterator<Item = FieldElement>) -> Result<Self, AbiError>;
+}
+
+/// Assigns the encoded inputs of a program to consecutive witnesses starting from the first.
+pub fn to_witness_map(fields: Vec<FieldElement>) -> WitnessMap<FieldElement> {
+    fields
+        .into_iter()
+        .enumerate()
+        .map(|(index, field)| (Witness(index as u32), field))
+        .collect::<std::collections::BTreeMap<_, _>>()
+        .into()
+}
+
+/// Decodes a program's return value from the witnesses following its `input_field_count` inputs.
+pub fn decode_return_value<T: AbiDecode>(
+    witness_map: &WitnessMap<FieldElement>,
+    input_field_count: u32,
+) -> Result<T, AbiError> {
+    let fields = (input_field_count..input_field_count + T::FIELD_COUNT)
+        .map(|index| {
+            let witness_index = Witness(index);
+            witness_map.get(&witness_index).copied().ok_or_else(|| {
+                AbiError::MissingParamWitnessValue {
+                    name: MAIN_RETURN_NAME.to_string(),
+                    witness_index,
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    T::decode(&mut fields.into_iter())
+}
+
+fn next_field(fields: &mut impl Iterator<Item = FieldElement>) -> Result<FieldElement, AbiError> {
+    fields.next().ok_or(AbiError::NotEnoughFields)
+}
+
+fn unrepresentable<T>(value: FieldElement) -> AbiError {
+    AbiError::UnrepresentableValue { value, typ: std::any::type_name::<T>() }
+}
+
+impl AbiEncode for FieldElement {
+    fn encode(&self, fields: &mut Vec<FieldElement>) {
+        fields.push(*self);
+    }
+}
+
+impl AbiDecode for FieldElement {
+    const FIELD_COUNT: u32 = 1;
+
+    fn decode(fields: &mut impl Iterator<Item = FieldElement>) -> Result<Self, AbiError> {
+        next_field(fields)
+    }
+}
+
+impl AbiEncode for bool {
+    fn encode(&self, fields: &mut Vec<FieldElement>) {
+        fields.push(FieldElement::from(*self));
+    }
+}
+
+impl AbiDecode for bool {
+    const FIELD_COUNT: u32 = 1;
+
+    fn decode(fields: &mut impl Iterator<Item = FieldElement>) -> Result<Self, AbiError> {
+        let field = next_field(fields)?;
+        if field.is_zero() {
+            Ok(false)
+        } else if field.is_one() {
+            Ok(true)
+        } else {
+            Err(unrepresentable::<bool>(field))
+        }
+    }
+}
+
+macro_rules! impl_unsigned {
+    ($($typ:ty),*) => {
+        $(
+            impl AbiEncode for $typ {
+                fn encode(&self, fields: &mut Vec<FieldElement>) {
+                    fields.push(FieldElement::from(u128::from(*self)));
+                }
+            }
+
+            impl AbiDecode for $typ {
+                const FIELD_COUNT: u32 = 1;
+
+                fn decode(fields: &mut impl Iterator<Item = FieldElement>) -> Result<Self, AbiError> {
+                    let field = next_field(fields)?;
+                    if field.num_bits() > <$typ>::BITS {
+                        return Err(unrepresentable::<$typ>(field));
+                    }
+                    <$typ>::try_from(field.to_u128()).map_err(|_| unrepresentable::<$typ>(field))
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128);
+
+/// A Noir unsigned integer whose width doesn't match that of a Rust integer, such as a `u1`.
+///
+/// Values can only be constructed within the range of the width, so they are always valid inputs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Uint<const BITS: u32>(u128);
+
+impl<const BITS: u32> Uint<BITS> {
+    /// The largest value of the integer.
+    pub const MAX: u128 = if BITS >= u128::BITS { u128::MAX } else { (1 << BITS) - 1 };
+
+    /// Returns the value if it fits in `BITS` bits.
+    pub fn new(value: u128) -> Option<Self> {
+        (value <= Self::MAX).then_some(Uint(value))
+    }
+
+    /// Returns the value of the integer.
+    pub fn value(self) -> u128 {
+        self.0
+    }
+}
+
+impl<const BITS: u32> TryFrom<u128> for Uint<BITS> {
+    type Error = AbiError;
+
+    fn try_from(value: u128) -> Result<Self, Self::Error> {
+        Uint::new(value).ok_or_else(|| unrepresentable::<Self>(FieldElement::from(value)))
+    }
+}
+
+impl<const BITS: u32> AbiEncode for Uint<BITS> {
+    fn encode(&self, fields: &mut Vec<FieldElement>) {
+        fields.push(FieldElement::from(self.0));
+    }
+}
+
+impl<const BITS: u32> AbiDecode for Uint<BITS> {
+    const FIELD_COUNT: u32 = 1;
+
+    fn decode(fields: &mut impl Iterator<Item = FieldElement>) -> Result<Self, AbiError> {
+        let field = next_field(fields)?;
+        if field.num_bits() > BITS {
+            return Err(unrepresentable::<Self>(field));
+        }
+        Ok(Uint(field.to_u128()))
+    }
+}
+
+// Signed integers are represented by their two's complement in the width of the integer.
+macro_rules! impl_signed {
+    ($($typ:ty => $unsigned:ty),*) => {
+        $(
+            impl AbiEncode for $typ {
+                fn encode(&self, fields: &mut Vec<FieldElement>) {
+                    (*self as $unsigned).encode(fields);
+                }
+            }
+
+            impl AbiDecode for $typ {
+                const FIELD_COUNT: u32 = 1;
+
+                fn decode(fields: &mut impl Iterator<Item = FieldElement>) -> Result<Self, AbiError> {
+                    let field = next_field(fields)?;
+                    let value = <$unsigned>::decode(&mut std::iter::once(field))
+                        .map_err(|_| unrepresentable::<$typ>(field))?;
+                    Ok(value as $typ)
+                }
+            }
+        )*
+    };
+}
+
+impl_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64);
+
+impl<T: AbiEncode, const N: usize> AbiEncode for [T; N] {
+    fn encode(&self, fields: &mut Vec<FieldElement>) {
+        for element in self {
+            element.encode(fields);
+        }
+    }
+}
+
+impl<T: AbiDecode, const N: usize> AbiDecode for [T; N] {
+    const FIELD_COUNT: u32 = T::FIELD_COUNT * N as u32;
+
+    fn decode(fields: &mut impl Iterator<Item = FieldElement>) -> Result<Self, AbiError> {
+        let elements = (0..N).map(|_| T::decode(fields)).collect::<Result<Vec<_>, _>>()?;
+        Ok(elements.try_into().unwrap_or_else(|_| unreachable!("exactly {N} elements are decoded")))
+    }
+}
+
+/// A Noir `str<N>`, which is encoded as one field element per byte.
+///
+/// Values can only be constructed from strings of exactly `N` bytes, so they are always valid inputs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Str<const N: usize>(String);
+
+impl<const N: usize> Str<N> {
+    /// Returns the string if it is `N` bytes long.
+    pub fn new(value: impl Into<String>) -> Result<Self, AbiError> {
+        let value = value.into();
+        if value.len() != N {
+            return Err(AbiError::StringLengthMismatch { expected: N, found: value.len() });
+        }
+        Ok(Str(value))
+    }
+
+    /// Returns the contents of the string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for Str<N> {
+    type Error = AbiError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Str::new(value)
+    }
+}
+
+impl<const N: usize> AbiEncode for Str<N> {
+    fn encode(&self, fields: &mut Vec<FieldElement>) {
+        fields.extend(self.0.bytes().map(|byte| FieldElement::from(byte as u128)));
+    }
+}
+
+impl<const N: usize> AbiDecode for Str<N> {
+    const FIELD_COUNT: u32 = N as u32;
+
+    fn decode(fields: &mut impl Iterator<Item = FieldElement>) -> Result<Self, AbiError> {
+        let bytes = <[u8; N]>::decode(fields)?;
+        String::from_utf8(bytes.to_vec())
+            .map(Str)
+            .map_err(|error| AbiError::InvalidUtf8String(error.to_string()))
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident: $index:tt),*) => {
+        impl<$($name: AbiEncode),*> AbiEncode for ($($name,)*) {
+            #[allow(unused_variables)]
+            fn encode(&self, fields: &mut Vec<FieldElement>) {
+                $(self.$index.encode(fields);)*
+            }
+        }
+
+        impl<$($name: AbiDecode),*> AbiDecode for ($($name,)*) {
+            const FIELD_COUNT: u32 = 0 $(+ $name::FIELD_COUNT)*;
+
+            #[allow(unused_variables)]
+            fn decode(fields: &mut impl Iterator<Item = FieldElement>) -> Result<Self, AbiError> {
+                Ok(($($name::decode(fields)?,)*))
+            }
+        }
+    };
+}
+
+impl_tuple!();
+impl_tuple!(A: 0);
+impl_tuple!(A: 0, B: 1);
+impl_tuple!(A: 0, B: 1, C: 2);
+impl_tuple!(A: 0, B: 1, C: 2, D: 3);
+impl_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+impl_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+impl_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+
+#[cfg(test)]
+mod tests {
+    use acvm::{AcirField, FieldElement};
+
+    use super::{decode_return_value, to_witness_map, AbiDecode, AbiEncode, Str, Uint};
+
+    #[test]
+    fn round_trips_values() {
+        let value = ([-1i8, 2], (true, 300u16), FieldElement::from(7u128));
+        let mut fields = Vec::new();
+        value.encode(&mut fields);
+        assert_eq!(fields[0], FieldElement::from(255u128));
+        assert_eq!(fields.len() as u32, <([i8; 2], (bool, u16), FieldElement)>::FIELD_COUNT);
+
+        let mut witness_fields = vec![FieldElement::one()];
+        witness_fields.extend(fields);
+        let witness_map = to_witness_map(witness_fields);
+        assert_eq!(
+            decode_return_value::<([i8; 2], (bool, u16), FieldElement)>(&witness_map, 1).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn decodes_strings() {
+        let mut fields = Vec::new();
+        Str::<5>::new("hello").unwrap().encode(&mut fields);
+        assert_eq!(Str::<5>::decode(&mut fields.into_iter()).unwrap().as_str(), "hello");
+    }
+
+    #[test]
+    fn rejects_strings_of_the_wrong_length() {
+        assert!(Str::<5>::new("hell").is_err());
+        assert!(Str::<5>::try_from("hello!").is_err());
+        // The length is counted in bytes rather than characters.
+        assert!(Str::<2>::new("é").is_ok());
+    }
+
+    #[test]
+    fn rejects_values_out_of_range() {
+        let field = FieldElement::from(256u128);
+        assert!(u8::decode(&mut std::iter::once(field)).is_err());
+        assert!(i8::decode(&mut std::iter::once(field)).is_err());
+        assert!(bool::decode(&mut std::iter::once(FieldElement::from(2u128))).is_err());
+        assert!(Uint::<1>::decode(&mut std::iter::once(FieldElement::from(2u128))).is_err());
+        assert!(Uint::<1>::try_from(2u128).is_err());
+        assert_eq!(Uint::<1>::MAX, 1);
+        assert_eq!(Uint::<128>::MAX, u128::MAX);
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     input_parser::{InputTypecheckingError, InputValue},
     AbiType,
 };
-use acvm::acir::native_types::Witness;
+use acvm::{acir::native_types::Witness, FieldElement};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -17,6 +17,10 @@ pub enum InputParserError {
     AbiTypeMismatch(AbiType),
     #[error("Expected argument `{0}`, but none was found")]
     MissingArgument(String),
+    #[error("Expected {expected} field elements, or that many followed by the return value, but found {found}")]
+    FieldCountMismatch { expected: usize, found: usize },
+    #[error("{0} is not a supported input format, expected one of toml, json, fields or calldata")]
+    UnknownFormat(String),
 }
 
 impl From<toml::ser::Error> for InputParserError {
@@ -55,4 +59,12 @@ pub enum AbiError {
     ReturnTypeMismatch { return_type: AbiType, value: InputValue },
     #[error("No return value is expected but received {0:?}")]
     UnexpectedReturnValue(InputValue),
+    #[error("Ran out of field elements while decoding a value")]
+    NotEnoughFields,
+    #[error("The value {value} cannot be represented as a `{typ}`")]
+    UnrepresentableValue { value: FieldElement, typ: &'static str },
+    #[error("String is not valid UTF-8: {0}")]
+    InvalidUtf8String(String),
+    #[error("Expected a string of {expected} bytes but found one of {found} bytes")]
+    StringLengthMismatch { expected: usize, found: usize },
 }
@@ -0,0 +1,144 @@
+use super::{parse_str_to_field, InputValue};
+use crate::{decode_value, errors::InputParserError, Abi, AbiType, MAIN_RETURN_NAME};
+use acvm::{AcirField, FieldElement};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// The number of hexadecimal characters used to encode each field element in calldata.
+const CALLDATA_WORD_LENGTH: usize = 64;
+
+/// Parses a whitespace separated list of field elements, each written either in decimal or in hexadecimal.
+pub fn parse_fields(input_string: &str) -> Result<Vec<FieldElement>, InputParserError> {
+    input_string.split_whitespace().map(parse_str_to_field).collect()
+}
+
+/// Writes field elements in hexadecimal, one per line.
+pub fn serialize_to_fields(fields: &[FieldElement]) -> String {
+    fields.iter().fold(String::new(), |mut output, field| {
+        let _ = writeln!(output, "0x{}", field.to_hex());
+        output
+    })
+}
+
+/// Parses calldata made up of a single hexadecimal string in which each field element
+/// takes up a 32 byte big-endian word.
+pub fn parse_calldata(input_string: &str) -> Result<Vec<FieldElement>, InputParserError> {
+    let input_string = input_string.trim();
+    let calldata = input_string.strip_prefix("0x").unwrap_or(input_string);
+    if calldata.len() % CALLDATA_WORD_LENGTH != 0 || !calldata.is_ascii() {
+        return Err(InputParserError::ParseHexStr(format!(
+            "calldata must be made up of {} byte words",
+            CALLDATA_WORD_LENGTH / 2
+        )));
+    }
+
+    (0..calldata.len())
+        .step_by(CALLDATA_WORD_LENGTH)
+        .map(|start| {
+            let word = &calldata[start..start + CALLDATA_WORD_LENGTH];
+            parse_str_to_field(&format!("0x{word}"))
+        })
+        .collect()
+}
+
+/// Writes field elements as a single hexadecimal string of 32 byte big-endian words.
+pub fn serialize_to_calldata(fields: &[FieldElement]) -> String {
+    let words: String = fields.iter().map(|field| field.to_hex()).collect();
+    format!("0x{words}\n")
+}
+
+/// Decodes the inputs of a program from its parameters laid out as field elements in the order
+/// the program receives them, optionally followed by its return value.
+pub(crate) fn decode_fields(
+    fields: Vec<FieldElement>,
+    abi: &Abi,
+) -> Result<BTreeMap<String, InputValue>, InputParserError> {
+    let parameters_field_count = abi.field_count() as usize;
+    let return_field_count =
+        abi.return_type.as_ref().map_or(0, |return_type| return_type.abi_type.field_count())
+            as usize;
+    let has_return_value = match fields.len() {
+        found if found == parameters_field_count => false,
+        found if return_field_count > 0 && found == parameters_field_count + return_field_count => {
+            true
+        }
+        found => {
+            return Err(InputParserError::FieldCountMismatch {
+                expected: parameters_field_count,
+                found,
+            })
+        }
+    };
+
+    let mut fields = fields.into_iter();
+    let mut input_map = BTreeMap::new();
+    for parameter in &abi.parameters {
+        let value = decode_value(&mut fields, &parameter.typ)
+            .map_err(|error| InputParserError::ParseInputMap(error.to_string()))?;
+        input_map.insert(parameter.name.clone(), value);
+    }
+
+    if let (true, Some(return_type)) = (has_return_value, &abi.return_type) {
+        let value = decode_value(&mut fields, &return_type.abi_type)
+            .map_err(|error| InputParserError::ParseInputMap(error.to_string()))?;
+        input_map.insert(MAIN_RETURN_NAME.to_owned(), value);
+    }
+
+    Ok(input_map)
+}
+
+/// Encodes the inputs of a program as field elements in the order the program receives them,
+/// followed by its return value if one is given.
+pub(crate) fn encode_fields(
+    input_map: &BTreeMap<String, InputValue>,
+    abi: &Abi,
+) -> Result<Vec<FieldElement>, InputParserError> {
+    let mut fields = Vec::new();
+    for parameter in &abi.parameters {
+        let value = input_map
+            .get(&parameter.name)
+            .ok_or_else(|| InputParserError::MissingArgument(parameter.name.clone()))?;
+        fields.extend(encode_value(value, &parameter.typ)?);
+    }
+
+    if let (Some(return_type), Some(return_value)) =
+        (&abi.return_type, input_map.get(MAIN_RETURN_NAME))
+    {
+        fields.extend(encode_value(return_value, &return_type.abi_type)?);
+    }
+
+    Ok(fields)
+}
+
+fn encode_value(
+    value: &InputValue,
+    abi_type: &AbiType,
+) -> Result<Vec<FieldElement>, InputParserError> {
+    if !value.matches_abi(abi_type) {
+        return Err(InputParserError::AbiTypeMismatch(abi_type.clone()));
+    }
+    Abi::encode_value(value.clone(), abi_type)
+        .map_err(|error| InputParserError::ParseInputMap(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{AcirField, FieldElement};
+
+    use super::{parse_calldata, parse_fields, serialize_to_calldata};
+
+    #[test]
+    fn parses_calldata_words() {
+        let fields = vec![FieldElement::one(), FieldElement::from(255u128)];
+        let calldata = serialize_to_calldata(&fields);
+        assert_eq!(calldata.len(), 2 + 2 * 64 + 1);
+        assert_eq!(parse_calldata(&calldata).unwrap(), fields);
+
+        assert!(parse_calldata("0x01").is_err());
+    }
+
+    #[test]
+    fn parses_decimal_and_hex_fields() {
+        let fields = parse_fields("1 0x02\n3\n").unwrap();
+        assert_eq!(fields, vec![1u128.into(), 2u128.into(), 3u128.into()]);
+    }
+}
@@ -9,9 +9,12 @@ use serde::Serialize;
 use crate::errors::InputParserError;
 use crate::{Abi, AbiType};
 
+mod fields;
 pub mod json;
 mod toml;
 
+pub use fields::{parse_calldata, parse_fields, serialize_to_calldata, serialize_to_fields};
+
 /// This is what all formats eventually transform into
 /// For example, a toml file will parse into TomlTypes
 /// and those TomlTypes will be mapped to Value
@@ -186,10 +189,15 @@ impl InputValue {
 
 /// The different formats that are supported when parsing
 /// the initial witness values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(test, derive(strum_macros::EnumIter))]
 pub enum Format {
     Json,
     Toml,
+    /// A whitespace separated list of field elements, laid out as the circuit receives them.
+    Fields,
+    /// A hexadecimal string of the same field elements as [Format::Fields], each in a 32 byte word.
+    Calldata,
 }
 
 impl Format {
@@ -197,6 +205,22 @@ impl Format {
         match self {
             Format::Json => "json",
             Format::Toml => "toml",
+            Format::Fields => "fields",
+            Format::Calldata => "calldata",
+        }
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = InputParserError;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "json" => Ok(Format::Json),
+            "toml" => Ok(Format::Toml),
+            "fields" => Ok(Format::Fields),
+            "calldata" => Ok(Format::Calldata),
+            _ => Err(InputParserError::UnknownFormat(format.to_owned())),
         }
     }
 }
@@ -210,6 +234,8 @@ impl Format {
         match self {
             Format::Json => json::parse_json(input_string, abi),
             Format::Toml => toml::parse_toml(input_string, abi),
+            Format::Fields => fields::decode_fields(parse_fields(input_string)?, abi),
+            Format::Calldata => fields::decode_fields(parse_calldata(input_string)?, abi),
         }
     }
 
@@ -221,6 +247,8 @@ impl Format {
         match self {
             Format::Json => json::serialize_to_json(input_map, abi),
             Format::Toml => toml::serialize_to_toml(input_map, abi),
+            Format::Fields => Ok(serialize_to_fields(&fields::encode_fields(input_map, abi)?)),
+            Format::Calldata => Ok(serialize_to_calldata(&fields::encode_fields(input_map, abi)?)),
         }
     }
 }
@@ -28,6 +28,7 @@ use std::{collections::BTreeMap, str};
 #[cfg(test)]
 mod arbitrary;
 
+pub mod bindings;
 pub mod errors;
 pub mod input_parser;
 mod serialization;
@@ -280,7 +281,10 @@ impl Abi {
         Ok(witness_map.into())
     }
 
-    fn encode_value(value: InputValue, abi_type: &AbiType) -> Result<Vec<FieldElement>, AbiError> {
+    pub(crate) fn encode_value(
+        value: InputValue,
+        abi_type: &AbiType,
+    ) -> Result<Vec<FieldElement>, AbiError> {
         let mut encoded_value = Vec::new();
         match (value, abi_type) {
             (InputValue::Field(elem), _) => encoded_value.push(elem),
@@ -16,8 +16,8 @@ use iter_extended::try_btree_map;
 use noirc_abi::{
     decode_value, display_abi_error,
     errors::InputParserError,
-    input_parser::{json::JsonTypes, InputValue},
-    Abi, AbiErrorType, MAIN_RETURN_NAME,
+    input_parser::{json::JsonTypes, Format, InputValue},
+    Abi, AbiErrorType, InputMap, MAIN_RETURN_NAME,
 };
 use serde::Serialize;
 use std::collections::BTreeMap;
@@ -65,6 +65,53 @@ pub fn abi_encode(
     console_error_panic_hook::set_once();
     let abi: Abi =
         JsValueSerdeExt::into_serde(&JsValue::from(abi)).map_err(|err| err.to_string())?;
+    let (parsed_inputs, return_value) = parse_js_inputs(&abi, inputs, return_value)?;
+
+    let witness_map = abi.encode(&parsed_inputs, return_value)?;
+
+    Ok(witness_map.into())
+}
+
+/// Writes the inputs in one of the formats `nargo` reads a prover's inputs from,
+/// e.g. `fields` or `calldata` for the field elements the circuit receives.
+#[wasm_bindgen(js_name = abiSerializeInputs)]
+pub fn abi_serialize_inputs(
+    abi: JsAbi,
+    inputs: JsInputMap,
+    return_value: Option<JsInputValue>,
+    format: String,
+) -> Result<String, JsAbiError> {
+    console_error_panic_hook::set_once();
+    let abi: Abi =
+        JsValueSerdeExt::into_serde(&JsValue::from(abi)).map_err(|err| err.to_string())?;
+    let format: Format = format.parse()?;
+    let (mut parsed_inputs, return_value) = parse_js_inputs(&abi, inputs, return_value)?;
+    if let Some(return_value) = return_value {
+        parsed_inputs.insert(MAIN_RETURN_NAME.to_owned(), return_value);
+    }
+
+    Ok(format.serialize(&parsed_inputs, &abi)?)
+}
+
+/// Reads inputs written in one of the formats `nargo` reads a prover's inputs from.
+#[wasm_bindgen(js_name = abiParseInputs)]
+pub fn abi_parse_inputs(abi: JsAbi, input: String, format: String) -> Result<JsValue, JsAbiError> {
+    console_error_panic_hook::set_once();
+    let abi: Abi =
+        JsValueSerdeExt::into_serde(&JsValue::from(abi)).map_err(|err| err.to_string())?;
+    let format: Format = format.parse()?;
+
+    let mut inputs = format.parse(&input, &abi)?;
+    let return_value = inputs.remove(MAIN_RETURN_NAME);
+
+    inputs_and_return_to_js(&abi, inputs, return_value)
+}
+
+fn parse_js_inputs(
+    abi: &Abi,
+    inputs: JsInputMap,
+    return_value: Option<JsInputValue>,
+) -> Result<(InputMap, Option<InputValue>), JsAbiError> {
     let inputs: BTreeMap<String, JsonTypes> =
         JsValueSerdeExt::into_serde(&JsValue::from(inputs)).map_err(|err| err.to_string())?;
     let return_value: Option<InputValue> = return_value
@@ -90,9 +137,7 @@ pub fn abi_encode(
                 .map(|input_value| (arg_name, input_value))
         })?;
 
-    let witness_map = abi.encode(&parsed_inputs, return_value)?;
-
-    Ok(witness_map.into())
+    Ok((parsed_inputs, return_value))
 }
 
 #[wasm_bindgen(js_name = abiDecode)]
@@ -105,13 +150,23 @@ pub fn abi_decode(abi: JsAbi, witness_map: JsWitnessMap) -> Result<JsValue, JsAb
 
     let (inputs, return_value) = abi.decode(&witness_map)?;
 
+    inputs_and_return_to_js(&abi, inputs, return_value)
+}
+
+fn inputs_and_return_to_js(
+    abi: &Abi,
+    inputs: InputMap,
+    return_value: Option<InputValue>,
+) -> Result<JsValue, JsAbiError> {
     let abi_types = abi.to_btree_map();
     let inputs_map: BTreeMap<String, JsonTypes> = try_btree_map(inputs, |(key, value)| {
         JsonTypes::try_from_input_value(&value, &abi_types[&key]).map(|value| (key, value))
     })?;
 
     let return_value = return_value
-        .map(|value| JsonTypes::try_from_input_value(&value, &abi.return_type.unwrap().abi_type))
+        .map(|value| {
+            JsonTypes::try_from_input_value(&value, &abi.return_type.as_ref().unwrap().abi_type)
+        })
         .transpose()?;
 
     #[derive(Serialize)]
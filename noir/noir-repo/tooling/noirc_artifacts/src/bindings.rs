@@ -0,0 +1,406 @@
+//! Generation of typed Rust bindings for the ABIs of compiled programs and contracts.
+//!
+//! Each struct in an ABI becomes a Rust struct and every other ABI type is mapped onto a
+//! primitive Rust type, all of which implement the traits of [`noirc_abi::bindings`].
+//! A program's bindings then provide an `Inputs` struct which encodes the program's initial
+//! witness and a `decode_return` function which reads its return value back from the solved witness.
+//! Contracts get the same items in a module per function.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use noirc_abi::{Abi, AbiType, Sign};
+
+use crate::contract::ContractArtifact;
+use crate::program::ProgramArtifact;
+
+/// Names which the generated code uses itself and so can't be given to structs.
+const RESERVED_NAMES: [&str; 9] = [
+    "AbiDecode",
+    "AbiEncode",
+    "AbiError",
+    "FieldElement",
+    "Inputs",
+    "Return",
+    "Str",
+    "Uint",
+    "WitnessMap",
+];
+
+const RUST_KEYWORDS: [&str; 51] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Generates the Rust bindings of a program, to be placed in a module of their own.
+pub fn generate_program_bindings(name: &str, artifact: &ProgramArtifact) -> String {
+    let mut generator = BindingsGenerator::default();
+    generator.collect_structs(&artifact.abi);
+
+    let mut output =
+        format!("//! Rust bindings for the `{name}` program, generated by `nargo codegen`.\n");
+    generator.write_preamble(&mut output);
+    generator.write_structs(&mut output);
+    generator.write_entry_point(&mut output, &artifact.abi, "");
+    output
+}
+
+/// Generates the Rust bindings of a contract, to be placed in a module of their own.
+///
+/// The items for each function are placed in a module named after the function.
+pub fn generate_contract_bindings(artifact: &ContractArtifact) -> String {
+    let mut generator = BindingsGenerator::default();
+    for function in &artifact.functions {
+        generator.collect_structs(&function.abi);
+    }
+
+    let mut output = format!(
+        "//! Rust bindings for the `{}` contract, generated by `nargo codegen`.\n",
+        artifact.name
+    );
+    generator.write_preamble(&mut output);
+    generator.write_structs(&mut output);
+    for function in &artifact.functions {
+        writeln!(output, "\npub mod {} {{\n    use super::*;", identifier(&function.name)).unwrap();
+        generator.write_entry_point(&mut output, &function.abi, "    ");
+        output.push_str("}\n");
+    }
+    output
+}
+
+#[derive(Default)]
+struct BindingsGenerator {
+    /// The structs found in the ABIs, along with the names they're given in the bindings.
+    ///
+    /// Structs are told apart by their fields as well as their paths, as the same generic struct
+    /// turns up with different fields for each of its instantiations.
+    structs: Vec<(AbiType, String)>,
+}
+
+impl BindingsGenerator {
+    fn collect_structs(&mut self, abi: &Abi) {
+        let return_type = abi.return_type.iter().map(|return_type| &return_type.abi_type);
+        for typ in abi.parameters.iter().map(|parameter| &parameter.typ).chain(return_type) {
+            self.collect_structs_in_type(typ);
+        }
+    }
+
+    fn collect_structs_in_type(&mut self, typ: &AbiType) {
+        match typ {
+            AbiType::Array { typ, .. } => self.collect_structs_in_type(typ),
+            AbiType::Tuple { fields } => {
+                fields.iter().for_each(|field| self.collect_structs_in_type(field));
+            }
+            AbiType::Struct { path, fields } => {
+                fields.iter().for_each(|(_, field)| self.collect_structs_in_type(field));
+                if self.struct_name(typ).is_some() {
+                    return;
+                }
+
+                let base_name = path.rsplit("::").next().unwrap_or(path);
+                let taken: BTreeSet<&str> =
+                    self.structs.iter().map(|(_, name)| name.as_str()).collect();
+                let name = std::iter::once(base_name.to_string())
+                    .chain((2..).map(|index| format!("{base_name}{index}")))
+                    .find(|name| {
+                        !taken.contains(name.as_str()) && !RESERVED_NAMES.contains(&name.as_str())
+                    })
+                    .expect("an unused name exists");
+                self.structs.push((typ.clone(), name));
+            }
+            AbiType::Field
+            | AbiType::Integer { .. }
+            | AbiType::Boolean
+            | AbiType::String { .. } => {}
+        }
+    }
+
+    fn struct_name(&self, typ: &AbiType) -> Option<&str> {
+        self.structs
+            .iter()
+            .find(|(struct_type, _)| struct_type == typ)
+            .map(|(_, name)| name.as_str())
+    }
+
+    fn rust_type(&self, typ: &AbiType) -> String {
+        match typ {
+            AbiType::Field => "FieldElement".to_string(),
+            AbiType::Boolean => "bool".to_string(),
+            // Other widths get a wrapper which checks that values fit in the width.
+            AbiType::Integer { sign: Sign::Unsigned, width } => match width {
+                8 | 16 | 32 | 64 | 128 => format!("u{width}"),
+                1..=127 => format!("Uint<{width}>"),
+                _ => "FieldElement".to_string(),
+            },
+            // Signed integers are encoded in two's complement in their own width,
+            // which only matches a Rust integer of exactly the same width.
+            AbiType::Integer { sign: Sign::Signed, width } => match width {
+                8 | 16 | 32 | 64 => format!("i{width}"),
+                _ => "FieldElement".to_string(),
+            },
+            AbiType::String { length } => format!("Str<{length}>"),
+            AbiType::Array { length, typ } => format!("[{}; {length}]", self.rust_type(typ)),
+            AbiType::Tuple { fields } if fields.len() == 1 => {
+                format!("({},)", self.rust_type(&fields[0]))
+            }
+            AbiType::Tuple { fields } => {
+                let fields: Vec<_> = fields.iter().map(|field| self.rust_type(field)).collect();
+                format!("({})", fields.join(", "))
+            }
+            AbiType::Struct { .. } => {
+                self.struct_name(typ).expect("structs are collected before being used").to_string()
+            }
+        }
+    }
+
+    fn write_preamble(&self, output: &mut String) {
+        output.push_str(
+            "
+#![allow(unused_imports)]
+
+use noirc_abi::bindings::{
+    decode_return_value, to_witness_map, AbiDecode, AbiEncode, FieldElement, Str, Uint,
+    WitnessMap,
+};
+use noirc_abi::errors::AbiError;
+",
+        );
+    }
+
+    fn write_structs(&self, output: &mut String) {
+        for (typ, name) in &self.structs {
+            let AbiType::Struct { path, fields } = typ else {
+                unreachable!("only structs are collected");
+            };
+            let fields_parameter = if fields.is_empty() { "_fields" } else { "fields" };
+
+            writeln!(output, "\n/// The Noir struct `{path}`.").unwrap();
+            writeln!(output, "#[derive(Clone, Debug, PartialEq, Eq)]\npub struct {name} {{")
+                .unwrap();
+            for (field_name, field_type) in fields {
+                writeln!(
+                    output,
+                    "    pub {}: {},",
+                    identifier(field_name),
+                    self.rust_type(field_type)
+                )
+                .unwrap();
+            }
+            output.push_str("}\n");
+
+            writeln!(output, "\nimpl AbiEncode for {name} {{").unwrap();
+            writeln!(output, "    fn encode(&self, {fields_parameter}: &mut Vec<FieldElement>) {{")
+                .unwrap();
+            for (field_name, _) in fields {
+                writeln!(output, "        self.{}.encode(fields);", identifier(field_name))
+                    .unwrap();
+            }
+            output.push_str("    }\n}\n");
+
+            writeln!(output, "\nimpl AbiDecode for {name} {{").unwrap();
+            writeln!(output, "    const FIELD_COUNT: u32 = {};\n", typ.field_count()).unwrap();
+            writeln!(
+                output,
+                "    fn decode({fields_parameter}: &mut impl Iterator<Item = FieldElement>) -> Result<Self, AbiError> {{"
+            )
+            .unwrap();
+            writeln!(output, "        Ok({name} {{").unwrap();
+            for (field_name, _) in fields {
+                writeln!(
+                    output,
+                    "            {}: AbiDecode::decode(fields)?,",
+                    identifier(field_name)
+                )
+                .unwrap();
+            }
+            output.push_str("        })\n    }\n}\n");
+        }
+    }
+
+    /// Writes the `Inputs` struct and `decode_return` function for a program or contract function.
+    fn write_entry_point(&self, output: &mut String, abi: &Abi, indent: &str) {
+        let mut lines = vec![
+            String::new(),
+            "/// The inputs of the function.".to_string(),
+            "#[derive(Clone, Debug, PartialEq, Eq)]".to_string(),
+            "pub struct Inputs {".to_string(),
+        ];
+        for parameter in &abi.parameters {
+            lines.push(format!(
+                "    pub {}: {},",
+                identifier(&parameter.name),
+                self.rust_type(&parameter.typ)
+            ));
+        }
+        lines.extend([
+            "}".to_string(),
+            String::new(),
+            "impl Inputs {".to_string(),
+            "    /// Encodes the inputs into the initial witness of the function.".to_string(),
+            "    pub fn encode_inputs(&self) -> WitnessMap<FieldElement> {".to_string(),
+        ]);
+        if abi.parameters.is_empty() {
+            lines.push("        to_witness_map(Vec::new())".to_string());
+        } else {
+            lines.push("        let mut fields = Vec::new();".to_string());
+            for parameter in &abi.parameters {
+                lines.push(format!(
+                    "        self.{}.encode(&mut fields);",
+                    identifier(&parameter.name)
+                ));
+            }
+            lines.push("        to_witness_map(fields)".to_string());
+        }
+        lines.extend(["    }".to_string(), "}".to_string()]);
+
+        if let Some(return_type) = &abi.return_type {
+            lines.extend([
+                String::new(),
+                "/// The return value of the function.".to_string(),
+                format!("pub type Return = {};", self.rust_type(&return_type.abi_type)),
+                String::new(),
+                "/// Decodes the return value of the function from its solved witness.".to_string(),
+                "pub fn decode_return(witness_map: &WitnessMap<FieldElement>) -> Result<Return, AbiError> {"
+                    .to_string(),
+                format!("    decode_return_value(witness_map, {})", abi.field_count()),
+                "}".to_string(),
+            ]);
+        }
+
+        for line in lines {
+            if line.is_empty() {
+                output.push('\n');
+            } else {
+                writeln!(output, "{indent}{line}").unwrap();
+            }
+        }
+    }
+}
+
+/// Turns a Noir identifier into a Rust one, escaping it if it's a Rust keyword.
+fn identifier(name: &str) -> String {
+    match name {
+        // These keywords can't be used as raw identifiers.
+        "crate" | "self" | "Self" | "super" => format!("{name}_"),
+        _ if RUST_KEYWORDS.contains(&name) => format!("r#{name}"),
+        _ => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noirc_abi::{Abi, AbiParameter, AbiReturnType, AbiType, AbiVisibility, Sign};
+
+    use super::BindingsGenerator;
+
+    #[test]
+    fn generates_structs_and_entry_point() {
+        let point = AbiType::Struct {
+            path: "foo::Point".to_string(),
+            fields: vec![
+                ("x".to_string(), AbiType::Field),
+                ("type".to_string(), AbiType::Integer { sign: Sign::Signed, width: 8 }),
+            ],
+        };
+        let abi = Abi {
+            parameters: vec![AbiParameter {
+                name: "points".to_string(),
+                typ: AbiType::Array { length: 2, typ: Box::new(point) },
+                visibility: AbiVisibility::Private,
+            }],
+            return_type: Some(AbiReturnType {
+                abi_type: AbiType::String { length: 3 },
+                visibility: AbiVisibility::Public,
+            }),
+            error_types: Default::default(),
+        };
+
+        let mut generator = BindingsGenerator::default();
+        generator.collect_structs(&abi);
+        let mut output = String::new();
+        generator.write_structs(&mut output);
+        generator.write_entry_point(&mut output, &abi, "");
+
+        assert_eq!(
+            output,
+            "
+/// The Noir struct `foo::Point`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Point {
+    pub x: FieldElement,
+    pub r#type: i8,
+}
+
+impl AbiEncode for Point {
+    fn encode(&self, fields: &mut Vec<FieldElement>) {
+        self.x.encode(fields);
+        self.r#type.encode(fields);
+    }
+}
+
+impl AbiDecode for Point {
+    const FIELD_COUNT: u32 = 2;
+
+    fn decode(fields: &mut impl Iterator<Item = FieldElement>) -> Result<Self, AbiError> {
+        Ok(Point {
+            x: AbiDecode::decode(fields)?,
+            r#type: AbiDecode::decode(fields)?,
+        })
+    }
+}
+
+/// The inputs of the function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Inputs {
+    pub points: [Point; 2],
+}
+
+impl Inputs {
+    /// Encodes the inputs into the initial witness of the function.
+    pub fn encode_inputs(&self) -> WitnessMap<FieldElement> {
+        let mut fields = Vec::new();
+        self.points.encode(&mut fields);
+        to_witness_map(fields)
+    }
+}
+
+/// The return value of the function.
+pub type Return = Str<3>;
+
+/// Decodes the return value of the function from its solved witness.
+pub fn decode_return(witness_map: &WitnessMap<FieldElement>) -> Result<Return, AbiError> {
+    decode_return_value(witness_map, 4)
+}
+"
+        );
+    }
+
+    #[test]
+    fn gives_different_instantiations_of_a_struct_distinct_names() {
+        let wrapper = |typ| AbiType::Struct {
+            path: "Inputs".to_string(),
+            fields: vec![("value".to_string(), typ)],
+        };
+        let abi = Abi {
+            parameters: ["a", "b", "c"]
+                .into_iter()
+                .zip([AbiType::Field, AbiType::Boolean, AbiType::Field])
+                .map(|(name, typ)| AbiParameter {
+                    name: name.to_string(),
+                    typ: wrapper(typ),
+                    visibility: AbiVisibility::Private,
+                })
+                .collect(),
+            return_type: None,
+            error_types: Default::default(),
+        };
+
+        let mut generator = BindingsGenerator::default();
+        generator.collect_structs(&abi);
+        let names: Vec<_> = generator.structs.iter().map(|(_, name)| name.as_str()).collect();
+        assert_eq!(names, vec!["Inputs2", "Inputs3"]);
+    }
+}
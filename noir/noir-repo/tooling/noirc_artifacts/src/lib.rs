@@ -9,6 +9,7 @@
 //! Should any projects require/desire a different artifact format, it's expected that they will write a transformer
 //! to generate them using these artifacts as a starting point.
 
+pub mod bindings;
 pub mod contract;
 pub mod debug;
 mod debug_vars;
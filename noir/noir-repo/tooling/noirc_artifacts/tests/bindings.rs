@@ -0,0 +1,101 @@
+//! Checks that the bindings generated for a program compile and encode values as its ABI expects.
+//!
+//! The bindings in `bindings/example.rs` are compiled as part of this test, so any change to the
+//! generated code must keep them valid Rust.
+
+use std::collections::BTreeMap;
+
+use acvm::AcirField;
+use noirc_abi::bindings::{to_witness_map, AbiDecode, FieldElement, Uint};
+use noirc_abi::input_parser::InputValue;
+use noirc_abi::{Abi, AbiParameter, AbiReturnType, AbiType, AbiVisibility, Sign};
+use noirc_artifacts::bindings::generate_program_bindings;
+use noirc_artifacts::program::ProgramArtifact;
+
+// The bindings are kept exactly as they're generated, rather than as rustfmt would lay them out.
+#[rustfmt::skip]
+#[path = "bindings/example.rs"]
+mod example;
+
+fn example_abi() -> Abi {
+    let point = AbiType::Struct {
+        path: "geometry::Point".to_string(),
+        fields: vec![
+            ("x".to_string(), AbiType::Integer { sign: Sign::Unsigned, width: 3 }),
+            ("type".to_string(), AbiType::Integer { sign: Sign::Signed, width: 8 }),
+        ],
+    };
+    let parameter = |name: &str, typ| AbiParameter {
+        name: name.to_string(),
+        typ,
+        visibility: AbiVisibility::Private,
+    };
+    Abi {
+        parameters: vec![
+            parameter("flag", AbiType::Integer { sign: Sign::Unsigned, width: 1 }),
+            parameter("points", AbiType::Array { length: 2, typ: Box::new(point) }),
+            parameter("name", AbiType::String { length: 3 }),
+        ],
+        return_type: Some(AbiReturnType {
+            abi_type: AbiType::Tuple {
+                fields: vec![AbiType::Integer { sign: Sign::Unsigned, width: 64 }, AbiType::Field],
+            },
+            visibility: AbiVisibility::Public,
+        }),
+        error_types: Default::default(),
+    }
+}
+
+#[test]
+fn generated_bindings_are_up_to_date() {
+    let artifact = ProgramArtifact {
+        noir_version: String::new(),
+        hash: 0,
+        abi: example_abi(),
+        bytecode: Default::default(),
+        debug_symbols: Default::default(),
+        file_map: Default::default(),
+        names: Vec::new(),
+        brillig_names: Vec::new(),
+    };
+    let bindings = generate_program_bindings("example", &artifact);
+    assert_eq!(bindings, include_str!("bindings/example.rs"));
+}
+
+#[test]
+fn generated_bindings_encode_inputs_as_the_abi_does() {
+    let point = |x, r#type| example::Point { x: Uint::new(x).unwrap(), r#type };
+    let inputs = example::Inputs {
+        flag: Uint::new(1).unwrap(),
+        points: [point(7, -1), point(0, 2)],
+        name: "foo".try_into().unwrap(),
+    };
+
+    let point_input = |x: u128, r#type: u128| {
+        InputValue::Struct(BTreeMap::from([
+            ("x".to_string(), InputValue::Field(x.into())),
+            ("type".to_string(), InputValue::Field(r#type.into())),
+        ]))
+    };
+    let input_map = BTreeMap::from([
+        ("flag".to_string(), InputValue::Field(1u128.into())),
+        ("points".to_string(), InputValue::Vec(vec![point_input(7, 255), point_input(0, 2)])),
+        ("name".to_string(), InputValue::String("foo".to_string())),
+    ]);
+    assert_eq!(inputs.encode_inputs(), example_abi().encode(&input_map, None).unwrap());
+}
+
+#[test]
+fn generated_bindings_decode_the_return_value() {
+    let mut fields = vec![FieldElement::zero(); example_abi().field_count() as usize];
+    fields.extend([FieldElement::from(5u128), FieldElement::from(6u128)]);
+    let witness_map = to_witness_map(fields);
+    assert_eq!(example::decode_return(&witness_map).unwrap(), (5, FieldElement::from(6u128)));
+}
+
+#[test]
+fn odd_width_integers_are_range_checked() {
+    assert!(Uint::<1>::new(1).is_some());
+    assert!(Uint::<1>::new(2).is_none());
+    assert!(Uint::<3>::decode(&mut std::iter::once(FieldElement::from(8u128))).is_err());
+}
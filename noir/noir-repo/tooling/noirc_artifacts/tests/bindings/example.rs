@@ -0,0 +1,61 @@
+//! Rust bindings for the `example` program, generated by `nargo codegen`.
+
+#![allow(unused_imports)]
+
+use noirc_abi::bindings::{
+    decode_return_value, to_witness_map, AbiDecode, AbiEncode, FieldElement, Str, Uint,
+    WitnessMap,
+};
+use noirc_abi::errors::AbiError;
+
+/// The Noir struct `geometry::Point`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Point {
+    pub x: Uint<3>,
+    pub r#type: i8,
+}
+
+impl AbiEncode for Point {
+    fn encode(&self, fields: &mut Vec<FieldElement>) {
+        self.x.encode(fields);
+        self.r#type.encode(fields);
+    }
+}
+
+impl AbiDecode for Point {
+    const FIELD_COUNT: u32 = 2;
+
+    fn decode(fields: &mut impl Iterator<Item = FieldElement>) -> Result<Self, AbiError> {
+        Ok(Point {
+            x: AbiDecode::decode(fields)?,
+            r#type: AbiDecode::decode(fields)?,
+        })
+    }
+}
+
+/// The inputs of the function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Inputs {
+    pub flag: Uint<1>,
+    pub points: [Point; 2],
+    pub name: Str<3>,
+}
+
+impl Inputs {
+    /// Encodes the inputs into the initial witness of the function.
+    pub fn encode_inputs(&self) -> WitnessMap<FieldElement> {
+        let mut fields = Vec::new();
+        self.flag.encode(&mut fields);
+        self.points.encode(&mut fields);
+        self.name.encode(&mut fields);
+        to_witness_map(fields)
+    }
+}
+
+/// The return value of the function.
+pub type Return = (u64, FieldElement);
+
+/// Decodes the return value of the function from its solved witness.
+pub fn decode_return(witness_map: &WitnessMap<FieldElement>) -> Result<Return, AbiError> {
+    decode_return_value(witness_map, 8)
+}
@@ -0,0 +1,268 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use acir::circuit::OpcodeLocation;
+use clap::Args;
+use color_eyre::eyre::{self, Context};
+use noirc_artifacts::program::ProgramArtifact;
+
+use crate::fs::read_program_from_file;
+use crate::opcode_formatter::format_acir_opcode;
+
+/// The name used for opcodes which have no source location in the debug info.
+const UNKNOWN_FILE: &str = "<unknown>";
+
+#[derive(Debug, Clone, Args)]
+pub(crate) struct StatsCommand {
+    /// The path to the artifact JSON file
+    #[clap(long, short)]
+    artifact_path: String,
+
+    /// The path to an artifact JSON file of an earlier version of the program to compare against
+    #[clap(long, short)]
+    baseline_path: Option<String>,
+}
+
+pub(crate) fn run(args: StatsCommand) -> eyre::Result<()> {
+    let artifact = read_program_from_file(PathBuf::from(args.artifact_path))
+        .context("Error reading program from file")?;
+    let baseline = args
+        .baseline_path
+        .map(|baseline_path| read_program_from_file(PathBuf::from(baseline_path)))
+        .transpose()
+        .context("Error reading baseline program from file")?;
+
+    let stats = AcirStats::from_artifact(&artifact);
+    let baseline_stats = baseline.as_ref().map(AcirStats::from_artifact);
+    print!("{}", format_stats(baseline_stats.as_ref(), &stats));
+
+    Ok(())
+}
+
+/// Counts of the ACIR opcodes in a program.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct AcirStats {
+    /// Opcode counts per function, by kind of opcode.
+    functions: BTreeMap<String, BTreeMap<String, usize>>,
+    /// Opcode counts per source file, by line.
+    /// Each opcode is attributed to the innermost location of its call stack.
+    files: BTreeMap<String, BTreeMap<usize, usize>>,
+}
+
+impl AcirStats {
+    fn from_artifact(artifact: &ProgramArtifact) -> Self {
+        let mut stats = AcirStats::default();
+
+        let line_starts: BTreeMap<_, Vec<usize>> = artifact
+            .file_map
+            .iter()
+            .map(|(file_id, file)| {
+                let line_starts = file.source.match_indices('\n').map(|(index, _)| index + 1);
+                (*file_id, std::iter::once(0).chain(line_starts).collect())
+            })
+            .collect();
+
+        for (function_index, function) in artifact.bytecode.functions.iter().enumerate() {
+            let function_name = artifact
+                .names
+                .get(function_index)
+                .cloned()
+                .unwrap_or_else(|| format!("function_{function_index}"));
+            let kinds = stats.functions.entry(function_name).or_default();
+            let debug_info = artifact.debug_symbols.debug_infos.get(function_index);
+
+            for (opcode_index, opcode) in function.opcodes.iter().enumerate() {
+                *kinds.entry(format_acir_opcode(opcode)).or_default() += 1;
+
+                let location = debug_info
+                    .and_then(|debug_info| {
+                        debug_info.opcode_location(&OpcodeLocation::Acir(opcode_index))
+                    })
+                    .and_then(|call_stack| call_stack.last().copied());
+                let file_and_line = location.and_then(|location| {
+                    let file = artifact.file_map.get(&location.file)?;
+                    let line_starts = &line_starts[&location.file];
+                    let offset = location.span.start() as usize;
+                    let line = line_starts.partition_point(|start| *start <= offset);
+                    Some((file.path.display().to_string(), line))
+                });
+                let (file, line) = file_and_line.unwrap_or_else(|| (UNKNOWN_FILE.to_string(), 0));
+                *stats.files.entry(file).or_default().entry(line).or_default() += 1;
+            }
+        }
+
+        stats
+    }
+}
+
+/// Formats the opcode counts of a program, alongside the differences from a baseline if there is one.
+///
+/// When comparing against a baseline only the kinds of opcodes and the lines whose counts changed are listed.
+fn format_stats(baseline: Option<&AcirStats>, stats: &AcirStats) -> String {
+    let mut output = String::new();
+    let empty = BTreeMap::new();
+
+    let function_names =
+        union_of_keys(baseline.map(|baseline| &baseline.functions), &stats.functions);
+    for function_name in function_names {
+        let before =
+            baseline.map(|baseline| baseline.functions.get(function_name).unwrap_or(&empty));
+        let after = stats.functions.get(function_name).unwrap_or(&empty);
+        write_count(
+            &mut output,
+            &format!("function {function_name}"),
+            total(before),
+            after.values().sum(),
+        );
+        for kind in union_of_keys(before, after) {
+            let count_before = before.map(|before| before.get(kind).copied().unwrap_or_default());
+            let count_after = after.get(kind).copied().unwrap_or_default();
+            if count_before != Some(count_after) {
+                write_count(&mut output, &format!("  {kind}"), count_before, count_after);
+            }
+        }
+    }
+
+    let empty = BTreeMap::new();
+    let files = union_of_keys(baseline.map(|baseline| &baseline.files), &stats.files);
+    for file in files {
+        let before = baseline.map(|baseline| baseline.files.get(file).unwrap_or(&empty));
+        let after = stats.files.get(file).unwrap_or(&empty);
+        write_count(&mut output, &format!("file {file}"), total(before), after.values().sum());
+        if file == UNKNOWN_FILE {
+            continue;
+        }
+        for line in union_of_keys(before, after) {
+            let count_before = before.map(|before| before.get(line).copied().unwrap_or_default());
+            let count_after = after.get(line).copied().unwrap_or_default();
+            if count_before != Some(count_after) {
+                write_count(&mut output, &format!("  line {line}"), count_before, count_after);
+            }
+        }
+    }
+
+    output
+}
+
+fn union_of_keys<'a, K: Ord, V>(
+    before: Option<&'a BTreeMap<K, V>>,
+    after: &'a BTreeMap<K, V>,
+) -> BTreeSet<&'a K> {
+    before.into_iter().flat_map(BTreeMap::keys).chain(after.keys()).collect()
+}
+
+fn total<K>(counts: Option<&BTreeMap<K, usize>>) -> Option<usize> {
+    counts.map(|counts| counts.values().sum())
+}
+
+fn write_count(output: &mut String, label: &str, before: Option<usize>, after: usize) {
+    match before {
+        Some(before) => {
+            let delta = after as i64 - before as i64;
+            writeln!(output, "{label}: {before} -> {after} ({delta:+})")
+        }
+        None => writeln!(output, "{label}: {after}"),
+    }
+    .expect("writing to a string should not fail");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    use acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+    use acir::circuit::{Circuit, Opcode, OpcodeLocation, Program};
+    use acir::native_types::{Expression, Witness};
+    use acir::FieldElement;
+    use fm::FileId;
+    use noirc_artifacts::program::ProgramArtifact;
+    use noirc_driver::DebugFile;
+    use noirc_errors::debug_info::{DebugInfo, ProgramDebugInfo};
+    use noirc_errors::{Location, Span};
+
+    use super::{format_stats, AcirStats};
+
+    const SOURCE: &str = "fn main(x: u8) {\n    assert(x == 1);\n    let y = x as u32;\n}\n";
+
+    fn artifact(opcodes: Vec<(Opcode<FieldElement>, &str)>) -> ProgramArtifact {
+        let file = FileId::dummy();
+        let mut locations = BTreeMap::new();
+        for (index, (_, code)) in opcodes.iter().enumerate() {
+            let start = SOURCE.find(code).unwrap() as u32;
+            let span = Span::from(start..start + code.len() as u32);
+            locations.insert(OpcodeLocation::Acir(index), vec![Location::new(span, file)]);
+        }
+        let debug_info = DebugInfo { locations, ..DebugInfo::default() };
+        let opcodes = opcodes.into_iter().map(|(opcode, _)| opcode).collect();
+
+        ProgramArtifact {
+            noir_version: "0.0.0".to_string(),
+            hash: 27,
+            abi: noirc_abi::Abi::default(),
+            bytecode: Program {
+                functions: vec![Circuit { opcodes, ..Circuit::default() }],
+                ..Program::default()
+            },
+            debug_symbols: ProgramDebugInfo { debug_infos: vec![debug_info] },
+            file_map: BTreeMap::from([(
+                file,
+                DebugFile { source: SOURCE.to_string(), path: PathBuf::from("src/main.nr") },
+            )]),
+            names: vec!["main".to_string()],
+            brillig_names: Vec::new(),
+        }
+    }
+
+    fn assert_zero() -> Opcode<FieldElement> {
+        Opcode::AssertZero(Expression::zero())
+    }
+
+    fn range() -> Opcode<FieldElement> {
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+            input: FunctionInput::witness(Witness(0), 8),
+        })
+    }
+
+    #[test]
+    fn counts_opcodes_by_kind_and_line() {
+        let artifact = artifact(vec![(range(), "x: u8"), (assert_zero(), "x == 1")]);
+        let stats = AcirStats::from_artifact(&artifact);
+
+        assert_eq!(
+            format_stats(None, &stats),
+            "function main: 2
+  acir::arithmetic: 1
+  acir::blackbox::range: 1
+file src/main.nr: 2
+  line 1: 1
+  line 2: 1
+"
+        );
+    }
+
+    #[test]
+    fn reports_changes_from_baseline() {
+        let baseline = artifact(vec![(range(), "x: u8"), (assert_zero(), "x == 1")]);
+        let artifact = artifact(vec![
+            (range(), "x: u8"),
+            (assert_zero(), "x == 1"),
+            (range(), "x as u32"),
+            (range(), "x as u32"),
+        ]);
+
+        let output = format_stats(
+            Some(&AcirStats::from_artifact(&baseline)),
+            &AcirStats::from_artifact(&artifact),
+        );
+        assert_eq!(
+            output,
+            "function main: 2 -> 4 (+2)
+  acir::blackbox::range: 1 -> 3 (+2)
+file src/main.nr: 2 -> 4 (+2)
+  line 3: 0 -> 2 (+2)
+"
+        );
+    }
+}